@@ -1 +1,2 @@
 pub mod out;
+pub mod term;
@@ -0,0 +1,42 @@
+/// Terminal size in `(columns, rows)` from `stty size` on the controlling terminal.
+///
+/// Spawns a process; `None` when there is no terminal, `stty` fails, or on non-Unix targets.
+/// Examples use it as a fallback after `kpix::term::terminal_size` (`COLUMNS`/`LINES`).
+#[cfg(unix)]
+pub fn stty_size() -> Option<(usize, usize)> {
+    use std::process::{Command, Stdio};
+    let tty = std::fs::File::open("/dev/tty").ok()?;
+    let out = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::from(tty))
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    parse_stty(&String::from_utf8(out.stdout).ok()?)
+}
+
+/// Terminal size in `(columns, rows)` from `stty size`; always `None` off Unix.
+#[cfg(not(unix))]
+pub fn stty_size() -> Option<(usize, usize)> {
+    None
+}
+
+/// Parse `stty size` output, `"<rows> <columns>"`, into `(columns, rows)`.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_stty(text: &str) -> Option<(usize, usize)> {
+    let mut it = text.split_whitespace().map(|v| v.parse::<usize>().ok());
+    let (rows, cols) = (it.next()??, it.next()??);
+    Some((cols, rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rows_then_columns() {
+        assert_eq!(parse_stty("24 80\n"), Some((80, 24)));
+        assert_eq!(parse_stty("24"), None);
+        assert_eq!(parse_stty(""), None);
+    }
+}
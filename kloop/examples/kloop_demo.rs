@@ -2,7 +2,8 @@ use std::time::{Duration, Instant};
 
 use kdev::out;
 use kloop::{App, FixedLoop, LoopConfig};
-use kpix::{Color, Surface};
+use kpix::term::{ColorMode, TermOptions, TermRenderer};
+use kpix::{Color, Rect, Surface};
 use std::process::Command;
 
 struct BallDemo {
@@ -15,9 +16,14 @@ struct BallDemo {
     prev_py: f32,
     w: usize,
     h: usize,
+    frames: u32,
     surface: Surface,
+    // areas drawn last frame, erased before the next one
+    drawn: Vec<Rect>,
 }
 
+const BACKGROUND: Color = Color::rgba(20, 30, 50, 255);
+
 impl BallDemo {
     fn new(w: usize, h: usize) -> Self {
         Self {
@@ -29,13 +35,20 @@ impl BallDemo {
             prev_py: 40.0,
             w,
             h,
+            frames: 0,
             surface: Surface::new(w, h),
+            drawn: Vec::new(),
         }
     }
 
     fn surface(&self) -> &Surface {
         &self.surface
     }
+
+    /// Turn on dirty-rect tracking; `render` then reports only the areas it redraws.
+    fn track_dirty(&mut self) {
+        self.surface.set_dirty_tracking(true);
+    }
 }
 
 impl App for BallDemo {
@@ -66,34 +79,77 @@ impl App for BallDemo {
     }
 
     fn render(&mut self, alpha: f32) {
-        // background
-        self.surface.clear(Color::rgba(20, 30, 50, 255));
+        // background: full clear on the first frame, then only erase what was drawn
+        if self.frames == 0 {
+            self.surface.clear(BACKGROUND);
+        }
+        for r in self.drawn.drain(..) {
+            kpix::draw::fill_rect(&mut self.surface, r.x, r.y, r.w, r.h, BACKGROUND);
+        }
         // interpolate position for rendering
         let x = self.prev_px + (self.px - self.prev_px) * alpha;
         let y = self.prev_py + (self.py - self.prev_py) * alpha;
-        // simple filled circle (approx) using set_pixel
-        let cx = x as i32;
-        let cy = y as i32;
-        let r = 8i32;
-        for dy in -r..=r {
-            for dx in -r..=r {
-                if dx * dx + dy * dy <= r * r {
-                    self.surface
-                        .set_pixel(cx + dx, cy + dy, Color::rgba(230, 180, 40, 255));
-                }
-            }
+        let (x, y) = (x as i32, y as i32);
+        kpix::draw::fill_circle(&mut self.surface, x, y, 8, Color::rgba(230, 180, 40, 255));
+        self.drawn.push(Rect::new(x - 8, y - 8, 17, 17));
+        // frame counter overlay
+        self.frames += 1;
+        let label = format!("FRAME {}", self.frames);
+        kpix::text::draw_text(
+            &mut self.surface,
+            4,
+            4,
+            &label,
+            Color::rgba(235, 235, 235, 255),
+        );
+        let (tw, th) = kpix::text::measure_text(&label);
+        self.drawn.push(Rect::new(4, 4, tw, th));
+    }
+}
+
+/// Real-time loop that redraws the surface in the terminal, fitted to its size.
+fn run_in_terminal(app: &mut BallDemo, secs: f64) {
+    let (cols, rows) = kpix::term::terminal_size()
+        .or_else(kdev::term::stty_size)
+        .unwrap_or((80, 24));
+    // Keep one row free so the final newline does not scroll the image.
+    let opts = TermOptions::new()
+        .with_mode(ColorMode::detect())
+        .with_fit(cols, rows.saturating_sub(1));
+    let mut renderer = TermRenderer::new(opts);
+    let mut stdout = std::io::stdout().lock();
+
+    let cfg = LoopConfig::from_hz(60).with_limits(Duration::from_millis(250), 1000);
+    let mut looper = FixedLoop::new(ktime::SystemClock, cfg);
+    let start = Instant::now();
+    let target_frame = Duration::from_secs_f64(1.0 / 30.0);
+    while start.elapsed().as_secs_f64() < secs {
+        let frame_start = Instant::now();
+        looper.tick(app);
+        if let Err(e) = renderer.draw(app.surface(), &mut stdout) {
+            eprintln!("端末への出力に失敗しました: {}", e);
+            break;
+        }
+        let elapsed = frame_start.elapsed();
+        if elapsed < target_frame {
+            std::thread::sleep(target_frame - elapsed);
         }
     }
+    let _ = renderer.finish(&mut stdout);
 }
 
 fn main() {
-    // Option parsing: --video to encode MP4, --realtime <seconds> to run with real time
+    // Option parsing: --video to encode MP4, --realtime <seconds> to run with real time,
+    // --term to preview live in the terminal instead of writing frames
     let mut make_video = false;
+    let mut term_preview = false;
     let mut realtime_secs: Option<f64> = None;
     let mut args = std::env::args().skip(1).peekable();
     while let Some(a) = args.next() {
         if a == "--video" {
             make_video = true;
+        } else if a == "--term" {
+            term_preview = true;
         } else if a == "--realtime" {
             if let Some(s) = args.next() {
                 match s.parse::<f64>() {
@@ -115,6 +171,10 @@ fn main() {
     let (w, h) = (256usize, 256usize);
 
     let mut app = BallDemo::new(w, h);
+    if term_preview {
+        run_in_terminal(&mut app, realtime_secs.unwrap_or(5.0));
+        return;
+    }
     let out_dir = out::example_output_dir("kloop_demo").expect("create out dir");
 
     if let Some(secs) = realtime_secs {
@@ -145,15 +205,27 @@ fn main() {
         let clock = ktime::FakeClock::default();
         let cfg = LoopConfig::from_hz(60).with_limits(Duration::from_millis(250), 1000);
         let mut looper = FixedLoop::new(clock, cfg);
+        app.track_dirty();
+        let mut dirty_area = 0i64;
 
         for i in 0..120u32 {
             // Advance by one fixed step deterministically
             looper.run_steps(&mut app, 1);
             // Since run_steps doesn't call render, invoke render with alpha=0.0
             app.render(0.0);
+            dirty_area += app
+                .surface
+                .take_dirty_rects()
+                .iter()
+                .map(Rect::area)
+                .sum::<i64>();
             let path = out_dir.join(format!("frame_{:06}.ppm", i));
             kpix::io::write_ppm(app.surface(), path).expect("write ppm");
         }
+        println!(
+            "Changed area per frame: {:.1}% of the surface on average",
+            dirty_area as f64 * 100.0 / (120 * w * h) as f64
+        );
     }
 
     // Optional: create a video from frames using ffmpeg when --video is passed.
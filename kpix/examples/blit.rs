@@ -0,0 +1,66 @@
+use kdev::out;
+use kpix::blit::{self, BlendMode, BlitOptions, Filter, Rotation};
+use kpix::{Color, Rect, Surface, draw, io};
+
+/// 16x16 arrow sprite on a magenta key background.
+fn sprite() -> Surface {
+    let mut s = Surface::new(16, 16);
+    s.clear(Color::rgba(255, 0, 255, 255));
+    draw::fill_rect(&mut s, 2, 6, 8, 4, Color::rgba(240, 200, 60, 255));
+    for i in 0..6 {
+        draw::fill_rect(
+            &mut s,
+            10 + i,
+            2 + i,
+            1,
+            12 - 2 * i,
+            Color::rgba(240, 120, 60, 255),
+        );
+    }
+    s
+}
+
+fn main() {
+    let mut s = Surface::new(320, 240);
+    s.clear(Color::rgba(20, 20, 30, 255));
+    let spr = sprite();
+    let all = spr.bounds();
+    let key = BlitOptions::new().with_mode(BlendMode::ColorKey(Color::rgba(255, 0, 255, 255)));
+
+    // Top row: raw copy, then keyed copies in every orientation
+    blit::blit(&spr, all, &mut s, 16, 16);
+    let orientations = [
+        key,
+        key.with_flip(true, false),
+        key.with_flip(false, true),
+        key.with_rotation(Rotation::Cw90),
+        key.with_rotation(Rotation::Cw180),
+        key.with_rotation(Rotation::Cw270),
+    ];
+    for (i, opts) in orientations.iter().enumerate() {
+        blit::blit_ex(&spr, all, &mut s, 48 + i as i32 * 32, 16, opts);
+    }
+
+    // Middle row: nearest vs bilinear stretch
+    blit::blit_scaled(&spr, all, &mut s, Rect::new(16, 56, 96, 96), &key);
+    blit::blit_scaled(
+        &spr,
+        all,
+        &mut s,
+        Rect::new(128, 56, 96, 96),
+        &key.with_filter(Filter::Bilinear),
+    );
+
+    // Translucent layer composited over the scene, clipped to a window
+    let mut layer = Surface::new(80, 80);
+    layer.clear(Color::rgba(90, 160, 230, 140));
+    draw::fill_circle(&mut layer, 40, 40, 24, Color::rgba(255, 255, 255, 200));
+    s.push_clip(Rect::new(232, 56, 72, 160));
+    let alpha = BlitOptions::new().with_mode(BlendMode::Alpha);
+    blit::blit_ex(&layer, layer.bounds(), &mut s, 220, 100, &alpha);
+    s.pop_clip();
+
+    let out_dir = out::example_output_dir("blit").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("blit.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("blit.bmp")).expect("failed to write BMP");
+}
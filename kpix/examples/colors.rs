@@ -0,0 +1,63 @@
+use kdev::out;
+use kpix::color::palette;
+use kpix::{Color, Surface, draw, io, text};
+
+fn main() {
+    let mut s = Surface::new(480, 300);
+    s.clear(Color::parse("#202028").expect("valid color"));
+    let label = Color::parse("gainsboro").expect("valid color");
+
+    // Hue sweeps: HSV at full value, HSL at half lightness, OKLab at constant lightness
+    text::draw_text(&mut s, 8, 6, "HSV / HSL / OKLAB", label);
+    for x in 0..464 {
+        let h = x as f32 * 360.0 / 464.0;
+        draw::fill_rect(&mut s, 8 + x, 18, 1, 14, Color::from_hsv(h, 1.0, 1.0));
+        draw::fill_rect(&mut s, 8 + x, 32, 1, 14, Color::from_hsl(h, 0.6, 0.5));
+        let (sin, cos) = h.to_radians().sin_cos();
+        draw::fill_rect(
+            &mut s,
+            8 + x,
+            46,
+            1,
+            14,
+            Color::from_oklab(0.7, 0.12 * cos, 0.12 * sin),
+        );
+    }
+
+    // Interpolation: gamma-space lerp (top) vs linear light (bottom)
+    text::draw_text(&mut s, 8, 68, "LERP SRGB / LINEAR", label);
+    let pairs = [
+        (Color::BLACK, Color::WHITE),
+        (palette::RED, palette::LIME),
+        (palette::BLUE, palette::YELLOW),
+    ];
+    for (i, (a, b)) in pairs.into_iter().enumerate() {
+        let y = 80 + i as i32 * 30;
+        for x in 0..464 {
+            let t = x as f32 / 463.0;
+            draw::fill_rect(&mut s, 8 + x, y, 1, 13, a.lerp(b, t));
+            draw::fill_rect(&mut s, 8 + x, y + 13, 1, 13, a.lerp_linear(b, t));
+        }
+    }
+
+    // Named palette swatches, ordered by luminance
+    text::draw_text(&mut s, 8, 172, "CSS PALETTE BY LUMINANCE", label);
+    let mut named: Vec<Color> = palette::NAMED.iter().map(|&(_, c)| c).collect();
+    named.sort_by(|a, b| a.luminance().total_cmp(&b.luminance()));
+    named.dedup();
+    for (i, c) in named.iter().enumerate() {
+        let (col, row) = (i % 33, i / 33);
+        draw::fill_rect(
+            &mut s,
+            8 + col as i32 * 14,
+            184 + row as i32 * 14,
+            13,
+            13,
+            *c,
+        );
+    }
+
+    let out_dir = out::example_output_dir("colors").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("colors.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("colors.bmp")).expect("failed to write BMP");
+}
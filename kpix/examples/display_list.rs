@@ -0,0 +1,66 @@
+use kdev::out;
+use kpix::blit::{self, BlitOptions};
+use kpix::display::DisplayList;
+use kpix::{Color, Rect, Surface, io, text};
+
+/// Small debug diagram: a tree of nodes with bounding boxes and links.
+fn diagram() -> DisplayList {
+    let mut list = DisplayList::new(80, 60);
+    let link = Color::rgba(140, 150, 170, 255);
+    let node = Color::rgba(90, 170, 250, 255);
+    let leaf = Color::rgba(250, 170, 60, 255);
+    list.clear(Color::rgba(24, 26, 32, 255))
+        .draw_rect(2, 2, 76, 56, Color::rgba(70, 74, 86, 255))
+        .fill_rect(6, 44, 68, 10, Color::rgba(40, 96, 70, 255))
+        .draw_line(40, 12, 20, 30, link)
+        .draw_line(40, 12, 60, 30, link)
+        .draw_line(20, 30, 12, 48, link)
+        .draw_line(20, 30, 28, 48, link)
+        .draw_line(60, 30, 60, 48, link)
+        .fill_circle(40, 12, 5, node)
+        .draw_circle(40, 12, 7, Color::WHITE)
+        .fill_circle(20, 30, 4, node)
+        .fill_circle(60, 30, 4, node);
+    for x in [12, 28, 60] {
+        list.fill_rect(x - 3, 46, 7, 5, leaf);
+    }
+    list
+}
+
+fn main() {
+    const SCALE: usize = 4;
+    let list = diagram();
+    let label = Color::rgba(230, 230, 230, 255);
+    let (w, h) = (list.width() * SCALE, list.height() * SCALE);
+    let mut s = Surface::new(8 + 2 * (w + 8), h + 32);
+    s.clear(Color::rgba(12, 12, 16, 255));
+
+    // Left: rasterized at 1x, then enlarged. Right: replayed as vectors at 4x.
+    let small = list.render(1.0);
+    let dst = Rect::new(8, 8, w as i32, h as i32);
+    blit::blit_scaled(&small, small.bounds(), &mut s, dst, &BlitOptions::new());
+    let big = list.render(SCALE as f32);
+    blit::blit(&big, big.bounds(), &mut s, 16 + w as i32, 8);
+    text::draw_text(&mut s, 8, h as i32 + 16, "REPLAY 1X, ENLARGED", label);
+    text::draw_text(
+        &mut s,
+        16 + w as i32,
+        h as i32 + 16,
+        "REPLAY_SCALED 4X",
+        label,
+    );
+
+    let out_dir =
+        out::example_output_dir("display_list").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("display_list.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("display_list.bmp")).expect("failed to write BMP");
+    list.save(out_dir.join("display_list.txt"))
+        .expect("failed to write display list");
+    list.write_svg(out_dir.join("display_list.svg"))
+        .expect("failed to write SVG");
+
+    // The saved text loads back to the same list.
+    let loaded =
+        DisplayList::load(out_dir.join("display_list.txt")).expect("failed to read display list");
+    assert_eq!(loaded, list);
+}
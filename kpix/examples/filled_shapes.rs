@@ -0,0 +1,58 @@
+use std::f32::consts::PI;
+
+use kdev::out;
+use kpix::draw::{self, CornerRadii};
+use kpix::{Color, Surface, io};
+
+fn main() {
+    let (w, h) = (320i32, 240i32);
+    let mut s = Surface::new(w as usize, h as usize);
+    s.clear(Color::rgba(20, 20, 30, 255));
+
+    let red = Color::rgba(230, 90, 90, 255);
+    let green = Color::rgba(90, 210, 120, 255);
+    let blue = Color::rgba(90, 140, 230, 255);
+    let yellow = Color::rgba(230, 200, 80, 255);
+    let white = Color::rgba(240, 240, 240, 255);
+
+    // Top row: aliased (left) vs anti-aliased (right) circles and ellipses
+    draw::fill_circle(&mut s, 40, 40, 24, red);
+    draw::draw_circle(&mut s, 40, 40, 30, white);
+    draw::fill_circle_aa(&mut s, 110, 40, 24, red);
+    draw::draw_circle_aa(&mut s, 110, 40, 30, white);
+    draw::fill_ellipse(&mut s, 190, 40, 34, 16, green);
+    draw::draw_ellipse_aa(&mut s, 270, 40, 34, 16, green);
+
+    // Middle row: rotated ellipses
+    for i in 0..4 {
+        let a = i as f32 * PI / 4.0;
+        draw::draw_ellipse_rotated_aa(&mut s, 60, 120, 40, 12, a, blue);
+    }
+    draw::fill_ellipse_rotated_aa(&mut s, 160, 120, 40, 14, PI / 6.0, blue);
+
+    // Pie chart and arcs
+    let slices = [(0.0, 0.35, red), (0.35, 0.6, green), (0.6, 1.0, yellow)];
+    for (a0, a1, c) in slices {
+        draw::fill_pie_aa(&mut s, 260, 120, 34, a0 * 2.0 * PI, a1 * 2.0 * PI, c);
+    }
+    draw::draw_arc_aa(&mut s, 260, 120, 40, -PI / 2.0, PI / 2.0, white);
+
+    // Bottom row: rounded rectangles
+    draw::fill_round_rect(&mut s, 20, 180, 80, 44, CornerRadii::all(10), yellow);
+    draw::draw_round_rect(&mut s, 16, 176, 88, 52, CornerRadii::all(14), white);
+    draw::fill_round_rect_aa(
+        &mut s,
+        120,
+        180,
+        80,
+        44,
+        CornerRadii::new(0, 20, 0, 20),
+        green,
+    );
+    draw::draw_round_rect_aa(&mut s, 220, 180, 80, 44, CornerRadii::all(8), blue);
+
+    let out_dir =
+        out::example_output_dir("filled_shapes").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("filled_shapes.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("filled_shapes.bmp")).expect("failed to write BMP");
+}
@@ -0,0 +1,68 @@
+use kdev::out;
+use kpix::filter::{self, EdgeMode, Kernel};
+use kpix::{Color, Surface, blit, draw, io, text};
+
+const TILE: usize = 96;
+
+/// Small test scene: shapes, thin lines and text.
+fn scene() -> Surface {
+    let mut s = Surface::new(TILE, TILE);
+    s.clear(Color::rgba(235, 230, 220, 255));
+    draw::fill_circle(&mut s, 34, 38, 22, Color::rgba(200, 60, 60, 255));
+    draw::fill_rect(&mut s, 52, 44, 34, 30, Color::rgba(50, 90, 190, 255));
+    for i in 0..6 {
+        draw::draw_line(
+            &mut s,
+            8,
+            80 + i * 2,
+            88,
+            70 + i * 2,
+            Color::rgba(30, 30, 30, 255),
+        );
+    }
+    text::draw_text(&mut s, 8, 6, "kpix", Color::rgba(20, 20, 20, 255));
+    s
+}
+
+fn main() {
+    let src = scene();
+    let mut results: Vec<Surface> = vec![src.clone()];
+    let mut run = |op: &dyn Fn(&Surface, &mut Surface)| {
+        let mut dst = Surface::new(TILE, TILE);
+        op(&src, &mut dst);
+        results.push(dst);
+    };
+    run(&|s, d| filter::box_blur(s, d, 3, EdgeMode::Clamp));
+    run(&|s, d| filter::gaussian_blur(s, d, 2.5, EdgeMode::Clamp));
+    run(&|s, d| filter::unsharp_mask(s, d, 1.5, 1.5, 2, EdgeMode::Clamp));
+    run(&|s, d| filter::convolve(s, d, &Kernel::sharpen(), EdgeMode::Clamp));
+    run(&|s, d| filter::sobel(s, d, EdgeMode::Clamp));
+    run(&|s, d| filter::erode(s, d, 2, EdgeMode::Clamp));
+    run(&|s, d| filter::dilate(s, d, 2, EdgeMode::Clamp));
+
+    // Wrap vs transparent edges on a heavy blur, filtered in place
+    let mut wrap = src.clone();
+    filter::in_place(&mut wrap, |s, d| {
+        filter::gaussian_blur(s, d, 6.0, EdgeMode::Wrap)
+    });
+    let mut clear = src.clone();
+    filter::in_place(&mut clear, |s, d| {
+        filter::gaussian_blur(s, d, 6.0, EdgeMode::Transparent)
+    });
+    results.extend([wrap, clear]);
+
+    let cols = 5;
+    let rows = results.len().div_ceil(cols);
+    let mut sheet = Surface::new(cols * (TILE + 4) + 4, rows * (TILE + 4) + 4);
+    sheet.clear(Color::rgba(60, 60, 70, 255));
+    let alpha = blit::BlitOptions::new().with_mode(blit::BlendMode::Alpha);
+    for (i, tile) in results.iter().enumerate() {
+        let x = 4 + (i % cols) * (TILE + 4);
+        let y = 4 + (i / cols) * (TILE + 4);
+        blit::blit_ex(tile, tile.bounds(), &mut sheet, x as i32, y as i32, &alpha);
+    }
+
+    let out_dir = out::example_output_dir("filters").expect("failed to create output directory");
+    io::write_ppm(&sheet, out_dir.join("filters.ppm")).expect("failed to write PPM");
+    io::write_bmp(&sheet, out_dir.join("filters.bmp")).expect("failed to write BMP");
+}
@@ -0,0 +1,58 @@
+use kdev::out;
+use kpix::flood::{self, Connectivity, FloodOptions};
+use kpix::{Color, Rect, Surface, draw, io};
+
+fn main() {
+    let mut s = Surface::new(320, 160);
+    let paper = Color::rgba(250, 248, 240, 255);
+    let ink = Color::rgba(40, 40, 50, 255);
+    s.clear(paper);
+
+    // Left: line-art cells filled one by one (4-connected, exact match)
+    draw::draw_circle(&mut s, 60, 80, 50, ink);
+    draw::draw_line(&mut s, 10, 80, 110, 80, ink);
+    draw::draw_line(&mut s, 60, 30, 60, 130, ink);
+    let quadrants = [
+        (40, 60, Color::rgba(230, 90, 80, 255)),
+        (80, 60, Color::rgba(240, 190, 60, 255)),
+        (40, 100, Color::rgba(80, 170, 110, 255)),
+        (80, 100, Color::rgba(70, 130, 210, 255)),
+    ];
+    for (x, y, c) in quadrants {
+        flood::flood_fill(&mut s, x, y, c);
+    }
+
+    // Middle: a horizontal gradient; a tolerant fill selects only the similar band
+    for x in 0..90 {
+        let v = 60 + x as u8 * 2;
+        draw::fill_rect(&mut s, 120 + x, 30, 1, 100, Color::rgba(v, v, 220, 255));
+    }
+    let band = FloodOptions::new().with_tolerance(30);
+    flood::flood_fill_ex(&mut s, 165, 80, Color::rgba(230, 90, 80, 255), &band);
+
+    // Right: a staircase wall splits the clipped box in two, but its blocks only touch at
+    // corners, so the 8-connected region leaks into the other half. Masks are painted after.
+    for i in 0..10 {
+        draw::fill_rect(&mut s, 230 + i * 8, 30 + i * 10, 8, 10, ink);
+    }
+    s.push_clip(Rect::new(230, 30, 80, 100));
+    let four = flood::flood_mask(&s, 300, 40, &FloodOptions::new());
+    let eight = flood::flood_mask(
+        &s,
+        300,
+        40,
+        &FloodOptions::new().with_connectivity(Connectivity::Eight),
+    );
+    flood::fill_mask(&mut s, &eight, Color::rgba(200, 200, 230, 255));
+    flood::fill_mask(&mut s, &four, Color::rgba(120, 200, 160, 255));
+    s.pop_clip();
+    println!(
+        "4-connected: {} px, 8-connected: {} px",
+        four.count(),
+        eight.count()
+    );
+
+    let out_dir = out::example_output_dir("flood_fill").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("flood_fill.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("flood_fill.bmp")).expect("failed to write BMP");
+}
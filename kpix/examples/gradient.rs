@@ -1,20 +1,101 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
 use kdev::out;
-use kpix::{Color, Surface, io};
+use kpix::paint::{Gradient, GradientStop, Interpolation, Spread};
+use kpix::path::{self, FillRule};
+use kpix::{Color, Paint, Surface, draw, io};
 
 fn main() {
-    let (w, h) = (256usize, 256usize);
-
+    let (w, h) = (384usize, 256usize);
     let mut s = Surface::new(w, h);
+    s.clear(Color::rgba(24, 24, 32, 255));
 
-    for y in 0..h {
-        for x in 0..w {
-            let r = x as u8;
-            let g = y as u8;
-            let b = ((x + y) as u8).wrapping_div(2);
-            s.set_pixel(x as i32, y as i32, Color::rgba(r, g, b, 255));
-        }
+    let red = Color::rgba(230, 40, 40, 255);
+    let green = Color::rgba(40, 200, 80, 255);
+    let blue = Color::rgba(40, 80, 230, 255);
+    let white = Color::rgba(255, 255, 255, 255);
+
+    // Row 1: one short red-to-blue ramp with pad / repeat / reflect spread
+    let ramp = Gradient::two(red, blue);
+    for (i, spread) in [Spread::Pad, Spread::Repeat, Spread::Reflect]
+        .into_iter()
+        .enumerate()
+    {
+        let x = 8 + i as i32 * 124;
+        let x0 = x as f32 + 40.0;
+        let paint = Paint::linear(x0, 0.0, x0 + 32.0, 0.0, ramp.clone().with_spread(spread));
+        draw::fill_rect_paint(&mut s, x, 8, 120, 40, &paint);
     }
 
+    // Row 2: the same stops interpolated in sRGB (top) and in linear light (bottom)
+    let rainbow = Gradient::new([
+        GradientStop::new(0.0, red),
+        GradientStop::new(0.5, green),
+        GradientStop::new(1.0, blue),
+    ]);
+    let srgb = Paint::linear(8.0, 0.0, 376.0, 0.0, rainbow.clone());
+    let linear = Paint::linear(
+        8.0,
+        0.0,
+        376.0,
+        0.0,
+        rainbow.with_interpolation(Interpolation::Linear),
+    );
+    draw::fill_rect_paint(&mut s, 8, 56, 368, 20, &srgb);
+    draw::fill_rect_paint(&mut s, 8, 76, 368, 20, &linear);
+
+    // Row 3: radial sphere, conic color wheel, gradient star and a fading disc
+    let sphere = Paint::radial(
+        52.0,
+        156.0,
+        56.0,
+        Gradient::new([
+            GradientStop::new(0.0, white),
+            GradientStop::new(1.0, Color::rgba(30, 60, 160, 255)),
+        ]),
+    );
+    draw::fill_circle_aa_paint(&mut s, 64, 172, 50, &sphere);
+
+    let hue = |deg: f32| {
+        let c = |n: f32| {
+            let k = (n + deg / 60.0) % 6.0;
+            (255.0 * (1.0 - (k.min(4.0 - k).clamp(0.0, 1.0)))) as u8
+        };
+        Color::rgba(c(5.0), c(3.0), c(1.0), 255)
+    };
+    let wheel =
+        Gradient::new((0..=6).map(|i| GradientStop::new(i as f32 / 6.0, hue(i as f32 * 60.0))));
+    let conic = Paint::conic(186.0, 172.5, -FRAC_PI_2, wheel);
+    draw::fill_circle_aa_paint(&mut s, 186, 172, 50, &conic);
+
+    let star: Vec<(f32, f32)> = (0..10)
+        .map(|i| {
+            let a = -FRAC_PI_2 + i as f32 * PI / 5.0;
+            let r = if i % 2 == 0 { 50.0 } else { 22.0 };
+            (300.0 + r * a.cos(), 172.0 + r * a.sin())
+        })
+        .collect();
+    let gold = Paint::linear(
+        300.0,
+        122.0,
+        300.0,
+        222.0,
+        Gradient::two(
+            Color::rgba(255, 230, 120, 255),
+            Color::rgba(200, 90, 20, 255),
+        ),
+    );
+    path::fill_polygon_aa_paint(&mut s, &star, FillRule::NonZero, &gold);
+
+    // Transparent stops fade over whatever is underneath
+    let fade = Paint::radial(
+        360.0,
+        232.0,
+        24.0,
+        Gradient::two(white, Color::rgba(255, 255, 255, 0)),
+    );
+    draw::fill_rect_paint(&mut s, 336, 208, 48, 48, &fade);
+
     // Write outputs under target/examples/gradient
     let out_dir = out::example_output_dir("gradient").expect("failed to create output directory");
     io::write_ppm(&s, out_dir.join("gradient.ppm")).expect("failed to write PPM");
@@ -0,0 +1,74 @@
+use kdev::out;
+use kpix::color::LinearRgba;
+use kpix::hdr::{Dither, HdrBlend, ResolveOptions, ToneMap};
+use kpix::{Color, HdrSurface, Rect, Surface, blit, io, text};
+
+const W: usize = 160;
+const H: usize = 120;
+
+/// Additive glows plus a motion-blurred streak, far brighter than 1.0 where they overlap.
+fn scene() -> HdrSurface {
+    let mut s = HdrSurface::new(W, H);
+    s.clear(LinearRgba::new(0.01, 0.012, 0.02, 1.0));
+    let lights = [
+        (50.0, 50.0, LinearRgba::new(4.0, 1.2, 0.3, 1.0)),
+        (85.0, 60.0, LinearRgba::new(0.3, 1.5, 4.0, 1.0)),
+        (68.0, 35.0, LinearRgba::new(1.0, 3.0, 0.6, 1.0)),
+    ];
+    for (cx, cy, c) in lights {
+        for y in 0..H as i32 {
+            for x in 0..W as i32 {
+                let d2 = (x as f32 + 0.5 - cx).powi(2) + (y as f32 + 0.5 - cy).powi(2);
+                let k = 25.0 / (25.0 + d2);
+                s.add_pixel(x, y, LinearRgba::new(c.r * k, c.g * k, c.b * k, 1.0));
+            }
+        }
+    }
+    // Motion blur: average 16 sub-frames of a moving bright square
+    let mut blur = HdrSurface::new(W, H);
+    let mut frame = HdrSurface::new(W, H);
+    for i in 0..16 {
+        frame.clear(LinearRgba::default());
+        frame.fill_rect(10 + i * 6, 92, 12, 12, LinearRgba::new(6.0, 5.0, 3.0, 1.0));
+        blur.accumulate(&frame, 1.0 / 16.0);
+    }
+    s.blit(&blur, blur.bounds(), 0, 0, HdrBlend::Add);
+    s
+}
+
+fn main() {
+    let hdr = scene();
+    let variants = [
+        ("CLAMP", ResolveOptions::new()),
+        ("CLAMP -2EV", ResolveOptions::new().with_exposure(-2.0)),
+        (
+            "REINHARD",
+            ResolveOptions::new().with_tone_map(ToneMap::Reinhard),
+        ),
+        (
+            "ACES DITHER",
+            ResolveOptions::new()
+                .with_tone_map(ToneMap::AcesFit)
+                .with_dither(Dither::Ordered),
+        ),
+    ];
+
+    let mut sheet = Surface::new(2 * W + 12, 2 * H + 12);
+    sheet.clear(Color::rgba(60, 60, 70, 255));
+    for (i, (name, opts)) in variants.iter().enumerate() {
+        let mut tile = hdr.resolve(opts);
+        text::draw_text(&mut tile, 4, 4, name, Color::WHITE);
+        let (x, y) = (4 + (i % 2) * (W + 4), 4 + (i / 2) * (H + 4));
+        blit::blit(
+            &tile,
+            Rect::new(0, 0, W as i32, H as i32),
+            &mut sheet,
+            x as i32,
+            y as i32,
+        );
+    }
+
+    let out_dir = out::example_output_dir("hdr").expect("failed to create output directory");
+    io::write_ppm(&sheet, out_dir.join("hdr.ppm")).expect("failed to write PPM");
+    io::write_bmp(&sheet, out_dir.join("hdr.bmp")).expect("failed to write BMP");
+}
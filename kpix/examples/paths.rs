@@ -0,0 +1,82 @@
+use std::f32::consts::{PI, TAU};
+
+use kdev::out;
+use kpix::path::{self, FillRule, LineCap, LineJoin, Path, Stroke};
+use kpix::{Color, Surface, io};
+
+fn star(cx: f32, cy: f32, r: f32) -> Path {
+    // Five-pointed star drawn in one stroke, so its center has winding number 2.
+    let mut p = Path::new();
+    for i in 0..5 {
+        let a = -PI / 2.0 + i as f32 * 2.0 * TAU / 5.0;
+        let (x, y) = (cx + a.cos() * r, cy + a.sin() * r);
+        if i == 0 {
+            p.move_to(x, y);
+        } else {
+            p.line_to(x, y);
+        }
+    }
+    p.close();
+    p
+}
+
+fn main() {
+    let mut s = Surface::new(320, 240);
+    s.clear(Color::rgba(20, 20, 30, 255));
+
+    let gold = Color::rgba(230, 190, 60, 255);
+    let teal = Color::rgba(70, 190, 180, 255);
+    let pink = Color::rgba(230, 110, 150, 255);
+    let white = Color::rgba(235, 235, 235, 255);
+
+    // Fill rules: nonzero (left) vs even-odd (right), aliased vs anti-aliased
+    path::fill_path(&mut s, &star(60.0, 60.0, 50.0), FillRule::NonZero, gold);
+    path::fill_path_aa(&mut s, &star(170.0, 60.0, 50.0), FillRule::EvenOdd, gold);
+
+    // Curves: quadratic and cubic Béziers closed into a leaf shape
+    let mut leaf = Path::new();
+    leaf.move_to(230.0, 100.0)
+        .quad_to(250.0, 10.0, 310.0, 20.0)
+        .cubic_to(300.0, 60.0, 290.0, 110.0, 230.0, 100.0)
+        .close();
+    path::fill_path_aa(&mut s, &leaf, FillRule::NonZero, teal);
+
+    // Rounded card via arc_to
+    let mut card = Path::new();
+    card.move_to(40.0, 130.0)
+        .arc_to(140.0, 130.0, 140.0, 220.0, 16.0)
+        .arc_to(140.0, 220.0, 20.0, 220.0, 16.0)
+        .arc_to(20.0, 220.0, 20.0, 130.0, 16.0)
+        .arc_to(20.0, 130.0, 140.0, 130.0, 16.0)
+        .close();
+    path::fill_path_aa(&mut s, &card, FillRule::NonZero, pink);
+    path::stroke_path_aa(&mut s, &card, &Stroke::new(3.0), white);
+
+    // Strokes: joins and caps on a zig-zag
+    let mut zig = Path::new();
+    zig.move_to(170.0, 200.0)
+        .line_to(200.0, 140.0)
+        .line_to(230.0, 200.0)
+        .line_to(260.0, 140.0)
+        .line_to(290.0, 200.0);
+    let styles = [
+        (
+            Stroke::new(12.0).with_join(LineJoin::Miter),
+            Color::rgba(90, 90, 120, 255),
+        ),
+        (
+            Stroke::new(8.0)
+                .with_join(LineJoin::Round)
+                .with_cap(LineCap::Round),
+            teal,
+        ),
+        (Stroke::new(2.0).with_join(LineJoin::Bevel), white),
+    ];
+    for (stroke, color) in styles {
+        path::stroke_path_aa(&mut s, &zig, &stroke, color);
+    }
+
+    let out_dir = out::example_output_dir("paths").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("paths.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("paths.bmp")).expect("failed to write BMP");
+}
@@ -0,0 +1,118 @@
+use std::f32::consts::{FRAC_PI_4, TAU};
+
+use kdev::out;
+use kpix::draw::{self, Dash};
+use kpix::paint::patterns;
+use kpix::path::{self, FillRule, LineCap, Path, Stroke};
+use kpix::{Color, Paint, Surface, io, text};
+
+const INK: Color = Color::rgba(0, 0, 0, 255);
+const PAPER: Color = Color::rgba(255, 255, 255, 255);
+const CLEAR: Color = Color::rgba(0, 0, 0, 0);
+
+/// Dashed pixel primitives: each row uses a different pattern.
+fn outlines(s: &mut Surface) {
+    text::draw_text(s, 8, 6, "DASHED PIXEL OUTLINES", INK);
+    let dashes = [
+        Dash::new(&[6.0, 3.0]),
+        Dash::new(&[2.0]),
+        Dash::dotted(),
+        Dash::new(&[8.0, 2.0, 2.0, 2.0]),
+    ];
+    for (i, dash) in dashes.iter().enumerate() {
+        let y = 24 + i as i32 * 10;
+        draw::draw_line_dashed(s, 8, y, 120, y, dash, INK);
+    }
+    draw::draw_rect_dashed(s, 132, 20, 60, 40, &dashes[0], INK);
+    draw::draw_circle_dashed(s, 226, 40, 22, &dashes[1], INK);
+    draw::draw_circle_dashed(s, 226, 40, 14, &dashes[2], INK);
+
+    // A zigzag: the pattern runs on around every corner. Continuing with the returned
+    // phase draws a second zigzag as if it were part of the first.
+    let zig: Vec<(i32, i32)> = (0..7).map(|i| (260 + i * 14, 20 + (i % 2) * 36)).collect();
+    let phase = draw::draw_polyline_dashed(s, &zig, false, &dashes[3], INK);
+    let zag: Vec<(i32, i32)> = zig.iter().map(|&(x, y)| (x, y + 8)).collect();
+    draw::draw_polyline_dashed(s, &zag, false, &dashes[3].clone().with_phase(phase), INK);
+}
+
+/// Dashed vector strokes; the straight lines step the phase so their dashes interleave.
+fn strokes(s: &mut Surface) {
+    text::draw_text(s, 8, 74, "DASHED PATH STROKES", INK);
+    let mut wave = Path::new();
+    wave.move_to(12.0, 110.0)
+        .cubic_to(60.0, 70.0, 100.0, 150.0, 150.0, 110.0)
+        .cubic_to(200.0, 70.0, 240.0, 150.0, 290.0, 110.0);
+    let dash = Dash::new(&[14.0, 6.0]);
+    let stroke = Stroke::new(4.0).with_cap(LineCap::Round);
+    path::stroke_path_dashed_aa(s, &wave, &stroke, &dash, INK);
+    for i in 0..3 {
+        let dy = 18.0 + 8.0 * i as f32;
+        let mut p = Path::new();
+        p.move_to(12.0, 110.0 + dy).line_to(290.0, 110.0 + dy);
+        let d = Dash::new(&[10.0, 10.0]).with_phase(i as f32 * 5.0);
+        path::stroke_path_dashed(s, &p, &Stroke::new(2.0), &d, INK);
+    }
+    let mut ring = Path::new();
+    ring.arc(350.0, 120.0, 30.0, 0.0, TAU).close();
+    // Near-zero dashes with round caps give a row of dots.
+    let dots = Dash::new(&[0.01, 6.0]);
+    let stroke = Stroke::new(3.0).with_cap(LineCap::Round);
+    path::stroke_path_dashed_aa(s, &ring, &stroke, &dots, INK);
+}
+
+/// A bar chart and a pie chart whose series differ only by fill pattern.
+fn charts(s: &mut Surface) {
+    text::draw_text(s, 8, 166, "MONOCHROME CHARTS", INK);
+    let fills = [
+        Paint::pattern(patterns::DIAGONAL, INK, PAPER),
+        Paint::pattern(patterns::DOTS, INK, PAPER),
+        Paint::hatch(-FRAC_PI_4, 6.0, 2.0, INK, PAPER),
+        Paint::pattern(patterns::CHECKER, INK, CLEAR),
+        Paint::hatch(0.0, 4.0, 1.0, INK, PAPER),
+        Paint::pattern(patterns::BRICK, INK, PAPER),
+    ];
+    let values = [0.3, 0.15, 0.2, 0.1, 0.15, 0.1];
+
+    let base = 290;
+    let grid = Dash::new(&[2.0, 3.0]);
+    for y in (base - 100..base).step_by(25) {
+        draw::draw_line_dashed(s, 12, y, 210, y, &grid, INK);
+    }
+    draw::draw_line(s, 12, base, 210, base, INK);
+    for (i, (fill, v)) in fills.iter().zip(values).enumerate() {
+        let h = (v * 300.0) as i32;
+        let x = 18 + i as i32 * 32;
+        draw::fill_rect_paint(s, x, base - h, 24, h, fill);
+        draw::draw_rect(s, x, base - h, 24, h, INK);
+    }
+
+    let (cx, cy, r) = (300.0, 236.0, 56.0);
+    let mut start = -TAU / 4.0;
+    for (fill, v) in fills.iter().zip(values) {
+        let end = start + v * TAU;
+        let mut slice = Path::new();
+        slice.move_to(cx, cy).arc(cx, cy, r, start, end).close();
+        path::fill_path_paint(s, &slice, FillRule::NonZero, fill);
+        path::stroke_path_aa(s, &slice, &Stroke::new(1.5), INK);
+        start = end;
+    }
+    for (i, fill) in fills.iter().enumerate() {
+        let (x, y) = (370, 184 + i as i32 * 18);
+        draw::fill_rect_paint(s, x, y, 14, 14, fill);
+        draw::draw_rect(s, x, y, 14, 14, INK);
+        let label = format!("{:.0}%", values[i] * 100.0);
+        text::draw_text(s, x + 20, y + 3, &label, INK);
+    }
+}
+
+fn main() {
+    let mut s = Surface::new(440, 300);
+    s.clear(PAPER);
+    outlines(&mut s);
+    strokes(&mut s);
+    charts(&mut s);
+
+    let out_dir = out::example_output_dir("patterns").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("patterns.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("patterns.bmp")).expect("failed to write BMP");
+}
@@ -0,0 +1,105 @@
+use kdev::out;
+use kpix::color::palette;
+use kpix::format::{Gray8, Gray16, Indexed, PixelFormat, Rgb565, Rgba8};
+use kpix::paint::Gradient;
+use kpix::{Color, Paint, Surface, blit, draw, io, text};
+
+const W: usize = 160;
+const H: usize = 120;
+
+/// The same scene in every format: a gradient sky, a sun, and a few outlined shapes.
+fn scene<P: PixelFormat>(s: &mut Surface<P>) {
+    let sky = Gradient::two(palette::MIDNIGHT_BLUE, palette::CORAL);
+    draw::fill_rect_paint(
+        s,
+        0,
+        0,
+        W as i32,
+        H as i32,
+        &Paint::linear(0.0, 0.0, 0.0, H as f32, sky),
+    );
+    draw::fill_circle_aa(s, 110, 70, 24, palette::GOLD);
+    draw::fill_rect(s, 0, 90, W as i32, 30, palette::DARK_OLIVE_GREEN);
+    draw::draw_circle_aa(s, 40, 40, 18, palette::WHITE);
+    draw::fill_circle_aa(s, 40, 40, 10, Color::rgba(255, 255, 255, 128));
+    draw::draw_line(s, 0, 90, 159, 60, palette::BLACK);
+}
+
+/// Copy a panel into the output at grid cell `(col, row)` with a caption.
+fn place<P: PixelFormat>(dst: &mut Surface, panel: &Surface<P>, col: i32, row: i32, label: &str) {
+    let (x, y) = (8 + col * (W as i32 + 8), 8 + row * (H as i32 + 20));
+    blit::blit(panel, panel.bounds(), dst, x, y);
+    text::draw_text(
+        dst,
+        x,
+        y + H as i32 + 4,
+        label,
+        Color::rgba(220, 220, 220, 255),
+    );
+}
+
+fn main() {
+    let mut out_s = Surface::new(3 * (W + 8) + 8, 2 * (H + 20) + 8);
+    out_s.clear(Color::rgba(24, 24, 28, 255));
+
+    let mut rgba = Surface::with_format(W, H, Rgba8);
+    scene(&mut rgba);
+    place(&mut out_s, &rgba, 0, 0, "RGBA8");
+
+    let mut gray = Surface::with_format(W, H, Gray8);
+    scene(&mut gray);
+    place(&mut out_s, &gray, 1, 0, "GRAY8");
+
+    let mut rgb565 = Surface::with_format(W, H, Rgb565);
+    scene(&mut rgb565);
+    place(&mut out_s, &rgb565, 2, 0, "RGB565");
+
+    // A 16-color palette: blends and gradients snap to the nearest entry.
+    let retro = Indexed::new([
+        palette::BLACK,
+        palette::MIDNIGHT_BLUE,
+        palette::NAVY,
+        palette::INDIGO,
+        palette::PURPLE,
+        palette::MEDIUM_VIOLET_RED,
+        palette::CRIMSON,
+        palette::CORAL,
+        palette::ORANGE,
+        palette::GOLD,
+        palette::KHAKI,
+        palette::DARK_OLIVE_GREEN,
+        palette::OLIVE_DRAB,
+        palette::GRAY,
+        palette::SILVER,
+        palette::WHITE,
+    ]);
+    let mut indexed = Surface::with_format(W, H, retro);
+    scene(&mut indexed);
+    place(&mut out_s, &indexed, 0, 1, "INDEXED 16");
+
+    // Palette edits recolor the stored indices without redrawing.
+    let mut night = indexed.clone();
+    let colors = night.format().palette().to_vec();
+    for (i, c) in colors.into_iter().enumerate() {
+        let dim = Color::rgba(c.r / 3, c.g / 3, (c.b / 2).saturating_add(40), 255);
+        night.format_mut().set_color(i as u8, dim);
+    }
+    place(&mut out_s, &night, 1, 1, "PALETTE SWAP");
+
+    // 16-bit heightmap written with raw values, shown through a Gray8 conversion.
+    let mut height = Surface::with_format(W, H, Gray16);
+    for y in 0..H as i32 {
+        for x in 0..W as i32 {
+            let (dx, dy) = (x as f32 - 80.0, y as f32 - 60.0);
+            let d = (dx * dx + dy * dy).sqrt();
+            let v = ((d * 0.25).cos() * 0.5 + 0.5) * (1.0 - d / 110.0).max(0.0);
+            height.set_raw(x, y, (v * u16::MAX as f32) as u16);
+        }
+    }
+    place(&mut out_s, &height.convert(Gray8), 2, 1, "GRAY16 HEIGHT");
+
+    let out_dir =
+        out::example_output_dir("pixel_formats").expect("failed to create output directory");
+    io::write_ppm(&out_s, out_dir.join("pixel_formats.ppm")).expect("failed to write PPM");
+    io::write_bmp(&out_s, out_dir.join("pixel_formats.bmp")).expect("failed to write BMP");
+}
@@ -0,0 +1,111 @@
+use kdev::out;
+use kpix::filter::{self, EdgeMode};
+use kpix::plot::Chart;
+use kpix::{Color, Rect, Surface, draw, io};
+use krand::Krand;
+use ktime::Stopwatch;
+
+const BLUE: Color = Color::rgba(52, 110, 220, 255);
+const ORANGE: Color = Color::rgba(236, 130, 40, 255);
+const GREEN: Color = Color::rgba(60, 160, 90, 255);
+const GRAY: Color = Color::rgba(120, 120, 130, 255);
+
+/// Like `krand`'s `stats` example: the running mean of uniform samples settles at 0.5.
+fn running_mean(rng: &mut Krand) -> Chart {
+    let (mut sum, mut points) = (0.0, Vec::new());
+    for i in 1..=2000 {
+        sum += rng.next_f32_0_1() as f64;
+        if i % 10 == 0 {
+            points.push((i as f64, sum / i as f64));
+        }
+    }
+    let mut c = Chart::new()
+        .with_title("Running mean of U(0,1)")
+        .with_x_label("samples")
+        .with_y_label("mean")
+        .with_y_range(0.3, 0.7);
+    c.line("mean", &points, BLUE)
+        .line("theory", &[(0.0, 0.5), (2000.0, 0.5)], GRAY);
+    c
+}
+
+/// One uniform draw against the sum of three: the sum piles up in the middle.
+fn histograms(rng: &mut Krand) -> Chart {
+    let one: Vec<f64> = (0..5000).map(|_| rng.next_f32_0_1() as f64 * 3.0).collect();
+    let three: Vec<f64> = (0..5000)
+        .map(|_| (0..3).map(|_| rng.next_f32_0_1() as f64).sum())
+        .collect();
+    let mut c = Chart::new()
+        .with_title("Histogram, 5000 samples")
+        .with_x_label("value")
+        .with_y_label("count");
+    c.histogram("3U", &one, 24, Color::rgba(52, 110, 220, 160))
+        .histogram("U+U+U", &three, 24, Color::rgba(236, 130, 40, 180));
+    c
+}
+
+/// Consecutive draws as (x, y) pairs should fill the square evenly.
+fn pairs(rng: &mut Krand) -> Chart {
+    let mut points = Vec::new();
+    let mut prev = rng.next_f32_0_1() as f64;
+    for _ in 0..300 {
+        let next = rng.next_f32_0_1() as f64;
+        points.push((prev, next));
+        prev = next;
+    }
+    let mut c = Chart::new()
+        .with_title("Consecutive pairs")
+        .with_x_label("x[i]")
+        .with_y_label("x[i+1]")
+        .with_x_range(0.0, 1.0)
+        .with_y_range(0.0, 1.0)
+        .with_legend(false);
+    c.scatter("", &points, GREEN);
+    c
+}
+
+/// Time a few kpix operations with `ktime` and chart the results.
+fn timings() -> Chart {
+    let mut src = Surface::new(256, 256);
+    draw::fill_circle_aa(&mut src, 128, 128, 100, BLUE);
+    let mut dst = Surface::new(256, 256);
+    let time = |f: &mut dyn FnMut()| {
+        let mut sw = Stopwatch::new();
+        sw.start();
+        for _ in 0..5 {
+            f();
+        }
+        sw.stop();
+        ktime::secs_f64(sw.elapsed()) * 1000.0 / 5.0
+    };
+    let fill = time(&mut || draw::fill_rect(&mut dst, 0, 0, 256, 256, ORANGE));
+    let circle = time(&mut || draw::fill_circle_aa(&mut dst, 128, 128, 100, GREEN));
+    let boxed = time(&mut || filter::box_blur(&src, &mut dst, 4, EdgeMode::Clamp));
+    let gauss = time(&mut || filter::gaussian_blur(&src, &mut dst, 3.0, EdgeMode::Clamp));
+    let mut c = Chart::new()
+        .with_title("256x256 timings")
+        .with_y_label("ms per call")
+        .with_categories(&["fill", "circle", "box", "gauss"]);
+    c.bars("measured", &[fill, circle, boxed, gauss], ORANGE);
+    c
+}
+
+fn main() {
+    let mut rng = Krand::new(123);
+    let charts = [
+        running_mean(&mut rng),
+        histograms(&mut rng),
+        pairs(&mut rng),
+        timings(),
+    ];
+    let (w, h) = (320, 240);
+    let mut s = Surface::new(2 * w, 2 * h);
+    for (i, chart) in charts.iter().enumerate() {
+        let (x, y) = ((i % 2) as i32 * w as i32, (i / 2) as i32 * h as i32);
+        chart.draw(&mut s, Rect::new(x, y, w as i32, h as i32));
+    }
+
+    let out_dir = out::example_output_dir("plot").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("plot.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("plot.bmp")).expect("failed to write BMP");
+}
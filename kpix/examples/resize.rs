@@ -0,0 +1,119 @@
+use kdev::out;
+use kpix::blit::{self, BlendMode, BlitOptions};
+use kpix::resample::{ResizeKernel, ResizeOptions};
+use kpix::{Color, Surface, draw, io, text};
+
+/// 12x12 pixel-art face on a transparent background.
+fn face() -> Surface {
+    let mut s = Surface::new(12, 12);
+    draw::fill_circle(&mut s, 6, 6, 5, Color::rgba(250, 200, 40, 255));
+    draw::draw_circle(&mut s, 6, 6, 5, Color::rgba(120, 60, 10, 255));
+    draw::fill_rect(&mut s, 4, 4, 1, 2, Color::rgba(40, 20, 10, 255));
+    draw::fill_rect(&mut s, 7, 4, 1, 2, Color::rgba(40, 20, 10, 255));
+    draw::draw_line(&mut s, 4, 8, 7, 8, Color::rgba(200, 40, 40, 255));
+    s
+}
+
+/// Zone plate: concentric rings whose frequency rises toward the edge. Aliasing shows up as
+/// phantom ring patterns when it is shrunk badly.
+fn zone_plate(size: usize) -> Surface {
+    let mut s = Surface::new(size, size);
+    let k = std::f32::consts::PI / size as f32;
+    for y in 0..size as i32 {
+        for x in 0..size as i32 {
+            let (dx, dy) = (x as f32 - size as f32 / 2.0, y as f32 - size as f32 / 2.0);
+            let v = ((dx * dx + dy * dy) * k).cos() * 0.5 + 0.5;
+            let g = (v * 255.0).round() as u8;
+            s.set_pixel(x, y, Color::rgba(g, g, g, 255));
+        }
+    }
+    s
+}
+
+/// Background checkerboard so transparency (and color bleeding) is visible.
+fn checker(s: &mut Surface, x: i32, y: i32, w: i32, h: i32) {
+    for cy in (0..h).step_by(6) {
+        for cx in (0..w).step_by(6) {
+            let v = if (cx / 6 + cy / 6) % 2 == 0 { 90 } else { 120 };
+            let c = Color::rgba(v, v, v, 255);
+            draw::fill_rect(s, x + cx, y + cy, 6.min(w - cx), 6.min(h - cy), c);
+        }
+    }
+}
+
+fn main() {
+    let label = Color::rgba(220, 220, 220, 255);
+    let mut s = Surface::new(8 + 6 * 84, 236);
+    s.clear(Color::rgba(28, 28, 34, 255));
+    let alpha = BlitOptions::new().with_mode(BlendMode::Alpha);
+
+    // Upscaling pixel art 6x.
+    let sprite = face();
+    let up = [
+        ("NEAREST", ResizeKernel::Nearest),
+        ("BILINEAR", ResizeKernel::Bilinear),
+        ("CATMULL", ResizeKernel::CatmullRom),
+        ("MITCHELL", ResizeKernel::Mitchell),
+        ("LANCZOS3", ResizeKernel::Lanczos3),
+        ("BOX", ResizeKernel::Box),
+    ];
+    for (i, (name, kernel)) in up.into_iter().enumerate() {
+        let x = 8 + i as i32 * 84;
+        checker(&mut s, x, 8, 72, 72);
+        let big = sprite.resize(72, 72, kernel);
+        blit::blit_ex(&big, big.bounds(), &mut s, x, 8, &alpha);
+        text::draw_text(&mut s, x, 84, name, label);
+    }
+
+    // Shrinking a 288px zone plate to 72px.
+    let plate = zone_plate(288);
+    let down = [
+        (
+            "NEAREST",
+            ResizeOptions::new().with_kernel(ResizeKernel::Nearest),
+        ),
+        (
+            "BILINEAR",
+            ResizeOptions::new().with_kernel(ResizeKernel::Bilinear),
+        ),
+        (
+            "MITCHELL",
+            ResizeOptions::new().with_kernel(ResizeKernel::Mitchell),
+        ),
+        (
+            "LANCZOS3",
+            ResizeOptions::new().with_kernel(ResizeKernel::Lanczos3),
+        ),
+        ("BOX", ResizeOptions::new().with_kernel(ResizeKernel::Box)),
+        (
+            "BOX LIN",
+            ResizeOptions::new()
+                .with_kernel(ResizeKernel::Box)
+                .with_linear(true),
+        ),
+    ];
+    for (i, (name, opts)) in down.into_iter().enumerate() {
+        let x = 8 + i as i32 * 84;
+        let small = plate.resize_ex(72, 72, &opts);
+        blit::blit(&small, small.bounds(), &mut s, x, 104);
+        text::draw_text(&mut s, x, 180, name, label);
+    }
+    text::draw_text(
+        &mut s,
+        8,
+        200,
+        "TOP: 12PX SPRITE AT 6X   BOTTOM: 288PX ZONE PLATE AT 1/4",
+        label,
+    );
+    text::draw_text(
+        &mut s,
+        8,
+        214,
+        "BOX LIN = AREA AVERAGE IN LINEAR LIGHT",
+        label,
+    );
+
+    let out_dir = out::example_output_dir("resize").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("resize.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("resize.bmp")).expect("failed to write BMP");
+}
@@ -0,0 +1,69 @@
+use kdev::out;
+use kpix::blit::{self, BlitOptions};
+use kpix::{Color, Rect, Surface, draw, io, text};
+
+const W: usize = 480;
+const H: usize = 320;
+
+/// Smooth-colored Mandelbrot escape time at pixel `(x, y)`.
+fn mandelbrot(x: usize, y: usize) -> Color {
+    let cx = (x as f64 / W as f64) * 3.2 - 2.2;
+    let cy = (y as f64 / H as f64) * 2.2 - 1.1;
+    let (mut zx, mut zy) = (0.0f64, 0.0f64);
+    for i in 0..256 {
+        let (x2, y2) = (zx * zx, zy * zy);
+        if x2 + y2 > 256.0 {
+            let mu = i as f64 + 1.0 - ((x2 + y2).ln() / 2.0).ln() / std::f64::consts::LN_2;
+            return Color::from_hsv((mu * 9.0) as f32 % 360.0, 0.8, 1.0);
+        }
+        zy = 2.0 * zx * zy + cy;
+        zx = x2 - y2 + cx;
+    }
+    Color::rgba(0, 0, 0, 255)
+}
+
+fn main() {
+    let label = Color::rgba(240, 240, 240, 255);
+    let mut s = Surface::new(W, H);
+
+    // Every row is computed independently, so the whole frame runs in parallel.
+    s.par_rows_mut(|y, row| {
+        for (x, p) in row.iter_mut().enumerate() {
+            *p = mandelbrot(x, y).to_u32();
+        }
+    });
+
+    // Post-process a sub-rect in place: grayscale on the left, inverted on the right.
+    let gray = Rect::new(20, 180, 140, 120);
+    s.view_mut(gray).par_rows_mut(|_, row| {
+        for p in row {
+            let c = Color::from_u32(*p);
+            let l = ((c.r as u32 * 77 + c.g as u32 * 150 + c.b as u32 * 29) >> 8) as u8;
+            *p = Color::rgba(l, l, l, 255).to_u32();
+        }
+    });
+    let invert = Rect::new(320, 180, 140, 120);
+    for row in s.view_mut(invert).rows_mut() {
+        for p in row {
+            let c = Color::from_u32(*p);
+            *p = Color::rgba(255 - c.r, 255 - c.g, 255 - c.b, 255).to_u32();
+        }
+    }
+
+    // Copy a detail out through a read-only view and show it enlarged.
+    let detail = s.view(Rect::new(100, 120, 40, 30)).to_surface();
+    let zoom = Rect::new(180, 180, 120, 90);
+    blit::blit_scaled(&detail, detail.bounds(), &mut s, zoom, &BlitOptions::new());
+
+    for r in [gray, invert, zoom, Rect::new(99, 119, 42, 32)] {
+        draw::draw_rect(&mut s, r.x, r.y, r.w, r.h, label);
+    }
+    text::draw_text(&mut s, 20, 168, "GRAY VIEW", label);
+    text::draw_text(&mut s, 180, 168, "ZOOM 3X", label);
+    text::draw_text(&mut s, 320, 168, "INVERT VIEW", label);
+    text::draw_text(&mut s, 8, 8, "PAR_ROWS_MUT MANDELBROT", label);
+
+    let out_dir = out::example_output_dir("shader").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("shader.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("shader.bmp")).expect("failed to write BMP");
+}
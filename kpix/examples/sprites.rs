@@ -0,0 +1,141 @@
+use std::time::Duration;
+
+use kdev::out;
+use kpix::blit::{self, BlitOptions, Rotation};
+use kpix::color::palette;
+use kpix::sprite::{Animation, AtlasBuilder, PlayMode, SpriteSheet};
+use kpix::{Color, Rect, Surface, draw, io, text};
+
+/// Four-frame walk cycle, 16x16 cells side by side.
+fn walker() -> Surface {
+    let mut s = Surface::new(64, 16);
+    let legs = [(-3, 3), (-1, 1), (3, -3), (1, -1)];
+    for (i, (l, r)) in legs.into_iter().enumerate() {
+        let x = i as i32 * 16;
+        draw::fill_circle(&mut s, x + 8, 4, 3, palette::PEACH_PUFF);
+        draw::fill_rect(&mut s, x + 6, 7, 4, 5, palette::ROYAL_BLUE);
+        draw::fill_rect(&mut s, x + 10, 8, 3, 2, palette::PEACH_PUFF); // arm, facing right
+        draw::draw_line(&mut s, x + 7, 12, x + 7 + l, 15, palette::SADDLE_BROWN);
+        draw::draw_line(&mut s, x + 8, 12, x + 8 + r, 15, palette::SADDLE_BROWN);
+    }
+    s
+}
+
+/// A loose image of the given size: a filled gem with a highlight.
+fn gem(w: usize, h: usize, color: Color) -> Surface {
+    let mut s = Surface::new(w, h);
+    let (cx, cy) = (w as i32 / 2, h as i32 / 2);
+    draw::fill_ellipse_aa(&mut s, cx, cy, cx - 1, cy - 1, color);
+    draw::fill_circle_aa(
+        &mut s,
+        cx / 2 + 1,
+        cy / 2 + 1,
+        1,
+        Color::rgba(255, 255, 255, 200),
+    );
+    s
+}
+
+fn main() {
+    let label = Color::rgba(220, 220, 220, 255);
+    let mut s = Surface::new(520, 205);
+    s.clear(Color::rgba(30, 32, 40, 255));
+
+    // Pack loose images into one atlas and show it at 2x with frame outlines.
+    let colors = [
+        palette::CRIMSON,
+        palette::GOLD,
+        palette::LIME_GREEN,
+        palette::DEEP_SKY_BLUE,
+        palette::ORCHID,
+        palette::ORANGE,
+    ];
+    let mut builder = AtlasBuilder::new(64).with_padding(1);
+    for i in 0..18 {
+        let (w, h) = (6 + (i * 7) % 15, 6 + (i * 5) % 13);
+        builder.add(&format!("gem{i}"), &gem(w, h, colors[i % colors.len()]));
+    }
+    let walk = walker();
+    for f in 0..4 {
+        let frame = Rect::new(f * 16, 0, 16, 16);
+        let mut img = Surface::new(16, 16);
+        blit::blit(&walk, frame, &mut img, 0, 0);
+        builder.add(&format!("walk{f}"), &img);
+    }
+    let atlas = builder.build().expect("gems fit the atlas width");
+    text::draw_text(&mut s, 8, 6, "ATLAS", label);
+    let area = Rect::new(8, 18, 128, atlas.surface().height() as i32 * 2);
+    draw::fill_rect(
+        &mut s,
+        area.x,
+        area.y,
+        area.w,
+        area.h,
+        Color::rgba(0, 0, 0, 255),
+    );
+    let sheet_px = atlas.surface();
+    blit::blit_scaled(
+        sheet_px,
+        sheet_px.bounds(),
+        &mut s,
+        area,
+        &BlitOptions::new(),
+    );
+    for i in 0..atlas.len() {
+        let r = atlas.frame(i).unwrap();
+        let o = Color::rgba(255, 255, 255, 70);
+        let (x, y) = (area.x + r.x * 2, area.y + r.y * 2);
+        draw::draw_rect(&mut s, x, y, r.w * 2, r.h * 2, o);
+    }
+
+    // Playback modes sampled every 100ms.
+    let walk = SpriteSheet::from_grid(walk, 16, 16);
+    let anim = Animation::uniform([0, 1, 2, 3], Duration::from_millis(100));
+    let modes = [
+        ("LOOP", PlayMode::Loop),
+        ("PING-PONG", PlayMode::PingPong),
+        ("ONCE", PlayMode::Once),
+    ];
+    text::draw_text(&mut s, 160, 6, "ANIMATION, 100MS STEPS", label);
+    for (row, (name, mode)) in modes.into_iter().enumerate() {
+        let y = 20 + row as i32 * 40;
+        text::draw_text(&mut s, 160, y + 12, name, label);
+        let mut a = anim.clone().with_mode(mode);
+        for step in 0..10 {
+            let x = 240 + step * 26;
+            draw::fill_rect(&mut s, x - 2, y - 2, 20, 20, Color::rgba(50, 54, 66, 255));
+            walk.draw(&mut s, a.frame(), x, y);
+            a.update(Duration::from_millis(100));
+        }
+    }
+
+    // The same frame flipped and rotated.
+    text::draw_text(&mut s, 160, 146, "FLIP / ROTATE (2X)", label);
+    let variants = [
+        BlitOptions::new(),
+        BlitOptions::new().with_flip(true, false),
+        BlitOptions::new().with_flip(false, true),
+        BlitOptions::new().with_rotation(Rotation::Cw90),
+    ];
+    for (i, opts) in variants.iter().enumerate() {
+        let mut tile = Surface::new(16, 16);
+        walk.draw_ex(&mut tile, 1, 0, 0, opts);
+        let dst = Rect::new(160 + i as i32 * 44, 160, 32, 32);
+        draw::fill_rect(
+            &mut s,
+            dst.x,
+            dst.y,
+            dst.w,
+            dst.h,
+            Color::rgba(50, 54, 66, 255),
+        );
+        let alpha = BlitOptions::new().with_mode(blit::BlendMode::Alpha);
+        blit::blit_scaled(&tile, tile.bounds(), &mut s, dst, &alpha);
+    }
+
+    let out_dir = out::example_output_dir("sprites").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("sprites.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("sprites.bmp")).expect("failed to write BMP");
+    std::fs::write(out_dir.join("sprites_atlas.txt"), atlas.to_manifest())
+        .expect("failed to write manifest");
+}
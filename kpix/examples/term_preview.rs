@@ -0,0 +1,52 @@
+use kpix::color::palette;
+use kpix::paint::{Gradient, GradientStop};
+use kpix::term::{self, ColorMode, TermOptions};
+use kpix::text::{BitmapFont, TextStyle};
+use kpix::{Color, Paint, Surface, draw, text};
+
+fn main() {
+    let mode = match std::env::args().nth(1).as_deref() {
+        None => ColorMode::detect(),
+        Some("truecolor") => ColorMode::TrueColor,
+        Some("256") => ColorMode::Ansi256,
+        Some("16") => ColorMode::Ansi16,
+        Some(other) => {
+            eprintln!("色数は truecolor・256・16 のいずれかで指定してください: {other}");
+            return;
+        }
+    };
+
+    let mut s = Surface::new(240, 120);
+    let sky = Gradient::two(palette::MIDNIGHT_BLUE, palette::LIGHT_SALMON);
+    draw::fill_rect_paint(
+        &mut s,
+        0,
+        0,
+        240,
+        120,
+        &Paint::linear(0.0, 0.0, 0.0, 120.0, sky),
+    );
+    let hue = Paint::conic(
+        60.0,
+        60.0,
+        0.0,
+        Gradient::new((0..=6).map(|i| {
+            GradientStop::new(i as f32 / 6.0, Color::from_hsv(i as f32 * 60.0, 1.0, 1.0))
+        })),
+    );
+    draw::fill_circle_paint(&mut s, 60, 60, 40, &hue);
+    draw::fill_circle_aa(&mut s, 170, 70, 30, palette::GOLD);
+    draw::fill_rect(&mut s, 0, 100, 240, 20, palette::DARK_OLIVE_GREEN);
+    let big = TextStyle::new().with_scale(3);
+    let font = BitmapFont::builtin_8x8();
+    text::draw_text_styled(&mut s, font, 120, 10, "KPIX", Color::WHITE, &big);
+
+    // Fit the terminal, leaving a row for the shell prompt.
+    let (cols, rows) = kpix::term::terminal_size()
+        .or_else(kdev::term::stty_size)
+        .unwrap_or((80, 24));
+    let opts = TermOptions::new()
+        .with_mode(mode)
+        .with_fit(cols, rows.saturating_sub(1));
+    print!("{}", term::to_ansi(&s, &opts));
+}
@@ -0,0 +1,53 @@
+use kdev::out;
+use kpix::text::{self, Align, BitmapFont, TextStyle};
+use kpix::{Color, Rect, Surface, draw, io};
+
+fn main() {
+    let mut s = Surface::new(320, 240);
+    s.clear(Color::rgba(20, 20, 30, 255));
+
+    let white = Color::rgba(235, 235, 235, 255);
+    let gold = Color::rgba(230, 190, 60, 255);
+    let teal = Color::rgba(70, 190, 180, 255);
+    let f8 = BitmapFont::builtin_8x8();
+    let f16 = BitmapFont::builtin_8x16();
+
+    // Default 8x8 text and a measured background plate
+    let label = "FPS 60.0";
+    let (w, h) = text::measure_text(label);
+    draw::fill_rect(&mut s, 6, 6, w + 4, h + 4, Color::rgba(60, 60, 90, 255));
+    text::draw_text(&mut s, 8, 8, label, white);
+
+    // Integer scales with both built-in fonts
+    text::draw_text_styled(
+        &mut s,
+        f8,
+        8,
+        28,
+        "Scale 2",
+        gold,
+        &TextStyle::new().with_scale(2),
+    );
+    text::draw_text_styled(&mut s, f16, 8, 50, "8x16 font", teal, &TextStyle::new());
+    text::draw_text_styled(
+        &mut s,
+        f16,
+        312,
+        8,
+        "right\naligned",
+        white,
+        &TextStyle::new().with_align(Align::Right),
+    );
+
+    // Wrapped, centered text in a box
+    let rect = Rect::new(20, 90, 280, 130);
+    draw::draw_rect(&mut s, rect.x - 1, rect.y - 1, rect.w + 2, rect.h + 2, teal);
+    let body = "The quick brown fox jumps over the lazy dog. \
+                Pack my box with five dozen liquor jugs!\n\n0123456789 !?#$%&*+-/<=>@[]{}";
+    let style = TextStyle::new().with_align(Align::Center).with_line_gap(2);
+    text::draw_text_box(&mut s, f8, rect, body, white, &style);
+
+    let out_dir = out::example_output_dir("text").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("text.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("text.bmp")).expect("failed to write BMP");
+}
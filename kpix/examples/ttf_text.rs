@@ -0,0 +1,59 @@
+use kdev::out;
+use kpix::text::{self, GlyphCache, TtfFont};
+use kpix::{Color, Surface, draw, io};
+
+const DEFAULT_FONT: &str = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+
+fn main() {
+    // Usage: ttf_text [path/to/font.ttf]
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_FONT.to_string());
+    let font = match TtfFont::load(&path) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("フォントを読み込めません（{path}）: {e}");
+            eprintln!("TrueType フォントのパスを引数で指定してください");
+            return;
+        }
+    };
+    let mut cache = GlyphCache::new(font);
+
+    let mut s = Surface::new(480, 320);
+    s.clear(Color::rgba(250, 248, 240, 255));
+    let ink = Color::rgba(30, 30, 40, 255);
+    let accent = Color::rgba(200, 60, 80, 255);
+
+    // Size ramp
+    let mut y = 8;
+    for px in [10.0, 14.0, 20.0, 28.0, 40.0] {
+        text::draw_text_ttf(
+            &mut s,
+            &mut cache,
+            12,
+            y,
+            &format!("{px}px Hamburgefonts"),
+            px,
+            ink,
+        );
+        y += cache.font().line_height(px).ceil() as i32;
+    }
+
+    // Kerning pairs and composite (accented) glyphs, with a measured underline
+    let sample = "AVATAR To Wa\néàüÅ ñç";
+    text::draw_text_ttf(&mut s, &mut cache, 12, 190, sample, 32.0, accent);
+    let (w, h) = text::measure_text_ttf(cache.font(), sample, 32.0);
+    draw::draw_rect(
+        &mut s,
+        11,
+        189,
+        w + 2,
+        h + 2,
+        Color::rgba(120, 120, 140, 255),
+    );
+
+    println!("cached glyph bitmaps: {}", cache.len());
+    let out_dir = out::example_output_dir("ttf_text").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("ttf_text.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("ttf_text.bmp")).expect("failed to write BMP");
+}
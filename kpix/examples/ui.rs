@@ -0,0 +1,111 @@
+use kdev::out;
+use kpix::draw::{self, CornerRadii};
+use kpix::ui::{Input, Key, NineSlice, Ui, UiState};
+use kpix::{Color, Rect, Surface, io, text};
+
+/// 12x12 skin: dark body, light bevel, 4px corners.
+fn skin() -> Surface {
+    let mut s = Surface::new(12, 12);
+    let r = CornerRadii::all(4);
+    draw::fill_round_rect(&mut s, 0, 0, 12, 12, r, Color::rgba(120, 90, 50, 255));
+    draw::fill_round_rect(&mut s, 1, 1, 10, 10, r, Color::rgba(58, 44, 30, 255));
+    draw::draw_round_rect(
+        &mut s,
+        2,
+        2,
+        8,
+        8,
+        CornerRadii::all(2),
+        Color::rgba(200, 160, 90, 255),
+    );
+    s
+}
+
+#[derive(Default)]
+struct Settings {
+    grid: bool,
+    snap: bool,
+    zoom: f32,
+    opacity: f32,
+    clicks: u32,
+}
+
+fn frame(state: &mut UiState, s: &mut Surface, input: &Input, app: &mut Settings, skin: &Surface) {
+    s.clear(Color::rgba(18, 20, 26, 255));
+    let mut ui = Ui::new(state, s, input);
+    ui.panel(Rect::new(8, 8, 220, 136), |ui| {
+        ui.label("VIEW");
+        ui.checkbox("SHOW GRID", &mut app.grid);
+        ui.checkbox("SNAP", &mut app.snap);
+        ui.slider("ZOOM", &mut app.zoom, 0.25..=4.0);
+        ui.slider("ALPHA", &mut app.opacity, 0.0..=1.0);
+        ui.horizontal(|ui| {
+            if ui.button("APPLY").clicked {
+                app.clicks += 1;
+            }
+            ui.button("RESET");
+            ui.button("HELP");
+        });
+    });
+    let nine = NineSlice::new(skin.bounds(), 4);
+    ui.panel_nine(skin, &nine, Rect::new(236, 8, 156, 136), |ui| {
+        ui.label("NINE-SLICE PANEL");
+        ui.space(4);
+        let clicks = format!("APPLY CLICKS: {}", app.clicks);
+        ui.label(&clicks);
+        ui.label(if app.grid { "GRID: ON" } else { "GRID: OFF" });
+        ui.vertical(|ui| {
+            ui.button("OK");
+            ui.button("CANCEL");
+        });
+    });
+}
+
+fn main() {
+    let skin = skin();
+    let mut s = Surface::new(400, 180);
+    let mut state = UiState::new();
+    let mut app = Settings {
+        zoom: 1.0,
+        opacity: 0.5,
+        ..Settings::default()
+    };
+
+    // A scripted session: Tab through the widgets to toggle SNAP and nudge ALPHA with the
+    // arrow keys, then tick SHOW GRID, drag the zoom slider and click APPLY twice, ending
+    // with the mouse hovering over RESET.
+    let at = |x, y| Input::new().with_mouse(x, y);
+    let key = |k| Input::new().with_key(k);
+    let script = [
+        at(0, 0),
+        key(Key::Tab),
+        key(Key::Tab),
+        key(Key::Space),
+        key(Key::Tab),
+        key(Key::Tab),
+        key(Key::Right).with_key(Key::Right),
+        at(18, 42).with_mouse_down(true),
+        at(18, 42),
+        at(20, 82).with_mouse_down(true),
+        at(60, 82).with_mouse_down(true),
+        at(92, 82).with_mouse_down(true),
+        at(92, 82),
+        at(24, 122).with_mouse_down(true),
+        at(24, 122),
+        at(24, 122).with_mouse_down(true),
+        at(24, 122),
+        at(86, 122),
+    ];
+    for input in &script {
+        frame(&mut state, &mut s, input, &mut app, &skin);
+    }
+    let note = format!(
+        "GRID={} SNAP={} ZOOM={:.2} ALPHA={:.2} CLICKS={}",
+        app.grid, app.snap, app.zoom, app.opacity, app.clicks
+    );
+    text::draw_text(&mut s, 8, 156, &note, Color::rgba(150, 156, 170, 255));
+
+    let out_dir = out::example_output_dir("ui").expect("failed to create output directory");
+    io::write_ppm(&s, out_dir.join("ui.ppm")).expect("failed to write PPM");
+    io::write_bmp(&s, out_dir.join("ui.bmp")).expect("failed to write BMP");
+}
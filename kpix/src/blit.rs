@@ -0,0 +1,390 @@
+//! Copying pixels between surfaces.
+//!
+//! Every blit walks the destination pixels that survive the clip rect and maps each one back
+//! into the source rect, so flips, quarter turns and scaling share one code path. Destination
+//! pixels whose source sample falls outside the source surface are left untouched.
+
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+use crate::rect::Rect;
+
+/// How source pixels are combined with the destination.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Overwrite destination pixels, alpha included.
+    #[default]
+    Replace,
+    /// Straight-alpha source-over, like `Surface::blend_pixel`.
+    Alpha,
+    /// Skip source pixels whose RGB equals the key (alpha is ignored); copy the rest.
+    ColorKey(Color),
+}
+
+/// Clockwise quarter turn applied after flipping.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    None,
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+impl Rotation {
+    fn swaps_axes(self) -> bool {
+        matches!(self, Rotation::Cw90 | Rotation::Cw270)
+    }
+}
+
+/// Sampling used when the destination size differs from the source.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Filter {
+    #[default]
+    Nearest,
+    /// Bilinear on premultiplied colors, clamped to the source rect.
+    Bilinear,
+}
+
+/// Blit options. Defaults: replace, no flips, no rotation, nearest filtering.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlitOptions {
+    pub mode: BlendMode,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub rotation: Rotation,
+    pub filter: Filter,
+}
+
+impl BlitOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mode(mut self, mode: BlendMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// Copy `src_rect` of `src` onto `dst` with its top-left corner at `(x, y)`.
+pub fn blit<S: PixelFormat, D: PixelFormat>(
+    src: &Surface<S>,
+    src_rect: Rect,
+    dst: &mut Surface<D>,
+    x: i32,
+    y: i32,
+) {
+    blit_ex(src, src_rect, dst, x, y, &BlitOptions::default());
+}
+
+/// Unscaled blit with blending, flips and rotation. A quarter turn swaps the footprint's
+/// width and height; `(x, y)` is always its top-left corner.
+pub fn blit_ex<S: PixelFormat, D: PixelFormat>(
+    src: &Surface<S>,
+    src_rect: Rect,
+    dst: &mut Surface<D>,
+    x: i32,
+    y: i32,
+    opts: &BlitOptions,
+) {
+    let (w, h) = if opts.rotation.swaps_axes() {
+        (src_rect.h, src_rect.w)
+    } else {
+        (src_rect.w, src_rect.h)
+    };
+    let opts = BlitOptions {
+        filter: Filter::Nearest,
+        ..*opts
+    };
+    blit_scaled(src, src_rect, dst, Rect::new(x, y, w, h), &opts);
+}
+
+/// Stretch `src_rect` of `src` (after flips and rotation) to cover `dst_rect`.
+pub fn blit_scaled<S: PixelFormat, D: PixelFormat>(
+    src: &Surface<S>,
+    src_rect: Rect,
+    dst: &mut Surface<D>,
+    dst_rect: Rect,
+    opts: &BlitOptions,
+) {
+    let valid = src_rect.intersect(src.bounds());
+    let target = dst_rect.intersect(dst.clip_rect());
+    if valid.is_empty() || target.is_empty() || src_rect.is_empty() {
+        return;
+    }
+    let (sw, sh) = (src_rect.w as f32, src_rect.h as f32);
+    let (ow, oh) = if opts.rotation.swaps_axes() {
+        (sh, sw)
+    } else {
+        (sw, sh)
+    };
+    let (kx, ky) = (ow / dst_rect.w as f32, oh / dst_rect.h as f32);
+    for dy in target.y..target.bottom() {
+        let oy = (dy - dst_rect.y) as f32 + 0.5;
+        for dx in target.x..target.right() {
+            let ox = (dx - dst_rect.x) as f32 + 0.5;
+            let (sx, sy) = to_source(ox * kx, oy * ky, sw, sh, opts);
+            let (px, py) = (src_rect.x as f32 + sx, src_rect.y as f32 + sy);
+            if !valid.contains(px.floor() as i32, py.floor() as i32) {
+                continue;
+            }
+            let color = match opts.filter {
+                Filter::Nearest => src.get_pixel(px as i32, py as i32),
+                Filter::Bilinear => Some(bilinear(src, valid, px, py, opts.mode)),
+            };
+            if let Some(c) = color {
+                write(dst, dx, dy, c, opts);
+            }
+        }
+    }
+}
+
+/// Undo rotation, then flips: oriented coordinates (in a `sw` x `sh` or rotated footprint)
+/// back to continuous source-rect coordinates.
+fn to_source(ox: f32, oy: f32, sw: f32, sh: f32, opts: &BlitOptions) -> (f32, f32) {
+    let (mut sx, mut sy) = match opts.rotation {
+        Rotation::None => (ox, oy),
+        Rotation::Cw90 => (oy, sh - ox),
+        Rotation::Cw180 => (sw - ox, sh - oy),
+        Rotation::Cw270 => (sw - oy, ox),
+    };
+    if opts.flip_x {
+        sx = sw - sx;
+    }
+    if opts.flip_y {
+        sy = sh - sy;
+    }
+    (sx, sy)
+}
+
+fn write<D: PixelFormat>(dst: &mut Surface<D>, x: i32, y: i32, c: Color, opts: &BlitOptions) {
+    match (opts.mode, opts.filter) {
+        (BlendMode::Replace, _) => dst.set_pixel(x, y, c),
+        (BlendMode::ColorKey(key), Filter::Nearest) => {
+            if !same_rgb(c, key) {
+                dst.set_pixel(x, y, c);
+            }
+        }
+        // Filtered color keying has already turned keyed texels transparent.
+        (BlendMode::Alpha, _) | (BlendMode::ColorKey(_), Filter::Bilinear) => {
+            dst.blend_pixel(x, y, c)
+        }
+    }
+}
+
+fn same_rgb(a: Color, b: Color) -> bool {
+    a.r == b.r && a.g == b.g && a.b == b.b
+}
+
+/// Bilinear sample at continuous position `(px, py)`, with taps clamped into `valid`.
+/// Color-keyed texels count as fully transparent.
+fn bilinear<S: PixelFormat>(
+    src: &Surface<S>,
+    valid: Rect,
+    px: f32,
+    py: f32,
+    mode: BlendMode,
+) -> Color {
+    let (fx, fy) = (px - 0.5, py - 0.5);
+    let (x0, y0) = (fx.floor(), fy.floor());
+    let (tx, ty) = (fx - x0, fy - y0);
+    let clamp_x = |x: i32| x.clamp(valid.x, valid.right() - 1);
+    let clamp_y = |y: i32| y.clamp(valid.y, valid.bottom() - 1);
+    let (x0, y0) = (x0 as i32, y0 as i32);
+    let taps = [
+        (clamp_x(x0), clamp_y(y0), (1.0 - tx) * (1.0 - ty)),
+        (clamp_x(x0 + 1), clamp_y(y0), tx * (1.0 - ty)),
+        (clamp_x(x0), clamp_y(y0 + 1), (1.0 - tx) * ty),
+        (clamp_x(x0 + 1), clamp_y(y0 + 1), tx * ty),
+    ];
+    let mut acc = [0.0f32; 4];
+    for (x, y, w) in taps {
+        let Some(c) = src.get_pixel(x, y) else {
+            continue;
+        };
+        let a = match mode {
+            BlendMode::ColorKey(key) if same_rgb(c, key) => 0.0,
+            _ => c.a as f32,
+        };
+        let wa = w * a;
+        acc[0] += c.r as f32 * wa;
+        acc[1] += c.g as f32 * wa;
+        acc[2] += c.b as f32 * wa;
+        acc[3] += wa;
+    }
+    if acc[3] <= 0.0 {
+        return Color::rgba(0, 0, 0, 0);
+    }
+    let channel = |v: f32| (v / acc[3] + 0.5).clamp(0.0, 255.0) as u8;
+    Color::rgba(
+        channel(acc[0]),
+        channel(acc[1]),
+        channel(acc[2]),
+        (acc[3] + 0.5).clamp(0.0, 255.0) as u8,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3x2 surface with distinct pixels: value = 10*y + x + 1 in the red channel.
+    fn sample() -> Surface {
+        let mut s = Surface::new(3, 2);
+        for y in 0..2 {
+            for x in 0..3 {
+                s.set_pixel(x, y, Color::rgba((10 * y + x + 1) as u8, 0, 0, 255));
+            }
+        }
+        s
+    }
+
+    fn reds(s: &Surface) -> Vec<Vec<u8>> {
+        (0..s.height() as i32)
+            .map(|y| {
+                (0..s.width() as i32)
+                    .map(|x| s.get_pixel(x, y).unwrap().r)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn copies_sub_rect_at_position() {
+        let src = sample();
+        let mut dst = Surface::new(4, 3);
+        blit(&src, Rect::new(1, 0, 2, 2), &mut dst, 2, 1);
+        assert_eq!(
+            reds(&dst),
+            vec![vec![0, 0, 0, 0], vec![0, 0, 2, 3], vec![0, 0, 12, 13]]
+        );
+    }
+
+    #[test]
+    fn honours_clip_and_source_bounds() {
+        let src = sample();
+        let mut dst = Surface::new(4, 2);
+        dst.push_clip(Rect::new(0, 0, 2, 2));
+        // Source rect hangs off the right of `src`; those pixels are skipped.
+        blit(&src, Rect::new(0, 0, 5, 2), &mut dst, -1, 0);
+        assert_eq!(reds(&dst), vec![vec![2, 3, 0, 0], vec![12, 13, 0, 0]]);
+    }
+
+    #[test]
+    fn flips_and_rotations() {
+        let src = sample();
+        let all = src.bounds();
+        let run = |opts: BlitOptions, w: usize, h: usize| {
+            let mut dst = Surface::new(w, h);
+            blit_ex(&src, all, &mut dst, 0, 0, &opts);
+            reds(&dst)
+        };
+        let o = BlitOptions::new();
+        assert_eq!(
+            run(o.with_flip(true, false), 3, 2),
+            vec![vec![3, 2, 1], vec![13, 12, 11]]
+        );
+        assert_eq!(
+            run(o.with_flip(false, true), 3, 2),
+            vec![vec![11, 12, 13], vec![1, 2, 3]]
+        );
+        assert_eq!(
+            run(o.with_rotation(Rotation::Cw90), 2, 3),
+            vec![vec![11, 1], vec![12, 2], vec![13, 3]]
+        );
+        assert_eq!(
+            run(o.with_rotation(Rotation::Cw180), 3, 2),
+            vec![vec![13, 12, 11], vec![3, 2, 1]]
+        );
+        assert_eq!(
+            run(o.with_rotation(Rotation::Cw270), 2, 3),
+            vec![vec![3, 13], vec![2, 12], vec![1, 11]]
+        );
+    }
+
+    #[test]
+    fn color_key_and_alpha_modes() {
+        let mut src = Surface::new(2, 1);
+        src.set_pixel(0, 0, Color::rgba(255, 0, 255, 255));
+        src.set_pixel(1, 0, Color::rgba(0, 0, 0, 128));
+        let bg = Color::rgba(200, 200, 200, 255);
+
+        let mut dst = Surface::new(2, 1);
+        dst.clear(bg);
+        let key = BlitOptions::new().with_mode(BlendMode::ColorKey(Color::rgba(255, 0, 255, 0)));
+        blit_ex(&src, src.bounds(), &mut dst, 0, 0, &key);
+        assert_eq!(dst.get_pixel(0, 0), Some(bg));
+        assert_eq!(dst.get_pixel(1, 0), Some(Color::rgba(0, 0, 0, 128)));
+
+        dst.clear(bg);
+        let alpha = BlitOptions::new().with_mode(BlendMode::Alpha);
+        blit_ex(&src, Rect::new(1, 0, 1, 1), &mut dst, 0, 0, &alpha);
+        assert_eq!(dst.get_pixel(0, 0), Some(Color::rgba(100, 100, 100, 255)));
+    }
+
+    #[test]
+    fn nearest_scaling_repeats_pixels() {
+        let src = sample();
+        let mut dst = Surface::new(6, 4);
+        let area = dst.bounds();
+        blit_scaled(&src, src.bounds(), &mut dst, area, &BlitOptions::new());
+        assert_eq!(reds(&dst)[0], vec![1, 1, 2, 2, 3, 3]);
+        assert_eq!(reds(&dst)[3], vec![11, 11, 12, 12, 13, 13]);
+    }
+
+    #[test]
+    fn bilinear_interpolates_between_texels() {
+        let mut src = Surface::new(2, 1);
+        src.set_pixel(0, 0, Color::rgba(0, 0, 0, 255));
+        src.set_pixel(1, 0, Color::rgba(200, 0, 0, 255));
+        let mut dst = Surface::new(4, 1);
+        let area = dst.bounds();
+        let opts = BlitOptions::new().with_filter(Filter::Bilinear);
+        blit_scaled(&src, src.bounds(), &mut dst, area, &opts);
+        // Outer pixels clamp to the edge texels, inner ones blend 1/4 and 3/4.
+        assert_eq!(reds(&dst)[0], vec![0, 50, 150, 200]);
+    }
+
+    #[test]
+    fn bilinear_ignores_color_of_transparent_texels() {
+        let mut src = Surface::new(2, 1);
+        src.set_pixel(0, 0, Color::rgba(255, 255, 255, 0));
+        src.set_pixel(1, 0, Color::rgba(0, 0, 200, 255));
+        let mut dst = Surface::new(4, 1);
+        let area = dst.bounds();
+        let opts = BlitOptions::new().with_filter(Filter::Bilinear);
+        blit_scaled(&src, src.bounds(), &mut dst, area, &opts);
+        let c = dst.get_pixel(1, 0).unwrap();
+        assert_eq!((c.r, c.g, c.b, c.a), (0, 0, 200, 64));
+    }
+
+    #[test]
+    fn blits_across_pixel_formats() {
+        use crate::format::Indexed;
+        let pal = Indexed::new([Color::rgba(0, 0, 0, 0), Color::rgba(0, 200, 0, 255)]);
+        let mut sprite = Surface::with_format(2, 1, pal);
+        sprite.set_raw(1, 0, 1);
+        let mut dst = Surface::new(2, 1);
+        dst.clear(Color::rgba(9, 9, 9, 255));
+        let opts = BlitOptions::new().with_mode(BlendMode::Alpha);
+        blit_ex(&sprite, sprite.bounds(), &mut dst, 0, 0, &opts);
+        assert_eq!(dst.get_pixel(0, 0), Some(Color::rgba(9, 9, 9, 255)));
+        assert_eq!(dst.get_pixel(1, 0), Some(Color::rgba(0, 200, 0, 255)));
+    }
+}
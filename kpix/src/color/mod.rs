@@ -0,0 +1,399 @@
+//! Color math on top of `Color`: sRGB transfer, linear float colors, HSV/HSL/OKLab,
+//! premultiplication, interpolation, luminance and parsing.
+//!
+//! `Color` channels are sRGB-encoded and alpha is straight (not premultiplied) unless a method
+//! says otherwise. Hue is in degrees `[0, 360)`; saturation, value and lightness are `0..=1`.
+
+pub mod palette;
+
+use std::io;
+use std::str::FromStr;
+
+use crate::core::Color;
+
+/// Decode an sRGB-encoded channel in `0..=1` to linear light.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear-light channel in `0..=1` with the sRGB transfer curve.
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Linear-light RGB with straight alpha, all channels nominally `0..=1`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LinearRgba {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl LinearRgba {
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Component-wise interpolation: `t = 0` gives `self`, `t = 1` gives `other`.
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let m = |a: f32, b: f32| a + (b - a) * t;
+        Self::new(
+            m(self.r, other.r),
+            m(self.g, other.g),
+            m(self.b, other.b),
+            m(self.a, other.a),
+        )
+    }
+
+    /// Relative luminance (Rec. 709 / sRGB primaries).
+    pub fn luminance(self) -> f32 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+}
+
+impl Color {
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+    pub const BLACK: Color = Color::rgba(0, 0, 0, 255);
+    pub const WHITE: Color = Color::rgba(255, 255, 255, 255);
+
+    /// Opaque color from RGB.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, 255)
+    }
+
+    /// Same color with alpha replaced.
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Self::rgba(self.r, self.g, self.b, a)
+    }
+
+    /// Decode to linear light. Alpha is mapped to `0..=1` unchanged.
+    pub fn to_linear(self) -> LinearRgba {
+        let d = |v: u8| srgb_to_linear(v as f32 / 255.0);
+        LinearRgba::new(d(self.r), d(self.g), d(self.b), self.a as f32 / 255.0)
+    }
+
+    /// Encode from linear light, clamping to the displayable range.
+    pub fn from_linear(c: LinearRgba) -> Self {
+        let e = |v: f32| unit_to_u8(linear_to_srgb(v.clamp(0.0, 1.0)));
+        Self::rgba(e(c.r), e(c.g), e(c.b), unit_to_u8(c.a))
+    }
+
+    /// Multiply the color channels by alpha (rounded).
+    pub fn premultiply(self) -> Self {
+        let a = self.a as u32;
+        let m = |v: u8| ((v as u32 * a + 127) / 255) as u8;
+        Self::rgba(m(self.r), m(self.g), m(self.b), self.a)
+    }
+
+    /// Inverse of `premultiply`; fully transparent colors become `TRANSPARENT`.
+    pub fn unpremultiply(self) -> Self {
+        if self.a == 0 {
+            return Self::TRANSPARENT;
+        }
+        let a = self.a as u32;
+        let d = |v: u8| ((v as u32 * 255 + a / 2) / a).min(255) as u8;
+        Self::rgba(d(self.r), d(self.g), d(self.b), self.a)
+    }
+
+    /// Interpolate the encoded channels (and alpha) directly, as most 2D APIs do.
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let m = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Self::rgba(
+            m(self.r, other.r),
+            m(self.g, other.g),
+            m(self.b, other.b),
+            m(self.a, other.a),
+        )
+    }
+
+    /// Interpolate in linear light, which keeps perceived brightness more even.
+    pub fn lerp_linear(self, other: Self, t: f32) -> Self {
+        Self::from_linear(self.to_linear().lerp(other.to_linear(), t.clamp(0.0, 1.0)))
+    }
+
+    /// Relative luminance in `0..=1`, computed in linear light (alpha ignored).
+    pub fn luminance(self) -> f32 {
+        self.to_linear().luminance()
+    }
+
+    /// Hue, saturation and value.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.unit_rgb();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let s = if max > 0.0 { (max - min) / max } else { 0.0 };
+        (hue(r, g, b, max, min), s, max)
+    }
+
+    /// Opaque color from hue, saturation and value. Hue wraps; `s` and `v` are clamped.
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let f = |n: f32| {
+            let k = (n + h.rem_euclid(360.0) / 60.0) % 6.0;
+            v - v * s * k.min(4.0 - k).clamp(0.0, 1.0)
+        };
+        Self::rgb(unit_to_u8(f(5.0)), unit_to_u8(f(3.0)), unit_to_u8(f(1.0)))
+    }
+
+    /// Hue, saturation and lightness.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (r, g, b) = self.unit_rgb();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        let s = if d == 0.0 {
+            0.0
+        } else {
+            d / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (hue(r, g, b, max, min), s, l)
+    }
+
+    /// Opaque color from hue, saturation and lightness. Hue wraps; `s` and `l` are clamped.
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Self {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let a = s * l.min(1.0 - l);
+        let f = |n: f32| {
+            let k = (n + h.rem_euclid(360.0) / 30.0) % 12.0;
+            l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Self::rgb(unit_to_u8(f(0.0)), unit_to_u8(f(8.0)), unit_to_u8(f(4.0)))
+    }
+
+    /// OKLab coordinates `(L, a, b)`: perceptual lightness `0..=1` and two opponent axes.
+    pub fn to_oklab(self) -> (f32, f32, f32) {
+        let c = self.to_linear();
+        let l = (0.412_221_46 * c.r + 0.536_332_55 * c.g + 0.051_445_995 * c.b).cbrt();
+        let m = (0.211_903_5 * c.r + 0.680_699_5 * c.g + 0.107_396_96 * c.b).cbrt();
+        let s = (0.088_302_46 * c.r + 0.281_718_85 * c.g + 0.629_978_7 * c.b).cbrt();
+        (
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        )
+    }
+
+    /// Opaque color from OKLab, clamped to the sRGB gamut.
+    pub fn from_oklab(l: f32, a: f32, b: f32) -> Self {
+        let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+        let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+        let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+        Self::from_linear(LinearRgba::new(
+            4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_,
+            -1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_,
+            -0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_,
+            1.0,
+        ))
+    }
+
+    /// Parse `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa` (the `#` is optional) or a CSS color
+    /// name such as `"cornflowerblue"` or `"transparent"`.
+    pub fn parse(s: &str) -> io::Result<Self> {
+        let s = s.trim();
+        let invalid =
+            || io::Error::new(io::ErrorKind::InvalidInput, format!("invalid color: {s:?}"));
+        if s.eq_ignore_ascii_case("transparent") {
+            return Ok(Self::TRANSPARENT);
+        }
+        if let Some(c) = palette::by_name(s) {
+            return Ok(c);
+        }
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).map(|v| v * 17);
+        let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+        let parsed = match hex.len() {
+            3 => (digit(0), digit(1), digit(2), Ok(255)),
+            4 => (digit(0), digit(1), digit(2), digit(3)),
+            6 => (pair(0), pair(2), pair(4), Ok(255)),
+            8 => (pair(0), pair(2), pair(4), pair(6)),
+            _ => return Err(invalid()),
+        };
+        match parsed {
+            (Ok(r), Ok(g), Ok(b), Ok(a)) => Ok(Self::rgba(r, g, b, a)),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// `#rrggbbaa` in lowercase hex.
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+
+    fn unit_rgb(self) -> (f32, f32, f32) {
+        (
+            self.r as f32 / 255.0,
+            self.g as f32 / 255.0,
+            self.b as f32 / 255.0,
+        )
+    }
+}
+
+impl FromStr for Color {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::parse(s)
+    }
+}
+
+fn unit_to_u8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Shared hue formula for HSV and HSL; 0 for grays.
+fn hue(r: f32, g: f32, b: f32, max: f32, min: f32) -> f32 {
+    let d = max - min;
+    if d == 0.0 {
+        return 0.0;
+    }
+    let h = if max == r {
+        (g - b) / d
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0).rem_euclid(360.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn srgb_transfer_roundtrips_every_byte() {
+        for v in 0..=255u8 {
+            let c = Color::rgba(v, v, v, v);
+            assert_eq!(Color::from_linear(c.to_linear()), c);
+        }
+        assert!(close(srgb_to_linear(0.5), 0.214));
+        assert!((Color::rgb(188, 188, 188).to_linear().r - 0.5).abs() < 0.005);
+    }
+
+    #[test]
+    fn hsv_and_hsl_known_values() {
+        let orange = Color::rgb(255, 128, 0);
+        let (h, s, v) = orange.to_hsv();
+        assert!(close(h, 30.1176) && close(s, 1.0) && close(v, 1.0));
+        assert_eq!(Color::from_hsv(h, s, v), orange);
+        assert_eq!(Color::from_hsv(120.0, 1.0, 0.5), Color::rgb(0, 128, 0));
+        assert_eq!(Color::from_hsv(-120.0, 1.0, 1.0), Color::rgb(0, 0, 255));
+
+        let (h, s, l) = palette::STEEL_BLUE.to_hsl();
+        assert!(close(h, 207.273) && close(s, 0.44) && close(l, 0.49));
+        assert_eq!(Color::from_hsl(h, s, l), palette::STEEL_BLUE);
+        assert_eq!(Color::from_hsl(0.0, 0.0, 0.5), Color::rgb(128, 128, 128));
+        assert_eq!(Color::WHITE.to_hsl(), (0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn hsv_hsl_roundtrip_many_colors() {
+        for i in 0..4096u32 {
+            let c = Color::rgb(
+                (i * 37 % 256) as u8,
+                (i * 101 % 256) as u8,
+                (i * 13 % 256) as u8,
+            );
+            let (h, s, v) = c.to_hsv();
+            assert_eq!(Color::from_hsv(h, s, v), c);
+            let (h, s, l) = c.to_hsl();
+            assert_eq!(Color::from_hsl(h, s, l), c);
+        }
+    }
+
+    #[test]
+    fn oklab_reference_values() {
+        let (l, a, b) = Color::WHITE.to_oklab();
+        assert!(close(l, 1.0) && close(a, 0.0) && close(b, 0.0));
+        let (l, a, b) = Color::rgb(255, 0, 0).to_oklab();
+        assert!(close(l, 0.62796) && close(a, 0.22486) && close(b, 0.12585));
+        for c in [palette::TEAL, palette::GOLD, palette::INDIGO, Color::BLACK] {
+            let (l, a, b) = c.to_oklab();
+            assert_eq!(Color::from_oklab(l, a, b), c);
+        }
+    }
+
+    #[test]
+    fn premultiply_lerp_and_luminance() {
+        let c = Color::rgba(200, 100, 50, 128);
+        let p = c.premultiply();
+        assert_eq!(p, Color::rgba(100, 50, 25, 128));
+        assert_eq!(p.unpremultiply(), Color::rgba(199, 100, 50, 128));
+        assert_eq!(Color::rgba(9, 9, 9, 0).unpremultiply(), Color::TRANSPARENT);
+
+        assert_eq!(
+            Color::BLACK.lerp(Color::WHITE, 0.5),
+            Color::rgb(128, 128, 128)
+        );
+        assert_eq!(
+            Color::BLACK.lerp_linear(Color::WHITE, 0.5),
+            Color::rgb(188, 188, 188)
+        );
+        assert_eq!(Color::BLACK.lerp(Color::WHITE, 2.0), Color::WHITE);
+
+        assert!(close(Color::WHITE.luminance(), 1.0));
+        assert!(close(Color::rgb(0, 255, 0).luminance(), 0.7152));
+    }
+
+    #[test]
+    fn parse_hex_and_names() {
+        assert_eq!("#ff8000".parse::<Color>().unwrap(), Color::rgb(255, 128, 0));
+        assert_eq!(
+            Color::parse("#11223344").unwrap(),
+            Color::rgba(0x11, 0x22, 0x33, 0x44)
+        );
+        assert_eq!(Color::parse("f80").unwrap(), Color::rgb(255, 136, 0));
+        assert_eq!(
+            Color::parse("#f808").unwrap(),
+            Color::rgba(255, 136, 0, 136)
+        );
+        assert_eq!(
+            Color::parse(" CornflowerBlue ").unwrap(),
+            palette::CORNFLOWER_BLUE
+        );
+        assert_eq!(Color::parse("transparent").unwrap(), Color::TRANSPARENT);
+        for bad in [
+            "",
+            "#12",
+            "#12345",
+            "#gg0000",
+            "nocolor",
+            "#ff00ff00ff",
+            "#+f0000",
+        ] {
+            let e = Color::parse(bad).unwrap_err();
+            assert_eq!(e.kind(), io::ErrorKind::InvalidInput, "{bad:?}");
+        }
+        let c = Color::rgba(1, 2, 250, 128);
+        assert_eq!(Color::parse(&c.to_hex()).unwrap(), c);
+    }
+
+    #[test]
+    fn palette_is_sorted_and_css_exact() {
+        assert_eq!(palette::NAMED.len(), 148);
+        assert!(palette::NAMED.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(palette::GREEN, Color::rgb(0, 128, 0));
+        assert_eq!(palette::GRAY, palette::GREY);
+        assert_eq!(palette::REBECCA_PURPLE, Color::rgb(102, 51, 153));
+        assert_eq!(palette::by_name("LIME"), Some(Color::rgb(0, 255, 0)));
+        assert_eq!(palette::by_name("bogus"), None);
+    }
+}
@@ -0,0 +1,313 @@
+//! CSS named colors (CSS Color Module Level 4), all fully opaque.
+
+use crate::core::Color;
+
+pub const ALICE_BLUE: Color = Color::rgba(240, 248, 255, 255);
+pub const ANTIQUE_WHITE: Color = Color::rgba(250, 235, 215, 255);
+pub const AQUA: Color = Color::rgba(0, 255, 255, 255);
+pub const AQUAMARINE: Color = Color::rgba(127, 255, 212, 255);
+pub const AZURE: Color = Color::rgba(240, 255, 255, 255);
+pub const BEIGE: Color = Color::rgba(245, 245, 220, 255);
+pub const BISQUE: Color = Color::rgba(255, 228, 196, 255);
+pub const BLACK: Color = Color::rgba(0, 0, 0, 255);
+pub const BLANCHED_ALMOND: Color = Color::rgba(255, 235, 205, 255);
+pub const BLUE: Color = Color::rgba(0, 0, 255, 255);
+pub const BLUE_VIOLET: Color = Color::rgba(138, 43, 226, 255);
+pub const BROWN: Color = Color::rgba(165, 42, 42, 255);
+pub const BURLYWOOD: Color = Color::rgba(222, 184, 135, 255);
+pub const CADET_BLUE: Color = Color::rgba(95, 158, 160, 255);
+pub const CHARTREUSE: Color = Color::rgba(127, 255, 0, 255);
+pub const CHOCOLATE: Color = Color::rgba(210, 105, 30, 255);
+pub const CORAL: Color = Color::rgba(255, 127, 80, 255);
+pub const CORNFLOWER_BLUE: Color = Color::rgba(100, 149, 237, 255);
+pub const CORNSILK: Color = Color::rgba(255, 248, 220, 255);
+pub const CRIMSON: Color = Color::rgba(220, 20, 60, 255);
+pub const CYAN: Color = Color::rgba(0, 255, 255, 255);
+pub const DARK_BLUE: Color = Color::rgba(0, 0, 139, 255);
+pub const DARK_CYAN: Color = Color::rgba(0, 139, 139, 255);
+pub const DARK_GOLDENROD: Color = Color::rgba(184, 134, 11, 255);
+pub const DARK_GRAY: Color = Color::rgba(169, 169, 169, 255);
+pub const DARK_GREEN: Color = Color::rgba(0, 100, 0, 255);
+pub const DARK_GREY: Color = Color::rgba(169, 169, 169, 255);
+pub const DARK_KHAKI: Color = Color::rgba(189, 183, 107, 255);
+pub const DARK_MAGENTA: Color = Color::rgba(139, 0, 139, 255);
+pub const DARK_OLIVE_GREEN: Color = Color::rgba(85, 107, 47, 255);
+pub const DARK_ORANGE: Color = Color::rgba(255, 140, 0, 255);
+pub const DARK_ORCHID: Color = Color::rgba(153, 50, 204, 255);
+pub const DARK_RED: Color = Color::rgba(139, 0, 0, 255);
+pub const DARK_SALMON: Color = Color::rgba(233, 150, 122, 255);
+pub const DARK_SEA_GREEN: Color = Color::rgba(143, 188, 143, 255);
+pub const DARK_SLATE_BLUE: Color = Color::rgba(72, 61, 139, 255);
+pub const DARK_SLATE_GRAY: Color = Color::rgba(47, 79, 79, 255);
+pub const DARK_SLATE_GREY: Color = Color::rgba(47, 79, 79, 255);
+pub const DARK_TURQUOISE: Color = Color::rgba(0, 206, 209, 255);
+pub const DARK_VIOLET: Color = Color::rgba(148, 0, 211, 255);
+pub const DEEP_PINK: Color = Color::rgba(255, 20, 147, 255);
+pub const DEEP_SKY_BLUE: Color = Color::rgba(0, 191, 255, 255);
+pub const DIM_GRAY: Color = Color::rgba(105, 105, 105, 255);
+pub const DIM_GREY: Color = Color::rgba(105, 105, 105, 255);
+pub const DODGER_BLUE: Color = Color::rgba(30, 144, 255, 255);
+pub const FIREBRICK: Color = Color::rgba(178, 34, 34, 255);
+pub const FLORAL_WHITE: Color = Color::rgba(255, 250, 240, 255);
+pub const FOREST_GREEN: Color = Color::rgba(34, 139, 34, 255);
+pub const FUCHSIA: Color = Color::rgba(255, 0, 255, 255);
+pub const GAINSBORO: Color = Color::rgba(220, 220, 220, 255);
+pub const GHOST_WHITE: Color = Color::rgba(248, 248, 255, 255);
+pub const GOLD: Color = Color::rgba(255, 215, 0, 255);
+pub const GOLDENROD: Color = Color::rgba(218, 165, 32, 255);
+pub const GRAY: Color = Color::rgba(128, 128, 128, 255);
+pub const GREEN: Color = Color::rgba(0, 128, 0, 255);
+pub const GREEN_YELLOW: Color = Color::rgba(173, 255, 47, 255);
+pub const GREY: Color = Color::rgba(128, 128, 128, 255);
+pub const HONEYDEW: Color = Color::rgba(240, 255, 240, 255);
+pub const HOT_PINK: Color = Color::rgba(255, 105, 180, 255);
+pub const INDIAN_RED: Color = Color::rgba(205, 92, 92, 255);
+pub const INDIGO: Color = Color::rgba(75, 0, 130, 255);
+pub const IVORY: Color = Color::rgba(255, 255, 240, 255);
+pub const KHAKI: Color = Color::rgba(240, 230, 140, 255);
+pub const LAVENDER: Color = Color::rgba(230, 230, 250, 255);
+pub const LAVENDER_BLUSH: Color = Color::rgba(255, 240, 245, 255);
+pub const LAWN_GREEN: Color = Color::rgba(124, 252, 0, 255);
+pub const LEMON_CHIFFON: Color = Color::rgba(255, 250, 205, 255);
+pub const LIGHT_BLUE: Color = Color::rgba(173, 216, 230, 255);
+pub const LIGHT_CORAL: Color = Color::rgba(240, 128, 128, 255);
+pub const LIGHT_CYAN: Color = Color::rgba(224, 255, 255, 255);
+pub const LIGHT_GOLDENROD_YELLOW: Color = Color::rgba(250, 250, 210, 255);
+pub const LIGHT_GRAY: Color = Color::rgba(211, 211, 211, 255);
+pub const LIGHT_GREEN: Color = Color::rgba(144, 238, 144, 255);
+pub const LIGHT_GREY: Color = Color::rgba(211, 211, 211, 255);
+pub const LIGHT_PINK: Color = Color::rgba(255, 182, 193, 255);
+pub const LIGHT_SALMON: Color = Color::rgba(255, 160, 122, 255);
+pub const LIGHT_SEA_GREEN: Color = Color::rgba(32, 178, 170, 255);
+pub const LIGHT_SKY_BLUE: Color = Color::rgba(135, 206, 250, 255);
+pub const LIGHT_SLATE_GRAY: Color = Color::rgba(119, 136, 153, 255);
+pub const LIGHT_SLATE_GREY: Color = Color::rgba(119, 136, 153, 255);
+pub const LIGHT_STEEL_BLUE: Color = Color::rgba(176, 196, 222, 255);
+pub const LIGHT_YELLOW: Color = Color::rgba(255, 255, 224, 255);
+pub const LIME: Color = Color::rgba(0, 255, 0, 255);
+pub const LIME_GREEN: Color = Color::rgba(50, 205, 50, 255);
+pub const LINEN: Color = Color::rgba(250, 240, 230, 255);
+pub const MAGENTA: Color = Color::rgba(255, 0, 255, 255);
+pub const MAROON: Color = Color::rgba(128, 0, 0, 255);
+pub const MEDIUM_AQUAMARINE: Color = Color::rgba(102, 205, 170, 255);
+pub const MEDIUM_BLUE: Color = Color::rgba(0, 0, 205, 255);
+pub const MEDIUM_ORCHID: Color = Color::rgba(186, 85, 211, 255);
+pub const MEDIUM_PURPLE: Color = Color::rgba(147, 112, 219, 255);
+pub const MEDIUM_SEA_GREEN: Color = Color::rgba(60, 179, 113, 255);
+pub const MEDIUM_SLATE_BLUE: Color = Color::rgba(123, 104, 238, 255);
+pub const MEDIUM_SPRING_GREEN: Color = Color::rgba(0, 250, 154, 255);
+pub const MEDIUM_TURQUOISE: Color = Color::rgba(72, 209, 204, 255);
+pub const MEDIUM_VIOLET_RED: Color = Color::rgba(199, 21, 133, 255);
+pub const MIDNIGHT_BLUE: Color = Color::rgba(25, 25, 112, 255);
+pub const MINT_CREAM: Color = Color::rgba(245, 255, 250, 255);
+pub const MISTY_ROSE: Color = Color::rgba(255, 228, 225, 255);
+pub const MOCCASIN: Color = Color::rgba(255, 228, 181, 255);
+pub const NAVAJO_WHITE: Color = Color::rgba(255, 222, 173, 255);
+pub const NAVY: Color = Color::rgba(0, 0, 128, 255);
+pub const OLD_LACE: Color = Color::rgba(253, 245, 230, 255);
+pub const OLIVE: Color = Color::rgba(128, 128, 0, 255);
+pub const OLIVE_DRAB: Color = Color::rgba(107, 142, 35, 255);
+pub const ORANGE: Color = Color::rgba(255, 165, 0, 255);
+pub const ORANGE_RED: Color = Color::rgba(255, 69, 0, 255);
+pub const ORCHID: Color = Color::rgba(218, 112, 214, 255);
+pub const PALE_GOLDENROD: Color = Color::rgba(238, 232, 170, 255);
+pub const PALE_GREEN: Color = Color::rgba(152, 251, 152, 255);
+pub const PALE_TURQUOISE: Color = Color::rgba(175, 238, 238, 255);
+pub const PALE_VIOLET_RED: Color = Color::rgba(219, 112, 147, 255);
+pub const PAPAYA_WHIP: Color = Color::rgba(255, 239, 213, 255);
+pub const PEACH_PUFF: Color = Color::rgba(255, 218, 185, 255);
+pub const PERU: Color = Color::rgba(205, 133, 63, 255);
+pub const PINK: Color = Color::rgba(255, 192, 203, 255);
+pub const PLUM: Color = Color::rgba(221, 160, 221, 255);
+pub const POWDER_BLUE: Color = Color::rgba(176, 224, 230, 255);
+pub const PURPLE: Color = Color::rgba(128, 0, 128, 255);
+pub const REBECCA_PURPLE: Color = Color::rgba(102, 51, 153, 255);
+pub const RED: Color = Color::rgba(255, 0, 0, 255);
+pub const ROSY_BROWN: Color = Color::rgba(188, 143, 143, 255);
+pub const ROYAL_BLUE: Color = Color::rgba(65, 105, 225, 255);
+pub const SADDLE_BROWN: Color = Color::rgba(139, 69, 19, 255);
+pub const SALMON: Color = Color::rgba(250, 128, 114, 255);
+pub const SANDY_BROWN: Color = Color::rgba(244, 164, 96, 255);
+pub const SEA_GREEN: Color = Color::rgba(46, 139, 87, 255);
+pub const SEASHELL: Color = Color::rgba(255, 245, 238, 255);
+pub const SIENNA: Color = Color::rgba(160, 82, 45, 255);
+pub const SILVER: Color = Color::rgba(192, 192, 192, 255);
+pub const SKY_BLUE: Color = Color::rgba(135, 206, 235, 255);
+pub const SLATE_BLUE: Color = Color::rgba(106, 90, 205, 255);
+pub const SLATE_GRAY: Color = Color::rgba(112, 128, 144, 255);
+pub const SLATE_GREY: Color = Color::rgba(112, 128, 144, 255);
+pub const SNOW: Color = Color::rgba(255, 250, 250, 255);
+pub const SPRING_GREEN: Color = Color::rgba(0, 255, 127, 255);
+pub const STEEL_BLUE: Color = Color::rgba(70, 130, 180, 255);
+pub const TAN: Color = Color::rgba(210, 180, 140, 255);
+pub const TEAL: Color = Color::rgba(0, 128, 128, 255);
+pub const THISTLE: Color = Color::rgba(216, 191, 216, 255);
+pub const TOMATO: Color = Color::rgba(255, 99, 71, 255);
+pub const TURQUOISE: Color = Color::rgba(64, 224, 208, 255);
+pub const VIOLET: Color = Color::rgba(238, 130, 238, 255);
+pub const WHEAT: Color = Color::rgba(245, 222, 179, 255);
+pub const WHITE: Color = Color::rgba(255, 255, 255, 255);
+pub const WHITE_SMOKE: Color = Color::rgba(245, 245, 245, 255);
+pub const YELLOW: Color = Color::rgba(255, 255, 0, 255);
+pub const YELLOW_GREEN: Color = Color::rgba(154, 205, 50, 255);
+
+/// Every named color with its lowercase CSS name, sorted by name.
+pub const NAMED: &[(&str, Color)] = &[
+    ("aliceblue", ALICE_BLUE),
+    ("antiquewhite", ANTIQUE_WHITE),
+    ("aqua", AQUA),
+    ("aquamarine", AQUAMARINE),
+    ("azure", AZURE),
+    ("beige", BEIGE),
+    ("bisque", BISQUE),
+    ("black", BLACK),
+    ("blanchedalmond", BLANCHED_ALMOND),
+    ("blue", BLUE),
+    ("blueviolet", BLUE_VIOLET),
+    ("brown", BROWN),
+    ("burlywood", BURLYWOOD),
+    ("cadetblue", CADET_BLUE),
+    ("chartreuse", CHARTREUSE),
+    ("chocolate", CHOCOLATE),
+    ("coral", CORAL),
+    ("cornflowerblue", CORNFLOWER_BLUE),
+    ("cornsilk", CORNSILK),
+    ("crimson", CRIMSON),
+    ("cyan", CYAN),
+    ("darkblue", DARK_BLUE),
+    ("darkcyan", DARK_CYAN),
+    ("darkgoldenrod", DARK_GOLDENROD),
+    ("darkgray", DARK_GRAY),
+    ("darkgreen", DARK_GREEN),
+    ("darkgrey", DARK_GREY),
+    ("darkkhaki", DARK_KHAKI),
+    ("darkmagenta", DARK_MAGENTA),
+    ("darkolivegreen", DARK_OLIVE_GREEN),
+    ("darkorange", DARK_ORANGE),
+    ("darkorchid", DARK_ORCHID),
+    ("darkred", DARK_RED),
+    ("darksalmon", DARK_SALMON),
+    ("darkseagreen", DARK_SEA_GREEN),
+    ("darkslateblue", DARK_SLATE_BLUE),
+    ("darkslategray", DARK_SLATE_GRAY),
+    ("darkslategrey", DARK_SLATE_GREY),
+    ("darkturquoise", DARK_TURQUOISE),
+    ("darkviolet", DARK_VIOLET),
+    ("deeppink", DEEP_PINK),
+    ("deepskyblue", DEEP_SKY_BLUE),
+    ("dimgray", DIM_GRAY),
+    ("dimgrey", DIM_GREY),
+    ("dodgerblue", DODGER_BLUE),
+    ("firebrick", FIREBRICK),
+    ("floralwhite", FLORAL_WHITE),
+    ("forestgreen", FOREST_GREEN),
+    ("fuchsia", FUCHSIA),
+    ("gainsboro", GAINSBORO),
+    ("ghostwhite", GHOST_WHITE),
+    ("gold", GOLD),
+    ("goldenrod", GOLDENROD),
+    ("gray", GRAY),
+    ("green", GREEN),
+    ("greenyellow", GREEN_YELLOW),
+    ("grey", GREY),
+    ("honeydew", HONEYDEW),
+    ("hotpink", HOT_PINK),
+    ("indianred", INDIAN_RED),
+    ("indigo", INDIGO),
+    ("ivory", IVORY),
+    ("khaki", KHAKI),
+    ("lavender", LAVENDER),
+    ("lavenderblush", LAVENDER_BLUSH),
+    ("lawngreen", LAWN_GREEN),
+    ("lemonchiffon", LEMON_CHIFFON),
+    ("lightblue", LIGHT_BLUE),
+    ("lightcoral", LIGHT_CORAL),
+    ("lightcyan", LIGHT_CYAN),
+    ("lightgoldenrodyellow", LIGHT_GOLDENROD_YELLOW),
+    ("lightgray", LIGHT_GRAY),
+    ("lightgreen", LIGHT_GREEN),
+    ("lightgrey", LIGHT_GREY),
+    ("lightpink", LIGHT_PINK),
+    ("lightsalmon", LIGHT_SALMON),
+    ("lightseagreen", LIGHT_SEA_GREEN),
+    ("lightskyblue", LIGHT_SKY_BLUE),
+    ("lightslategray", LIGHT_SLATE_GRAY),
+    ("lightslategrey", LIGHT_SLATE_GREY),
+    ("lightsteelblue", LIGHT_STEEL_BLUE),
+    ("lightyellow", LIGHT_YELLOW),
+    ("lime", LIME),
+    ("limegreen", LIME_GREEN),
+    ("linen", LINEN),
+    ("magenta", MAGENTA),
+    ("maroon", MAROON),
+    ("mediumaquamarine", MEDIUM_AQUAMARINE),
+    ("mediumblue", MEDIUM_BLUE),
+    ("mediumorchid", MEDIUM_ORCHID),
+    ("mediumpurple", MEDIUM_PURPLE),
+    ("mediumseagreen", MEDIUM_SEA_GREEN),
+    ("mediumslateblue", MEDIUM_SLATE_BLUE),
+    ("mediumspringgreen", MEDIUM_SPRING_GREEN),
+    ("mediumturquoise", MEDIUM_TURQUOISE),
+    ("mediumvioletred", MEDIUM_VIOLET_RED),
+    ("midnightblue", MIDNIGHT_BLUE),
+    ("mintcream", MINT_CREAM),
+    ("mistyrose", MISTY_ROSE),
+    ("moccasin", MOCCASIN),
+    ("navajowhite", NAVAJO_WHITE),
+    ("navy", NAVY),
+    ("oldlace", OLD_LACE),
+    ("olive", OLIVE),
+    ("olivedrab", OLIVE_DRAB),
+    ("orange", ORANGE),
+    ("orangered", ORANGE_RED),
+    ("orchid", ORCHID),
+    ("palegoldenrod", PALE_GOLDENROD),
+    ("palegreen", PALE_GREEN),
+    ("paleturquoise", PALE_TURQUOISE),
+    ("palevioletred", PALE_VIOLET_RED),
+    ("papayawhip", PAPAYA_WHIP),
+    ("peachpuff", PEACH_PUFF),
+    ("peru", PERU),
+    ("pink", PINK),
+    ("plum", PLUM),
+    ("powderblue", POWDER_BLUE),
+    ("purple", PURPLE),
+    ("rebeccapurple", REBECCA_PURPLE),
+    ("red", RED),
+    ("rosybrown", ROSY_BROWN),
+    ("royalblue", ROYAL_BLUE),
+    ("saddlebrown", SADDLE_BROWN),
+    ("salmon", SALMON),
+    ("sandybrown", SANDY_BROWN),
+    ("seagreen", SEA_GREEN),
+    ("seashell", SEASHELL),
+    ("sienna", SIENNA),
+    ("silver", SILVER),
+    ("skyblue", SKY_BLUE),
+    ("slateblue", SLATE_BLUE),
+    ("slategray", SLATE_GRAY),
+    ("slategrey", SLATE_GREY),
+    ("snow", SNOW),
+    ("springgreen", SPRING_GREEN),
+    ("steelblue", STEEL_BLUE),
+    ("tan", TAN),
+    ("teal", TEAL),
+    ("thistle", THISTLE),
+    ("tomato", TOMATO),
+    ("turquoise", TURQUOISE),
+    ("violet", VIOLET),
+    ("wheat", WHEAT),
+    ("white", WHITE),
+    ("whitesmoke", WHITE_SMOKE),
+    ("yellow", YELLOW),
+    ("yellowgreen", YELLOW_GREEN),
+];
+
+/// Look up a CSS color name, ignoring ASCII case.
+pub fn by_name(name: &str) -> Option<Color> {
+    let name = name.to_ascii_lowercase();
+    NAMED
+        .binary_search_by(|(n, _)| (*n).cmp(name.as_str()))
+        .ok()
+        .map(|i| NAMED[i].1)
+}
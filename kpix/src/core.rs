@@ -1,5 +1,11 @@
 //! Core types: Color and Surface.
 
+use std::io;
+
+use crate::dirty::DirtyRects;
+use crate::format::{PixelFormat, Rgba8};
+use crate::rect::{ClipStack, Rect};
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Color {
     pub r: u8,
@@ -27,23 +33,74 @@ impl Color {
     }
 }
 
+/// A pixel grid in format `P` (packed RGBA8 by default) with a clip stack.
+///
+/// Color-based accessors encode and decode through the format, so drawing code can stay
+/// generic; `get_raw`/`set_raw` and `pixels` expose the stored values directly.
+///
+/// With `set_dirty_tracking(true)`, every write (pixel setters, spans, `clear`, mutable
+/// buffer and row access, views) also records the touched area for `take_dirty_rects`.
 #[derive(Clone, Debug)]
-pub struct Surface {
+pub struct Surface<P: PixelFormat = Rgba8> {
     width: usize,
     height: usize,
-    pixels: Vec<u32>, // packed RGBA little-endian per pixel
+    pixels: Vec<P::Pixel>,
+    format: P,
+    clip: ClipStack,
+    dirty: Option<DirtyRects>,
 }
 
 impl Surface {
+    /// RGBA8 surface filled with transparent black.
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_format(width, height, Rgba8)
+    }
+
+    /// RGBA8 surface over existing packed pixels, row-major.
+    /// Fails with `InvalidInput` unless `pixels.len() == width * height`.
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<u32>) -> io::Result<Self> {
+        Self::from_pixels_with_format(width, height, pixels, Rgba8)
+    }
+}
+
+impl<P: PixelFormat> Surface<P> {
+    /// Surface in `format`, every pixel set to `P::Pixel::default()`.
+    pub fn with_format(width: usize, height: usize, format: P) -> Self {
         let len = width.saturating_mul(height);
         Self {
             width,
             height,
-            pixels: vec![0; len],
+            pixels: vec![P::Pixel::default(); len],
+            format,
+            clip: ClipStack::new(Rect::new(0, 0, width as i32, height as i32)),
+            dirty: None,
         }
     }
 
+    /// Surface in `format` over existing stored values, row-major.
+    /// Fails with `InvalidInput` unless `pixels.len() == width * height`.
+    pub fn from_pixels_with_format(
+        width: usize,
+        height: usize,
+        pixels: Vec<P::Pixel>,
+        format: P,
+    ) -> io::Result<Self> {
+        if Some(pixels.len()) != width.checked_mul(height) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected {width}x{height} pixels, got {}", pixels.len()),
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+            format,
+            clip: ClipStack::new(Rect::new(0, 0, width as i32, height as i32)),
+            dirty: None,
+        })
+    }
+
     #[inline]
     pub fn width(&self) -> usize {
         self.width
@@ -54,27 +111,140 @@ impl Surface {
         self.height
     }
 
-    /// Fill entire surface with a color.
+    #[inline]
+    pub fn format(&self) -> &P {
+        &self.format
+    }
+
+    /// Mutable format, e.g. to edit an indexed palette. Stored pixels are left untouched.
+    #[inline]
+    pub fn format_mut(&mut self) -> &mut P {
+        &mut self.format
+    }
+
+    /// Full surface area as a rect.
+    #[inline]
+    pub fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width as i32, self.height as i32)
+    }
+
+    /// Current clip rect: the innermost pushed clip, or the full surface.
+    #[inline]
+    pub fn clip_rect(&self) -> Rect {
+        self.clip.current()
+    }
+
+    /// Restrict drawing to `rect` intersected with the current clip. Undo with `pop_clip`.
+    pub fn push_clip(&mut self, rect: Rect) {
+        self.clip.push(rect);
+    }
+
+    /// Restore the clip that was active before the matching `push_clip`.
+    /// Returns the removed clip, or `None` when the stack is empty.
+    pub fn pop_clip(&mut self) -> Option<Rect> {
+        self.clip.pop()
+    }
+
+    /// Start or stop recording changed areas. Starting resets the list; stopping drops it.
+    pub fn set_dirty_tracking(&mut self, enabled: bool) {
+        self.dirty = enabled.then(DirtyRects::new);
+    }
+
+    #[inline]
+    pub fn is_dirty_tracking(&self) -> bool {
+        self.dirty.is_some()
+    }
+
+    /// Record `rect` (clipped to the bounds) as changed, e.g. after writing through
+    /// `pixels_mut`. Does nothing unless tracking is on.
+    pub fn mark_dirty(&mut self, rect: Rect) {
+        let rect = rect.intersect(self.bounds());
+        if let Some(d) = &mut self.dirty {
+            d.add(rect);
+        }
+    }
+
+    /// Areas changed since tracking started or since the last `take_dirty_rects`.
+    pub fn dirty_rects(&self) -> &[Rect] {
+        self.dirty.as_ref().map_or(&[], |d| d.rects())
+    }
+
+    /// Return and reset the changed areas. Empty when tracking is off.
+    pub fn take_dirty_rects(&mut self) -> Vec<Rect> {
+        self.dirty
+            .as_mut()
+            .map(DirtyRects::take)
+            .unwrap_or_default()
+    }
+
+    /// Fill the current clip region with a color (the entire surface when no clip is pushed).
     pub fn clear(&mut self, color: Color) {
-        let v = color.to_u32();
-        self.pixels.fill(v);
+        let c = self.clip.current();
+        self.mark_dirty(c);
+        let v = self.format.encode(color);
+        if c == self.bounds() {
+            self.pixels.fill(v);
+            return;
+        }
+        for y in c.y..c.bottom() {
+            let row = y as usize * self.width;
+            self.pixels[row + c.x as usize..row + c.right() as usize].fill(v);
+        }
     }
 
-    /// Set a pixel with clipping. Out-of-bounds coordinates are ignored.
+    /// Set a pixel with clipping. Coordinates outside the clip rect are ignored.
     pub fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
-        if x < 0 || y < 0 {
+        let v = self.format.encode(color);
+        self.set_raw(x, y, v);
+    }
+
+    /// Blend a pixel over the existing one using the source alpha (straight, non-premultiplied).
+    /// Out-of-bounds coordinates are ignored.
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: Color) {
+        if color.a == 255 {
+            self.set_pixel(x, y, color);
             return;
         }
-        let (x, y) = (x as usize, y as usize);
-        if x >= self.width || y >= self.height {
+        if color.a == 0 {
             return;
         }
-        let idx = y * self.width + x;
-        self.pixels[idx] = color.to_u32();
+        if let Some(dst) = self.get_pixel(x, y) {
+            self.set_pixel(x, y, blend_over(color, dst));
+        }
     }
 
-    /// Get a pixel if in-bounds.
+    /// Fill the half-open horizontal span [x0, x1) on row `y`, clipped to the clip rect.
+    pub fn fill_span(&mut self, x0: i32, x1: i32, y: i32, color: Color) {
+        let Some((x0, x1)) = self.clip.span(x0, x1, y) else {
+            return;
+        };
+        if let Some(d) = &mut self.dirty {
+            d.add(Rect::new(x0, y, x1 - x0, 1));
+        }
+        let v = self.format.encode(color);
+        let row = y as usize * self.width;
+        self.pixels[row + x0 as usize..row + x1 as usize].fill(v);
+    }
+
+    /// Get a pixel if in-bounds. Reads ignore the clip rect.
     pub fn get_pixel(&self, x: i32, y: i32) -> Option<Color> {
+        self.get_raw(x, y).map(|v| self.format.decode(v))
+    }
+
+    /// Store a raw pixel value (e.g. a palette index or a 16-bit height) with clipping.
+    pub fn set_raw(&mut self, x: i32, y: i32, value: P::Pixel) {
+        if !self.clip.contains(x, y) {
+            return;
+        }
+        if let Some(d) = &mut self.dirty {
+            d.add(Rect::new(x, y, 1, 1));
+        }
+        let idx = y as usize * self.width + x as usize;
+        self.pixels[idx] = value;
+    }
+
+    /// Raw pixel value if in-bounds. Reads ignore the clip rect.
+    pub fn get_raw(&self, x: i32, y: i32) -> Option<P::Pixel> {
         if x < 0 || y < 0 {
             return None;
         }
@@ -82,19 +252,120 @@ impl Surface {
         if x >= self.width || y >= self.height {
             return None;
         }
-        let idx = y * self.width + x;
-        Some(Color::from_u32(self.pixels[idx]))
+        Some(self.pixels[y * self.width + x])
     }
 
-    /// Read-only access to internal packed pixel buffer.
-    pub fn pixels(&self) -> &[u32] {
+    /// Read-only access to the stored pixel values, row-major.
+    pub fn pixels(&self) -> &[P::Pixel] {
         &self.pixels
     }
+
+    /// Mutable access to the stored pixel values, row-major. Writes bypass the clip rect.
+    pub fn pixels_mut(&mut self) -> &mut [P::Pixel] {
+        self.mark_dirty(self.bounds());
+        &mut self.pixels
+    }
+
+    /// Give up the surface, keeping its stored pixel values.
+    pub fn into_pixels(self) -> Vec<P::Pixel> {
+        self.pixels
+    }
+
+    /// Stored values of row `y`, or `None` past the bottom.
+    pub fn row(&self, y: usize) -> Option<&[P::Pixel]> {
+        if y >= self.height {
+            return None;
+        }
+        let start = y * self.width;
+        Some(&self.pixels[start..start + self.width])
+    }
+
+    /// Mutable row `y`. Writes bypass the clip rect.
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [P::Pixel]> {
+        self.mark_dirty(Rect::new(0, y as i32, self.width as i32, 1));
+        self.raw_row_mut(y)
+    }
+
+    /// `row_mut` without dirty tracking, for callers that mark a narrower area themselves.
+    pub(crate) fn raw_row_mut(&mut self, y: usize) -> Option<&mut [P::Pixel]> {
+        if y >= self.height {
+            return None;
+        }
+        let start = y * self.width;
+        Some(&mut self.pixels[start..start + self.width])
+    }
+
+    /// Rows from top to bottom.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[P::Pixel]> {
+        let w = self.width;
+        (0..self.height).map(move |y| &self.pixels[y * w..(y + 1) * w])
+    }
+
+    /// Mutable rows from top to bottom. Writes bypass the clip rect.
+    pub fn rows_mut(&mut self) -> impl ExactSizeIterator<Item = &mut [P::Pixel]> {
+        self.mark_dirty(self.bounds());
+        self.raw_rows_mut()
+    }
+
+    /// `rows_mut` without dirty tracking.
+    pub(crate) fn raw_rows_mut(&mut self) -> impl ExactSizeIterator<Item = &mut [P::Pixel]> {
+        // `chunks_exact_mut(0)` panics, and a zero-width surface still has `height` rows.
+        let (w, h) = (self.width, self.height);
+        let mut rest: &mut [P::Pixel] = &mut self.pixels;
+        (0..h).map(move |_| {
+            let (row, tail) = std::mem::take(&mut rest).split_at_mut(w);
+            rest = tail;
+            row
+        })
+    }
+
+    /// Copy into a new surface of another format, pixel by pixel through `Color`.
+    /// The copy starts with no clip pushed and no dirty tracking.
+    pub fn convert<Q: PixelFormat>(&self, format: Q) -> Surface<Q> {
+        let pixels = self
+            .pixels
+            .iter()
+            .map(|&v| format.encode(self.format.decode(v)))
+            .collect();
+        Surface {
+            width: self.width,
+            height: self.height,
+            pixels,
+            format,
+            clip: ClipStack::new(self.bounds()),
+            dirty: None,
+        }
+    }
+
+    /// Copy into an RGBA8 surface.
+    pub fn to_rgba8(&self) -> Surface {
+        self.convert(Rgba8)
+    }
+}
+
+/// Source-over composition of straight-alpha colors with rounding. The destination color is
+/// weighted by its own alpha, so blending onto transparent pixels does not darken.
+fn blend_over(src: Color, dst: Color) -> Color {
+    let sa = src.a as u32;
+    // Destination weight `da * (1 - sa)`, scaled by 255 * 255.
+    let dw = dst.a as u32 * (255 - sa);
+    let a = sa * 255 + dw;
+    if a == 0 {
+        return Color::TRANSPARENT;
+    }
+    let mix = |s: u8, d: u8| ((s as u32 * sa * 255 + d as u32 * dw + a / 2) / a) as u8;
+    Color::rgba(
+        mix(src.r, dst.r),
+        mix(src.g, dst.g),
+        mix(src.b, dst.b),
+        ((a + 127) / 255) as u8,
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::format::{Gray8, Gray16, Indexed, Rgb565};
 
     // Color
     #[test]
@@ -134,4 +405,185 @@ mod tests {
         // untouched pixel remains default (0)
         assert_eq!(s.get_pixel(0, 0), Some(Color::from_u32(0)));
     }
+
+    #[test]
+    fn blend_pixel_opaque_transparent_and_half() {
+        let mut s = Surface::new(1, 1);
+        s.clear(Color::rgba(0, 0, 0, 255));
+        s.blend_pixel(0, 0, Color::rgba(200, 100, 50, 0));
+        assert_eq!(s.get_pixel(0, 0), Some(Color::rgba(0, 0, 0, 255)));
+        s.blend_pixel(0, 0, Color::rgba(255, 255, 255, 128));
+        assert_eq!(s.get_pixel(0, 0), Some(Color::rgba(128, 128, 128, 255)));
+        s.blend_pixel(0, 0, Color::rgba(10, 20, 30, 255));
+        assert_eq!(s.get_pixel(0, 0), Some(Color::rgba(10, 20, 30, 255)));
+        // out-of-bounds is ignored
+        s.blend_pixel(1, 0, Color::rgba(1, 1, 1, 128));
+    }
+
+    #[test]
+    fn blend_pixel_onto_transparent_keeps_color() {
+        let mut s = Surface::new(1, 1);
+        s.blend_pixel(0, 0, Color::rgba(255, 255, 255, 128));
+        assert_eq!(s.get_pixel(0, 0), Some(Color::rgba(255, 255, 255, 128)));
+        // Half-transparent red under half-transparent blue: weights 128 vs 64
+        s.clear(Color::rgba(255, 0, 0, 128));
+        s.blend_pixel(0, 0, Color::rgba(0, 0, 255, 128));
+        assert_eq!(s.get_pixel(0, 0), Some(Color::rgba(85, 0, 170, 192)));
+        s.clear(Color::TRANSPARENT);
+        s.blend_pixel(0, 0, Color::rgba(9, 9, 9, 0));
+        assert_eq!(s.get_pixel(0, 0), Some(Color::TRANSPARENT));
+    }
+
+    #[test]
+    fn fill_span_half_open_and_clipped() {
+        let mut s = Surface::new(4, 2);
+        let c = Color::rgba(9, 9, 9, 255);
+        s.fill_span(-3, 2, 0, c);
+        s.fill_span(3, 10, 1, c);
+        s.fill_span(0, 4, 5, c); // row out of bounds
+        s.fill_span(2, 2, 1, c); // empty
+        let set: Vec<bool> = s.pixels().iter().map(|&p| p == c.to_u32()).collect();
+        assert_eq!(
+            set,
+            vec![true, true, false, false, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn clip_stack_intersects_and_restores() {
+        let mut s = Surface::new(10, 10);
+        assert_eq!(s.clip_rect(), s.bounds());
+        s.push_clip(Rect::new(2, 2, 6, 6));
+        s.push_clip(Rect::new(-5, 4, 9, 20));
+        assert_eq!(s.clip_rect(), Rect::new(2, 4, 2, 4));
+        assert_eq!(s.pop_clip(), Some(Rect::new(2, 4, 2, 4)));
+        assert_eq!(s.clip_rect(), Rect::new(2, 2, 6, 6));
+        s.pop_clip();
+        assert_eq!(s.clip_rect(), s.bounds());
+        assert_eq!(s.pop_clip(), None);
+    }
+
+    #[test]
+    fn clip_limits_writes_but_not_reads() {
+        let mut s = Surface::new(4, 4);
+        let c = Color::rgba(5, 6, 7, 255);
+        s.clear(c);
+        s.push_clip(Rect::new(1, 1, 2, 2));
+        let d = Color::rgba(1, 1, 1, 255);
+        s.clear(d);
+        s.set_pixel(0, 0, Color::rgba(9, 9, 9, 255));
+        s.blend_pixel(3, 3, Color::rgba(9, 9, 9, 128));
+        s.fill_span(-10, 10, 0, Color::rgba(9, 9, 9, 255));
+        for y in 0..4 {
+            for x in 0..4 {
+                let inside = (1..3).contains(&x) && (1..3).contains(&y);
+                assert_eq!(s.get_pixel(x, y), Some(if inside { d } else { c }));
+            }
+        }
+    }
+
+    #[test]
+    fn disjoint_clip_blocks_everything() {
+        let mut s = Surface::new(3, 3);
+        s.push_clip(Rect::new(5, 5, 2, 2));
+        assert!(s.clip_rect().is_empty());
+        s.clear(Color::rgba(1, 1, 1, 255));
+        s.set_pixel(1, 1, Color::rgba(1, 1, 1, 255));
+        assert!(s.pixels().iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn gray_surface_encodes_and_blends() {
+        let mut s = Surface::with_format(3, 1, Gray8);
+        assert_eq!(s.get_pixel(0, 0), Some(Color::rgba(0, 0, 0, 255)));
+        s.set_pixel(0, 0, Color::rgba(255, 255, 255, 255));
+        s.blend_pixel(1, 0, Color::rgba(255, 255, 255, 128));
+        s.fill_span(2, 3, 0, Color::rgba(0, 255, 0, 255));
+        assert_eq!(s.pixels(), &[255, 128, 150]);
+
+        let mut h = Surface::with_format(2, 2, Gray16);
+        h.set_raw(1, 1, 40_000);
+        assert_eq!(h.get_raw(1, 1), Some(40_000));
+        assert_eq!(h.get_raw(2, 0), None);
+    }
+
+    #[test]
+    fn indexed_surface_and_palette_edits() {
+        let black = Color::rgba(0, 0, 0, 255);
+        let red = Color::rgba(255, 0, 0, 255);
+        let mut s = Surface::with_format(2, 1, Indexed::new([black, red]));
+        s.set_pixel(1, 0, Color::rgba(230, 20, 10, 255));
+        assert_eq!(s.pixels(), &[0, 1]);
+        assert_eq!(s.get_pixel(1, 0), Some(red));
+        let blue = Color::rgba(0, 0, 255, 255);
+        s.format_mut().set_color(1, blue);
+        assert_eq!(s.get_pixel(1, 0), Some(blue));
+    }
+
+    #[test]
+    fn convert_between_formats() {
+        let mut s = Surface::new(2, 1);
+        s.push_clip(Rect::new(0, 0, 1, 1));
+        s.clear(Color::rgba(255, 0, 0, 255));
+        let c = s.convert(Rgb565);
+        assert_eq!(c.pixels(), &[0xf800, 0]);
+        assert_eq!(c.clip_rect(), c.bounds());
+        let back = c.to_rgba8();
+        assert_eq!(back.get_pixel(0, 0), Some(Color::rgba(255, 0, 0, 255)));
+        // RGB565 has no alpha, so the transparent pixel comes back opaque black.
+        assert_eq!(back.get_pixel(1, 0), Some(Color::rgba(0, 0, 0, 255)));
+    }
+
+    #[test]
+    fn pixel_and_row_access() {
+        let err = Surface::from_pixels(2, 2, vec![0; 3]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let mut s = Surface::from_pixels_with_format(3, 2, vec![1, 2, 3, 4, 5, 6], Gray8).unwrap();
+        assert_eq!(s.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(s.row(2), None);
+        s.row_mut(0).unwrap()[2] = 9;
+        for (y, row) in s.rows_mut().enumerate() {
+            row[0] = y as u8 * 10;
+        }
+        s.pixels_mut()[4] = 50;
+        assert_eq!(s.rows().len(), 2);
+        assert_eq!(s.into_pixels(), vec![0, 2, 9, 10, 50, 6]);
+    }
+
+    #[test]
+    fn dirty_tracking_covers_every_write_path() {
+        let c = Color::rgba(1, 2, 3, 255);
+        let mut s = Surface::new(16, 16);
+        s.set_pixel(1, 1, c);
+        assert!(s.take_dirty_rects().is_empty(), "off by default");
+
+        s.set_dirty_tracking(true);
+        s.set_pixel(1, 1, c);
+        s.fill_span(0, 4, 2, c);
+        s.blend_pixel(10, 10, Color::rgba(9, 9, 9, 128));
+        s.push_clip(Rect::new(0, 0, 8, 8));
+        s.set_pixel(12, 12, c); // clipped away, not dirty
+        s.pop_clip();
+        let mut rects = s.take_dirty_rects();
+        rects.sort_by_key(|r| r.x);
+        assert_eq!(rects, vec![Rect::new(0, 1, 4, 2), Rect::new(10, 10, 1, 1)]);
+        assert!(s.dirty_rects().is_empty());
+
+        crate::draw::fill_rect(&mut s, 3, 3, 4, 2, c);
+        assert_eq!(s.take_dirty_rects(), vec![Rect::new(3, 3, 4, 2)]);
+        s.push_clip(Rect::new(2, 2, 3, 3));
+        s.clear(c);
+        s.pop_clip();
+        assert_eq!(s.take_dirty_rects(), vec![Rect::new(2, 2, 3, 3)]);
+        s.row_mut(5).unwrap()[0] = 0;
+        assert_eq!(s.take_dirty_rects(), vec![Rect::new(0, 5, 16, 1)]);
+        s.view_mut(Rect::new(12, 12, 10, 10)).fill(c);
+        assert_eq!(s.take_dirty_rects(), vec![Rect::new(12, 12, 4, 4)]);
+        s.pixels_mut();
+        assert_eq!(s.dirty_rects(), &[s.bounds()]);
+
+        s.set_dirty_tracking(false);
+        assert!(!s.is_dirty_tracking() && s.dirty_rects().is_empty());
+    }
 }
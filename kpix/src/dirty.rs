@@ -0,0 +1,149 @@
+//! Dirty-rectangle lists: which parts of a surface changed since they were last taken.
+//!
+//! `DirtyRects` keeps a short list of rects that together cover every added area, possibly
+//! more. A new rect that overlaps or touches a listed one (diagonal neighbors included) is
+//! merged into it, so spans written row by row collapse into one rect. When the list grows
+//! past its limit, the two rects whose bounding box wastes the fewest pixels are merged.
+
+use crate::rect::Rect;
+
+/// Default limit on the number of separate rects.
+pub const DEFAULT_MAX_RECTS: usize = 16;
+
+/// Merged list of changed areas.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirtyRects {
+    rects: Vec<Rect>,
+    max: usize,
+}
+
+impl Default for DirtyRects {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DirtyRects {
+    pub fn new() -> Self {
+        Self::with_max(DEFAULT_MAX_RECTS)
+    }
+
+    /// List that never holds more than `max` rects (at least 1).
+    pub fn with_max(max: usize) -> Self {
+        Self {
+            rects: Vec::new(),
+            max: max.max(1),
+        }
+    }
+
+    /// Current rects, in no particular order. They do not overlap or touch.
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Sum of the rect areas.
+    pub fn area(&self) -> i64 {
+        self.rects.iter().map(Rect::area).sum()
+    }
+
+    /// Record `rect` as changed. Empty rects are ignored.
+    pub fn add(&mut self, rect: Rect) {
+        if rect.is_empty() || self.rects.iter().any(|r| covers(r, &rect)) {
+            return;
+        }
+        let mut rect = rect;
+        // Absorb every rect the growing one touches; each merge can reach new neighbors.
+        while let Some(i) = self.rects.iter().position(|r| touches(r, &rect)) {
+            rect = rect.union(self.rects.swap_remove(i));
+        }
+        self.rects.push(rect);
+        while self.rects.len() > self.max {
+            self.merge_cheapest();
+        }
+    }
+
+    /// Remove and return all rects.
+    pub fn take(&mut self) -> Vec<Rect> {
+        std::mem::take(&mut self.rects)
+    }
+
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+
+    /// Merge the pair whose union adds the least uncovered area.
+    fn merge_cheapest(&mut self) {
+        let mut best = (i64::MAX, 0, 1);
+        for i in 0..self.rects.len() {
+            for j in i + 1..self.rects.len() {
+                let (a, b) = (self.rects[i], self.rects[j]);
+                let waste = a.union(b).area() - a.area() - b.area();
+                if waste < best.0 {
+                    best = (waste, i, j);
+                }
+            }
+        }
+        let (_, i, j) = best;
+        let b = self.rects.swap_remove(j);
+        let merged = self.rects.swap_remove(i).union(b);
+        self.add(merged);
+    }
+}
+
+fn covers(outer: &Rect, inner: &Rect) -> bool {
+    inner.x >= outer.x
+        && inner.y >= outer.y
+        && inner.right() <= outer.right()
+        && inner.bottom() <= outer.bottom()
+}
+
+/// Overlapping or adjacent, corners included.
+fn touches(a: &Rect, b: &Rect) -> bool {
+    a.x <= b.right() && b.x <= a.right() && a.y <= b.bottom() && b.y <= a.bottom()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjacent_spans_merge_and_distant_ones_stay_apart() {
+        let mut d = DirtyRects::new();
+        for y in 2..6 {
+            d.add(Rect::new(1, y, 4, 1));
+        }
+        d.add(Rect::new(2, 3, 1, 1)); // already covered
+        d.add(Rect::new(20, 20, 2, 2));
+        d.add(Rect::new(5, 6, 1, 1)); // diagonal neighbor
+        d.add(Rect::new(0, 0, 0, 5)); // empty
+        let mut rects = d.take();
+        rects.sort_by_key(|r| (r.x, r.y));
+        assert_eq!(rects, vec![Rect::new(1, 2, 5, 5), Rect::new(20, 20, 2, 2)]);
+        assert!(d.is_empty());
+    }
+
+    #[test]
+    fn merging_a_bridge_pulls_in_every_neighbor() {
+        let mut d = DirtyRects::new();
+        d.add(Rect::new(0, 0, 2, 2));
+        d.add(Rect::new(6, 0, 2, 2));
+        d.add(Rect::new(2, 0, 4, 1));
+        assert_eq!(d.rects(), &[Rect::new(0, 0, 8, 2)]);
+    }
+
+    #[test]
+    fn limit_merges_the_cheapest_pair() {
+        let mut d = DirtyRects::with_max(2);
+        d.add(Rect::new(0, 0, 2, 2));
+        d.add(Rect::new(40, 0, 2, 2));
+        d.add(Rect::new(4, 0, 2, 2));
+        let mut rects = d.rects().to_vec();
+        rects.sort_by_key(|r| r.x);
+        assert_eq!(rects, vec![Rect::new(0, 0, 6, 2), Rect::new(40, 0, 2, 2)]);
+        assert_eq!(d.area(), 16);
+    }
+}
@@ -0,0 +1,612 @@
+//! Display lists: record draw calls, replay them later, save them as text or export SVG.
+//!
+//! A `DisplayList` has a logical size and an ordered list of `Command`s that mirror the
+//! `draw` helpers. `replay` issues exactly those calls, so it matches drawing directly.
+//! `replay_scaled` and `to_svg` treat the commands as vector shapes in continuous pixel
+//! space instead: a 1px line at scale 3 becomes a 3px stroke, and pixel `(x, y)` maps to the
+//! square `[x, x+1) x [y, y+1)` just like in `path`. One difference follows from that:
+//! the `draw` helpers store translucent colors as-is, while vector shapes (and SVG) blend them.
+//!
+//! Text format: the first line is `size W H`, then one command per line, e.g.
+//! `line 0 0 10 5 #ff0000ff`, `rect X Y W H COLOR`, `fill_rect ...`, `circle CX CY R COLOR`,
+//! `fill_circle ...`, `clear COLOR`. Colors accept anything `Color::parse` does. Blank lines
+//! and lines starting with `#` are ignored.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path as FsPath;
+
+use crate::core::{Color, Surface};
+use crate::draw;
+use crate::format::PixelFormat;
+use crate::path::{self, FillRule, LineCap, Path, Stroke};
+use crate::rect::Rect;
+
+/// One recorded draw call. Fields match the corresponding `draw` function.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Command {
+    /// `draw::clear`: replaces every pixel, including earlier commands.
+    Clear(Color),
+    /// `draw::draw_line`, endpoints included.
+    Line {
+        x0: i32,
+        y0: i32,
+        x1: i32,
+        y1: i32,
+        color: Color,
+    },
+    /// `draw::draw_rect`: 1px border of the rect.
+    Rect { rect: Rect, color: Color },
+    /// `draw::fill_rect`.
+    FillRect { rect: Rect, color: Color },
+    /// `draw::draw_circle`.
+    Circle {
+        cx: i32,
+        cy: i32,
+        r: i32,
+        color: Color,
+    },
+    /// `draw::fill_circle`.
+    FillCircle {
+        cx: i32,
+        cy: i32,
+        r: i32,
+        color: Color,
+    },
+}
+
+impl Command {
+    /// Rasterize with the matching `draw` function.
+    pub fn apply<P: PixelFormat>(&self, surface: &mut Surface<P>) {
+        match *self {
+            Command::Clear(color) => draw::clear(surface, color),
+            Command::Line {
+                x0,
+                y0,
+                x1,
+                y1,
+                color,
+            } => draw::draw_line(surface, x0, y0, x1, y1, color),
+            Command::Rect { rect: r, color } => draw::draw_rect(surface, r.x, r.y, r.w, r.h, color),
+            Command::FillRect { rect: r, color } => {
+                draw::fill_rect(surface, r.x, r.y, r.w, r.h, color)
+            }
+            Command::Circle { cx, cy, r, color } => draw::draw_circle(surface, cx, cy, r, color),
+            Command::FillCircle { cx, cy, r, color } => {
+                draw::fill_circle(surface, cx, cy, r, color)
+            }
+        }
+    }
+
+    /// Rasterize as an anti-aliased vector shape with every coordinate multiplied by `scale`.
+    pub fn apply_scaled<P: PixelFormat>(&self, surface: &mut Surface<P>, scale: f32) {
+        let s = scale;
+        let stroke = Stroke::new(s);
+        match *self {
+            Command::Clear(color) => surface.clear(color),
+            Command::Line {
+                x0,
+                y0,
+                x1,
+                y1,
+                color,
+            } => {
+                if (x0, y0) == (x1, y1) {
+                    return fill_box(surface, Rect::new(x0, y0, 1, 1), s, color);
+                }
+                let mut p = Path::new();
+                p.move_to((x0 as f32 + 0.5) * s, (y0 as f32 + 0.5) * s)
+                    .line_to((x1 as f32 + 0.5) * s, (y1 as f32 + 0.5) * s);
+                path::stroke_path_aa(surface, &p, &stroke.with_cap(LineCap::Square), color);
+            }
+            Command::Rect { rect: r, color } => match border(r) {
+                Some((x0, y0, x1, y1)) => {
+                    let mut p = Path::new();
+                    p.move_to(x0 * s, y0 * s)
+                        .line_to(x1 * s, y0 * s)
+                        .line_to(x1 * s, y1 * s)
+                        .line_to(x0 * s, y1 * s)
+                        .close();
+                    path::stroke_path_aa(surface, &p, &stroke, color);
+                }
+                None => fill_box(surface, r, s, color),
+            },
+            Command::FillRect { rect, color } => fill_box(surface, rect, s, color),
+            Command::Circle { cx, cy, r, color } if r > 0 => {
+                let mut p = Path::new();
+                let (x, y) = ((cx as f32 + 0.5) * s, (cy as f32 + 0.5) * s);
+                p.arc(x, y, r as f32 * s, 0.0, std::f32::consts::TAU)
+                    .close();
+                path::stroke_path_aa(surface, &p, &stroke, color);
+            }
+            Command::Circle { cx, cy, r, color } | Command::FillCircle { cx, cy, r, color } => {
+                if r == 0 {
+                    fill_box(surface, Rect::new(cx, cy, 1, 1), s, color);
+                } else if r > 0 {
+                    let mut p = Path::new();
+                    let (x, y) = ((cx as f32 + 0.5) * s, (cy as f32 + 0.5) * s);
+                    p.arc(x, y, (r as f32 + 0.5) * s, 0.0, std::f32::consts::TAU)
+                        .close();
+                    path::fill_path_aa(surface, &p, FillRule::NonZero, color);
+                }
+            }
+        }
+    }
+
+    /// One line of the text format, without the newline.
+    pub fn to_text(&self) -> String {
+        match *self {
+            Command::Clear(c) => format!("clear {}", c.to_hex()),
+            Command::Line {
+                x0,
+                y0,
+                x1,
+                y1,
+                color,
+            } => format!("line {x0} {y0} {x1} {y1} {}", color.to_hex()),
+            Command::Rect { rect: r, color } => {
+                format!("rect {} {} {} {} {}", r.x, r.y, r.w, r.h, color.to_hex())
+            }
+            Command::FillRect { rect: r, color } => {
+                format!(
+                    "fill_rect {} {} {} {} {}",
+                    r.x,
+                    r.y,
+                    r.w,
+                    r.h,
+                    color.to_hex()
+                )
+            }
+            Command::Circle { cx, cy, r, color } => {
+                format!("circle {cx} {cy} {r} {}", color.to_hex())
+            }
+            Command::FillCircle { cx, cy, r, color } => {
+                format!("fill_circle {cx} {cy} {r} {}", color.to_hex())
+            }
+        }
+    }
+
+    /// Parse one line of the text format.
+    pub fn parse(line: &str) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut fields = line.split_whitespace();
+        let op = fields.next().unwrap_or_default();
+        let args: Vec<&str> = fields.collect();
+        let arity = match op {
+            "clear" => 0,
+            "circle" | "fill_circle" => 3,
+            "line" | "rect" | "fill_rect" => 4,
+            _ => return Err(invalid(format!("unknown command {op:?}"))),
+        };
+        if args.len() != arity + 1 {
+            return Err(invalid(format!(
+                "{op} takes {arity} numbers and a color, got {} fields",
+                args.len()
+            )));
+        }
+        let mut n = [0i32; 4];
+        for (v, s) in n.iter_mut().zip(&args[..arity]) {
+            *v = s
+                .parse()
+                .map_err(|_| invalid(format!("{op}: bad number {s:?}")))?;
+        }
+        let color = Color::parse(args[arity]).map_err(|e| invalid(format!("{op}: {e}")))?;
+        let [a, b, c, d] = n;
+        Ok(match op {
+            "clear" => Command::Clear(color),
+            "line" => Command::Line {
+                x0: a,
+                y0: b,
+                x1: c,
+                y1: d,
+                color,
+            },
+            "rect" => Command::Rect {
+                rect: Rect::new(a, b, c, d),
+                color,
+            },
+            "fill_rect" => Command::FillRect {
+                rect: Rect::new(a, b, c, d),
+                color,
+            },
+            "circle" => Command::Circle {
+                cx: a,
+                cy: b,
+                r: c,
+                color,
+            },
+            _ => Command::FillCircle {
+                cx: a,
+                cy: b,
+                r: c,
+                color,
+            },
+        })
+    }
+
+    /// SVG element for this command, or an empty string when it draws nothing.
+    fn to_svg(self) -> String {
+        match self {
+            Command::Clear(c) => format!(r#"<rect width="100%" height="100%"{}/>"#, fill(c)),
+            Command::Line {
+                x0,
+                y0,
+                x1,
+                y1,
+                color,
+            } => format!(
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{} stroke-linecap="square"/>"#,
+                x0 as f32 + 0.5,
+                y0 as f32 + 0.5,
+                x1 as f32 + 0.5,
+                y1 as f32 + 0.5,
+                stroke(color),
+            ),
+            Command::Rect { rect: r, color } => match border(r) {
+                Some((x0, y0, x1, y1)) => format!(
+                    r#"<rect x="{x0}" y="{y0}" width="{}" height="{}" fill="none"{}/>"#,
+                    x1 - x0,
+                    y1 - y0,
+                    stroke(color),
+                ),
+                None => svg_box(r, color),
+            },
+            Command::FillRect { rect, color } => svg_box(rect, color),
+            Command::Circle { cx, cy, r, color } if r > 0 => format!(
+                r#"<circle cx="{}" cy="{}" r="{r}" fill="none"{}/>"#,
+                cx as f32 + 0.5,
+                cy as f32 + 0.5,
+                stroke(color),
+            ),
+            Command::Circle { cx, cy, r, color } | Command::FillCircle { cx, cy, r, color } => {
+                if r == 0 {
+                    svg_box(Rect::new(cx, cy, 1, 1), color)
+                } else if r > 0 {
+                    format!(
+                        r#"<circle cx="{}" cy="{}" r="{}"{}/>"#,
+                        cx as f32 + 0.5,
+                        cy as f32 + 0.5,
+                        r as f32 + 0.5,
+                        fill(color),
+                    )
+                } else {
+                    String::new()
+                }
+            }
+        }
+    }
+}
+
+/// Recorded draw calls plus the size of the canvas they were made for.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DisplayList {
+    width: usize,
+    height: usize,
+    commands: Vec<Command>,
+}
+
+impl DisplayList {
+    /// Empty list for a `width` x `height` canvas. The size is only used by `render` and SVG.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Recorded commands in order.
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Append a command. The recording methods below are shorthands for this.
+    pub fn push(&mut self, command: Command) -> &mut Self {
+        self.commands.push(command);
+        self
+    }
+
+    pub fn clear(&mut self, color: Color) -> &mut Self {
+        self.push(Command::Clear(color))
+    }
+
+    pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) -> &mut Self {
+        self.push(Command::Line {
+            x0,
+            y0,
+            x1,
+            y1,
+            color,
+        })
+    }
+
+    pub fn draw_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: Color) -> &mut Self {
+        let rect = Rect::new(x, y, w, h);
+        self.push(Command::Rect { rect, color })
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: Color) -> &mut Self {
+        let rect = Rect::new(x, y, w, h);
+        self.push(Command::FillRect { rect, color })
+    }
+
+    pub fn draw_circle(&mut self, cx: i32, cy: i32, r: i32, color: Color) -> &mut Self {
+        self.push(Command::Circle { cx, cy, r, color })
+    }
+
+    pub fn fill_circle(&mut self, cx: i32, cy: i32, r: i32, color: Color) -> &mut Self {
+        self.push(Command::FillCircle { cx, cy, r, color })
+    }
+
+    /// Issue every command onto `surface`, pixel for pixel as the direct `draw` calls would.
+    pub fn replay<P: PixelFormat>(&self, surface: &mut Surface<P>) {
+        for cmd in &self.commands {
+            cmd.apply(surface);
+        }
+    }
+
+    /// Replay as anti-aliased vector shapes scaled by `scale` (see `Command::apply_scaled`).
+    pub fn replay_scaled<P: PixelFormat>(&self, surface: &mut Surface<P>, scale: f32) {
+        for cmd in &self.commands {
+            cmd.apply_scaled(surface, scale);
+        }
+    }
+
+    /// New RGBA8 surface of the list's size times `scale`, with the list replayed onto it.
+    /// Scale 1 uses `replay`; anything else uses `replay_scaled`.
+    pub fn render(&self, scale: f32) -> Surface {
+        let size = |n: usize| (n as f32 * scale).round().max(0.0) as usize;
+        let mut s = Surface::new(size(self.width), size(self.height));
+        if scale == 1.0 {
+            self.replay(&mut s);
+        } else {
+            self.replay_scaled(&mut s, scale);
+        }
+        s
+    }
+
+    /// Serialize to the text format described in the module docs.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("size {} {}\n", self.width, self.height);
+        for cmd in &self.commands {
+            out.push_str(&cmd.to_text());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse the text format. Fails with `InvalidData` naming the offending line.
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut list: Option<Self> = None;
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |msg: &dyn std::fmt::Display| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("display list line {}: {msg}", n + 1),
+                )
+            };
+            match &mut list {
+                None => {
+                    let f: Vec<&str> = line.split_whitespace().collect();
+                    let size = match f[..] {
+                        ["size", w, h] => w.parse().ok().zip(h.parse().ok()),
+                        _ => None,
+                    };
+                    let (w, h) = size.ok_or_else(|| err(&"expected `size W H`"))?;
+                    list = Some(Self::new(w, h));
+                }
+                Some(list) => {
+                    list.push(Command::parse(line).map_err(|e| err(&e))?);
+                }
+            }
+        }
+        list.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "display list: missing `size` line",
+            )
+        })
+    }
+
+    /// Read a file in the text format.
+    pub fn load(path: impl AsRef<FsPath>) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Write the text format to a file.
+    pub fn save(&self, path: impl AsRef<FsPath>) -> io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+
+    /// SVG document with a `viewBox` of the list's size. Shapes sit where `replay_scaled`
+    /// puts them, so the SVG matches the raster at any zoom. Commands before the last `Clear`
+    /// are covered by it and left out.
+    pub fn to_svg(&self) -> String {
+        let (w, h) = (self.width, self.height);
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n"
+        );
+        let start = self
+            .commands
+            .iter()
+            .rposition(|c| matches!(c, Command::Clear(_)))
+            .unwrap_or(0);
+        for cmd in &self.commands[start..] {
+            let el = cmd.to_svg();
+            if !el.is_empty() {
+                let _ = writeln!(out, "  {el}");
+            }
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// Write `to_svg` to a file.
+    pub fn write_svg(&self, path: impl AsRef<FsPath>) -> io::Result<()> {
+        std::fs::write(path, self.to_svg())
+    }
+}
+
+/// Centerline of a rect's 1px border, or `None` when the border fills the whole rect.
+fn border(r: Rect) -> Option<(f32, f32, f32, f32)> {
+    (r.w > 2 && r.h > 2).then(|| {
+        (
+            r.x as f32 + 0.5,
+            r.y as f32 + 0.5,
+            r.right() as f32 - 0.5,
+            r.bottom() as f32 - 0.5,
+        )
+    })
+}
+
+fn fill_box<P: PixelFormat>(surface: &mut Surface<P>, r: Rect, s: f32, color: Color) {
+    if r.is_empty() {
+        return;
+    }
+    let (x0, y0) = (r.x as f32 * s, r.y as f32 * s);
+    let (x1, y1) = (r.right() as f32 * s, r.bottom() as f32 * s);
+    let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+    path::fill_polygon_aa(surface, &corners, FillRule::NonZero, color);
+}
+
+fn svg_box(r: Rect, color: Color) -> String {
+    if r.is_empty() {
+        return String::new();
+    }
+    format!(
+        r#"<rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+        r.x,
+        r.y,
+        r.w,
+        r.h,
+        fill(color)
+    )
+}
+
+/// ` fill="#rrggbb"`, plus `fill-opacity` when the color is translucent.
+fn fill(c: Color) -> String {
+    svg_paint("fill", c)
+}
+
+/// ` stroke="#rrggbb"`, plus `stroke-opacity` when the color is translucent.
+fn stroke(c: Color) -> String {
+    svg_paint("stroke", c)
+}
+
+fn svg_paint(attr: &str, c: Color) -> String {
+    let mut s = format!(r##" {attr}="#{:02x}{:02x}{:02x}""##, c.r, c.g, c.b);
+    if c.a < 255 {
+        let _ = write!(s, r#" {attr}-opacity="{:.3}""#, c.a as f32 / 255.0);
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> DisplayList {
+        let mut list = DisplayList::new(32, 24);
+        list.clear(Color::rgba(10, 20, 30, 255))
+            .draw_line(1, 1, 30, 20, Color::rgba(255, 0, 0, 255))
+            .draw_rect(2, 3, 10, -2, Color::rgba(0, 255, 0, 255))
+            .fill_rect(20, 4, 6, 6, Color::rgba(0, 0, 255, 128))
+            .draw_circle(8, 14, 5, Color::WHITE)
+            .fill_circle(24, 16, 3, Color::rgba(255, 255, 0, 255));
+        list
+    }
+
+    #[test]
+    fn replay_matches_direct_drawing() {
+        let list = sample();
+        let mut direct = Surface::new(32, 24);
+        draw::clear(&mut direct, Color::rgba(10, 20, 30, 255));
+        draw::draw_line(&mut direct, 1, 1, 30, 20, Color::rgba(255, 0, 0, 255));
+        draw::draw_rect(&mut direct, 2, 3, 10, -2, Color::rgba(0, 255, 0, 255));
+        draw::fill_rect(&mut direct, 20, 4, 6, 6, Color::rgba(0, 0, 255, 128));
+        draw::draw_circle(&mut direct, 8, 14, 5, Color::WHITE);
+        draw::fill_circle(&mut direct, 24, 16, 3, Color::rgba(255, 255, 0, 255));
+        assert_eq!(list.render(1.0).pixels(), direct.pixels());
+    }
+
+    #[test]
+    fn scaled_replay_covers_scaled_pixels() {
+        let mut list = DisplayList::new(4, 4);
+        list.fill_rect(1, 1, 2, 1, Color::WHITE)
+            .draw_line(0, 3, 3, 3, Color::rgba(255, 0, 0, 255));
+        let s = list.render(3.0);
+        assert_eq!((s.width(), s.height()), (12, 12));
+        assert_eq!(s.get_pixel(3, 3), Some(Color::WHITE));
+        assert_eq!(s.get_pixel(8, 5), Some(Color::WHITE));
+        assert_eq!(s.get_pixel(9, 5), Some(Color::TRANSPARENT));
+        assert_eq!(s.get_pixel(2, 2), Some(Color::TRANSPARENT));
+        // The square-capped line fills the whole bottom row of 3x3 blocks.
+        for x in 0..12 {
+            assert_eq!(s.get_pixel(x, 9), Some(Color::rgba(255, 0, 0, 255)));
+            assert_eq!(s.get_pixel(x, 8), Some(Color::TRANSPARENT));
+        }
+    }
+
+    #[test]
+    fn text_format_roundtrips_and_reports_lines() {
+        let list = sample();
+        let text = list.to_text();
+        assert!(text.starts_with("size 32 24\nclear #0a141eff\n"));
+        assert!(text.contains("rect 2 1 10 2 #00ff00ff\n"));
+        assert_eq!(DisplayList::parse(&text).unwrap(), list);
+
+        let parsed = DisplayList::parse("# demo\nsize 8 8\n\nfill_circle 4 4 2 gold\n").unwrap();
+        assert_eq!(parsed.len(), 1);
+        for (src, line) in [
+            ("size 8 8\nline 0 0 1 red\n", "line 2"),
+            ("size 8 8\nrect 0 0 1 1 nope\n", "line 2"),
+            ("fill_rect 0 0 1 1 red\n", "line 1"),
+            ("size 8 8\n\nspiral 1 2 3 red\n", "line 3"),
+        ] {
+            let err = DisplayList::parse(src).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(err.to_string().contains(line), "{err}");
+        }
+        assert!(DisplayList::parse("").is_err());
+    }
+
+    #[test]
+    fn svg_export_starts_at_last_clear() {
+        let mut list = sample();
+        list.clear(Color::BLACK)
+            .draw_rect(0, 0, 4, 4, Color::rgba(255, 0, 0, 128));
+        let svg = list.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"32\""));
+        assert!(svg.contains("viewBox=\"0 0 32 24\""));
+        assert!(!svg.contains("<circle"));
+        assert!(svg.contains(r##"<rect width="100%" height="100%" fill="#000000"/>"##));
+        assert!(svg.contains(
+            r##"<rect x="0.5" y="0.5" width="3" height="3" fill="none" stroke="#ff0000" stroke-opacity="0.502"/>"##
+        ));
+        assert!(svg.ends_with("</svg>\n"));
+
+        let svg = sample().to_svg();
+        assert!(svg.contains(r##"<circle cx="24.5" cy="16.5" r="3.5" fill="#ffff00"/>"##));
+        assert!(svg.contains(r#"<line x1="1.5" y1="1.5" x2="30.5" y2="20.5""#));
+    }
+}
@@ -0,0 +1,89 @@
+//! Coverage-based anti-aliasing by regular supersampling.
+
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+use crate::paint::Paint;
+
+/// Samples per axis; each pixel is tested at `GRID * GRID` points.
+const GRID: usize = 4;
+
+/// Scale a color's alpha by `covered / total` (rounded).
+pub(crate) fn with_coverage(color: Color, covered: u32, total: u32) -> Color {
+    let a = (color.a as u32 * covered + total / 2) / total;
+    Color::rgba(color.r, color.g, color.b, a as u8)
+}
+
+/// Blend `color` into the pixels of the half-open box [x0, x1) x [y0, y1), weighted by the
+/// fraction of sample points for which `inside(px, py)` holds. Sample coordinates are continuous,
+/// so pixel (x, y) covers [x, x+1) x [y, y+1).
+pub(crate) fn fill_coverage<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    color: Color,
+    inside: impl Fn(f32, f32) -> bool,
+) {
+    fill_coverage_paint(surface, x0, y0, x1, y1, &Paint::Solid(color), inside);
+}
+
+/// `fill_coverage` with the color sampled from `paint` at each pixel center.
+pub(crate) fn fill_coverage_paint<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    paint: &Paint,
+    inside: impl Fn(f32, f32) -> bool,
+) {
+    let c = surface.clip_rect();
+    let x0 = x0.max(c.x);
+    let y0 = y0.max(c.y);
+    let x1 = x1.min(c.right());
+    let y1 = y1.min(c.bottom());
+    let total = (GRID * GRID) as u32;
+    let step = 1.0 / GRID as f32;
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let mut covered = 0u32;
+            for sy in 0..GRID {
+                let py = y as f32 + (sy as f32 + 0.5) * step;
+                for sx in 0..GRID {
+                    let px = x as f32 + (sx as f32 + 0.5) * step;
+                    if inside(px, py) {
+                        covered += 1;
+                    }
+                }
+            }
+            if covered > 0 {
+                let color = paint.color_at(x as f32 + 0.5, y as f32 + 0.5);
+                surface.blend_pixel(x, y, with_coverage(color, covered, total));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_full_partial_and_none() {
+        let mut s = Surface::new(3, 1);
+        let c = Color::rgba(255, 255, 255, 255);
+        // Half-plane x < 1.5: pixel 0 full, pixel 1 half, pixel 2 empty
+        fill_coverage(&mut s, 0, 0, 3, 1, c, |px, _| px < 1.5);
+        assert_eq!(s.get_pixel(0, 0), Some(c));
+        assert_eq!(s.get_pixel(1, 0), Some(Color::rgba(255, 255, 255, 128)));
+        assert_eq!(s.get_pixel(2, 0), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn with_coverage_scales_alpha() {
+        let c = Color::rgba(10, 20, 30, 200);
+        assert_eq!(with_coverage(c, 8, 16).a, 100);
+        assert_eq!(with_coverage(c, 16, 16), c);
+    }
+}
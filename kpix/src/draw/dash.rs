@@ -0,0 +1,640 @@
+//! Dashed and dotted outlines.
+//!
+//! A `Dash` lists alternating on/off lengths, starting with "on", plus a phase that shifts
+//! where in the pattern drawing starts. The pixel primitives here measure length in pixels
+//! along the Bresenham walk (pixel `i` is on when `i + 0.5` falls in an "on" interval),
+//! draw exactly the pixels of their solid counterparts, and return the phase at which a
+//! following segment should continue. `path::stroke_path_dashed` measures the same pattern
+//! in continuous length along the path.
+
+use kmath::Vec2;
+
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+use crate::path::Polyline;
+
+/// Most dash intervals [`Dash::split`] walks; finer patterns are drawn solid.
+const MAX_DASH_INTERVALS: f32 = (1 << 20) as f32;
+
+/// Dash pattern with a phase offset. An empty, all-zero or negative pattern draws solid.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dash {
+    pattern: Vec<f32>,
+    pub phase: f32,
+}
+
+impl Dash {
+    /// On/off lengths, starting with "on". An odd-length pattern is repeated once so that
+    /// on and off alternate, as in SVG. Negative lengths count as 0.
+    pub fn new(pattern: &[f32]) -> Self {
+        let mut pattern: Vec<f32> = pattern.iter().map(|v| v.max(0.0)).collect();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_within(..);
+        }
+        Self {
+            pattern,
+            phase: 0.0,
+        }
+    }
+
+    /// Every other pixel.
+    pub fn dotted() -> Self {
+        Self::new(&[1.0, 1.0])
+    }
+
+    /// Start `phase` units into the pattern.
+    pub fn with_phase(mut self, phase: f32) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    pub fn pattern(&self) -> &[f32] {
+        &self.pattern
+    }
+
+    /// Total length of one repetition; 0 for a solid pattern.
+    pub fn period(&self) -> f32 {
+        let p: f32 = self.pattern.iter().sum();
+        if p.is_finite() { p } else { 0.0 }
+    }
+
+    /// Whether the point `distance` units from the start (after the phase) is drawn.
+    pub fn is_on(&self, distance: f32) -> bool {
+        let period = self.period();
+        if period <= 0.0 {
+            return true;
+        }
+        let mut p = (distance + self.phase).rem_euclid(period);
+        for (i, &len) in self.pattern.iter().enumerate() {
+            if p < len {
+                return i % 2 == 0;
+            }
+            p -= len;
+        }
+        false
+    }
+
+    /// Whether pixel `n` of a walk, centered at `n + 0.5`, is drawn. The offset is wrapped
+    /// in f64 first so the pattern stays exact on long walks.
+    fn pixel_on(&self, n: i64) -> bool {
+        let period = self.period();
+        if period <= 0.0 {
+            return true;
+        }
+        self.is_on((n as f64 + 0.5).rem_euclid(period as f64) as f32)
+    }
+
+    /// Phase after a walk of `n` pixels.
+    fn advanced_pixels(&self, n: i64) -> f32 {
+        let period = self.period();
+        if period <= 0.0 {
+            self.phase
+        } else {
+            (self.phase as f64 + n as f64).rem_euclid(period as f64) as f32
+        }
+    }
+
+    /// Phase after advancing `distance` units, wrapped into one period.
+    fn advanced(&self, distance: f32) -> f32 {
+        let period = self.period();
+        if period <= 0.0 {
+            self.phase
+        } else {
+            (self.phase + distance).rem_euclid(period)
+        }
+    }
+
+    /// Split polylines into the "on" pieces of the pattern. Each subpath restarts at the
+    /// dash's phase; closed subpaths are walked back to their first point. A pattern whose
+    /// shortest length fits more than 2^20 times into the total length is left solid.
+    pub fn split(&self, lines: &[Polyline]) -> Vec<Polyline> {
+        if self.period() <= 0.0 {
+            return lines.to_vec();
+        }
+        let closed: Vec<Vec<Vec2>> = lines
+            .iter()
+            .map(|line| {
+                let mut points = line.points.clone();
+                if line.closed && points.len() > 1 {
+                    points.push(points[0]);
+                }
+                points
+            })
+            .collect();
+        let total: f32 = closed
+            .iter()
+            .flat_map(|points| points.windows(2))
+            .map(|w| (w[1] - w[0]).length())
+            .sum();
+        let finest = self
+            .pattern
+            .iter()
+            .filter(|&&v| v > 0.0)
+            .fold(f32::INFINITY, |a, &v| a.min(v));
+        let intervals = total / finest;
+        if intervals.is_nan() || intervals > MAX_DASH_INTERVALS {
+            return lines.to_vec();
+        }
+        let mut out = Vec::new();
+        for points in &closed {
+            self.split_one(points, &mut out);
+        }
+        out
+    }
+
+    fn split_one(&self, points: &[Vec2], out: &mut Vec<Polyline>) {
+        let Some(&first) = points.first() else {
+            return;
+        };
+        // Locate the phase inside the pattern.
+        let n = self.pattern.len();
+        let mut idx = 0;
+        let mut left = self.pattern[0];
+        let mut skip = self.advanced(0.0);
+        while skip >= left {
+            skip -= left;
+            idx = (idx + 1) % n;
+            left = self.pattern[idx];
+        }
+        left -= skip;
+
+        let mut current = (idx % 2 == 0).then(|| vec![first]);
+        let mut finish = |piece: Vec<Vec2>| {
+            let len: f32 = piece.windows(2).map(|w| (w[1] - w[0]).length()).sum();
+            if len > 1e-6 {
+                out.push(Polyline {
+                    points: piece,
+                    closed: false,
+                });
+            }
+        };
+        for w in points.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            let len = (b - a).length();
+            let mut t = 0.0;
+            while len - t > left {
+                t += left;
+                let p = a + (b - a) * (t / len);
+                match current.take() {
+                    Some(mut piece) => {
+                        piece.push(p);
+                        finish(piece);
+                    }
+                    None => current = Some(vec![p]),
+                }
+                idx = (idx + 1) % n;
+                left = self.pattern[idx];
+            }
+            left -= len - t;
+            if let Some(piece) = &mut current {
+                piece.push(b);
+            }
+        }
+        if let Some(piece) = current {
+            finish(piece);
+        }
+    }
+}
+
+/// Dashed `draw_line`. Returns the phase for a segment that continues from `(x1, y1)`;
+/// that segment should skip its first pixel, as `draw_polyline_dashed` does.
+pub fn draw_line_dashed<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    dash: &Dash,
+    color: Color,
+) -> f32 {
+    let n = dashed_segment(surface, (x0, y0), (x1, y1), 0, 0, 0, dash, color);
+    dash.advanced_pixels(n)
+}
+
+/// Plot the visible pixels of one segment, leaving out `skip_head` pixels at its start and
+/// `skip_tail` at its end. Kept pixel `k` is pixel `base + k` of the whole walk. Returns the
+/// number of pixels kept, visible or not.
+#[allow(clippy::too_many_arguments)]
+fn dashed_segment<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    (x0, y0): (i32, i32),
+    (x1, y1): (i32, i32),
+    base: i64,
+    skip_head: i64,
+    skip_tail: i64,
+    dash: &Dash,
+    color: Color,
+) -> i64 {
+    let (x0, y0, x1, y1) = (x0 as i64, y0 as i64, x1 as i64, y1 as i64);
+    let len = super::line_len(x0, y0, x1, y1);
+    let clip = surface.clip_rect();
+    super::walk_line(clip, x0, y0, x1, y1, |k, x, y| {
+        if k >= skip_head && k < len - skip_tail && dash.pixel_on(base + k - skip_head) {
+            surface.set_pixel(x, y, color);
+        }
+    });
+    (len - skip_head - skip_tail).max(0)
+}
+
+/// Connected segments through `points` with the pattern running on across corners. Shared
+/// vertices are drawn once. `closed` adds a segment back to the first point. Returns the
+/// phase after the last pixel.
+pub fn draw_polyline_dashed<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    points: &[(i32, i32)],
+    closed: bool,
+    dash: &Dash,
+    color: Color,
+) -> f32 {
+    let Some(&first) = points.first() else {
+        return dash.advanced_pixels(0);
+    };
+    if points.len() == 1 {
+        let n = dashed_segment(surface, first, first, 0, 0, 0, dash, color);
+        return dash.advanced_pixels(n);
+    }
+    // Each segment after the first starts on the previous one's last pixel. A closed path
+    // also leaves out the pixel it ends on, which is the first one.
+    let last = points[points.len() - 1];
+    let segments = points.windows(2).count();
+    let mut n = 0;
+    for (i, w) in points.windows(2).enumerate() {
+        let head = (i > 0) as i64;
+        let tail = (closed && last == first && i > 0 && i + 1 == segments) as i64;
+        n += dashed_segment(surface, w[0], w[1], n, head, tail, dash, color);
+    }
+    if closed && last != first {
+        n += dashed_segment(surface, last, first, n, 1, 1, dash, color);
+    }
+    dash.advanced_pixels(n)
+}
+
+/// Dashed `draw_rect`: the border runs clockwise from the top-left corner.
+pub fn draw_rect_dashed<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    dash: &Dash,
+    color: Color,
+) -> f32 {
+    let (x0, x1) = if w >= 0 { (x, x + w) } else { (x + w, x) };
+    let (y0, y1) = if h >= 0 { (y, y + h) } else { (y + h, y) };
+    if x0 >= x1 || y0 >= y1 {
+        return dash.phase;
+    }
+    let (x1, y1) = (x1 - 1, y1 - 1);
+    if x0 == x1 || y0 == y1 {
+        return draw_line_dashed(surface, x0, y0, x1, y1, dash, color);
+    }
+    let corners = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+    draw_polyline_dashed(surface, &corners, true, dash, color)
+}
+
+/// Dashed `draw_circle`: the outline runs clockwise on screen from the rightmost pixel.
+pub fn draw_circle_dashed<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    dash: &Dash,
+    color: Color,
+) -> f32 {
+    if r < 0 {
+        return dash.phase;
+    }
+    if r == 0 {
+        if dash.pixel_on(0) {
+            surface.set_pixel(cx, cy, color);
+        }
+        return dash.advanced_pixels(1);
+    }
+    // First octant from (r, 0) toward the diagonal, as in `draw_circle`: the midpoint walk
+    // keeps `x` on row `y` while `x * (x - 1) <= r² - y²`, so each point is computed directly
+    // and `len` (the row count up to the diagonal) is found by bisection.
+    let r = r as i64;
+    let x_at = |y: i64| ((4 * (r * r - y * y) as u128 + 1).isqrt() as i64 + 1) / 2;
+    let (mut lo, mut hi) = (0, r);
+    while lo < hi {
+        let mid = (lo + hi + 1) / 2;
+        if mid <= x_at(mid) {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    let len = lo + 1;
+    // The outline visits the eight mirrored octants, alternating direction so the walk stays
+    // ordered. Where two octants meet on the same pixel it is drawn once: always at the axes,
+    // and at the diagonals when the octant ends on one. The final pixel closes the loop onto
+    // the first and is dropped.
+    type Map = fn((i64, i64)) -> (i64, i64);
+    let maps: [Map; 8] = [
+        |(x, y)| (x, y),
+        |(x, y)| (y, x),
+        |(x, y)| (-y, x),
+        |(x, y)| (-x, y),
+        |(x, y)| (-x, -y),
+        |(x, y)| (-y, -x),
+        |(x, y)| (y, -x),
+        |(x, y)| (x, -y),
+    ];
+    let diagonal = (x_at(lo) == lo) as i64;
+    let mut starts = [0i64; 8];
+    let mut heads = [0i64; 8];
+    let mut total = 0;
+    for i in 0..8 {
+        heads[i] = match i {
+            0 => 0,
+            _ if i % 2 == 1 => diagonal,
+            _ => 1,
+        };
+        starts[i] = total;
+        total += len - heads[i];
+    }
+    total -= 1;
+    // Only the octant rows whose mirrored step lands inside the clip are visited: each map
+    // sends the step `t` along one screen axis, so the visible steps form a range.
+    let clip = surface.clip_rect();
+    let (cx, cy) = (cx as i64, cy as i64);
+    for (i, map) in maps.iter().enumerate() {
+        let (ux, uy) = map((0, 1));
+        let (c, lo, hi, sign) = if ux != 0 {
+            (cx, clip.x as i64, clip.right() as i64 - 1, ux)
+        } else {
+            (cy, clip.y as i64, clip.bottom() as i64 - 1, uy)
+        };
+        let (t0, t1) = if sign > 0 {
+            (lo - c, hi - c)
+        } else {
+            (c - hi, c - lo)
+        };
+        for t in t0.max(0)..=t1.min(len - 1) {
+            let pos = if i % 2 == 0 { t } else { len - 1 - t };
+            let n = starts[i] + pos - heads[i];
+            if pos < heads[i] || n >= total || !dash.pixel_on(n) {
+                continue;
+            }
+            let (px, py) = map((x_at(t), t));
+            if let (Ok(x), Ok(y)) = (i32::try_from(cx + px), i32::try_from(cy + py)) {
+                surface.set_pixel(x, y, color);
+            }
+        }
+    }
+    dash.advanced_pixels(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw;
+    use crate::rect::Rect;
+
+    const C: Color = Color::rgba(255, 255, 255, 255);
+
+    fn lit(s: &Surface) -> Vec<(i32, i32)> {
+        let mut v = Vec::new();
+        for y in 0..s.height() as i32 {
+            for x in 0..s.width() as i32 {
+                if s.get_pixel(x, y) == Some(C) {
+                    v.push((x, y));
+                }
+            }
+        }
+        v
+    }
+
+    #[test]
+    fn patterns_alternate_and_wrap() {
+        let d = Dash::new(&[3.0]);
+        assert_eq!(d.pattern(), &[3.0, 3.0]);
+        assert!(d.is_on(0.5) && d.is_on(2.9) && !d.is_on(3.0) && d.is_on(6.5));
+        let d = Dash::new(&[2.0, 1.0]).with_phase(2.5);
+        assert!(!d.is_on(0.0) && d.is_on(0.5) && d.is_on(-0.6));
+        assert!(Dash::new(&[]).is_on(123.0));
+        assert!(Dash::new(&[0.0, -1.0]).is_on(1.0));
+    }
+
+    #[test]
+    fn dashed_line_and_polyline_carry_the_phase() {
+        let mut s = Surface::new(12, 4);
+        let dash = Dash::new(&[3.0, 2.0]);
+        let phase = draw_line_dashed(&mut s, 0, 0, 6, 0, &dash, C);
+        assert_eq!(phase, 2.0);
+        let xs: Vec<i32> = lit(&s).iter().map(|p| p.0).collect();
+        assert_eq!(xs, vec![0, 1, 2, 5, 6]);
+
+        // An L shape: the corner pixel is counted once, so the pattern runs on around it.
+        let mut s = Surface::new(12, 12);
+        draw_polyline_dashed(&mut s, &[(0, 0), (4, 0), (4, 6)], false, &dash, C);
+        assert_eq!(
+            lit(&s),
+            vec![(0, 0), (1, 0), (2, 0), (4, 1), (4, 2), (4, 3), (4, 6)]
+        );
+    }
+
+    #[test]
+    fn solid_dash_matches_the_plain_primitives() {
+        let solid = Dash::default();
+        let mut a = Surface::new(20, 20);
+        let mut b = Surface::new(20, 20);
+        draw_circle_dashed(&mut a, 9, 9, 7, &solid, C);
+        draw::draw_circle(&mut b, 9, 9, 7, C);
+        assert_eq!(a.pixels(), b.pixels());
+        draw_rect_dashed(&mut a, 1, 2, 15, -1, &solid, C);
+        draw::draw_rect(&mut b, 1, 2, 15, -1, C);
+        draw_rect_dashed(&mut a, 3, 3, 6, 5, &solid, C);
+        draw::draw_rect(&mut b, 3, 3, 6, 5, C);
+        assert_eq!(a.pixels(), b.pixels());
+    }
+
+    #[test]
+    fn dotted_circle_and_rect_light_every_other_pixel() {
+        let mut s = Surface::new(20, 20);
+        let total = {
+            let mut t = Surface::new(20, 20);
+            draw::draw_circle(&mut t, 9, 9, 6, C);
+            lit(&t).len()
+        };
+        draw_circle_dashed(&mut s, 9, 9, 6, &Dash::dotted(), C);
+        assert_eq!(lit(&s).len(), total.div_ceil(2));
+
+        let mut s = Surface::new(8, 8);
+        let phase = draw_rect_dashed(&mut s, 0, 0, 4, 4, &Dash::dotted(), C);
+        assert_eq!(
+            lit(&s),
+            vec![(0, 0), (2, 0), (3, 1), (0, 2), (1, 3), (3, 3)]
+        );
+        assert_eq!(phase, 0.0);
+    }
+
+    /// Pixels of a Bresenham segment, unclipped.
+    fn segment(a: (i32, i32), b: (i32, i32)) -> Vec<(i32, i32)> {
+        let mut out = Vec::new();
+        crate::draw::walk_line(
+            Rect::new(-99, -99, 999, 999),
+            a.0 as i64,
+            a.1 as i64,
+            b.0 as i64,
+            b.1 as i64,
+            |_, x, y| out.push((x, y)),
+        );
+        out
+    }
+
+    /// Buffered reference: the whole walk in order, then every other-phase pixel plotted.
+    fn reference(pixels: &[(i32, i32)], dash: &Dash, clip: Rect) -> (Surface, f32) {
+        let mut s = Surface::new(24, 24);
+        s.push_clip(clip);
+        for (n, &(x, y)) in pixels.iter().enumerate() {
+            if dash.is_on(n as f32 + 0.5) {
+                s.set_pixel(x, y, C);
+            }
+        }
+        (s, dash.advanced(pixels.len() as f32))
+    }
+
+    fn circle_pixels(cx: i32, cy: i32, r: i32) -> Vec<(i32, i32)> {
+        let mut octant = Vec::new();
+        let (mut x, mut y, mut d) = (r, 0, 1 - r);
+        while y <= x {
+            octant.push((x, y));
+            y += 1;
+            if d <= 0 {
+                d += 2 * y + 1;
+            } else {
+                x -= 1;
+                d += 2 * (y - x) + 1;
+            }
+        }
+        type Map = fn((i32, i32)) -> (i32, i32);
+        let maps: [Map; 8] = [
+            |(x, y)| (x, y),
+            |(x, y)| (y, x),
+            |(x, y)| (-y, x),
+            |(x, y)| (-x, y),
+            |(x, y)| (-x, -y),
+            |(x, y)| (-y, -x),
+            |(x, y)| (y, -x),
+            |(x, y)| (x, -y),
+        ];
+        let mut pixels: Vec<(i32, i32)> = Vec::new();
+        for (i, map) in maps.iter().enumerate() {
+            let mut pts = octant.clone();
+            if i % 2 == 1 {
+                pts.reverse();
+            }
+            for p in pts {
+                let (px, py) = map(p);
+                let p = (cx + px, cy + py);
+                if pixels.last() != Some(&p) {
+                    pixels.push(p);
+                }
+            }
+        }
+        while pixels.len() > 1 && pixels.last() == pixels.first() {
+            pixels.pop();
+        }
+        pixels
+    }
+
+    #[test]
+    fn clipped_walks_match_the_buffered_walk() {
+        let clips = [Rect::new(0, 0, 24, 24), Rect::new(5, 3, 9, 11)];
+        let dashes = [
+            Dash::new(&[3.0, 2.0]),
+            Dash::new(&[1.5, 0.5, 4.0]).with_phase(2.25),
+            Dash::dotted(),
+        ];
+        let polys: [(&[(i32, i32)], bool); 5] = [
+            (&[(2, 3), (20, 9), (7, 21)], false),
+            (&[(2, 3), (20, 9), (7, 21)], true),
+            (&[(4, 4), (18, 4), (4, 4)], true),
+            (&[(6, 6), (6, 6)], true),
+            (&[(9, 9)], false),
+        ];
+        for clip in clips {
+            for dash in &dashes {
+                for (points, closed) in polys {
+                    let mut pixels = vec![points[0]];
+                    let mut ends = points.to_vec();
+                    if closed && points.len() > 1 {
+                        ends.push(points[0]);
+                    }
+                    for w in ends.windows(2) {
+                        pixels.extend(segment(w[0], w[1]).into_iter().skip(1));
+                    }
+                    if closed && pixels.len() > 1 && pixels.last() == pixels.first() {
+                        pixels.pop();
+                    }
+                    let (expected, phase) = reference(&pixels, dash, clip);
+                    let mut s = Surface::new(24, 24);
+                    s.push_clip(clip);
+                    let got = draw_polyline_dashed(&mut s, points, closed, dash, C);
+                    assert_eq!(s.pixels(), expected.pixels(), "{points:?} {closed}");
+                    assert!((got - phase).abs() < 1e-4, "{got} vs {phase}");
+                }
+                for r in 0..12 {
+                    let (expected, phase) = reference(&circle_pixels(11, 12, r), dash, clip);
+                    let mut s = Surface::new(24, 24);
+                    s.push_clip(clip);
+                    let got = draw_circle_dashed(&mut s, 11, 12, r, dash, C);
+                    assert_eq!(s.pixels(), expected.pixels(), "r = {r}");
+                    assert!((got - phase).abs() < 1e-4, "{got} vs {phase}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn far_endpoints_walk_only_the_visible_pixels() {
+        let mut s = Surface::new(8, 8);
+        let dash = Dash::new(&[3.0, 2.0]);
+        let phase = draw_line_dashed(&mut s, 0, 0, 1_000_000_000, 0, &dash, C);
+        assert_eq!(phase, 1.0); // 1e9 + 1 pixels, period 5
+        assert_eq!(
+            lit(&s),
+            vec![(0, 0), (1, 0), (2, 0), (5, 0), (6, 0), (7, 0)]
+        );
+        draw_line_dashed(&mut s, i32::MIN, i32::MIN, i32::MAX, i32::MAX, &dash, C);
+        draw_polyline_dashed(&mut s, &[(i32::MIN, 0), (i32::MAX, 7)], true, &dash, C);
+        draw_circle_dashed(&mut s, 0, 0, i32::MAX, &dash, C);
+    }
+
+    #[test]
+    fn split_cuts_polylines_along_their_length() {
+        let line = Polyline {
+            points: vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(4.0, 0.0),
+                Vec2::new(4.0, 6.0),
+            ],
+            closed: false,
+        };
+        let pieces = Dash::new(&[2.0, 3.0]).with_phase(1.0).split(&[line]);
+        let pts: Vec<Vec<(f32, f32)>> = pieces
+            .iter()
+            .map(|p| p.points.iter().map(|v| (v.x, v.y)).collect())
+            .collect();
+        assert_eq!(
+            pts,
+            vec![
+                vec![(0.0, 0.0), (1.0, 0.0)],
+                vec![(4.0, 0.0), (4.0, 2.0)],
+                vec![(4.0, 5.0), (4.0, 6.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn split_leaves_too_fine_patterns_solid() {
+        let line = Polyline {
+            points: vec![Vec2::new(0.0, 0.0), Vec2::new(1000.0, 0.0)],
+            closed: false,
+        };
+        let pieces = Dash::new(&[1e-6, 1e-6]).split(std::slice::from_ref(&line));
+        assert_eq!(pieces, vec![line.clone()]);
+        assert_eq!(Dash::new(&[1.0, 1e-6]).split(&[line]).len(), 1);
+    }
+}
@@ -0,0 +1,904 @@
+//! Circles, ellipses, arcs and pie slices.
+//!
+//! Centers are integer pixel coordinates; for anti-aliased variants the geometry is centered on
+//! the pixel center `(cx + 0.5, cy + 0.5)`. Angles are in radians, measured from +x toward +y
+//! (clockwise on screen, since y grows downward). Negative radii draw nothing.
+
+use std::f32::consts::TAU;
+
+use super::aa::{fill_coverage, fill_coverage_paint};
+use super::span::{Spans, Window, isqrt_div};
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+use crate::paint::Paint;
+
+/// Fill a circle centered at (cx, cy): all pixels with `dx*dx + dy*dy <= r*r`.
+pub fn fill_circle<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    color: Color,
+) {
+    fill_ellipse(surface, cx, cy, r, r, color);
+}
+
+/// Draw an anti-aliased 1px circle outline of radius `r`.
+pub fn draw_circle_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    color: Color,
+) {
+    if r < 0 {
+        return;
+    }
+    let (ox, oy, rf) = (cx as f32 + 0.5, cy as f32 + 0.5, r as f32);
+    fill_coverage(
+        surface,
+        cx - r - 1,
+        cy - r - 1,
+        cx + r + 2,
+        cy + r + 2,
+        color,
+        |px, py| {
+            let d = (px - ox).hypot(py - oy);
+            (d - rf).abs() <= 0.5
+        },
+    );
+}
+
+/// Fill an anti-aliased disc of radius `r`.
+pub fn fill_circle_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    color: Color,
+) {
+    fill_circle_aa_paint(surface, cx, cy, r, &Paint::Solid(color));
+}
+
+/// Draw an axis-aligned ellipse outline with radii `rx`, `ry`.
+/// The outline is the border of `fill_ellipse`, so both always line up.
+pub fn draw_ellipse<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    color: Color,
+) {
+    if let Some(spans) = ellipse_spans(cx, cy, rx, ry, &Window::new(surface)) {
+        spans.outline(surface, color);
+    }
+}
+
+/// Fill an axis-aligned ellipse: all pixels with `(dx/rx)^2 + (dy/ry)^2 <= 1`.
+/// A zero radius degenerates to a line along the other axis.
+pub fn fill_ellipse<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    color: Color,
+) {
+    if let Some(spans) = ellipse_spans(cx, cy, rx, ry, &Window::new(surface)) {
+        spans.fill(surface, color);
+    }
+}
+
+/// Draw an anti-aliased axis-aligned ellipse outline.
+pub fn draw_ellipse_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    color: Color,
+) {
+    draw_ellipse_rotated_aa(surface, cx, cy, rx, ry, 0.0, color);
+}
+
+/// Fill an anti-aliased axis-aligned ellipse.
+pub fn fill_ellipse_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    color: Color,
+) {
+    fill_ellipse_rotated_aa(surface, cx, cy, rx, ry, 0.0, color);
+}
+
+/// `fill_circle` with a paint source.
+pub fn fill_circle_paint<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    paint: &Paint,
+) {
+    fill_ellipse_paint(surface, cx, cy, r, r, paint);
+}
+
+/// `fill_circle_aa` with a paint source.
+pub fn fill_circle_aa_paint<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    paint: &Paint,
+) {
+    if r < 0 {
+        return;
+    }
+    let (ox, oy, rf) = (cx as f32 + 0.5, cy as f32 + 0.5, r as f32 + 0.5);
+    fill_coverage_paint(
+        surface,
+        cx - r,
+        cy - r,
+        cx + r + 1,
+        cy + r + 1,
+        paint,
+        |px, py| (px - ox).hypot(py - oy) <= rf,
+    );
+}
+
+/// `fill_ellipse` with a paint source.
+pub fn fill_ellipse_paint<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    paint: &Paint,
+) {
+    if let Some(spans) = ellipse_spans(cx, cy, rx, ry, &Window::new(surface)) {
+        spans.fill_paint(surface, paint);
+    }
+}
+
+/// Draw the outline of an ellipse whose `rx` axis is rotated by `angle` radians.
+pub fn draw_ellipse_rotated<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    angle: f32,
+    color: Color,
+) {
+    if let Some(spans) = rotated_ellipse_spans(cx, cy, rx, ry, angle, &Window::new(surface)) {
+        spans.outline(surface, color);
+    }
+}
+
+/// Fill an ellipse whose `rx` axis is rotated by `angle` radians.
+pub fn fill_ellipse_rotated<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    angle: f32,
+    color: Color,
+) {
+    if let Some(spans) = rotated_ellipse_spans(cx, cy, rx, ry, angle, &Window::new(surface)) {
+        spans.fill(surface, color);
+    }
+}
+
+/// Draw an anti-aliased rotated ellipse outline (1px wide).
+pub fn draw_ellipse_rotated_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    angle: f32,
+    color: Color,
+) {
+    if rx <= 0 || ry <= 0 {
+        draw_ellipse_rotated(surface, cx, cy, rx, ry, angle, color);
+        return;
+    }
+    let e = EllipseSdf::new(cx, cy, rx as f32, ry as f32, angle);
+    let ext = rx.max(ry) + 2;
+    fill_coverage(
+        surface,
+        cx - ext,
+        cy - ext,
+        cx + ext + 1,
+        cy + ext + 1,
+        color,
+        |px, py| e.distance(px, py).abs() <= 0.5,
+    );
+}
+
+/// Fill an anti-aliased rotated ellipse.
+pub fn fill_ellipse_rotated_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    angle: f32,
+    color: Color,
+) {
+    if rx <= 0 || ry <= 0 {
+        fill_ellipse_rotated(surface, cx, cy, rx, ry, angle, color);
+        return;
+    }
+    let e = EllipseSdf::new(cx, cy, rx as f32 + 0.5, ry as f32 + 0.5, angle);
+    let ext = rx.max(ry) + 1;
+    fill_coverage(
+        surface,
+        cx - ext,
+        cy - ext,
+        cx + ext + 1,
+        cy + ext + 1,
+        color,
+        |px, py| e.inside(px, py),
+    );
+}
+
+/// Draw a circular arc of radius `r` sweeping from `start` to `end` (radians, clockwise on
+/// screen). If `end < start` it is wrapped forward; sweeps of a full turn or more draw the
+/// whole circle. Pixels are those of `draw_circle` that fall inside the sweep.
+pub fn draw_arc<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    start: f32,
+    end: f32,
+    color: Color,
+) {
+    if r < 0 {
+        return;
+    }
+    let c = surface.clip_rect();
+    let (cx64, cy64, r64) = (cx as i64, cy as i64, r as i64);
+    if cx64 + r64 < c.x as i64
+        || cx64 - r64 >= c.right() as i64
+        || cy64 + r64 < c.y as i64
+        || cy64 - r64 >= c.bottom() as i64
+    {
+        return;
+    }
+    let sweep = Sweep::new(start, end);
+    let mut x = r;
+    let mut y = 0;
+    let mut d = 1 - r;
+    while y <= x {
+        for (dx, dy) in [
+            (x, y),
+            (-x, y),
+            (x, -y),
+            (-x, -y),
+            (y, x),
+            (-y, x),
+            (y, -x),
+            (-y, -x),
+        ] {
+            if sweep.contains_offset(dx as f32, dy as f32) {
+                surface.set_pixel(cx + dx, cy + dy, color);
+            }
+        }
+        y += 1;
+        if d <= 0 {
+            d += 2 * y + 1;
+        } else {
+            x -= 1;
+            d += 2 * (y - x) + 1;
+        }
+    }
+}
+
+/// Draw an anti-aliased circular arc (1px wide, square-cut ends).
+pub fn draw_arc_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    start: f32,
+    end: f32,
+    color: Color,
+) {
+    if r < 0 {
+        return;
+    }
+    let sweep = Sweep::new(start, end);
+    let (ox, oy, rf) = (cx as f32 + 0.5, cy as f32 + 0.5, r as f32);
+    fill_coverage(
+        surface,
+        cx - r - 1,
+        cy - r - 1,
+        cx + r + 2,
+        cy + r + 2,
+        color,
+        |px, py| {
+            let (dx, dy) = (px - ox, py - oy);
+            (dx.hypot(dy) - rf).abs() <= 0.5 && sweep.contains_offset(dx, dy)
+        },
+    );
+}
+
+/// Draw a pie slice outline: the arc plus both radii back to the center.
+pub fn draw_pie<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    start: f32,
+    end: f32,
+    color: Color,
+) {
+    if r < 0 {
+        return;
+    }
+    draw_arc(surface, cx, cy, r, start, end, color);
+    let sweep = Sweep::new(start, end);
+    if sweep.full {
+        return;
+    }
+    for a in [sweep.start, sweep.start + sweep.len] {
+        let ex = cx + (a.cos() * r as f32).round() as i32;
+        let ey = cy + (a.sin() * r as f32).round() as i32;
+        super::draw_line(surface, cx, cy, ex, ey, color);
+    }
+}
+
+/// Draw an anti-aliased pie slice outline: the `draw_arc_aa` arc plus both radii as 1px
+/// lines, covered in a single pass so the joins are not blended twice.
+pub fn draw_pie_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    start: f32,
+    end: f32,
+    color: Color,
+) {
+    if r < 0 {
+        return;
+    }
+    let sweep = Sweep::new(start, end);
+    let (ox, oy, rf) = (cx as f32 + 0.5, cy as f32 + 0.5, r as f32);
+    let radii = if sweep.full {
+        Vec::new()
+    } else {
+        vec![sweep.start, sweep.start + sweep.len]
+    };
+    fill_coverage(
+        surface,
+        cx - r - 1,
+        cy - r - 1,
+        cx + r + 2,
+        cy + r + 2,
+        color,
+        |px, py| {
+            let (dx, dy) = (px - ox, py - oy);
+            let on_arc = (dx.hypot(dy) - rf).abs() <= 0.5 && sweep.contains_offset(dx, dy);
+            on_arc || radii.iter().any(|&a| radius_distance(dx, dy, a, rf) <= 0.5)
+        },
+    );
+}
+
+/// Distance from offset (dx, dy) to the segment from the center to radius `r` at angle `a`.
+fn radius_distance(dx: f32, dy: f32, a: f32, r: f32) -> f32 {
+    let (ux, uy) = (a.cos(), a.sin());
+    let t = (dx * ux + dy * uy).clamp(0.0, r);
+    (dx - ux * t).hypot(dy - uy * t)
+}
+
+/// Fill a pie slice: the pixels of `fill_circle` whose direction lies inside the sweep.
+/// The center pixel is always included.
+pub fn fill_pie<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    start: f32,
+    end: f32,
+    color: Color,
+) {
+    let Some(spans) = ellipse_spans(cx, cy, r, r, &Window::new(surface)) else {
+        return;
+    };
+    let sweep = Sweep::new(start, end);
+    if sweep.full {
+        spans.fill(surface, color);
+        return;
+    }
+    for (i, &(l, rr)) in spans.rows.iter().enumerate() {
+        let y = spans.y0 + i as i32;
+        let dy = (y as i64 - cy as i64) as f32;
+        let mut run: Option<i32> = None;
+        for x in l..=rr + 1 {
+            let hit = x <= rr && sweep.contains_offset((x as i64 - cx as i64) as f32, dy);
+            match (hit, run) {
+                (true, None) => run = Some(x),
+                (false, Some(x0)) => {
+                    surface.fill_span(x0, x, y, color);
+                    run = None;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Fill an anti-aliased pie slice.
+pub fn fill_pie_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    start: f32,
+    end: f32,
+    color: Color,
+) {
+    if r < 0 {
+        return;
+    }
+    let sweep = Sweep::new(start, end);
+    let (ox, oy, rf) = (cx as f32 + 0.5, cy as f32 + 0.5, r as f32 + 0.5);
+    fill_coverage(
+        surface,
+        cx - r,
+        cy - r,
+        cx + r + 1,
+        cy + r + 1,
+        color,
+        |px, py| {
+            let (dx, dy) = (px - ox, py - oy);
+            dx.hypot(dy) <= rf && sweep.contains_offset(dx, dy)
+        },
+    );
+}
+
+/// Row spans of an axis-aligned ellipse inside `window`, or `None` for negative radii or
+/// when no row is in the window.
+fn ellipse_spans(cx: i32, cy: i32, rx: i32, ry: i32, window: &Window) -> Option<Spans> {
+    if rx < 0 || ry < 0 {
+        return None;
+    }
+    let (cx, cy, rx, ry) = (cx as i64, cy as i64, rx as i64, ry as i64);
+    let rows = window.rows(cy - ry, cy + ry)?;
+    let mut spans = Spans::new(*rows.start());
+    let (rx2, ry2) = (rx * rx, ry * ry);
+    for y in rows {
+        let dy = y as i64 - cy;
+        let w = if rx == 0 || ry == 0 {
+            if ry == 0 { rx } else { 0 }
+        } else {
+            // largest dx with dx^2 * ry^2 <= rx^2 * (ry^2 - dy^2)
+            isqrt_div(rx2 as i128 * (ry2 - dy * dy) as i128, ry2 as i128) as i64
+        };
+        spans.push_within(window, cx - w, cx + w);
+    }
+    Some(spans)
+}
+
+/// Row spans of a rotated ellipse sampled at pixel centers, limited to `window`.
+fn rotated_ellipse_spans(
+    cx: i32,
+    cy: i32,
+    rx: i32,
+    ry: i32,
+    angle: f32,
+    window: &Window,
+) -> Option<Spans> {
+    if rx < 0 || ry < 0 {
+        return None;
+    }
+    let (cx, cy) = (cx as i64, cy as i64);
+    if rx == 0 || ry == 0 {
+        // Degenerate: a rotated line segment.
+        let r = rx.max(ry) as f32;
+        let a = if rx == 0 { angle + TAU / 4.0 } else { angle };
+        let (dx, dy) = ((a.cos() * r).round() as i64, (a.sin() * r).round() as i64);
+        let mut pts = Vec::new();
+        super::walk_line(
+            window.rect(),
+            cx - dx,
+            cy - dy,
+            cx + dx,
+            cy + dy,
+            |_, x, y| {
+                pts.push((x, y));
+            },
+        );
+        let y0 = pts.iter().map(|p| p.1).min()?;
+        let y1 = pts.iter().map(|p| p.1).max()?;
+        let mut spans = Spans::new(y0);
+        spans.rows = vec![(i32::MAX, i32::MIN); (y1 - y0 + 1) as usize];
+        for (x, y) in pts {
+            let row = &mut spans.rows[(y - y0) as usize];
+            row.0 = row.0.min(x);
+            row.1 = row.1.max(x);
+        }
+        return Some(spans);
+    }
+    let (s, c) = angle.sin_cos();
+    let (rx, ry) = (rx as f32, ry as f32);
+    let (irx2, iry2) = (1.0 / (rx * rx), 1.0 / (ry * ry));
+    let qa = c * c * irx2 + s * s * iry2;
+    let ext = (rx * rx * s * s + ry * ry * c * c).sqrt();
+    let ey = ext.floor() as i64;
+    let rows = window.rows(cy - ey, cy + ey)?;
+    let mut spans = Spans::new(*rows.start());
+    for y in rows {
+        let y = (y as i64 - cy) as f32;
+        let qb = 2.0 * y * s * c * (irx2 - iry2);
+        let qc = y * y * (s * s * irx2 + c * c * iry2) - 1.0;
+        let disc = qb * qb - 4.0 * qa * qc;
+        if disc < 0.0 {
+            spans.push_within(window, 0, -1);
+            continue;
+        }
+        let sq = disc.sqrt();
+        let xa = (-qb - sq) / (2.0 * qa);
+        let xb = (-qb + sq) / (2.0 * qa);
+        const TOL: f32 = 1e-3;
+        spans.push_within(
+            window,
+            cx + (xa - TOL).ceil() as i64,
+            cx + (xb + TOL).floor() as i64,
+        );
+    }
+    Some(spans)
+}
+
+/// Approximate signed distance to a rotated ellipse (negative inside), first-order in the
+/// implicit function; accurate near the boundary, which is all coverage needs.
+struct EllipseSdf {
+    ox: f32,
+    oy: f32,
+    rx: f32,
+    ry: f32,
+    sin: f32,
+    cos: f32,
+}
+
+impl EllipseSdf {
+    fn new(cx: i32, cy: i32, rx: f32, ry: f32, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            ox: cx as f32 + 0.5,
+            oy: cy as f32 + 0.5,
+            rx,
+            ry,
+            sin,
+            cos,
+        }
+    }
+
+    fn local(&self, px: f32, py: f32) -> (f32, f32) {
+        let (dx, dy) = (px - self.ox, py - self.oy);
+        (
+            dx * self.cos + dy * self.sin,
+            -dx * self.sin + dy * self.cos,
+        )
+    }
+
+    fn inside(&self, px: f32, py: f32) -> bool {
+        let (u, v) = self.local(px, py);
+        (u / self.rx).powi(2) + (v / self.ry).powi(2) <= 1.0
+    }
+
+    fn distance(&self, px: f32, py: f32) -> f32 {
+        let (u, v) = self.local(px, py);
+        let (rx2, ry2) = (self.rx * self.rx, self.ry * self.ry);
+        let f = u * u / rx2 + v * v / ry2 - 1.0;
+        let g = 2.0 * (u * u / (rx2 * rx2) + v * v / (ry2 * ry2)).sqrt();
+        if g <= f32::EPSILON {
+            return -self.rx.min(self.ry);
+        }
+        f / g
+    }
+}
+
+/// Normalized angular range `[start, start + len]`.
+struct Sweep {
+    start: f32,
+    len: f32,
+    full: bool,
+}
+
+impl Sweep {
+    fn new(start: f32, end: f32) -> Self {
+        let raw = end - start;
+        if raw.abs() >= TAU {
+            return Self {
+                start: 0.0,
+                len: TAU,
+                full: true,
+            };
+        }
+        Self {
+            start: start.rem_euclid(TAU),
+            len: raw.rem_euclid(TAU),
+            full: false,
+        }
+    }
+
+    /// Whether the direction of offset (dx, dy) from the center lies in the sweep.
+    /// The center itself counts as inside.
+    fn contains_offset(&self, dx: f32, dy: f32) -> bool {
+        if self.full || (dx == 0.0 && dy == 0.0) {
+            return true;
+        }
+        let a = dy.atan2(dx);
+        (a - self.start).rem_euclid(TAU) <= self.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    fn count(s: &Surface, c: Color) -> usize {
+        s.pixels().iter().filter(|&&p| p == c.to_u32()).count()
+    }
+
+    #[test]
+    fn fill_circle_matches_distance_test() {
+        let mut s = Surface::new(21, 21);
+        let c = Color::rgba(255, 0, 0, 255);
+        fill_circle(&mut s, 10, 10, 6, c);
+        for y in 0..21 {
+            for x in 0..21 {
+                let inside = (x - 10) * (x - 10) + (y - 10) * (y - 10) <= 36;
+                assert_eq!(s.get_pixel(x, y) == Some(c), inside, "({x},{y})");
+            }
+        }
+    }
+
+    #[test]
+    fn fill_circle_zero_and_negative_radius() {
+        let mut s = Surface::new(3, 3);
+        let c = Color::rgba(1, 1, 1, 255);
+        fill_circle(&mut s, 1, 1, -1, c);
+        assert_eq!(count(&s, c), 0);
+        fill_circle(&mut s, 1, 1, 0, c);
+        assert_eq!(count(&s, c), 1);
+        assert_eq!(s.get_pixel(1, 1), Some(c));
+    }
+
+    #[test]
+    fn ellipse_outline_is_subset_of_fill_and_touches_extremes() {
+        let c = Color::rgba(0, 255, 0, 255);
+        let mut fill = Surface::new(32, 20);
+        let mut outline = Surface::new(32, 20);
+        fill_ellipse(&mut fill, 16, 10, 12, 6, c);
+        draw_ellipse(&mut outline, 16, 10, 12, 6, c);
+        for (o, f) in outline.pixels().iter().zip(fill.pixels()) {
+            if *o == c.to_u32() {
+                assert_eq!(*f, c.to_u32());
+            }
+        }
+        for (x, y) in [(4, 10), (28, 10), (16, 4), (16, 16)] {
+            assert_eq!(outline.get_pixel(x, y), Some(c));
+        }
+        assert_eq!(outline.get_pixel(16, 10), Some(Color::from_u32(0)));
+        assert!(count(&outline, c) < count(&fill, c));
+    }
+
+    #[test]
+    fn ellipse_zero_radius_is_line() {
+        let mut s = Surface::new(9, 3);
+        let c = Color::rgba(9, 9, 9, 255);
+        fill_ellipse(&mut s, 4, 1, 3, 0, c);
+        for x in 1..=7 {
+            assert_eq!(s.get_pixel(x, 1), Some(c));
+        }
+        assert_eq!(count(&s, c), 7);
+    }
+
+    #[test]
+    fn rotated_ellipse_zero_angle_matches_axis_aligned() {
+        let c = Color::rgba(7, 8, 9, 255);
+        let mut a = Surface::new(40, 30);
+        let mut b = Surface::new(40, 30);
+        fill_ellipse(&mut a, 20, 15, 15, 9, c);
+        fill_ellipse_rotated(&mut b, 20, 15, 15, 9, 0.0, c);
+        assert_eq!(a.pixels(), b.pixels());
+    }
+
+    #[test]
+    fn rotated_ellipse_quarter_turn_swaps_axes() {
+        let c = Color::rgba(7, 8, 9, 255);
+        let mut a = Surface::new(40, 40);
+        let mut b = Surface::new(40, 40);
+        fill_ellipse(&mut a, 20, 20, 6, 14, c);
+        fill_ellipse_rotated(&mut b, 20, 20, 14, 6, PI / 2.0, c);
+        assert_eq!(a.pixels(), b.pixels());
+    }
+
+    #[test]
+    fn ellipse_clipping_does_not_panic() {
+        let mut s = Surface::new(8, 8);
+        let c = Color::rgba(1, 2, 3, 255);
+        fill_ellipse(&mut s, -2, 3, 6, 4, c);
+        draw_ellipse_rotated(&mut s, 7, 7, 10, 3, 0.7, c);
+        fill_ellipse_rotated_aa(&mut s, 0, 0, 5, 9, 1.1, c);
+        assert_eq!(s.get_pixel(0, 3), Some(c));
+    }
+
+    #[test]
+    fn clipped_spans_match_the_unclipped_shape() {
+        let c = Color::rgba(1, 2, 3, 255);
+        let clip = crate::rect::Rect::new(5, 7, 20, 15);
+        let shapes: [&dyn Fn(&mut Surface); 4] = [
+            &|s| draw_ellipse(s, 18, 16, 15, 11, c),
+            &|s| draw_ellipse_rotated(s, 18, 16, 15, 8, 0.6, c),
+            &|s| draw_ellipse_rotated(s, 18, 16, 0, 14, 0.3, c),
+            &|s| fill_pie(s, 18, 16, 13, 0.2, 4.0, c),
+        ];
+        for draw in shapes {
+            let mut full = Surface::new(40, 36);
+            draw(&mut full);
+            let mut clipped = Surface::new(40, 36);
+            clipped.push_clip(clip);
+            draw(&mut clipped);
+            for y in 0..36 {
+                for x in 0..40 {
+                    let expected = if clip.contains(x, y) {
+                        full.get_pixel(x, y)
+                    } else {
+                        Some(Color::from_u32(0))
+                    };
+                    assert_eq!(clipped.get_pixel(x, y), expected, "({x}, {y})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn huge_shapes_cost_only_their_visible_rows() {
+        let c = Color::rgba(1, 2, 3, 255);
+        let mut s = Surface::new(8, 8);
+        fill_ellipse(&mut s, 4, 4, 4, 200_000_000, c);
+        // Only the center row reaches dx = 4; the rest are a hair narrower.
+        assert_eq!(count(&s, c), 8 + 7 * 7);
+        assert_eq!(s.get_pixel(0, 4), Some(c));
+        let mut s = Surface::new(8, 8);
+        draw_ellipse(&mut s, 4, 4, 4, i32::MAX, c);
+        // Near the center the sides are vertical: columns 1 and 7, bulging to 0 and 8 on the
+        // center row, with nothing in between.
+        for y in 0..8 {
+            for x in 2..7 {
+                assert_eq!(s.get_pixel(x, y), Some(Color::from_u32(0)), "({x}, {y})");
+            }
+            let (l, r) = if y == 4 { (0, 8) } else { (1, 7) };
+            assert_eq!(s.get_pixel(l, y), Some(c));
+            assert_eq!(s.get_pixel(r, y), (r < 8).then_some(c));
+        }
+        let mut s = Surface::new(8, 8);
+        fill_ellipse_rotated(&mut s, i32::MAX, 4, i32::MAX, i32::MAX, 0.5, c);
+        draw_ellipse_rotated(&mut s, 0, 0, 0, i32::MAX, 0.5, c);
+        fill_pie(&mut s, i32::MIN, 0, i32::MAX, 0.0, 1.0, c);
+        draw_arc(&mut s, i32::MIN, i32::MIN, 1_000_000, 0.0, 1.0, c);
+    }
+
+    #[test]
+    fn arc_quarter_stays_in_quadrant() {
+        let mut s = Surface::new(21, 21);
+        let c = Color::rgba(200, 0, 0, 255);
+        draw_arc(&mut s, 10, 10, 8, 0.0, PI / 2.0, c);
+        assert_eq!(s.get_pixel(18, 10), Some(c));
+        assert_eq!(s.get_pixel(10, 18), Some(c));
+        for y in 0..21 {
+            for x in 0..21 {
+                if s.get_pixel(x, y) == Some(c) {
+                    assert!(x >= 10 && y >= 10, "({x},{y}) outside quadrant");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn arc_full_turn_equals_circle() {
+        let c = Color::rgba(200, 0, 0, 255);
+        let mut a = Surface::new(21, 21);
+        let mut b = Surface::new(21, 21);
+        draw_arc(&mut a, 10, 10, 7, 1.0, 1.0 + TAU, c);
+        super::super::draw_circle(&mut b, 10, 10, 7, c);
+        assert_eq!(a.pixels(), b.pixels());
+    }
+
+    #[test]
+    fn pie_half_fills_one_side() {
+        let mut s = Surface::new(21, 21);
+        let c = Color::rgba(0, 0, 200, 255);
+        // Lower half (y >= cy) on screen
+        fill_pie(&mut s, 10, 10, 6, 0.0, PI, c);
+        assert_eq!(s.get_pixel(10, 15), Some(c));
+        assert_eq!(s.get_pixel(10, 5), Some(Color::from_u32(0)));
+        assert_eq!(s.get_pixel(10, 10), Some(c));
+        // wrapped range: end < start
+        let mut w = Surface::new(21, 21);
+        fill_pie(&mut w, 10, 10, 6, PI, 0.0, c);
+        assert_eq!(w.get_pixel(10, 5), Some(c));
+        assert_eq!(w.get_pixel(10, 15), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn pie_outline_has_radii() {
+        let mut s = Surface::new(21, 21);
+        let c = Color::rgba(0, 0, 200, 255);
+        draw_pie(&mut s, 10, 10, 6, 0.0, PI / 2.0, c);
+        for i in 0..=6 {
+            assert_eq!(s.get_pixel(10 + i, 10), Some(c));
+            assert_eq!(s.get_pixel(10, 10 + i), Some(c));
+        }
+    }
+
+    #[test]
+    fn aa_pie_outline_has_arc_and_radii() {
+        let c = Color::rgba(255, 255, 255, 255);
+        let mut s = Surface::new(21, 21);
+        draw_pie_aa(&mut s, 10, 10, 8, 0.0, PI / 2.0, c);
+        // Both radii and the arc midpoint are covered; the inside stays empty.
+        assert!(s.get_pixel(10, 10).unwrap().a > 200);
+        for i in 1..=7 {
+            assert_eq!(s.get_pixel(10 + i, 10), Some(c), "x radius {i}");
+            assert_eq!(s.get_pixel(10, 10 + i), Some(c), "y radius {i}");
+        }
+        assert!(s.get_pixel(15, 15).unwrap().a > 0);
+        assert_eq!(s.get_pixel(13, 13), Some(Color::from_u32(0)));
+        assert_eq!(s.get_pixel(5, 5), Some(Color::from_u32(0)));
+        // A full turn has no radii and matches the AA circle.
+        let mut ring = Surface::new(21, 21);
+        let mut full = Surface::new(21, 21);
+        draw_circle_aa(&mut ring, 10, 10, 8, c);
+        draw_pie_aa(&mut full, 10, 10, 8, 0.0, TAU, c);
+        assert_eq!(ring.pixels(), full.pixels());
+    }
+
+    #[test]
+    fn aa_disc_is_opaque_inside_and_partial_on_edge() {
+        let mut s = Surface::new(21, 21);
+        let c = Color::rgba(255, 255, 255, 255);
+        fill_circle_aa(&mut s, 10, 10, 6, c);
+        assert_eq!(s.get_pixel(10, 10), Some(c));
+        let edge = s.get_pixel(16, 13).unwrap();
+        assert!(edge.a > 0 && edge.a < 255, "edge alpha {}", edge.a);
+        assert_eq!(s.get_pixel(0, 0), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn aa_ring_and_arc_leave_center_empty() {
+        let c = Color::rgba(255, 255, 255, 255);
+        let mut s = Surface::new(21, 21);
+        draw_circle_aa(&mut s, 10, 10, 6, c);
+        draw_ellipse_aa(&mut s, 10, 10, 8, 4, c);
+        draw_arc_aa(&mut s, 10, 10, 9, 0.0, PI, c);
+        fill_pie_aa(&mut s, 10, 10, 2, PI, TAU, c);
+        assert_eq!(s.get_pixel(10, 12), Some(Color::from_u32(0)));
+        assert_eq!(s.get_pixel(16, 10).map(|p| p.a), Some(255));
+        assert!(s.get_pixel(10, 9).unwrap().a > 0);
+    }
+
+    #[test]
+    fn aa_ellipse_fill_covers_nonaa_interior() {
+        let c = Color::rgba(255, 255, 255, 255);
+        let mut a = Surface::new(30, 20);
+        let mut b = Surface::new(30, 20);
+        fill_ellipse_aa(&mut a, 15, 10, 10, 5, c);
+        draw_ellipse(&mut b, 15, 10, 9, 4, c);
+        // everything on a slightly smaller outline must be fully covered
+        for (pa, pb) in a.pixels().iter().zip(b.pixels()) {
+            if *pb == c.to_u32() {
+                assert_eq!(*pa, c.to_u32());
+            }
+        }
+    }
+}
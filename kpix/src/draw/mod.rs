@@ -0,0 +1,572 @@
+//! Drawing helpers. More primitives will be added incrementally.
+
+pub(crate) mod aa;
+pub mod dash;
+pub mod ellipse;
+pub mod round_rect;
+mod span;
+
+pub use dash::{
+    Dash, draw_circle_dashed, draw_line_dashed, draw_polyline_dashed, draw_rect_dashed,
+};
+pub use ellipse::{
+    draw_arc, draw_arc_aa, draw_circle_aa, draw_ellipse, draw_ellipse_aa, draw_ellipse_rotated,
+    draw_ellipse_rotated_aa, draw_pie, draw_pie_aa, fill_circle, fill_circle_aa,
+    fill_circle_aa_paint, fill_circle_paint, fill_ellipse, fill_ellipse_aa, fill_ellipse_paint,
+    fill_ellipse_rotated, fill_ellipse_rotated_aa, fill_pie, fill_pie_aa,
+};
+pub use round_rect::{
+    CornerRadii, draw_round_rect, draw_round_rect_aa, fill_round_rect, fill_round_rect_aa,
+};
+
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+use crate::paint::{self, Paint};
+use crate::rect::Rect;
+
+/// Clear the surface to a color (wrapper around `Surface::clear`).
+pub fn clear<P: PixelFormat>(surface: &mut Surface<P>, color: Color) {
+    surface.clear(color);
+}
+
+/// Set a single pixel with clipping.
+pub fn set_pixel<P: PixelFormat>(surface: &mut Surface<P>, x: i32, y: i32, color: Color) {
+    surface.set_pixel(x, y, color);
+}
+
+/// Draw a line from (x0,y0) to (x1,y1) using Bresenham's integer algorithm.
+/// Endpoints are included. The walk starts at the first step inside the clip rect and stops
+/// after the last one, so long lines cost only their visible length; the pixels are exactly
+/// those of the unclipped walk.
+pub fn draw_line<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    color: Color,
+) {
+    let c = surface.clip_rect();
+    walk_line(c, x0 as i64, y0 as i64, x1 as i64, y1 as i64, |_, x, y| {
+        surface.set_pixel(x, y, color);
+    });
+}
+
+/// Visit the Bresenham pixels of the line from (x0,y0) to (x1,y1) that lie inside `clip`, in
+/// walk order, with their step index along the whole line (0 at the start). Only the
+/// visible steps are walked.
+pub(crate) fn walk_line(
+    clip: Rect,
+    x0: i64,
+    y0: i64,
+    x1: i64,
+    y1: i64,
+    mut visit: impl FnMut(i64, i32, i32),
+) {
+    if clip.is_empty() {
+        return;
+    }
+    let c = clip;
+    let dx = (x1 - x0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let dy = -(y1 - y0).abs();
+    let sy = if y0 < y1 { 1 } else { -1 };
+
+    // Major-axis steps whose pixel lies inside the clip rect.
+    let walk = LineSteps::new(dx, -dy);
+    let xs = walk.within(
+        axis_offsets(x0, sx, c.x as i64, c.right() as i64),
+        dx >= -dy,
+    );
+    let ys = walk.within(
+        axis_offsets(y0, sy, c.y as i64, c.bottom() as i64),
+        dx < -dy,
+    );
+    let (Some((kx0, kx1)), Some((ky0, ky1))) = (xs, ys) else {
+        return;
+    };
+    let (k0, k1) = (kx0.max(ky0), kx1.min(ky1));
+    if k0 > k1 {
+        return;
+    }
+
+    // Resume the walk at step k0 with its exact error term.
+    let (nx, ny) = walk.offsets(k0);
+    let (mut x, mut y) = (x0 + sx * nx, y0 + sy * ny);
+    // Error term; the products can exceed i64 on long diagonals, the sum stays small.
+    let mut err =
+        (dx as i128 + dy as i128 + nx as i128 * dy as i128 + ny as i128 * dx as i128) as i64;
+    for k in k0..=k1 {
+        visit(k, x as i32, y as i32);
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Number of pixels on the Bresenham line from (x0,y0) to (x1,y1), endpoints included.
+fn line_len(x0: i64, y0: i64, x1: i64, y1: i64) -> i64 {
+    (x1 - x0).abs().max((y1 - y0).abs()) + 1
+}
+
+/// Range of offsets `n >= 0` along one axis for which `p0 + s * n` lies in `[lo, hi)`.
+fn axis_offsets(p0: i64, s: i64, lo: i64, hi: i64) -> (i64, i64) {
+    if s > 0 {
+        (lo - p0, hi - 1 - p0)
+    } else {
+        (p0 - (hi - 1), p0 - lo)
+    }
+}
+
+/// Offsets along a Bresenham walk of `adx` by `ady` steps. Each iteration advances the major
+/// (longer) axis; after `k` of them the minor axis has advanced `(2k*minor + major) / (2*major)`
+/// (floored), which is what the error-term updates in `draw_line` produce.
+struct LineSteps {
+    major: i64,
+    minor: i64,
+    x_major: bool,
+}
+
+impl LineSteps {
+    fn new(adx: i64, ady: i64) -> Self {
+        let x_major = adx >= ady;
+        let (major, minor) = if x_major { (adx, ady) } else { (ady, adx) };
+        Self {
+            major,
+            minor,
+            x_major,
+        }
+    }
+
+    fn minor_at(&self, k: i64) -> i64 {
+        if self.major == 0 {
+            return 0;
+        }
+        let (k, minor, major) = (k as i128, self.minor as i128, self.major as i128);
+        (2 * k * minor + major).div_euclid(2 * major) as i64
+    }
+
+    /// (x, y) offsets from the start after `k` steps.
+    fn offsets(&self, k: i64) -> (i64, i64) {
+        let m = self.minor_at(k);
+        if self.x_major { (k, m) } else { (m, k) }
+    }
+
+    /// Steps `k` in `0..=major` whose offset on one axis lies in `lo..=hi`; `major_axis` says
+    /// whether that axis is the one advancing every step.
+    fn within(&self, (lo, hi): (i64, i64), major_axis: bool) -> Option<(i64, i64)> {
+        let (k0, k1) = if major_axis {
+            (lo, hi)
+        } else if self.minor == 0 {
+            if lo > 0 || hi < 0 {
+                return None;
+            }
+            (0, self.major)
+        } else {
+            // minor_at(k) >= lo  <=>  k >= ceil((2*lo - 1) * major / (2*minor)), and
+            // minor_at(k) <= hi  <=>  k <= ceil((2*hi + 1) * major / (2*minor)) - 1.
+            let (major, d) = (self.major as i128, 2 * self.minor as i128);
+            let ceil = |n: i128| -(-n).div_euclid(d) as i64;
+            (
+                ceil((2 * lo as i128 - 1) * major),
+                ceil((2 * hi as i128 + 1) * major) - 1,
+            )
+        };
+        let (k0, k1) = (k0.max(0), k1.min(self.major));
+        (k0 <= k1).then_some((k0, k1))
+    }
+}
+
+/// Draw rectangle outline. `(x, y)` is a corner; `w`, `h` may be negative.
+/// Uses half-open semantics: draws the border of [x0, x1) x [y0, y1).
+pub fn draw_rect<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    color: Color,
+) {
+    if w == 0 || h == 0 {
+        return;
+    }
+    let (x0, x1) = if w >= 0 { (x, x + w) } else { (x + w, x) };
+    let (y0, y1) = if h >= 0 { (y, y + h) } else { (y + h, y) };
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+    // top and bottom (inclusive endpoints)
+    draw_line(surface, x0, y0, x1 - 1, y0, color);
+    draw_line(surface, x0, y1 - 1, x1 - 1, y1 - 1, color);
+    // left and right
+    draw_line(surface, x0, y0, x0, y1 - 1, color);
+    draw_line(surface, x1 - 1, y0, x1 - 1, y1 - 1, color);
+}
+
+/// Fill rectangle area. `(x, y)` is a corner; `w`, `h` may be negative.
+/// Fills all pixels within half-open region [x0, x1) x [y0, y1), clipped to the clip rect.
+pub fn fill_rect<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    color: Color,
+) {
+    let r = Rect::new(x, y, w, h).intersect(surface.clip_rect());
+    for yy in r.y..r.bottom() {
+        surface.fill_span(r.x, r.right(), yy, color);
+    }
+}
+
+/// `fill_rect` with a paint source sampled at each pixel center.
+pub fn fill_rect_paint<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    paint: &Paint,
+) {
+    let r = Rect::new(x, y, w, h).intersect(surface.clip_rect());
+    for yy in r.y..r.bottom() {
+        paint::paint_span(surface, yy, r.x, r.right(), 255, paint);
+    }
+}
+
+/// Draw a circle outline centered at (cx, cy) with integer radius `r` (r >= 0).
+/// Uses the Midpoint Circle Algorithm. Clipping is delegated to `set_pixel`.
+pub fn draw_circle<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cx: i32,
+    cy: i32,
+    r: i32,
+    color: Color,
+) {
+    if r < 0 {
+        return;
+    }
+    if r == 0 {
+        surface.set_pixel(cx, cy, color);
+        return;
+    }
+    let bbox = Rect::new(cx - r, cy - r, 2 * r + 1, 2 * r + 1);
+    if bbox.intersect(surface.clip_rect()).is_empty() {
+        return;
+    }
+
+    let mut x = r;
+    let mut y = 0;
+    let mut d = 1 - r; // decision parameter
+
+    while y <= x {
+        // 8-way symmetry
+        surface.set_pixel(cx + x, cy + y, color);
+        surface.set_pixel(cx - x, cy + y, color);
+        surface.set_pixel(cx + x, cy - y, color);
+        surface.set_pixel(cx - x, cy - y, color);
+        surface.set_pixel(cx + y, cy + x, color);
+        surface.set_pixel(cx - y, cy + x, color);
+        surface.set_pixel(cx + y, cy - x, color);
+        surface.set_pixel(cx - y, cy - x, color);
+
+        y += 1;
+        if d <= 0 {
+            d += 2 * y + 1;
+        } else {
+            x -= 1;
+            d += 2 * (y - x) + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrappers_delegate_to_surface() {
+        let mut s = Surface::new(2, 2);
+        let c = Color::rgba(1, 2, 3, 255);
+        clear(&mut s, c);
+        for &px in s.pixels() {
+            assert_eq!(px, c.to_u32());
+        }
+        let d = Color::rgba(4, 5, 6, 255);
+        set_pixel(&mut s, 1, 1, d);
+        assert_eq!(s.get_pixel(1, 1), Some(d));
+    }
+
+    /// Unclipped Bresenham walk, as `draw_line` did before clipping.
+    fn naive_line(mut x0: i32, mut y0: i32, x1: i32, y1: i32) -> Vec<(i32, i32)> {
+        let dx = (x1 - x0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let dy = -(y1 - y0).abs();
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        let mut pts = vec![(x0, y0)];
+        while (x0, y0) != (x1, y1) {
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+            pts.push((x0, y0));
+        }
+        pts
+    }
+
+    #[test]
+    fn clipped_line_matches_unclipped_walk() {
+        let c = Color::rgba(255, 255, 255, 255);
+        let clip = Rect::new(10, 12, 30, 38);
+        let ends = [-70, -3, 0, 9, 10, 11, 25, 39, 40, 41, 49, 50, 63, 130];
+        for &x0 in &ends {
+            for &y0 in &ends {
+                for (x1, y1) in [(25, 30), (-9, 77), (64, 1), (x0 + 3, y0 - 90), (y0, x0)] {
+                    let mut s = Surface::new(64, 64);
+                    s.push_clip(clip);
+                    draw_line(&mut s, x0, y0, x1, y1, c);
+                    let mut want = Surface::new(64, 64);
+                    for (x, y) in naive_line(x0, y0, x1, y1) {
+                        if clip.contains(x, y) {
+                            want.set_pixel(x, y, c);
+                        }
+                    }
+                    assert_eq!(s.pixels(), want.pixels(), "({x0},{y0})-({x1},{y1})");
+                }
+            }
+        }
+
+        // A huge line only walks its visible part.
+        let mut s = Surface::new(16, 16);
+        s.push_clip(Rect::new(0, 0, 10, 10));
+        draw_line(&mut s, -100_000, 5, 100_000, 7, c);
+        let row = |y: i32| (0..16).filter(|&x| s.get_pixel(x, y) == Some(c)).count();
+        assert_eq!(row(5) + row(6) + row(7), 10);
+        assert_eq!(row(6), 10);
+    }
+
+    #[test]
+    fn line_horizontal() {
+        let mut s = Surface::new(5, 3);
+        let c = Color::rgba(255, 0, 0, 255);
+        super::draw_line(&mut s, 0, 1, 4, 1, c);
+        // y=1, x=0..4 must be set
+        for x in 0..5 {
+            assert_eq!(s.get_pixel(x, 1), Some(c));
+        }
+        // other rows remain default (0)
+        for y in [0, 2] {
+            for x in 0..5 {
+                assert_eq!(s.get_pixel(x, y), Some(Color::from_u32(0)));
+            }
+        }
+    }
+
+    #[test]
+    fn line_vertical() {
+        let mut s = Surface::new(3, 5);
+        let c = Color::rgba(0, 255, 0, 255);
+        super::draw_line(&mut s, 1, 0, 1, 4, c);
+        for y in 0..5 {
+            assert_eq!(s.get_pixel(1, y), Some(c));
+        }
+    }
+
+    #[test]
+    fn line_diagonal_positive_slope() {
+        let mut s = Surface::new(5, 5);
+        let c = Color::rgba(0, 0, 255, 255);
+        super::draw_line(&mut s, 0, 0, 4, 4, c);
+        for i in 0..5 {
+            assert_eq!(s.get_pixel(i, i), Some(c));
+        }
+    }
+
+    #[test]
+    fn line_diagonal_negative_slope() {
+        let mut s = Surface::new(5, 5);
+        let c = Color::rgba(200, 200, 0, 255);
+        super::draw_line(&mut s, 0, 4, 4, 0, c);
+        for i in 0..5 {
+            assert_eq!(s.get_pixel(i, 4 - i), Some(c));
+        }
+    }
+
+    #[test]
+    fn line_forward_backward_equivalence() {
+        let mut a = Surface::new(8, 8);
+        let mut b = Surface::new(8, 8);
+        let c = Color::rgba(123, 45, 67, 255);
+        super::draw_line(&mut a, 1, 2, 7, 6, c);
+        super::draw_line(&mut b, 7, 6, 1, 2, c);
+
+        assert_eq!(a.pixels(), b.pixels());
+    }
+
+    #[test]
+    fn line_out_of_bounds_clipped_by_set_pixel() {
+        let mut s = Surface::new(5, 3);
+        let c = Color::rgba(255, 0, 255, 255);
+        // span beyond left/right; should draw across visible row y=1
+        super::draw_line(&mut s, -2, 1, 6, 1, c);
+        for x in 0..5 {
+            assert_eq!(s.get_pixel(x, 1), Some(c));
+        }
+    }
+
+    #[test]
+    fn rect_outline_basic() {
+        let mut s = Surface::new(5, 5);
+        let c = Color::rgba(10, 20, 30, 255);
+        super::draw_rect(&mut s, 1, 1, 3, 3, c); // covers x=[1,3], y=[1,3] edges
+        // corners
+        for (x, y) in [(1, 1), (3, 1), (1, 3), (3, 3)] {
+            assert_eq!(s.get_pixel(x, y), Some(c));
+        }
+        // inside remains default
+        assert_eq!(s.get_pixel(2, 2), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn rect_fill_basic_and_area() {
+        let mut s = Surface::new(6, 4);
+        let c = Color::rgba(200, 50, 50, 255);
+        super::fill_rect(&mut s, 1, 1, 3, 2, c); // x:[1,4), y:[1,3)
+        // count filled pixels
+        let mut cnt = 0;
+        for y in 0..4 {
+            for x in 0..6 {
+                if s.get_pixel(x, y) == Some(c) {
+                    cnt += 1;
+                }
+            }
+        }
+        assert_eq!(cnt, 3 * 2);
+    }
+
+    #[test]
+    fn rect_negative_size_normalizes() {
+        let mut a = Surface::new(6, 4);
+        let mut b = Surface::new(6, 4);
+        let c = Color::rgba(0, 200, 0, 255);
+        super::fill_rect(&mut a, 4, 3, -3, -2, c);
+        super::fill_rect(&mut b, 1, 1, 3, 2, c);
+        assert_eq!(a.pixels(), b.pixels());
+    }
+
+    #[test]
+    fn rect_clip_out_of_bounds() {
+        let mut s = Surface::new(4, 3);
+        let c = Color::rgba(50, 50, 200, 255);
+        super::fill_rect(&mut s, -2, -1, 6, 4, c); // spans beyond all sides; half-open covers full surface
+        // Visible region should be filled entirely
+        for y in 0..3 {
+            for x in 0..4 {
+                assert_eq!(s.get_pixel(x, y), Some(c));
+            }
+        }
+    }
+
+    #[test]
+    fn circle_radius_zero_sets_center() {
+        let mut s = Surface::new(5, 5);
+        let c = Color::rgba(100, 100, 100, 255);
+        super::draw_circle(&mut s, 2, 2, 0, c);
+        assert_eq!(s.get_pixel(2, 2), Some(c));
+    }
+
+    #[test]
+    fn circle_cardinals_for_small_radius() {
+        let mut s = Surface::new(7, 7);
+        let c = Color::rgba(10, 200, 10, 255);
+        super::draw_circle(&mut s, 3, 3, 2, c);
+        // Cardinal points at distance r should be drawn
+        assert_eq!(s.get_pixel(3 + 2, 3), Some(c));
+        assert_eq!(s.get_pixel(3 - 2, 3), Some(c));
+        assert_eq!(s.get_pixel(3, 3 + 2), Some(c));
+        assert_eq!(s.get_pixel(3, 3 - 2), Some(c));
+    }
+
+    #[test]
+    fn circle_symmetry_and_clipping() {
+        let mut s = Surface::new(5, 5);
+        let c = Color::rgba(200, 10, 10, 255);
+        // Partially out-of-bounds: should not panic and visible points should appear
+        super::draw_circle(&mut s, 0, 0, 3, c);
+        // Expect some visible pixels near the top-left corner
+        assert_eq!(s.get_pixel(0, 3), Some(c));
+        assert_eq!(s.get_pixel(3, 0), Some(c));
+    }
+
+    #[test]
+    fn primitives_respect_clip_rect() {
+        let mut s = Surface::new(8, 8);
+        let c = Color::rgba(9, 8, 7, 255);
+        s.push_clip(Rect::new(2, 2, 4, 4));
+        super::fill_rect(&mut s, 0, 0, 8, 8, c);
+        super::draw_line(&mut s, 0, 7, 7, 0, c);
+        super::draw_circle(&mut s, 4, 4, 3, c);
+        for y in 0..8 {
+            for x in 0..8 {
+                if !(2..6).contains(&x) || !(2..6).contains(&y) {
+                    assert_eq!(s.get_pixel(x, y), Some(Color::from_u32(0)));
+                } else {
+                    assert_eq!(s.get_pixel(x, y), Some(c));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn paint_fills_sample_pixel_centers() {
+        use crate::paint::Gradient;
+        let black = Color::rgba(0, 0, 0, 255);
+        let white = Color::rgba(255, 255, 255, 255);
+        let ramp = Paint::linear(0.0, 0.0, 8.0, 0.0, Gradient::two(black, white));
+        let mut s = Surface::new(8, 2);
+        super::fill_rect_paint(&mut s, 0, 0, 8, 2, &ramp);
+        // Pixel x samples t = (x + 0.5) / 8
+        let row: Vec<u8> = (0..8).map(|x| s.get_pixel(x, 1).unwrap().r).collect();
+        assert_eq!(row, vec![16, 48, 80, 112, 143, 175, 207, 239]);
+
+        // A solid paint matches the color variant exactly
+        let c = Color::rgba(1, 2, 3, 255);
+        let mut a = Surface::new(12, 12);
+        let mut b = Surface::new(12, 12);
+        super::fill_circle(&mut a, 6, 6, 4, c);
+        super::fill_circle_paint(&mut b, 6, 6, 4, &Paint::from(c));
+        super::fill_circle_aa(&mut a, 2, 2, 2, c);
+        super::fill_circle_aa_paint(&mut b, 2, 2, 2, &Paint::from(c));
+        assert_eq!(a.pixels(), b.pixels());
+    }
+
+    #[test]
+    fn helpers_draw_on_any_format() {
+        use crate::format::{Gray8, Indexed};
+        let mut g = Surface::with_format(6, 6, Gray8);
+        super::fill_rect(&mut g, 1, 1, 4, 4, Color::rgba(255, 255, 255, 255));
+        super::draw_line(&mut g, 0, 5, 5, 0, Color::rgba(0, 0, 0, 255));
+        assert_eq!(g.get_raw(1, 1), Some(255));
+        assert_eq!(g.get_raw(2, 3), Some(0));
+        assert_eq!(g.pixels().iter().filter(|&&v| v == 255).count(), 16 - 4);
+
+        let pal = Indexed::new([Color::rgba(0, 0, 0, 255), Color::rgba(255, 0, 0, 255)]);
+        let mut p = Surface::with_format(8, 8, pal);
+        super::fill_circle_aa(&mut p, 4, 4, 3, Color::rgba(255, 0, 0, 255));
+        // Coverage blends snap back onto the palette.
+        assert!(p.pixels().iter().all(|&i| i < 2));
+        assert_eq!(p.get_raw(4, 4), Some(1));
+    }
+}
@@ -0,0 +1,291 @@
+//! Rounded rectangles with per-corner radii.
+//!
+//! Rects follow `draw_rect`/`fill_rect`: `(x, y)` is a corner, `w`/`h` may be negative and the
+//! covered area is the half-open [x0, x1) x [y0, y1). Radii are clamped to half the shorter side
+//! so opposite corners never overlap; with all radii zero the result equals `draw_rect`/`fill_rect`.
+
+use super::aa::fill_coverage;
+use super::span::{Spans, Window, isqrt_div};
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+
+/// Corner radii in pixels, clockwise from the top-left corner.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CornerRadii {
+    pub top_left: i32,
+    pub top_right: i32,
+    pub bottom_right: i32,
+    pub bottom_left: i32,
+}
+
+impl CornerRadii {
+    pub const fn new(top_left: i32, top_right: i32, bottom_right: i32, bottom_left: i32) -> Self {
+        Self {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+
+    /// Same radius on all four corners.
+    pub const fn all(r: i32) -> Self {
+        Self::new(r, r, r, r)
+    }
+
+    fn clamped(self, max: i32) -> Self {
+        let c = |r: i32| r.clamp(0, max.max(0));
+        Self::new(
+            c(self.top_left),
+            c(self.top_right),
+            c(self.bottom_right),
+            c(self.bottom_left),
+        )
+    }
+}
+
+/// Draw a rounded rectangle outline.
+pub fn draw_round_rect<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    radii: CornerRadii,
+    color: Color,
+) {
+    if let Some(spans) = round_rect_spans(x, y, w, h, radii, &Window::new(surface)) {
+        spans.outline(surface, color);
+    }
+}
+
+/// Fill a rounded rectangle.
+pub fn fill_round_rect<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    radii: CornerRadii,
+    color: Color,
+) {
+    if let Some(spans) = round_rect_spans(x, y, w, h, radii, &Window::new(surface)) {
+        spans.fill(surface, color);
+    }
+}
+
+/// Draw an anti-aliased rounded rectangle outline (1px band just inside the edge).
+pub fn draw_round_rect_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    radii: CornerRadii,
+    color: Color,
+) {
+    if let Some(b) = RoundBox::new(x, y, w, h, radii) {
+        fill_coverage(surface, b.x0, b.y0, b.x1, b.y1, color, |px, py| {
+            let d = b.distance(px, py);
+            (-1.0..=0.0).contains(&d)
+        });
+    }
+}
+
+/// Fill an anti-aliased rounded rectangle.
+pub fn fill_round_rect_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    radii: CornerRadii,
+    color: Color,
+) {
+    if let Some(b) = RoundBox::new(x, y, w, h, radii) {
+        fill_coverage(surface, b.x0, b.y0, b.x1, b.y1, color, |px, py| {
+            b.distance(px, py) <= 0.0
+        });
+    }
+}
+
+/// Normalize a corner/size rect to half-open bounds; `None` when empty.
+fn normalize(x: i32, y: i32, w: i32, h: i32) -> Option<(i64, i64, i64, i64)> {
+    if w == 0 || h == 0 {
+        return None;
+    }
+    let (x, y, w, h) = (x as i64, y as i64, w as i64, h as i64);
+    let (x0, x1) = if w >= 0 { (x, x + w) } else { (x + w, x) };
+    let (y0, y1) = if h >= 0 { (y, y + h) } else { (y + h, y) };
+    Some((x0, y0, x1, y1))
+}
+
+fn round_rect_spans(
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+    radii: CornerRadii,
+    window: &Window,
+) -> Option<Spans> {
+    let (x0, y0, x1, y1) = normalize(x, y, w, h)?;
+    let r = radii.clamped((((x1 - x0).min(y1 - y0) - 1) / 2) as i32);
+    // Inset of a corner of radius `r` on a row `dy` rows away from the corner center.
+    let inset = |r: i32, dy: i64| {
+        if dy <= 0 {
+            0
+        } else {
+            let r = r as i64;
+            r - isqrt_div((r * r - dy * dy) as i128, 1) as i64
+        }
+    };
+    let rows = window.rows(y0, y1 - 1)?;
+    let mut spans = Spans::new(*rows.start());
+    for yy in rows {
+        let yy = yy as i64;
+        let top = |r: i32| y0 + r as i64 - yy;
+        let bottom = |r: i32| yy - (y1 - 1 - r as i64);
+        let left =
+            inset(r.top_left, top(r.top_left)).max(inset(r.bottom_left, bottom(r.bottom_left)));
+        let right =
+            inset(r.top_right, top(r.top_right)).max(inset(r.bottom_right, bottom(r.bottom_right)));
+        spans.push_within(window, x0 + left, x1 - 1 - right);
+    }
+    Some(spans)
+}
+
+/// Continuous rounded box used for coverage (signed distance, negative inside).
+struct RoundBox {
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    radii: CornerRadii,
+}
+
+impl RoundBox {
+    fn new(x: i32, y: i32, w: i32, h: i32, radii: CornerRadii) -> Option<Self> {
+        let (x0, y0, x1, y1) = normalize(x, y, w, h)?;
+        let radii = radii.clamped(((x1 - x0).min(y1 - y0) / 2) as i32);
+        let (x0, y0) = (i32::try_from(x0).ok()?, i32::try_from(y0).ok()?);
+        let (x1, y1) = (i32::try_from(x1).ok()?, i32::try_from(y1).ok()?);
+        Some(Self {
+            x0,
+            y0,
+            x1,
+            y1,
+            radii,
+        })
+    }
+
+    fn distance(&self, px: f32, py: f32) -> f32 {
+        let cx = (self.x0 + self.x1) as f32 * 0.5;
+        let cy = (self.y0 + self.y1) as f32 * 0.5;
+        let hx = (self.x1 - self.x0) as f32 * 0.5;
+        let hy = (self.y1 - self.y0) as f32 * 0.5;
+        let (dx, dy) = (px - cx, py - cy);
+        let r = match (dx >= 0.0, dy >= 0.0) {
+            (false, false) => self.radii.top_left,
+            (true, false) => self.radii.top_right,
+            (true, true) => self.radii.bottom_right,
+            (false, true) => self.radii.bottom_left,
+        } as f32;
+        let qx = dx.abs() - hx + r;
+        let qy = dy.abs() - hy + r;
+        qx.max(qy).min(0.0) + qx.max(0.0).hypot(qy.max(0.0)) - r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::{draw_rect, fill_rect};
+
+    #[test]
+    fn zero_radii_match_plain_rects() {
+        let c = Color::rgba(50, 100, 150, 255);
+        let mut a = Surface::new(12, 10);
+        let mut b = Surface::new(12, 10);
+        fill_round_rect(&mut a, 9, 8, -7, -5, CornerRadii::all(0), c);
+        fill_rect(&mut b, 9, 8, -7, -5, c);
+        assert_eq!(a.pixels(), b.pixels());
+
+        let mut a = Surface::new(12, 10);
+        let mut b = Surface::new(12, 10);
+        draw_round_rect(&mut a, 1, 1, 9, 7, CornerRadii::all(0), c);
+        draw_rect(&mut b, 1, 1, 9, 7, c);
+        assert_eq!(a.pixels(), b.pixels());
+    }
+
+    #[test]
+    fn rounded_corner_is_cut_and_edges_remain() {
+        let c = Color::rgba(50, 100, 150, 255);
+        let mut s = Surface::new(20, 20);
+        fill_round_rect(&mut s, 0, 0, 20, 20, CornerRadii::new(5, 0, 0, 0), c);
+        assert_eq!(s.get_pixel(0, 0), Some(Color::from_u32(0)));
+        assert_eq!(s.get_pixel(19, 0), Some(c));
+        assert_eq!(s.get_pixel(0, 19), Some(c));
+        assert_eq!(s.get_pixel(5, 0), Some(c));
+        assert_eq!(s.get_pixel(0, 5), Some(c));
+    }
+
+    #[test]
+    fn outline_is_border_of_fill() {
+        let c = Color::rgba(50, 100, 150, 255);
+        let mut f = Surface::new(24, 16);
+        let mut o = Surface::new(24, 16);
+        let radii = CornerRadii::new(2, 4, 6, 0);
+        fill_round_rect(&mut f, 1, 1, 22, 14, radii, c);
+        draw_round_rect(&mut o, 1, 1, 22, 14, radii, c);
+        for (po, pf) in o.pixels().iter().zip(f.pixels()) {
+            if *po == c.to_u32() {
+                assert_eq!(*pf, c.to_u32());
+            }
+        }
+        assert_eq!(o.get_pixel(12, 8), Some(Color::from_u32(0)));
+        assert_eq!(o.get_pixel(12, 1), Some(c));
+        assert_eq!(o.get_pixel(1, 14), Some(c));
+    }
+
+    #[test]
+    fn huge_rects_fill_only_the_clip() {
+        let c = Color::rgba(1, 1, 1, 255);
+        let mut s = Surface::new(8, 8);
+        let r = CornerRadii::all(i32::MAX);
+        fill_round_rect(&mut s, i32::MIN, i32::MIN, i32::MAX, i32::MAX, r, c);
+        fill_round_rect(&mut s, -1_000_000_000, 4, 2_000_000_000, 3, r, c);
+        assert!(s.pixels()[32..56].iter().all(|&p| p == c.to_u32()));
+        let mut o = Surface::new(8, 8);
+        draw_round_rect(&mut o, -1_000_000_000, 4, 2_000_000_000, 3, r, c);
+        assert_eq!(o.get_pixel(3, 4), Some(c));
+        assert_eq!(o.get_pixel(3, 5), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn huge_radius_is_clamped() {
+        let c = Color::rgba(1, 1, 1, 255);
+        let mut s = Surface::new(9, 5);
+        fill_round_rect(&mut s, 0, 0, 9, 5, CornerRadii::all(100), c);
+        // clamped to 2 -> stadium shape; middle row spans the full width
+        for x in 0..9 {
+            assert_eq!(s.get_pixel(x, 2), Some(c));
+        }
+        assert_eq!(s.get_pixel(0, 0), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn aa_variants_cover_interior_and_soften_corners() {
+        let c = Color::rgba(255, 255, 255, 255);
+        let mut s = Surface::new(20, 20);
+        fill_round_rect_aa(&mut s, 2, 2, 16, 16, CornerRadii::all(5), c);
+        assert_eq!(s.get_pixel(10, 10), Some(c));
+        assert_eq!(s.get_pixel(10, 2), Some(c));
+        let corner = s.get_pixel(3, 3).unwrap();
+        assert!(corner.a > 0 && corner.a < 255);
+
+        let mut o = Surface::new(20, 20);
+        draw_round_rect_aa(&mut o, 2, 2, 16, 16, CornerRadii::all(5), c);
+        assert_eq!(o.get_pixel(10, 2), Some(c));
+        assert_eq!(o.get_pixel(10, 10), Some(Color::from_u32(0)));
+    }
+}
@@ -0,0 +1,174 @@
+//! Row-span helpers shared by the filled primitives.
+//!
+//! A shape is described as one inclusive span `(left, right)` per row, starting at row `y0`.
+//! Rows with `left > right` are empty. Fills paint every span; outlines paint only the
+//! pixels that have a 4-neighbor outside the shape, which yields a thin 8-connected border
+//! that matches the fill exactly.
+//!
+//! Builders only produce the rows and columns of a [`Window`] around the clip rect, so huge
+//! shapes cost their visible size.
+
+use std::ops::RangeInclusive;
+
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+use crate::paint::{self, Paint};
+use crate::rect::Rect;
+
+/// Spans of a shape, one per row starting at `y0`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Spans {
+    pub y0: i32,
+    pub rows: Vec<(i32, i32)>,
+}
+
+const EMPTY: (i32, i32) = (i32::MAX, i32::MIN);
+
+impl Spans {
+    pub fn new(y0: i32) -> Self {
+        Self {
+            y0,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Push `left..=right` clamped to the window's columns; empty spans stay empty.
+    pub fn push_within(&mut self, window: &Window, left: i64, right: i64) {
+        if left > right {
+            self.rows.push(EMPTY);
+        } else {
+            let clamp = |v: i64| v.clamp(window.x0, window.x1) as i32;
+            self.rows.push((clamp(left), clamp(right)));
+        }
+    }
+
+    /// Rows intersecting the clip rect, as indices into `rows`.
+    fn visible<P: PixelFormat>(&self, surface: &Surface<P>) -> std::ops::Range<usize> {
+        let c = surface.clip_rect();
+        let lo = (c.y - self.y0).clamp(0, self.rows.len() as i32) as usize;
+        let hi = (c.bottom() - self.y0).clamp(0, self.rows.len() as i32) as usize;
+        lo..hi.max(lo)
+    }
+
+    /// Paint every span.
+    pub fn fill<P: PixelFormat>(&self, surface: &mut Surface<P>, color: Color) {
+        for i in self.visible(surface) {
+            let (l, r) = self.rows[i];
+            if l <= r {
+                surface.fill_span(l, r + 1, self.y0 + i as i32, color);
+            }
+        }
+    }
+
+    /// Paint every span with a paint source.
+    pub fn fill_paint<P: PixelFormat>(&self, surface: &mut Surface<P>, paint: &Paint) {
+        for i in self.visible(surface) {
+            let (l, r) = self.rows[i];
+            if l <= r {
+                paint::paint_span(surface, self.y0 + i as i32, l, r + 1, 255, paint);
+            }
+        }
+    }
+
+    /// Paint the border pixels of the spanned shape.
+    pub fn outline<P: PixelFormat>(&self, surface: &mut Surface<P>, color: Color) {
+        let row = |i: usize| self.rows.get(i).copied().unwrap_or(EMPTY);
+        for i in self.visible(surface) {
+            let (l, r) = self.rows[i];
+            if l > r {
+                continue;
+            }
+            let y = self.y0 + i as i32;
+            let above = if i > 0 { row(i - 1) } else { EMPTY };
+            let below = row(i + 1);
+            let inner_l = (l + 1).max(above.0).max(below.0);
+            let inner_r = (r - 1).min(above.1).min(below.1);
+            if inner_l > inner_r {
+                surface.fill_span(l, r + 1, y, color);
+            } else {
+                surface.fill_span(l, inner_l, y, color);
+                surface.fill_span(inner_r + 1, r + 1, y, color);
+            }
+        }
+    }
+}
+
+/// The clip rect grown by one pixel on every side, as inclusive bounds: the rows and columns
+/// span builders must cover so that outlines still see the neighbours of their visible pixels.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Window {
+    pub x0: i64,
+    pub y0: i64,
+    pub x1: i64,
+    pub y1: i64,
+}
+
+impl Window {
+    pub fn new<P: PixelFormat>(surface: &Surface<P>) -> Self {
+        let c = surface.clip_rect();
+        Self {
+            x0: c.x as i64 - 1,
+            y0: c.y as i64 - 1,
+            x1: c.right() as i64,
+            y1: c.bottom() as i64,
+        }
+    }
+
+    /// Rows of `top..=bottom` inside the window; `None` when there are none.
+    pub fn rows(&self, top: i64, bottom: i64) -> Option<RangeInclusive<i32>> {
+        let (a, b) = (top.max(self.y0), bottom.min(self.y1));
+        (a <= b).then_some(a as i32..=b as i32)
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(
+            self.x0 as i32,
+            self.y0 as i32,
+            (self.x1 - self.x0 + 1) as i32,
+            (self.y1 - self.y0 + 1) as i32,
+        )
+    }
+}
+
+/// Largest `d >= 0` with `d*d*den <= num`, or -1 when `num < 0`.
+pub(crate) fn isqrt_div(num: i128, den: i128) -> i32 {
+    if num < 0 || den <= 0 {
+        return -1;
+    }
+    let mut d = ((num as f64) / (den as f64)).sqrt() as i128;
+    while d > 0 && d * d * den > num {
+        d -= 1;
+    }
+    while (d + 1) * (d + 1) * den <= num {
+        d += 1;
+    }
+    d as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isqrt_div_exact_and_floor() {
+        assert_eq!(isqrt_div(16, 1), 4);
+        assert_eq!(isqrt_div(15, 1), 3);
+        assert_eq!(isqrt_div(0, 1), 0);
+        assert_eq!(isqrt_div(-1, 1), -1);
+        assert_eq!(isqrt_div(100, 4), 5);
+    }
+
+    #[test]
+    fn outline_of_block_is_its_border() {
+        let mut s = Surface::new(5, 5);
+        let c = Color::rgba(1, 2, 3, 255);
+        let mut spans = Spans::new(1);
+        for _ in 0..3 {
+            spans.rows.push((1, 3));
+        }
+        spans.outline(&mut s, c);
+        let mut r = Surface::new(5, 5);
+        crate::draw::draw_rect(&mut r, 1, 1, 3, 3, c);
+        assert_eq!(s.pixels(), r.pixels());
+    }
+}
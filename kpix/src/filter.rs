@@ -0,0 +1,628 @@
+//! Image filters: convolution, blurs, sharpening, edge detection and morphology.
+//!
+//! Every filter reads a whole `src` surface and writes the result into `dst`, which must have
+//! the same size; only pixels inside `dst`'s clip rect are written. `src` and `dst` may not
+//! alias, so use [`in_place`] to filter a surface onto itself. Pixels outside the source are
+//! supplied by an [`EdgeMode`].
+//!
+//! Blurs and convolutions run on premultiplied alpha so transparent pixels do not bleed their
+//! (meaningless) color into opaque neighbours. Box and Gaussian blurs are separable: one
+//! horizontal pass and one vertical pass.
+
+use crate::core::{Color, Surface};
+
+/// How samples outside the source surface are obtained.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum EdgeMode {
+    /// Repeat the nearest edge pixel.
+    #[default]
+    Clamp,
+    /// Wrap around to the opposite edge (tileable images).
+    Wrap,
+    /// Treat everything outside as transparent black.
+    Transparent,
+}
+
+/// A rectangular convolution kernel with odd width and height, centered on the middle tap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kernel {
+    width: usize,
+    height: usize,
+    weights: Vec<f32>,
+}
+
+impl Kernel {
+    /// Row-major weights. Panics unless both sizes are odd and `weights.len() == width * height`.
+    pub fn new(width: usize, height: usize, weights: Vec<f32>) -> Self {
+        assert!(
+            width % 2 == 1 && height % 2 == 1,
+            "kernel size must be odd, got {width}x{height}"
+        );
+        assert_eq!(weights.len(), width * height, "kernel weight count");
+        Self {
+            width,
+            height,
+            weights,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Scale the weights so they sum to 1 (unchanged when the sum is 0).
+    pub fn normalized(mut self) -> Self {
+        let sum: f32 = self.weights.iter().sum();
+        if sum != 0.0 {
+            self.weights.iter_mut().for_each(|w| *w /= sum);
+        }
+        self
+    }
+
+    /// `(2r+1)²` box average. Panics if that size overflows `usize`.
+    pub fn box_blur(radius: usize) -> Self {
+        let n = radius.checked_mul(2).and_then(|d| d.checked_add(1));
+        let len = n.and_then(|n| n.checked_mul(n));
+        let (Some(n), Some(len)) = (n, len) else {
+            panic!("box kernel radius {radius} is too large");
+        };
+        Self::new(n, n, vec![1.0; len]).normalized()
+    }
+
+    /// 2D Gaussian with standard deviation `sigma`, truncated at 3 sigma.
+    pub fn gaussian(sigma: f32) -> Self {
+        let taps = gaussian_taps(sigma);
+        let n = taps.len();
+        let weights = taps
+            .iter()
+            .flat_map(|a| taps.iter().map(move |b| a * b))
+            .collect();
+        Self::new(n, n, weights)
+    }
+
+    /// 3x3 sharpen: the center minus its four neighbours.
+    pub fn sharpen() -> Self {
+        Self::new(3, 3, vec![0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0])
+    }
+
+    /// Horizontal Sobel derivative (responds to vertical edges).
+    pub fn sobel_x() -> Self {
+        Self::new(3, 3, vec![-1.0, 0.0, 1.0, -2.0, 0.0, 2.0, -1.0, 0.0, 1.0])
+    }
+
+    /// Vertical Sobel derivative (responds to horizontal edges).
+    pub fn sobel_y() -> Self {
+        Self::new(3, 3, vec![-1.0, -2.0, -1.0, 0.0, 0.0, 0.0, 1.0, 2.0, 1.0])
+    }
+}
+
+/// Run `op(src, dst)` with a snapshot of `surface` as the source and `surface` itself as the
+/// destination, e.g. `in_place(&mut s, |src, dst| gaussian_blur(src, dst, 2.0, EdgeMode::Clamp))`.
+pub fn in_place(surface: &mut Surface, op: impl FnOnce(&Surface, &mut Surface)) {
+    let src = surface.clone();
+    op(&src, surface);
+}
+
+/// Convolve every channel with `kernel`. Results are clamped to the valid range.
+pub fn convolve(src: &Surface, dst: &mut Surface, kernel: &Kernel, edge: EdgeMode) {
+    let img = Image::load(src, true);
+    let (kw, kh) = (kernel.width as i32, kernel.height as i32);
+    let (rx, ry) = (kw / 2, kh / 2);
+    let mut out = vec![ZERO; img.data.len()];
+    for y in 0..img.h {
+        for x in 0..img.w {
+            let mut acc = ZERO;
+            for ky in 0..kh {
+                for kx in 0..kw {
+                    let wt = kernel.weights[(ky * kw + kx) as usize];
+                    if wt != 0.0 {
+                        let p = img.sample(x + kx - rx, y + ky - ry, edge);
+                        add_scaled(&mut acc, &p, wt);
+                    }
+                }
+            }
+            out[(y * img.w + x) as usize] = acc;
+        }
+    }
+    store(dst, &img.with_data(out), true);
+}
+
+/// Average over a `(2r+1)²` box, as two sliding-window passes (cost independent of radius).
+/// Radii past the longer image side are limited to it.
+pub fn box_blur(src: &Surface, dst: &mut Surface, radius: usize, edge: EdgeMode) {
+    let img = Image::load(src, true);
+    let r = img.limit_radius(radius);
+    let out = img.separable(|line, out| box_line(line, out, r, edge));
+    store(dst, &out, true);
+}
+
+/// Gaussian blur with standard deviation `sigma` pixels (kernel radius `ceil(3 * sigma)`).
+pub fn gaussian_blur(src: &Surface, dst: &mut Surface, sigma: f32, edge: EdgeMode) {
+    let img = Image::load(src, true);
+    store(dst, &blur_premul(&img, sigma, edge), true);
+}
+
+/// Unsharp mask: `src + amount * (src - gaussian_blur(src, sigma))`, applied only where a
+/// channel differs from the blur by more than `threshold` (0..=255). Alpha is kept.
+pub fn unsharp_mask(
+    src: &Surface,
+    dst: &mut Surface,
+    sigma: f32,
+    amount: f32,
+    threshold: u8,
+    edge: EdgeMode,
+) {
+    let img = Image::load(src, true);
+    let blurred = blur_premul(&img, sigma, edge);
+    let t = threshold as f32;
+    let out = img
+        .data
+        .iter()
+        .zip(&blurred.data)
+        .map(|(p, b)| {
+            let mut q = *p;
+            for c in 0..3 {
+                let d = p[c] - b[c];
+                if d.abs() > t {
+                    q[c] = (p[c] + amount * d).clamp(0.0, p[3]);
+                }
+            }
+            q
+        })
+        .collect();
+    store(dst, &img.with_data(out), true);
+}
+
+/// Sobel gradient magnitude of the luminance, as an opaque grayscale image.
+pub fn sobel(src: &Surface, dst: &mut Surface, edge: EdgeMode) {
+    let img = Image::load(src, false);
+    let luma = img.with_data(
+        img.data
+            .iter()
+            .map(|p| {
+                let l = 0.2126 * p[0] + 0.7152 * p[1] + 0.0722 * p[2];
+                [l, l, l, p[3]]
+            })
+            .collect(),
+    );
+    let (kx, ky) = (Kernel::sobel_x(), Kernel::sobel_y());
+    let mut out = vec![ZERO; img.data.len()];
+    for y in 0..img.h {
+        for x in 0..img.w {
+            let (mut gx, mut gy) = (0.0, 0.0);
+            for j in 0..3 {
+                for i in 0..3 {
+                    let l = luma.sample(x + i - 1, y + j - 1, edge)[0];
+                    gx += kx.weights[(j * 3 + i) as usize] * l;
+                    gy += ky.weights[(j * 3 + i) as usize] * l;
+                }
+            }
+            let m = f32::hypot(gx, gy);
+            out[(y * img.w + x) as usize] = [m, m, m, 255.0];
+        }
+    }
+    store(dst, &img.with_data(out), false);
+}
+
+/// Morphological erosion: each channel becomes the minimum over a `(2r+1)²` square.
+/// Shrinks bright/opaque regions.
+pub fn erode(src: &Surface, dst: &mut Surface, radius: usize, edge: EdgeMode) {
+    morphology(src, dst, radius, edge, f32::min);
+}
+
+/// Morphological dilation: each channel becomes the maximum over a `(2r+1)²` square.
+/// Grows bright/opaque regions.
+pub fn dilate(src: &Surface, dst: &mut Surface, radius: usize, edge: EdgeMode) {
+    morphology(src, dst, radius, edge, f32::max);
+}
+
+fn morphology(
+    src: &Surface,
+    dst: &mut Surface,
+    radius: usize,
+    edge: EdgeMode,
+    pick: fn(f32, f32) -> f32,
+) {
+    let img = Image::load(src, false);
+    // Past the longer side the window already spans every sample of a line.
+    let r = img.limit_radius(radius);
+    let out = img.separable(|line, out| {
+        for (i, o) in out.iter_mut().enumerate() {
+            let i = i as i32;
+            let mut acc = sample_line(line, i - r, edge);
+            for k in i - r + 1..=i + r {
+                let p = sample_line(line, k, edge);
+                acc = std::array::from_fn(|c| pick(acc[c], p[c]));
+            }
+            *o = acc;
+        }
+    });
+    store(dst, &out, false);
+}
+
+/// Normalized 1D Gaussian taps for `sigma`, `2 * ceil(3 * sigma) + 1` long. `[1.0]` for
+/// `sigma <= 0`.
+pub fn gaussian_taps(sigma: f32) -> Vec<f32> {
+    gaussian_taps_within(sigma, i32::MAX)
+}
+
+/// `gaussian_taps` with the radius limited to `max_radius`, renormalized.
+fn gaussian_taps_within(sigma: f32, max_radius: i32) -> Vec<f32> {
+    if sigma <= 0.0 || !sigma.is_finite() {
+        return vec![1.0];
+    }
+    let r = ((3.0 * sigma).ceil() as i32).min(max_radius.max(0));
+    let k = -0.5 / (sigma * sigma);
+    let taps: Vec<f32> = (-r..=r).map(|i| (k * (i * i) as f32).exp()).collect();
+    let sum: f32 = taps.iter().sum();
+    taps.into_iter().map(|t| t / sum).collect()
+}
+
+type Px = [f32; 4];
+const ZERO: Px = [0.0; 4];
+
+/// Pixels as `f32` channels in `0..=255`, optionally premultiplied.
+struct Image {
+    w: i32,
+    h: i32,
+    data: Vec<Px>,
+}
+
+impl Image {
+    fn load(src: &Surface, premul: bool) -> Self {
+        let data = src
+            .pixels()
+            .iter()
+            .map(|&p| {
+                let c = Color::from_u32(p);
+                let a = c.a as f32;
+                let k = if premul { a / 255.0 } else { 1.0 };
+                [c.r as f32 * k, c.g as f32 * k, c.b as f32 * k, a]
+            })
+            .collect();
+        Self {
+            w: src.width() as i32,
+            h: src.height() as i32,
+            data,
+        }
+    }
+
+    /// `radius` limited to the longer side, so window sizes stay small and in `i32`.
+    fn limit_radius(&self, radius: usize) -> i32 {
+        radius.min(self.w.max(self.h) as usize) as i32
+    }
+
+    fn with_data(&self, data: Vec<Px>) -> Self {
+        Self {
+            w: self.w,
+            h: self.h,
+            data,
+        }
+    }
+
+    fn sample(&self, x: i32, y: i32, edge: EdgeMode) -> Px {
+        match (wrap_index(x, self.w, edge), wrap_index(y, self.h, edge)) {
+            (Some(x), Some(y)) => self.data[y * self.w as usize + x],
+            _ => ZERO,
+        }
+    }
+
+    /// Apply a 1D line operation to every row, then to every column of the result.
+    fn separable(&self, mut op: impl FnMut(&[Px], &mut [Px])) -> Self {
+        let (w, h) = (self.w as usize, self.h as usize);
+        let mut rows = vec![ZERO; w * h];
+        for y in 0..h {
+            op(
+                &self.data[y * w..(y + 1) * w],
+                &mut rows[y * w..(y + 1) * w],
+            );
+        }
+        let mut col = vec![ZERO; h];
+        let mut res = vec![ZERO; h];
+        for x in 0..w {
+            for y in 0..h {
+                col[y] = rows[y * w + x];
+            }
+            op(&col, &mut res);
+            for y in 0..h {
+                rows[y * w + x] = res[y];
+            }
+        }
+        self.with_data(rows)
+    }
+}
+
+fn wrap_index(i: i32, n: i32, edge: EdgeMode) -> Option<usize> {
+    if n <= 0 {
+        return None;
+    }
+    if (0..n).contains(&i) {
+        return Some(i as usize);
+    }
+    match edge {
+        EdgeMode::Clamp => Some(i.clamp(0, n - 1) as usize),
+        EdgeMode::Wrap => Some(i.rem_euclid(n) as usize),
+        EdgeMode::Transparent => None,
+    }
+}
+
+fn sample_line(line: &[Px], i: i32, edge: EdgeMode) -> Px {
+    wrap_index(i, line.len() as i32, edge).map_or(ZERO, |i| line[i])
+}
+
+fn add_scaled(acc: &mut Px, p: &Px, k: f32) {
+    for c in 0..4 {
+        acc[c] += p[c] * k;
+    }
+}
+
+/// Sliding-window mean over `2r+1` samples.
+fn box_line(line: &[Px], out: &mut [Px], r: i32, edge: EdgeMode) {
+    let k = 1.0 / (2 * r + 1) as f32;
+    let mut sum = ZERO;
+    for i in -r..=r {
+        add_scaled(&mut sum, &sample_line(line, i, edge), 1.0);
+    }
+    for (i, o) in out.iter_mut().enumerate() {
+        let i = i as i32;
+        *o = sum.map(|v| v * k);
+        add_scaled(&mut sum, &sample_line(line, i + r + 1, edge), 1.0);
+        add_scaled(&mut sum, &sample_line(line, i - r, edge), -1.0);
+    }
+}
+
+fn blur_premul(img: &Image, sigma: f32, edge: EdgeMode) -> Image {
+    // Taps past the longer side add nothing a narrower window would not cover.
+    let taps = gaussian_taps_within(sigma, img.w.max(img.h));
+    let r = (taps.len() / 2) as i32;
+    img.separable(|line, out| {
+        for (i, o) in out.iter_mut().enumerate() {
+            let mut acc = ZERO;
+            for (k, &t) in taps.iter().enumerate() {
+                add_scaled(
+                    &mut acc,
+                    &sample_line(line, i as i32 + k as i32 - r, edge),
+                    t,
+                );
+            }
+            *o = acc;
+        }
+    })
+}
+
+/// Write `img` into `dst` inside its clip rect, clamping and un-premultiplying as needed.
+fn store(dst: &mut Surface, img: &Image, premul: bool) {
+    assert_eq!(
+        (dst.width() as i32, dst.height() as i32),
+        (img.w, img.h),
+        "filter source and destination sizes differ"
+    );
+    let clip = dst.clip_rect();
+    for y in clip.y..clip.bottom() {
+        for x in clip.x..clip.right() {
+            let p = img.data[(y * img.w + x) as usize];
+            let a = p[3].clamp(0.0, 255.0);
+            let color = if premul {
+                if a <= 0.0 {
+                    Color::rgba(0, 0, 0, 0)
+                } else {
+                    let ch = |v: f32| (v.clamp(0.0, a) * 255.0 / a).round() as u8;
+                    Color::rgba(ch(p[0]), ch(p[1]), ch(p[2]), a.round() as u8)
+                }
+            } else {
+                let ch = |v: f32| v.clamp(0.0, 255.0).round() as u8;
+                Color::rgba(ch(p[0]), ch(p[1]), ch(p[2]), a.round() as u8)
+            };
+            dst.set_pixel(x, y, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rect;
+
+    const WHITE: Color = Color::rgba(255, 255, 255, 255);
+    const BLACK: Color = Color::rgba(0, 0, 0, 255);
+
+    fn row(s: &Surface, y: i32) -> Vec<u8> {
+        (0..s.width() as i32)
+            .map(|x| s.get_pixel(x, y).unwrap().r)
+            .collect()
+    }
+
+    /// Black surface with a single white pixel at (x, y).
+    fn dot(w: usize, h: usize, x: i32, y: i32) -> Surface {
+        let mut s = Surface::new(w, h);
+        s.clear(BLACK);
+        s.set_pixel(x, y, WHITE);
+        s
+    }
+
+    #[test]
+    fn identity_kernel_and_in_place_roundtrip() {
+        let mut s = Surface::new(4, 3);
+        for (i, x) in (0..4).enumerate() {
+            s.set_pixel(x, 1, Color::rgba(10 * i as u8, 20, 30, 255));
+        }
+        let id = Kernel::new(3, 3, vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+        let before = s.pixels().to_vec();
+        in_place(&mut s, |src, dst| convolve(src, dst, &id, EdgeMode::Clamp));
+        assert_eq!(s.pixels(), &before[..]);
+    }
+
+    #[test]
+    fn box_blur_matches_box_kernel() {
+        let s = dot(7, 7, 3, 3);
+        let mut a = Surface::new(7, 7);
+        let mut b = Surface::new(7, 7);
+        box_blur(&s, &mut a, 1, EdgeMode::Clamp);
+        convolve(&s, &mut b, &Kernel::box_blur(1), EdgeMode::Clamp);
+        assert_eq!(a.pixels(), b.pixels());
+        assert_eq!(row(&a, 3), vec![0, 0, 28, 28, 28, 0, 0]);
+        assert_eq!(row(&a, 1), vec![0; 7]);
+    }
+
+    #[test]
+    fn edge_modes_at_border() {
+        // White column at x = 0 only
+        let mut s = Surface::new(4, 1);
+        s.clear(BLACK);
+        s.set_pixel(0, 0, WHITE);
+        let blur = |edge| {
+            let mut d = Surface::new(4, 1);
+            box_blur(&s, &mut d, 1, edge);
+            d
+        };
+        assert_eq!(row(&blur(EdgeMode::Clamp), 0), vec![170, 85, 0, 0]);
+        assert_eq!(row(&blur(EdgeMode::Wrap), 0), vec![85, 85, 0, 85]);
+        let t = blur(EdgeMode::Transparent);
+        // Transparent outside (also above and below this single row): alpha fades, while the
+        // color is averaged only over the opaque samples.
+        assert_eq!(t.get_pixel(0, 0), Some(Color::rgba(128, 128, 128, 57)));
+    }
+
+    #[test]
+    fn gaussian_taps_are_normalized_and_symmetric() {
+        let t = gaussian_taps(1.5);
+        assert_eq!(t.len(), 11);
+        assert!((t.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+        assert!((0..t.len()).all(|i| (t[i] - t[t.len() - 1 - i]).abs() < 1e-7));
+        assert_eq!(gaussian_taps(0.0), vec![1.0]);
+
+        let s = dot(9, 9, 4, 4);
+        let mut d = Surface::new(9, 9);
+        gaussian_blur(&s, &mut d, 1.0, EdgeMode::Clamp);
+        let r = row(&d, 4);
+        assert!(r[4] > r[3] && r[3] > r[2] && r[3] == r[5]);
+        let c = gaussian_taps(1.0)[3];
+        assert_eq!(r[4], (255.0 * c * c).round() as u8);
+    }
+
+    #[test]
+    fn huge_sigma_is_limited_to_the_image() {
+        let mut s = Surface::new(4, 3);
+        s.clear(Color::rgba(90, 90, 90, 255));
+        let mut d = Surface::new(4, 3);
+        gaussian_blur(&s, &mut d, 1e9, EdgeMode::Clamp);
+        assert_eq!(d.pixels(), s.pixels());
+        assert_eq!(gaussian_taps_within(1e9, 4).len(), 9);
+    }
+
+    #[test]
+    fn huge_radii_are_limited_to_the_image() {
+        let mut s = Surface::new(4, 3);
+        s.clear(Color::rgba(90, 90, 90, 255));
+        let mut d = Surface::new(4, 3);
+        box_blur(&s, &mut d, 1 << 30, EdgeMode::Clamp);
+        assert_eq!(d.pixels(), s.pixels());
+        box_blur(&s, &mut d, usize::MAX, EdgeMode::Wrap);
+        assert_eq!(d.pixels(), s.pixels());
+        s.set_pixel(1, 1, Color::rgba(200, 200, 200, 255));
+        dilate(&s, &mut d, usize::MAX, EdgeMode::Clamp);
+        assert!(
+            d.pixels()
+                .iter()
+                .all(|&p| p == Color::rgba(200, 200, 200, 255).to_u32())
+        );
+        erode(&s, &mut d, 1 << 40, EdgeMode::Clamp);
+        assert!(
+            d.pixels()
+                .iter()
+                .all(|&p| p == Color::rgba(90, 90, 90, 255).to_u32())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn oversized_box_kernel_panics() {
+        Kernel::box_blur(usize::MAX / 2);
+    }
+
+    #[test]
+    fn transparent_pixels_do_not_bleed_color() {
+        // Opaque red next to transparent *green*: the blur must stay red.
+        let mut s = Surface::new(2, 1);
+        s.set_pixel(0, 0, Color::rgba(255, 0, 0, 255));
+        s.set_pixel(1, 0, Color::rgba(0, 255, 0, 0));
+        let mut d = Surface::new(2, 1);
+        box_blur(&s, &mut d, 1, EdgeMode::Clamp);
+        let c = d.get_pixel(1, 0).unwrap();
+        assert_eq!((c.r, c.g), (255, 0));
+    }
+
+    #[test]
+    fn unsharp_boosts_edges_and_keeps_flat_areas() {
+        let mut s = Surface::new(8, 1);
+        s.clear(Color::rgba(100, 100, 100, 255));
+        draw_half(&mut s, Color::rgba(150, 150, 150, 255));
+        let mut d = Surface::new(8, 1);
+        unsharp_mask(&s, &mut d, 1.0, 1.0, 0, EdgeMode::Clamp);
+        let r = row(&d, 0);
+        assert_eq!(r[0], 100);
+        assert_eq!(r[7], 150);
+        assert!(r[3] < 100 && r[4] > 150);
+        // A large threshold leaves the image untouched
+        unsharp_mask(&s, &mut d, 1.0, 1.0, 100, EdgeMode::Clamp);
+        assert_eq!(d.pixels(), s.pixels());
+    }
+
+    fn draw_half(s: &mut Surface, c: Color) {
+        let (w, h) = (s.width() as i32, s.height() as i32);
+        crate::draw::fill_rect(s, w / 2, 0, w - w / 2, h, c);
+    }
+
+    #[test]
+    fn sobel_finds_vertical_edge() {
+        let mut s = Surface::new(6, 3);
+        s.clear(BLACK);
+        draw_half(&mut s, WHITE);
+        let mut d = Surface::new(6, 3);
+        sobel(&s, &mut d, EdgeMode::Clamp);
+        assert_eq!(row(&d, 1), vec![0, 0, 255, 255, 0, 0]);
+        assert_eq!(d.get_pixel(0, 0).unwrap().a, 255);
+    }
+
+    #[test]
+    fn erode_and_dilate_square() {
+        let mut s = Surface::new(7, 7);
+        s.clear(BLACK);
+        crate::draw::fill_rect(&mut s, 2, 2, 3, 3, WHITE);
+        let mut d = Surface::new(7, 7);
+        erode(&s, &mut d, 1, EdgeMode::Clamp);
+        let white = |s: &Surface| s.pixels().iter().filter(|&&p| p == WHITE.to_u32()).count();
+        assert_eq!(white(&d), 1);
+        assert_eq!(d.get_pixel(3, 3), Some(WHITE));
+        dilate(&s, &mut d, 1, EdgeMode::Clamp);
+        assert_eq!(white(&d), 25);
+        // Transparent edges erode opaque content touching the border
+        let mut full = Surface::new(3, 3);
+        full.clear(WHITE);
+        let mut e = Surface::new(3, 3);
+        erode(&full, &mut e, 1, EdgeMode::Transparent);
+        assert_eq!(e.get_pixel(0, 0).unwrap().a, 0);
+        assert_eq!(e.get_pixel(1, 1), Some(WHITE));
+    }
+
+    #[test]
+    fn writes_only_inside_destination_clip() {
+        let s = dot(5, 5, 2, 2);
+        let mut d = Surface::new(5, 5);
+        d.push_clip(Rect::new(0, 0, 5, 2));
+        box_blur(&s, &mut d, 1, EdgeMode::Clamp);
+        assert_eq!(d.get_pixel(2, 1).unwrap().r, 28);
+        assert_eq!(d.get_pixel(2, 2), Some(Color::from_u32(0)));
+    }
+}
@@ -0,0 +1,349 @@
+//! Flood fill and connected-region selection.
+//!
+//! Regions are grown with a scanline span stack: each popped seed is widened to the full
+//! matching run on its row, and only the start of each matching run on the neighbouring rows
+//! is pushed. Memory stays proportional to the number of pending runs, never the call depth.
+//! The search is confined to the surface's clip rect.
+
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+use crate::rect::Rect;
+
+/// Which neighbours count as connected.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Left, right, up and down.
+    #[default]
+    Four,
+    /// The four orthogonal neighbours plus the diagonals.
+    Eight,
+}
+
+/// Flood options. Defaults: 4-connected, exact match.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FloodOptions {
+    pub connectivity: Connectivity,
+    /// Largest per-channel difference (RGBA) from the seed color still treated as a match.
+    /// 0 means exact match.
+    pub tolerance: u8,
+}
+
+impl FloodOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+/// Per-pixel selection the size of the surface it was computed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mask {
+    width: usize,
+    height: usize,
+    bits: Vec<bool>,
+    bounds: Rect,
+    count: usize,
+}
+
+impl Mask {
+    /// Empty mask of the given size.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            bits: vec![false; width.saturating_mul(height)],
+            bounds: Rect::default(),
+            count: 0,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// True when `(x, y)` is selected; false outside the mask.
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return false;
+        }
+        self.bits[y as usize * self.width + x as usize]
+    }
+
+    /// Number of selected pixels.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Bounding box of the selection (empty when nothing is selected).
+    #[inline]
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// Half-open runs `[x0, x1)` of selected pixels on row `y`.
+    pub fn row_spans(&self, y: i32) -> impl Iterator<Item = (i32, i32)> + '_ {
+        let row: &[bool] = if y >= 0 && (y as usize) < self.height {
+            let start = y as usize * self.width;
+            &self.bits[start..start + self.width]
+        } else {
+            &[]
+        };
+        let mut x = 0;
+        std::iter::from_fn(move || {
+            while x < row.len() && !row[x] {
+                x += 1;
+            }
+            if x == row.len() {
+                return None;
+            }
+            let start = x;
+            while x < row.len() && row[x] {
+                x += 1;
+            }
+            Some((start as i32, x as i32))
+        })
+    }
+
+    fn set_span(&mut self, y: i32, x0: i32, x1: i32) {
+        let row = y as usize * self.width;
+        self.bits[row + x0 as usize..row + x1 as usize].fill(true);
+        let span = Rect::from_min_max(x0, y, x1, y + 1);
+        self.bounds = if self.count == 0 {
+            span
+        } else {
+            self.bounds.union(span)
+        };
+        self.count += (x1 - x0) as usize;
+    }
+}
+
+/// Fill the region connected to `(x, y)` whose pixels exactly match the seed pixel,
+/// using 4-connectivity.
+pub fn flood_fill<P: PixelFormat>(surface: &mut Surface<P>, x: i32, y: i32, color: Color) {
+    flood_fill_ex(surface, x, y, color, &FloodOptions::default());
+}
+
+/// Fill the region connected to `(x, y)` selected with `opts`.
+pub fn flood_fill_ex<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    color: Color,
+    opts: &FloodOptions,
+) {
+    let mask = flood_mask(surface, x, y, opts);
+    fill_mask(surface, &mask, color);
+}
+
+/// Select the region connected to `(x, y)` without painting it. Pixels match when every
+/// channel is within `opts.tolerance` of the seed pixel. A seed outside the clip rect
+/// selects nothing.
+pub fn flood_mask<P: PixelFormat>(
+    surface: &Surface<P>,
+    x: i32,
+    y: i32,
+    opts: &FloodOptions,
+) -> Mask {
+    let Some(seed) = surface.get_pixel(x, y) else {
+        return Mask::new(surface.width(), surface.height());
+    };
+    let tol = opts.tolerance;
+    flood_mask_by(surface, x, y, opts.connectivity, |c| {
+        c.r.abs_diff(seed.r) <= tol
+            && c.g.abs_diff(seed.g) <= tol
+            && c.b.abs_diff(seed.b) <= tol
+            && c.a.abs_diff(seed.a) <= tol
+    })
+}
+
+/// Select the region connected to `(x, y)` whose pixels satisfy `matches`, e.g. everything
+/// up to a border color. Selects nothing when the seed itself does not match.
+pub fn flood_mask_by<P: PixelFormat>(
+    surface: &Surface<P>,
+    x: i32,
+    y: i32,
+    connectivity: Connectivity,
+    mut matches: impl FnMut(Color) -> bool,
+) -> Mask {
+    let mut mask = Mask::new(surface.width(), surface.height());
+    let clip = surface.clip_rect();
+    if !clip.contains(x, y) {
+        return mask;
+    }
+    let width = surface.width();
+    let px = surface.pixels();
+    let format = surface.format();
+    let mut hit = |mask: &Mask, x: i32, y: i32| {
+        let i = y as usize * width + x as usize;
+        !mask.bits[i] && matches(format.decode(px[i]))
+    };
+    let reach = match connectivity {
+        Connectivity::Four => 0,
+        Connectivity::Eight => 1,
+    };
+
+    let mut stack = vec![(x, y)];
+    while let Some((sx, sy)) = stack.pop() {
+        if !hit(&mask, sx, sy) {
+            continue;
+        }
+        let mut x0 = sx;
+        while x0 > clip.x && hit(&mask, x0 - 1, sy) {
+            x0 -= 1;
+        }
+        let mut x1 = sx + 1;
+        while x1 < clip.right() && hit(&mask, x1, sy) {
+            x1 += 1;
+        }
+        mask.set_span(sy, x0, x1);
+
+        let lo = (x0 - reach).max(clip.x);
+        let hi = (x1 + reach).min(clip.right());
+        for ny in [sy - 1, sy + 1] {
+            if ny < clip.y || ny >= clip.bottom() {
+                continue;
+            }
+            // Push one seed per matching run on the neighbouring row.
+            let mut nx = lo;
+            while nx < hi {
+                if hit(&mask, nx, ny) {
+                    stack.push((nx, ny));
+                    while nx < hi && hit(&mask, nx, ny) {
+                        nx += 1;
+                    }
+                } else {
+                    nx += 1;
+                }
+            }
+        }
+    }
+    mask
+}
+
+/// Paint every selected pixel of `mask` with `color`, honouring the clip rect.
+pub fn fill_mask<P: PixelFormat>(surface: &mut Surface<P>, mask: &Mask, color: Color) {
+    let b = mask.bounds();
+    for y in b.y..b.bottom() {
+        for (x0, x1) in mask.row_spans(y) {
+            surface.fill_span(x0, x1, y, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw;
+
+    const WHITE: Color = Color::rgba(255, 255, 255, 255);
+    const RED: Color = Color::rgba(255, 0, 0, 255);
+
+    /// 9x9 white square outline on a transparent surface.
+    fn ring() -> Surface {
+        let mut s = Surface::new(12, 12);
+        draw::draw_rect(&mut s, 1, 1, 9, 9, WHITE);
+        s
+    }
+
+    #[test]
+    fn fills_inside_outline_only() {
+        let mut s = ring();
+        flood_fill(&mut s, 5, 5, RED);
+        assert_eq!(s.get_pixel(2, 2), Some(RED));
+        assert_eq!(s.get_pixel(8, 8), Some(RED));
+        assert_eq!(s.get_pixel(1, 5), Some(WHITE));
+        assert_eq!(s.get_pixel(0, 0), Some(Color::from_u32(0)));
+        let filled = s.pixels().iter().filter(|&&p| p == RED.to_u32()).count();
+        assert_eq!(filled, 7 * 7);
+    }
+
+    #[test]
+    fn eight_connectivity_leaks_through_diagonal_gap() {
+        // Two squares touching only at a corner.
+        let mut s = Surface::new(6, 6);
+        draw::fill_rect(&mut s, 0, 0, 3, 3, WHITE);
+        draw::fill_rect(&mut s, 3, 3, 3, 3, WHITE);
+        let four = flood_mask(&s, 0, 0, &FloodOptions::new());
+        assert_eq!(four.count(), 9);
+        let eight = flood_mask(
+            &s,
+            0,
+            0,
+            &FloodOptions::new().with_connectivity(Connectivity::Eight),
+        );
+        assert_eq!(eight.count(), 18);
+        assert_eq!(eight.bounds(), Rect::new(0, 0, 6, 6));
+    }
+
+    #[test]
+    fn tolerance_includes_similar_colors() {
+        let mut s = Surface::new(8, 1);
+        for x in 0..8 {
+            s.set_pixel(x, 0, Color::rgba(100 + x as u8 * 5, 0, 0, 255));
+        }
+        assert_eq!(flood_mask(&s, 0, 0, &FloodOptions::new()).count(), 1);
+        let m = flood_mask(&s, 0, 0, &FloodOptions::new().with_tolerance(12));
+        assert_eq!(m.count(), 3);
+        assert!(m.get(2, 0) && !m.get(3, 0));
+    }
+
+    #[test]
+    fn refilling_with_same_color_terminates() {
+        let mut s = Surface::new(4, 4);
+        s.clear(RED);
+        flood_fill(&mut s, 1, 1, RED);
+        assert!(s.pixels().iter().all(|&p| p == RED.to_u32()));
+    }
+
+    #[test]
+    fn spiral_region_needs_no_recursion() {
+        // A serpentine corridor visits every row many times; a recursive fill would go deep.
+        let (w, h) = (401, 401);
+        let mut s = Surface::new(w, h);
+        for y in (1..h as i32).step_by(2) {
+            let gap = if (y / 2) % 2 == 0 { w as i32 - 1 } else { 0 };
+            draw::fill_rect(&mut s, 0, y, w as i32, 1, WHITE);
+            s.set_pixel(gap, y, Color::from_u32(0));
+        }
+        let m = flood_mask(&s, 0, 0, &FloodOptions::new());
+        assert_eq!(m.count(), 201 * w + 200);
+    }
+
+    #[test]
+    fn region_stays_inside_clip_and_custom_predicate() {
+        let mut s = ring();
+        s.push_clip(Rect::new(0, 0, 5, 12));
+        flood_fill(&mut s, 5, 5, RED); // seed outside the clip
+        assert!(!s.pixels().contains(&RED.to_u32()));
+        flood_fill(&mut s, 3, 3, RED);
+        assert_eq!(s.get_pixel(4, 4), Some(RED));
+        assert_eq!(s.get_pixel(5, 4), Some(Color::from_u32(0)));
+        s.pop_clip();
+
+        // Fill up to the white border regardless of what is inside it.
+        let m = flood_mask_by(&s, 3, 3, Connectivity::Four, |c| c != WHITE);
+        assert_eq!(m.count(), 7 * 7);
+        assert_eq!(m.row_spans(5).collect::<Vec<_>>(), vec![(2, 9)]);
+    }
+}
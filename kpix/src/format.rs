@@ -0,0 +1,218 @@
+//! Pixel formats for `Surface<P>`.
+//!
+//! A format owns the mapping between its stored pixel value and `Color`. Drawing always goes
+//! through `encode`/`decode`, so every primitive works on every format; formats that cannot
+//! hold a channel drop it on encode and report it as opaque (or gray) on decode. Formats are
+//! values rather than marker types so that `Indexed` can carry its palette.
+
+use std::fmt;
+
+use crate::core::Color;
+
+/// Storage and color conversion for one pixel layout.
+pub trait PixelFormat: Clone + fmt::Debug {
+    /// Stored value of one pixel. `Default` is the value of a freshly created surface.
+    type Pixel: Copy + Default + PartialEq + fmt::Debug + Send + Sync;
+
+    /// Convert a straight-alpha color to the stored value.
+    fn encode(&self, color: Color) -> Self::Pixel;
+
+    /// Convert a stored value back to a straight-alpha color.
+    fn decode(&self, pixel: Self::Pixel) -> Color;
+}
+
+/// Packed little-endian RGBA in a `u32`, the default format.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rgba8;
+
+impl PixelFormat for Rgba8 {
+    type Pixel = u32;
+
+    #[inline]
+    fn encode(&self, color: Color) -> u32 {
+        color.to_u32()
+    }
+
+    #[inline]
+    fn decode(&self, pixel: u32) -> Color {
+        Color::from_u32(pixel)
+    }
+}
+
+/// 8-bit luma. Alpha is dropped; decodes to opaque gray.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Gray8;
+
+impl PixelFormat for Gray8 {
+    type Pixel = u8;
+
+    #[inline]
+    fn encode(&self, color: Color) -> u8 {
+        luma(color)
+    }
+
+    #[inline]
+    fn decode(&self, pixel: u8) -> Color {
+        Color::rgba(pixel, pixel, pixel, 255)
+    }
+}
+
+/// 16-bit luma, e.g. for heightmaps. 8-bit colors widen by `v * 257`, so white encodes to
+/// `u16::MAX`; decoding keeps the high byte.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Gray16;
+
+impl PixelFormat for Gray16 {
+    type Pixel = u16;
+
+    #[inline]
+    fn encode(&self, color: Color) -> u16 {
+        luma(color) as u16 * 257
+    }
+
+    #[inline]
+    fn decode(&self, pixel: u16) -> Color {
+        let v = (pixel >> 8) as u8;
+        Color::rgba(v, v, v, 255)
+    }
+}
+
+/// 5-6-5 packed RGB (red in the high bits). Alpha is dropped; decoding replicates the high
+/// bits into the low ones so full intensity stays 255.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rgb565;
+
+impl PixelFormat for Rgb565 {
+    type Pixel = u16;
+
+    #[inline]
+    fn encode(&self, color: Color) -> u16 {
+        let r = (color.r as u16 * 31 + 127) / 255;
+        let g = (color.g as u16 * 63 + 127) / 255;
+        let b = (color.b as u16 * 31 + 127) / 255;
+        (r << 11) | (g << 5) | b
+    }
+
+    #[inline]
+    fn decode(&self, pixel: u16) -> Color {
+        let r = (pixel >> 11) as u8 & 0x1f;
+        let g = (pixel >> 5) as u8 & 0x3f;
+        let b = pixel as u8 & 0x1f;
+        Color::rgba(
+            (r << 3) | (r >> 2),
+            (g << 2) | (g >> 4),
+            (b << 3) | (b >> 2),
+            255,
+        )
+    }
+}
+
+/// 8-bit palette indices. Encoding picks the nearest palette entry (squared RGBA distance,
+/// first entry wins ties); indices past the end of the palette decode as transparent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Indexed {
+    palette: Vec<Color>,
+}
+
+impl Indexed {
+    /// Only the first 256 colors are reachable.
+    pub fn new(palette: impl IntoIterator<Item = Color>) -> Self {
+        Self {
+            palette: palette.into_iter().take(256).collect(),
+        }
+    }
+
+    pub fn palette(&self) -> &[Color] {
+        &self.palette
+    }
+
+    /// Replace entry `index`, e.g. for palette cycling. Ignored past the end of the palette.
+    pub fn set_color(&mut self, index: u8, color: Color) {
+        if let Some(c) = self.palette.get_mut(index as usize) {
+            *c = color;
+        }
+    }
+
+    /// Index of the palette entry closest to `color`; 0 for an empty palette.
+    pub fn nearest(&self, color: Color) -> u8 {
+        let dist = |c: &Color| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(c.r, color.r) + d(c.g, color.g) + d(c.b, color.b) + d(c.a, color.a)
+        };
+        let mut best = (0, i32::MAX);
+        for (i, c) in self.palette.iter().enumerate() {
+            let d = dist(c);
+            if d < best.1 {
+                best = (i, d);
+            }
+        }
+        best.0 as u8
+    }
+}
+
+impl PixelFormat for Indexed {
+    type Pixel = u8;
+
+    #[inline]
+    fn encode(&self, color: Color) -> u8 {
+        self.nearest(color)
+    }
+
+    #[inline]
+    fn decode(&self, pixel: u8) -> Color {
+        self.palette
+            .get(pixel as usize)
+            .copied()
+            .unwrap_or(Color::rgba(0, 0, 0, 0))
+    }
+}
+
+/// Rec. 601 luma of the encoded channels, rounded.
+#[inline]
+fn luma(c: Color) -> u8 {
+    ((c.r as u32 * 299 + c.g as u32 * 587 + c.b as u32 * 114 + 500) / 1000) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gray_formats_round_trip_gray_levels() {
+        for v in [0u8, 1, 127, 200, 255] {
+            let c = Color::rgba(v, v, v, 255);
+            assert_eq!(Gray8.decode(Gray8.encode(c)), c);
+            assert_eq!(Gray16.decode(Gray16.encode(c)), c);
+        }
+        assert_eq!(Gray16.encode(Color::rgba(255, 255, 255, 255)), u16::MAX);
+        // Green weighs most.
+        assert!(
+            Gray8.encode(Color::rgba(0, 255, 0, 255)) > Gray8.encode(Color::rgba(255, 0, 0, 255))
+        );
+    }
+
+    #[test]
+    fn rgb565_keeps_extremes_and_drops_alpha() {
+        let white = Color::rgba(255, 255, 255, 255);
+        assert_eq!(Rgb565.encode(white), 0xffff);
+        assert_eq!(Rgb565.decode(0xffff), white);
+        assert_eq!(Rgb565.encode(Color::rgba(255, 0, 0, 10)), 0xf800);
+        let c = Rgb565.decode(Rgb565.encode(Color::rgba(100, 150, 200, 255)));
+        assert!(c.r.abs_diff(100) <= 4 && c.g.abs_diff(150) <= 2 && c.b.abs_diff(200) <= 4);
+    }
+
+    #[test]
+    fn indexed_maps_to_nearest_entry() {
+        let mut f = Indexed::new([
+            Color::rgba(0, 0, 0, 255),
+            Color::rgba(255, 255, 255, 255),
+            Color::rgba(255, 0, 0, 255),
+        ]);
+        assert_eq!(f.encode(Color::rgba(200, 30, 20, 255)), 2);
+        assert_eq!(f.encode(Color::rgba(180, 180, 180, 255)), 1);
+        assert_eq!(f.decode(7), Color::rgba(0, 0, 0, 0));
+        f.set_color(2, Color::rgba(0, 0, 255, 255));
+        assert_eq!(f.decode(2), Color::rgba(0, 0, 255, 255));
+        assert_eq!(Indexed::default().encode(Color::rgba(1, 2, 3, 4)), 0);
+    }
+}
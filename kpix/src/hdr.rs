@@ -0,0 +1,425 @@
+//! Floating-point HDR surface and tone-mapped resolve to an 8-bit `Surface`.
+//!
+//! `HdrSurface` mirrors the `Surface` pixel API (clip stack, set/get, spans, rect fills,
+//! blits) but stores `LinearRgba`: linear light with straight alpha and no upper bound, so
+//! light can be accumulated without clipping or banding. `resolve` applies exposure, a tone
+//! mapping operator, the sRGB transfer curve and optional dithering.
+
+use crate::color::{LinearRgba, linear_to_srgb};
+use crate::core::{Color, Surface};
+use crate::rect::{ClipStack, Rect};
+
+/// How a blit combines source and destination.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum HdrBlend {
+    /// Overwrite destination pixels.
+    #[default]
+    Replace,
+    /// Straight-alpha source-over.
+    Alpha,
+    /// Add the source light, weighted by its alpha; destination alpha is kept.
+    Add,
+}
+
+/// Curve mapping unbounded scene values to `0..=1`, applied per channel after exposure.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ToneMap {
+    /// Clip at 1.
+    #[default]
+    Clamp,
+    /// `x / (1 + x)`.
+    Reinhard,
+    /// Narkowicz's fit of the ACES filmic curve.
+    AcesFit,
+}
+
+impl ToneMap {
+    pub fn apply(self, x: f32) -> f32 {
+        let x = x.max(0.0);
+        match self {
+            ToneMap::Clamp => x.min(1.0),
+            ToneMap::Reinhard => x / (1.0 + x),
+            ToneMap::AcesFit => {
+                ((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+/// Quantization to 8 bits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Dither {
+    /// Round to nearest.
+    #[default]
+    None,
+    /// 4x4 Bayer ordered dither: breaks up banding in smooth gradients.
+    Ordered,
+}
+
+/// Settings for `HdrSurface::resolve`. Defaults: clamp, exposure 0, no dithering.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ResolveOptions {
+    pub tone_map: ToneMap,
+    /// Exposure in stops: colors are scaled by `2^exposure` before tone mapping.
+    pub exposure: f32,
+    pub dither: Dither,
+}
+
+impl ResolveOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tone_map(mut self, tone_map: ToneMap) -> Self {
+        self.tone_map = tone_map;
+        self
+    }
+
+    pub fn with_exposure(mut self, exposure: f32) -> Self {
+        self.exposure = exposure;
+        self
+    }
+
+    pub fn with_dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+}
+
+const BAYER4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+#[derive(Clone, Debug)]
+pub struct HdrSurface {
+    width: usize,
+    height: usize,
+    pixels: Vec<LinearRgba>,
+    clip: ClipStack,
+}
+
+impl HdrSurface {
+    /// New surface cleared to transparent black.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![LinearRgba::default(); width.saturating_mul(height)],
+            clip: ClipStack::new(Rect::new(0, 0, width as i32, height as i32)),
+        }
+    }
+
+    /// Decode an 8-bit surface to linear light.
+    pub fn from_surface(src: &Surface) -> Self {
+        let mut s = Self::new(src.width(), src.height());
+        for (d, &p) in s.pixels.iter_mut().zip(src.pixels()) {
+            *d = Color::from_u32(p).to_linear();
+        }
+        s
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Full surface area as a rect.
+    #[inline]
+    pub fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width as i32, self.height as i32)
+    }
+
+    /// Current clip rect: the innermost pushed clip, or the full surface.
+    #[inline]
+    pub fn clip_rect(&self) -> Rect {
+        self.clip.current()
+    }
+
+    /// Restrict drawing to `rect` intersected with the current clip. Undo with `pop_clip`.
+    pub fn push_clip(&mut self, rect: Rect) {
+        self.clip.push(rect);
+    }
+
+    /// Restore the clip that was active before the matching `push_clip`.
+    pub fn pop_clip(&mut self) -> Option<Rect> {
+        self.clip.pop()
+    }
+
+    /// Fill the current clip region.
+    pub fn clear(&mut self, color: LinearRgba) {
+        let c = self.clip.current();
+        self.fill_rect(c.x, c.y, c.w, c.h, color);
+    }
+
+    /// Set a pixel; coordinates outside the clip rect are ignored.
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: LinearRgba) {
+        if let Some(p) = self.clipped_mut(x, y) {
+            *p = color;
+        }
+    }
+
+    /// Get a pixel if in-bounds. Reads ignore the clip rect.
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<LinearRgba> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(self.pixels[y as usize * self.width + x as usize])
+    }
+
+    /// Straight-alpha source-over, in linear light.
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: LinearRgba) {
+        if let Some(p) = self.clipped_mut(x, y) {
+            *p = blend_over(color, *p);
+        }
+    }
+
+    /// Add `color`'s light, weighted by its alpha, keeping the destination alpha.
+    pub fn add_pixel(&mut self, x: i32, y: i32, color: LinearRgba) {
+        if let Some(p) = self.clipped_mut(x, y) {
+            *p = add(color, *p);
+        }
+    }
+
+    /// Fill the half-open span [x0, x1) on row `y`, clipped.
+    pub fn fill_span(&mut self, x0: i32, x1: i32, y: i32, color: LinearRgba) {
+        if let Some((x0, x1)) = self.clip.span(x0, x1, y) {
+            let row = y as usize * self.width;
+            self.pixels[row + x0 as usize..row + x1 as usize].fill(color);
+        }
+    }
+
+    /// Fill a rect (`w`, `h` may be negative, like `draw::fill_rect`), clipped.
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: LinearRgba) {
+        let r = Rect::new(x, y, w, h).intersect(self.clip.current());
+        for yy in r.y..r.bottom() {
+            self.fill_span(r.x, r.right(), yy, color);
+        }
+    }
+
+    /// Copy `src_rect` of `src` to `(x, y)`, combined according to `mode` and clipped.
+    pub fn blit(&mut self, src: &HdrSurface, src_rect: Rect, x: i32, y: i32, mode: HdrBlend) {
+        let sr = src_rect.intersect(src.bounds());
+        let dst = Rect::new(x + sr.x - src_rect.x, y + sr.y - src_rect.y, sr.w, sr.h)
+            .intersect(self.clip.current());
+        for dy in dst.y..dst.bottom() {
+            for dx in dst.x..dst.right() {
+                let sx = dx - x + src_rect.x;
+                let sy = dy - y + src_rect.y;
+                let s = src.pixels[sy as usize * src.width + sx as usize];
+                let d = &mut self.pixels[dy as usize * self.width + dx as usize];
+                *d = match mode {
+                    HdrBlend::Replace => s,
+                    HdrBlend::Alpha => blend_over(s, *d),
+                    HdrBlend::Add => add(s, *d),
+                };
+            }
+        }
+    }
+
+    /// Add `weight` times every pixel of `src` (same size) inside the clip, e.g. to average
+    /// sub-frames for motion blur. Alpha is accumulated too.
+    pub fn accumulate(&mut self, src: &HdrSurface, weight: f32) {
+        assert_eq!(
+            (self.width, self.height),
+            (src.width, src.height),
+            "accumulate requires equal surface sizes"
+        );
+        let c = self.clip.current();
+        for y in c.y..c.bottom() {
+            let row = y as usize * self.width;
+            for i in row + c.x as usize..row + c.right() as usize {
+                let (d, s) = (&mut self.pixels[i], src.pixels[i]);
+                d.r += s.r * weight;
+                d.g += s.g * weight;
+                d.b += s.b * weight;
+                d.a += s.a * weight;
+            }
+        }
+    }
+
+    /// Read-only access to the pixel buffer (row-major).
+    pub fn pixels(&self) -> &[LinearRgba] {
+        &self.pixels
+    }
+
+    /// Tone map and quantize the whole surface to a new 8-bit surface.
+    pub fn resolve(&self, opts: &ResolveOptions) -> Surface {
+        let mut out = Surface::new(self.width, self.height);
+        self.resolve_into(&mut out, opts);
+        out
+    }
+
+    /// Like `resolve`, writing into an existing surface of the same size (inside its clip).
+    pub fn resolve_into(&self, dst: &mut Surface, opts: &ResolveOptions) {
+        assert_eq!(
+            (self.width, self.height),
+            (dst.width(), dst.height()),
+            "resolve requires equal surface sizes"
+        );
+        let k = opts.exposure.exp2();
+        let c = dst.clip_rect();
+        for y in c.y..c.bottom() {
+            for x in c.x..c.right() {
+                let p = self.pixels[y as usize * self.width + x as usize];
+                let offset = match opts.dither {
+                    Dither::None => 0.5,
+                    Dither::Ordered => (BAYER4[y as usize & 3][x as usize & 3] as f32 + 0.5) / 16.0,
+                };
+                let q = |v: f32| (v * 255.0 + offset).floor().clamp(0.0, 255.0) as u8;
+                let ch = |v: f32| q(linear_to_srgb(opts.tone_map.apply(v * k)));
+                dst.set_pixel(
+                    x,
+                    y,
+                    Color::rgba(ch(p.r), ch(p.g), ch(p.b), q(p.a.clamp(0.0, 1.0))),
+                );
+            }
+        }
+    }
+
+    fn clipped_mut(&mut self, x: i32, y: i32) -> Option<&mut LinearRgba> {
+        if !self.clip.contains(x, y) {
+            return None;
+        }
+        Some(&mut self.pixels[y as usize * self.width + x as usize])
+    }
+}
+
+/// Straight-alpha source-over: the destination shows through with weight `d.a * (1 - s.a)`.
+fn blend_over(s: LinearRgba, d: LinearRgba) -> LinearRgba {
+    let sa = s.a.clamp(0.0, 1.0);
+    let dw = d.a * (1.0 - sa);
+    let a = sa + dw;
+    if a <= 0.0 {
+        return LinearRgba::default();
+    }
+    LinearRgba::new(
+        (s.r * sa + d.r * dw) / a,
+        (s.g * sa + d.g * dw) / a,
+        (s.b * sa + d.b * dw) / a,
+        a,
+    )
+}
+
+fn add(s: LinearRgba, d: LinearRgba) -> LinearRgba {
+    LinearRgba::new(d.r + s.r * s.a, d.g + s.g * s.a, d.b + s.b * s.a, d.a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAY: LinearRgba = LinearRgba::new(0.5, 0.5, 0.5, 1.0);
+
+    #[test]
+    fn pixels_clip_and_fill() {
+        let mut s = HdrSurface::new(4, 3);
+        s.set_pixel(1, 1, LinearRgba::new(4.0, 0.0, 0.0, 1.0));
+        assert_eq!(s.get_pixel(1, 1).unwrap().r, 4.0);
+        assert_eq!(s.get_pixel(4, 0), None);
+        s.push_clip(Rect::new(0, 0, 2, 3));
+        s.fill_rect(0, 0, 4, 1, GRAY);
+        s.set_pixel(3, 2, GRAY);
+        assert_eq!(s.pop_clip(), Some(Rect::new(0, 0, 2, 3)));
+        assert_eq!(s.get_pixel(1, 0), Some(GRAY));
+        assert_eq!(s.get_pixel(2, 0), Some(LinearRgba::default()));
+        assert_eq!(s.get_pixel(3, 2), Some(LinearRgba::default()));
+    }
+
+    #[test]
+    fn light_accumulates_past_one() {
+        let mut s = HdrSurface::new(1, 1);
+        s.clear(LinearRgba::new(0.0, 0.0, 0.0, 1.0));
+        for _ in 0..5 {
+            s.add_pixel(0, 0, LinearRgba::new(0.5, 0.25, 0.0, 1.0));
+        }
+        s.add_pixel(0, 0, LinearRgba::new(1.0, 1.0, 1.0, 0.5));
+        let p = s.get_pixel(0, 0).unwrap();
+        assert_eq!((p.r, p.g, p.b, p.a), (3.0, 1.75, 0.5, 1.0));
+
+        // Averaging sub-frames
+        let mut acc = HdrSurface::new(1, 1);
+        acc.accumulate(&s, 0.5);
+        acc.accumulate(&s, 0.5);
+        assert_eq!(acc.get_pixel(0, 0), Some(p));
+    }
+
+    #[test]
+    fn blit_modes() {
+        let mut src = HdrSurface::new(2, 1);
+        src.set_pixel(0, 0, LinearRgba::new(1.0, 0.0, 0.0, 0.5));
+        src.set_pixel(1, 0, LinearRgba::new(2.0, 2.0, 2.0, 1.0));
+        let base = LinearRgba::new(0.0, 0.0, 1.0, 1.0);
+        let run = |mode| {
+            let mut d = HdrSurface::new(3, 1);
+            d.clear(base);
+            d.blit(&src, src.bounds(), 1, 0, mode);
+            d
+        };
+        let r = run(HdrBlend::Replace);
+        assert_eq!(r.get_pixel(0, 0), Some(base));
+        assert_eq!(r.get_pixel(1, 0), Some(LinearRgba::new(1.0, 0.0, 0.0, 0.5)));
+        let a = run(HdrBlend::Alpha);
+        assert_eq!(a.get_pixel(1, 0), Some(LinearRgba::new(0.5, 0.0, 0.5, 1.0)));
+        assert_eq!(a.get_pixel(2, 0), Some(LinearRgba::new(2.0, 2.0, 2.0, 1.0)));
+        let s = run(HdrBlend::Add);
+        assert_eq!(s.get_pixel(1, 0), Some(LinearRgba::new(0.5, 0.0, 1.0, 1.0)));
+        assert_eq!(s.get_pixel(2, 0), Some(LinearRgba::new(2.0, 2.0, 3.0, 1.0)));
+    }
+
+    #[test]
+    fn tone_map_curves() {
+        assert_eq!(ToneMap::Clamp.apply(3.0), 1.0);
+        assert_eq!(ToneMap::Reinhard.apply(1.0), 0.5);
+        assert!((ToneMap::AcesFit.apply(1.0) - 0.8038).abs() < 1e-3);
+        assert_eq!(ToneMap::AcesFit.apply(100.0), 1.0);
+        for t in [ToneMap::Clamp, ToneMap::Reinhard, ToneMap::AcesFit] {
+            assert_eq!(t.apply(-1.0), 0.0);
+            assert!(t.apply(0.5) <= t.apply(0.6));
+        }
+    }
+
+    #[test]
+    fn resolve_roundtrips_8bit_and_applies_exposure() {
+        let mut src = Surface::new(16, 16);
+        for (i, y) in (0..16).enumerate() {
+            for x in 0..16 {
+                src.set_pixel(x, y, Color::rgba((i * 16 + x as usize) as u8, 7, 200, 255));
+            }
+        }
+        let hdr = HdrSurface::from_surface(&src);
+        assert_eq!(hdr.resolve(&ResolveOptions::new()).pixels(), src.pixels());
+
+        let mut s = HdrSurface::new(1, 1);
+        s.set_pixel(0, 0, LinearRgba::new(0.25, 2.0, 0.0, 1.0));
+        let opts = ResolveOptions::new().with_exposure(1.0);
+        let c = Color::from_u32(s.resolve(&opts).pixels()[0]);
+        assert_eq!(c, Color::from_linear(LinearRgba::new(0.5, 1.0, 0.0, 1.0)));
+        let opts = opts.with_tone_map(ToneMap::Reinhard).with_exposure(0.0);
+        let c = Color::from_u32(s.resolve(&opts).pixels()[0]);
+        assert_eq!(
+            c.g,
+            Color::from_linear(LinearRgba::new(2.0 / 3.0, 0.0, 0.0, 1.0)).r
+        );
+    }
+
+    #[test]
+    fn ordered_dither_averages_between_levels() {
+        // A level a quarter of the way between two 8-bit codes
+        let mut s = HdrSurface::new(4, 4);
+        let lin = crate::color::srgb_to_linear(100.25 / 255.0);
+        s.clear(LinearRgba::new(lin, lin, lin, 1.0));
+        let plain = s.resolve(&ResolveOptions::new());
+        assert!(plain.pixels().iter().all(|&p| Color::from_u32(p).r == 100));
+        let dithered = s.resolve(&ResolveOptions::new().with_dither(Dither::Ordered));
+        let reds: Vec<u8> = dithered
+            .pixels()
+            .iter()
+            .map(|&p| Color::from_u32(p).r)
+            .collect();
+        assert_eq!(reds.iter().filter(|&&r| r == 101).count(), 4);
+        assert!(reds.iter().all(|&r| r == 100 || r == 101));
+    }
+}
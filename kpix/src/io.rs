@@ -4,6 +4,7 @@ use std::path::Path;
 use crate::core::Surface;
 
 /// Write the surface as binary PPM (P6). Alpha is ignored.
+/// Other pixel formats can be written after `Surface::to_rgba8`.
 /// Delegates to `kimgfmt` for encoding.
 pub fn write_ppm(surface: &Surface, path: impl AsRef<Path>) -> io::Result<()> {
     kimgfmt::ppm::write_ppm_from_rgba_le(surface.pixels(), surface.width(), surface.height(), path)
@@ -1,5 +1,27 @@
+pub mod blit;
+pub mod color;
 pub mod core;
+pub mod dirty;
+pub mod display;
 pub mod draw;
+pub mod filter;
+pub mod flood;
+pub mod format;
+pub mod hdr;
 pub mod io;
+pub mod paint;
+pub mod path;
+pub mod plot;
+pub mod rect;
+pub mod resample;
+pub mod sprite;
+pub mod term;
+pub mod text;
+pub mod ui;
+pub mod view;
 
 pub use core::{Color, Surface};
+pub use format::{Gray8, Gray16, Indexed, PixelFormat, Rgb565, Rgba8};
+pub use hdr::HdrSurface;
+pub use paint::Paint;
+pub use rect::Rect;
@@ -0,0 +1,447 @@
+//! Paint sources for fills: solid colors, linear, radial and conic gradients, and two-color
+//! bit patterns and hatching for fills that must stay distinguishable in monochrome.
+//!
+//! A paint maps a continuous point to a color; fills sample it at pixel centers
+//! `(x + 0.5, y + 0.5)`. Gradients map the point to a parameter `t`, apply the spread mode,
+//! then interpolate between color stops in premultiplied alpha, either directly on the
+//! sRGB-encoded channels or in linear light.
+
+use std::f32::consts::TAU;
+
+use crate::color::{linear_to_srgb, srgb_to_linear};
+use crate::core::{Color, Surface};
+use crate::draw::aa::with_coverage;
+use crate::format::PixelFormat;
+use crate::path::paint_span as paint_color_span;
+
+/// How `t` outside `[0, 1]` is mapped back onto the stops.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Spread {
+    /// Clamp to the end colors.
+    #[default]
+    Pad,
+    /// Restart from the first stop.
+    Repeat,
+    /// Mirror back and forth.
+    Reflect,
+}
+
+/// Space in which stop colors are blended.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Blend the sRGB-encoded channels directly, like CSS and most 2D APIs.
+    #[default]
+    Srgb,
+    /// Decode to linear light, blend, then re-encode. Brighter, physically plausible midpoints.
+    Linear,
+}
+
+/// A color at parameter `offset` (clamped to `[0, 1]`).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+impl GradientStop {
+    pub fn new(offset: f32, color: Color) -> Self {
+        Self {
+            offset: offset.clamp(0.0, 1.0),
+            color,
+        }
+    }
+}
+
+/// Color stops plus spread and interpolation settings, independent of geometry.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Gradient {
+    stops: Vec<GradientStop>,
+    pub spread: Spread,
+    pub interpolation: Interpolation,
+}
+
+impl Gradient {
+    /// Stops are sorted by offset; stops sharing an offset keep their order and form a hard
+    /// edge. Defaults: `Spread::Pad`, `Interpolation::Srgb`.
+    pub fn new(stops: impl IntoIterator<Item = GradientStop>) -> Self {
+        let mut stops: Vec<GradientStop> = stops.into_iter().collect();
+        stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        Self {
+            stops,
+            ..Self::default()
+        }
+    }
+
+    /// Two-stop gradient from `a` at 0 to `b` at 1.
+    pub fn two(a: Color, b: Color) -> Self {
+        Self::new([GradientStop::new(0.0, a), GradientStop::new(1.0, b)])
+    }
+
+    pub fn with_spread(mut self, spread: Spread) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn stops(&self) -> &[GradientStop] {
+        &self.stops
+    }
+
+    /// Color at parameter `t` after applying the spread mode. Transparent without stops.
+    pub fn color_at(&self, t: f32) -> Color {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(f), Some(l)) => (f, l),
+            _ => return Color::rgba(0, 0, 0, 0),
+        };
+        let t = match self.spread {
+            _ if !t.is_finite() => 0.0,
+            Spread::Pad => t.clamp(0.0, 1.0),
+            Spread::Repeat => t - t.floor(),
+            Spread::Reflect => {
+                let m = t.rem_euclid(2.0);
+                if m > 1.0 { 2.0 - m } else { m }
+            }
+        };
+        if t <= first.offset {
+            return first.color;
+        }
+        if t >= last.offset {
+            return last.color;
+        }
+        // First stop strictly past t; its predecessor is at or before t.
+        let i = self.stops.partition_point(|s| s.offset <= t);
+        let (a, b) = (self.stops[i - 1], self.stops[i]);
+        let f = (t - a.offset) / (b.offset - a.offset);
+        mix(a.color, b.color, f, self.interpolation)
+    }
+}
+
+/// What a fill paints with.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+    Solid(Color),
+    /// `t` runs from 0 at `start` to 1 at `end`, constant along perpendicular lines.
+    Linear {
+        start: (f32, f32),
+        end: (f32, f32),
+        gradient: Gradient,
+    },
+    /// `t` is the distance from `center` divided by `radius`.
+    Radial {
+        center: (f32, f32),
+        radius: f32,
+        gradient: Gradient,
+    },
+    /// `t` sweeps 0..1 clockwise on screen around `center`, starting at `angle` radians
+    /// (measured from +x toward +y).
+    Conic {
+        center: (f32, f32),
+        angle: f32,
+        gradient: Gradient,
+    },
+    /// 8x8 bit tile repeated from the origin: bit 7 of `rows[y % 8]` is column 0. Set bits
+    /// take `fg`, clear bits `bg`; a transparent `bg` leaves those pixels untouched.
+    Pattern {
+        rows: [u8; 8],
+        fg: Color,
+        bg: Color,
+    },
+    /// Parallel lines `width` wide every `spacing` units, running at `angle` radians
+    /// (measured from +x toward +y) with one line's edge through the origin. Lines are `fg`,
+    /// gaps `bg`.
+    Hatch {
+        angle: f32,
+        spacing: f32,
+        width: f32,
+        fg: Color,
+        bg: Color,
+    },
+}
+
+/// Ready-made tiles for `Paint::pattern`.
+pub mod patterns {
+    pub const CHECKER: [u8; 8] = [0xaa, 0x55, 0xaa, 0x55, 0xaa, 0x55, 0xaa, 0x55];
+    /// One pixel in four.
+    pub const DOTS: [u8; 8] = [0x88, 0x00, 0x22, 0x00, 0x88, 0x00, 0x22, 0x00];
+    pub const GRID: [u8; 8] = [0xff, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80];
+    /// Rising diagonal lines, bottom-left to top-right.
+    pub const DIAGONAL: [u8; 8] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80];
+    pub const CROSSHATCH: [u8; 8] = [0x81, 0x42, 0x24, 0x18, 0x18, 0x24, 0x42, 0x81];
+    pub const BRICK: [u8; 8] = [0xff, 0x80, 0x80, 0x80, 0xff, 0x08, 0x08, 0x08];
+}
+
+impl Paint {
+    pub fn solid(color: Color) -> Self {
+        Paint::Solid(color)
+    }
+
+    pub fn linear(x0: f32, y0: f32, x1: f32, y1: f32, gradient: Gradient) -> Self {
+        Paint::Linear {
+            start: (x0, y0),
+            end: (x1, y1),
+            gradient,
+        }
+    }
+
+    pub fn radial(cx: f32, cy: f32, radius: f32, gradient: Gradient) -> Self {
+        Paint::Radial {
+            center: (cx, cy),
+            radius,
+            gradient,
+        }
+    }
+
+    pub fn conic(cx: f32, cy: f32, angle: f32, gradient: Gradient) -> Self {
+        Paint::Conic {
+            center: (cx, cy),
+            angle,
+            gradient,
+        }
+    }
+
+    pub fn pattern(rows: [u8; 8], fg: Color, bg: Color) -> Self {
+        Paint::Pattern { rows, fg, bg }
+    }
+
+    pub fn hatch(angle: f32, spacing: f32, width: f32, fg: Color, bg: Color) -> Self {
+        Paint::Hatch {
+            angle,
+            spacing,
+            width,
+            fg,
+            bg,
+        }
+    }
+
+    /// Color at the continuous point `(x, y)`.
+    pub fn color_at(&self, x: f32, y: f32) -> Color {
+        match self {
+            Paint::Solid(c) => *c,
+            Paint::Linear {
+                start,
+                end,
+                gradient,
+            } => {
+                let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+                let len2 = dx * dx + dy * dy;
+                let t = if len2 > 0.0 {
+                    ((x - start.0) * dx + (y - start.1) * dy) / len2
+                } else {
+                    0.0
+                };
+                gradient.color_at(t)
+            }
+            Paint::Radial {
+                center,
+                radius,
+                gradient,
+            } => {
+                let d = (x - center.0).hypot(y - center.1);
+                let t = if *radius > 0.0 { d / radius } else { 1.0 };
+                gradient.color_at(t)
+            }
+            Paint::Conic {
+                center,
+                angle,
+                gradient,
+            } => {
+                let a = (y - center.1).atan2(x - center.0) - angle;
+                gradient.color_at((a / TAU).rem_euclid(1.0))
+            }
+            Paint::Pattern { rows, fg, bg } => {
+                let (px, py) = (x.floor() as i64, y.floor() as i64);
+                let row = rows[py.rem_euclid(8) as usize];
+                if row & (0x80 >> px.rem_euclid(8)) != 0 {
+                    *fg
+                } else {
+                    *bg
+                }
+            }
+            Paint::Hatch {
+                angle,
+                spacing,
+                width,
+                fg,
+                bg,
+            } => {
+                if *spacing <= 0.0 {
+                    return *fg;
+                }
+                // Distance across the lines; a line starts at every multiple of spacing.
+                let (s, c) = angle.sin_cos();
+                let d = (y * c - x * s).rem_euclid(*spacing);
+                if d < *width { *fg } else { *bg }
+            }
+        }
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Self {
+        Paint::Solid(color)
+    }
+}
+
+/// Paint a run of pixels `[x0, x1)` on row `y` at coverage `alpha` (255 = full), sampling
+/// the paint at each pixel center.
+pub(crate) fn paint_span<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    y: i32,
+    x0: i32,
+    x1: i32,
+    alpha: u8,
+    paint: &Paint,
+) {
+    if let Paint::Solid(c) = paint {
+        paint_color_span(surface, y, x0, x1, alpha, *c);
+        return;
+    }
+    let c = surface.clip_rect();
+    if y < c.y || y >= c.bottom() {
+        return;
+    }
+    let py = y as f32 + 0.5;
+    for x in x0.max(c.x)..x1.min(c.right()) {
+        let color = paint.color_at(x as f32 + 0.5, py);
+        if alpha == 255 && color.a == 255 {
+            surface.set_pixel(x, y, color);
+        } else {
+            surface.blend_pixel(x, y, with_coverage(color, alpha as u32, 255));
+        }
+    }
+}
+
+/// Interpolate two straight-alpha colors at `f` in premultiplied space.
+fn mix(a: Color, b: Color, f: f32, interpolation: Interpolation) -> Color {
+    let decode = |c: Color| {
+        let ch = |v: u8| match interpolation {
+            Interpolation::Srgb => v as f32 / 255.0,
+            Interpolation::Linear => srgb_to_linear(v as f32 / 255.0),
+        };
+        let al = c.a as f32 / 255.0;
+        [ch(c.r) * al, ch(c.g) * al, ch(c.b) * al, al]
+    };
+    let (pa, pb) = (decode(a), decode(b));
+    let p: [f32; 4] = std::array::from_fn(|i| pa[i] + (pb[i] - pa[i]) * f);
+    let al = p[3];
+    if al <= 0.0 {
+        return Color::rgba(0, 0, 0, 0);
+    }
+    let enc = |v: f32| {
+        let v = (v / al).clamp(0.0, 1.0);
+        let v = match interpolation {
+            Interpolation::Srgb => v,
+            Interpolation::Linear => linear_to_srgb(v),
+        };
+        (v * 255.0).round() as u8
+    };
+    Color::rgba(enc(p[0]), enc(p[1]), enc(p[2]), (al * 255.0).round() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Color = Color::rgba(0, 0, 0, 255);
+    const WHITE: Color = Color::rgba(255, 255, 255, 255);
+
+    #[test]
+    fn stops_interpolate_and_pad() {
+        let g = Gradient::new([
+            GradientStop::new(1.0, WHITE),
+            GradientStop::new(0.0, BLACK),
+            GradientStop::new(0.5, Color::rgba(255, 0, 0, 255)),
+        ]);
+        assert_eq!(g.stops()[1].offset, 0.5);
+        assert_eq!(g.color_at(-1.0), BLACK);
+        assert_eq!(g.color_at(0.25), Color::rgba(128, 0, 0, 255));
+        assert_eq!(g.color_at(0.5), Color::rgba(255, 0, 0, 255));
+        assert_eq!(g.color_at(2.0), WHITE);
+        assert_eq!(Gradient::default().color_at(0.5), Color::rgba(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn spread_modes_wrap_t() {
+        let g = Gradient::two(BLACK, WHITE);
+        let at = |s: Spread, t: f32| g.clone().with_spread(s).color_at(t).r;
+        assert_eq!(at(Spread::Pad, 1.25), 255);
+        assert_eq!(at(Spread::Repeat, 1.25), 64);
+        assert_eq!(at(Spread::Repeat, -0.25), 191);
+        assert_eq!(at(Spread::Reflect, 1.25), 191);
+        assert_eq!(at(Spread::Reflect, -0.25), 64);
+    }
+
+    #[test]
+    fn hard_stop_and_linear_light_midpoint() {
+        let red = Color::rgba(255, 0, 0, 255);
+        let g = Gradient::new([
+            GradientStop::new(0.0, BLACK),
+            GradientStop::new(0.5, BLACK),
+            GradientStop::new(0.5, red),
+            GradientStop::new(1.0, red),
+        ]);
+        assert_eq!(g.color_at(0.49), BLACK);
+        assert_eq!(g.color_at(0.5), red);
+
+        let g = Gradient::two(BLACK, WHITE);
+        assert_eq!(g.color_at(0.5).r, 128);
+        // Half the light of white encodes to ~188 in sRGB.
+        let lin = g.with_interpolation(Interpolation::Linear);
+        assert_eq!(lin.color_at(0.5).r, 188);
+        assert_eq!(lin.color_at(1.0), WHITE);
+    }
+
+    #[test]
+    fn interpolation_is_premultiplied() {
+        // Fading red to transparent black keeps the hue instead of darkening.
+        let g = Gradient::two(Color::rgba(255, 0, 0, 255), Color::rgba(0, 0, 0, 0));
+        assert_eq!(g.color_at(0.5), Color::rgba(255, 0, 0, 128));
+    }
+
+    #[test]
+    fn geometry_maps_points_to_t() {
+        let g = Gradient::two(BLACK, WHITE);
+        let lin = Paint::linear(0.0, 0.0, 10.0, 0.0, g.clone());
+        assert_eq!(lin.color_at(5.0, 99.0).r, 128);
+        let rad = Paint::radial(0.0, 0.0, 10.0, g.clone());
+        assert_eq!(rad.color_at(6.0, 8.0), WHITE);
+        assert_eq!(rad.color_at(0.0, 0.0), BLACK);
+        let con = Paint::conic(0.0, 0.0, 0.0, g);
+        // A quarter turn clockwise on screen (+y) is t = 0.25.
+        assert_eq!(con.color_at(0.0, 5.0).r, 64);
+        assert_eq!(con.color_at(-5.0, 0.0).r, 128);
+        assert_eq!(Paint::from(WHITE).color_at(1.0, 2.0), WHITE);
+    }
+
+    #[test]
+    fn patterns_and_hatches_tile_the_plane() {
+        let clear = Color::rgba(0, 0, 0, 0);
+        let checker = Paint::pattern(patterns::CHECKER, WHITE, clear);
+        assert_eq!(checker.color_at(0.5, 0.5), WHITE);
+        assert_eq!(checker.color_at(1.5, 0.5), clear);
+        assert_eq!(checker.color_at(-0.5, 0.5), clear);
+        assert_eq!(checker.color_at(8.5, 9.5), clear);
+
+        let rows = Paint::hatch(0.0, 4.0, 1.0, WHITE, BLACK);
+        let lit: Vec<bool> = (0..8)
+            .map(|y| rows.color_at(3.5, y as f32 + 0.5) == WHITE)
+            .collect();
+        assert_eq!(lit, [true, false, false, false, true, false, false, false]);
+        let cols = Paint::hatch(std::f32::consts::FRAC_PI_2, 3.0, 1.0, WHITE, BLACK);
+        assert_eq!(cols.color_at(2.5, 7.5), WHITE);
+        assert_eq!(cols.color_at(1.5, 7.5), BLACK);
+        assert_eq!(cols.color_at(-0.5, 7.5), WHITE);
+
+        // Every paint fill accepts patterns; clear bits leave the background alone.
+        let mut s = Surface::new(4, 2);
+        s.clear(BLACK);
+        crate::draw::fill_rect_paint(&mut s, 0, 0, 4, 2, &checker);
+        assert_eq!(s.get_pixel(0, 0), Some(WHITE));
+        assert_eq!(s.get_pixel(1, 0), Some(BLACK));
+        assert_eq!(s.get_pixel(1, 1), Some(WHITE));
+    }
+}
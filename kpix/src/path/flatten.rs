@@ -0,0 +1,132 @@
+//! Curve flattening.
+//!
+//! The segment count of each Bézier is derived from its second-derivative bound, so flat curves
+//! get few segments and tight ones many, with the chord error kept under the tolerance.
+
+use kmath::Vec2;
+
+use super::{PathCmd, Polyline};
+
+/// Upper bound on segments per curve, guarding against absurd inputs.
+const MAX_SEGMENTS: usize = 1024;
+
+pub(super) fn flatten(cmds: &[PathCmd], tolerance: f32) -> Vec<Polyline> {
+    let tol = tolerance.max(1e-3);
+    let mut out = Vec::new();
+    let mut cur = Polyline::default();
+    let mut last = Vec2::ZERO;
+    for &cmd in cmds {
+        match cmd {
+            PathCmd::MoveTo(p) => {
+                finish(&mut cur, &mut out);
+                cur.points.push(p);
+                last = p;
+            }
+            PathCmd::LineTo(p) => {
+                cur.points.push(p);
+                last = p;
+            }
+            PathCmd::QuadTo(c, p) => {
+                let dd = (last - c * 2.0 + p).length();
+                let n = segments(dd / 4.0, tol);
+                for i in 1..=n {
+                    let t = i as f32 / n as f32;
+                    let mt = 1.0 - t;
+                    cur.points
+                        .push(last * (mt * mt) + c * (2.0 * mt * t) + p * (t * t));
+                }
+                last = p;
+            }
+            PathCmd::CubicTo(c1, c2, p) => {
+                let dd1 = (last - c1 * 2.0 + c2).length();
+                let dd2 = (c1 - c2 * 2.0 + p).length();
+                let n = segments(dd1.max(dd2) * 0.75, tol);
+                for i in 1..=n {
+                    let t = i as f32 / n as f32;
+                    let mt = 1.0 - t;
+                    cur.points.push(
+                        last * (mt * mt * mt)
+                            + c1 * (3.0 * mt * mt * t)
+                            + c2 * (3.0 * mt * t * t)
+                            + p * (t * t * t),
+                    );
+                }
+                last = p;
+            }
+            PathCmd::Close => {
+                cur.closed = true;
+                let start = cur.points.first().copied().unwrap_or(last);
+                finish(&mut cur, &mut out);
+                last = start;
+            }
+        }
+    }
+    finish(&mut cur, &mut out);
+    out
+}
+
+/// Emit the current subpath unless it is a lone point.
+fn finish(cur: &mut Polyline, out: &mut Vec<Polyline>) {
+    if cur.points.len() > 1 || (cur.closed && !cur.points.is_empty()) {
+        out.push(std::mem::take(cur));
+    } else {
+        *cur = Polyline::default();
+    }
+}
+
+/// Segments needed so that `bound / n^2 <= tol`.
+fn segments(bound: f32, tol: f32) -> usize {
+    let n = (bound / tol).sqrt().ceil();
+    if n.is_finite() {
+        (n as usize).clamp(1, MAX_SEGMENTS)
+    } else {
+        MAX_SEGMENTS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::Path;
+
+    #[test]
+    fn subpaths_split_on_move_and_close() {
+        let mut p = Path::new();
+        p.move_to(0.0, 0.0)
+            .line_to(1.0, 0.0)
+            .line_to(1.0, 1.0)
+            .close();
+        p.move_to(5.0, 5.0).line_to(6.0, 5.0);
+        p.move_to(9.0, 9.0); // lone move is dropped
+        let lines = p.flatten(0.25);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].closed);
+        assert_eq!(lines[0].points.len(), 3);
+        assert!(!lines[1].closed);
+    }
+
+    #[test]
+    fn quad_points_stay_within_tolerance() {
+        let mut p = Path::new();
+        p.move_to(0.0, 0.0).quad_to(50.0, 100.0, 100.0, 0.0);
+        let pts = &p.flatten(0.1)[0].points;
+        // Every chord midpoint must be close to the curve point at the same x (parabola y(x)).
+        let y_at = |x: f32| {
+            let t = x / 100.0;
+            2.0 * (1.0 - t) * t * 100.0
+        };
+        for w in pts.windows(2) {
+            let m = (w[0] + w[1]) * 0.5;
+            assert!((y_at(m.x) - m.y).abs() <= 0.1 + 1e-3);
+        }
+        assert_eq!(*pts.last().unwrap(), Vec2::new(100.0, 0.0));
+    }
+
+    #[test]
+    fn straight_cubic_uses_one_segment() {
+        let mut p = Path::new();
+        p.move_to(0.0, 0.0)
+            .cubic_to(10.0, 0.0, 20.0, 0.0, 30.0, 0.0);
+        assert_eq!(p.flatten(0.25)[0].points.len(), 2);
+    }
+}
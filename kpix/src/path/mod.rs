@@ -0,0 +1,658 @@
+//! Vector paths: build an outline from lines and curves, then fill or stroke it onto a `Surface`.
+//!
+//! Coordinates are continuous pixel space: pixel (x, y) covers [x, x+1) x [y, y+1) and is
+//! sampled at its center (x + 0.5, y + 0.5), like `kraster2d`. Curves are flattened to polylines
+//! within `TOLERANCE` pixels, then filled by an active-edge-table scanline rasterizer.
+
+mod flatten;
+mod raster;
+mod stroke;
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use kmath::Vec2;
+
+use crate::core::{Color, Surface};
+use crate::draw::Dash;
+use crate::format::PixelFormat;
+use crate::paint::{self, Paint};
+
+pub use raster::FillRule;
+pub use stroke::{LineCap, LineJoin, Stroke};
+
+pub(crate) use raster::{paint_span, rasterize};
+
+/// Maximum distance in pixels between a curve and its flattened polyline.
+pub const TOLERANCE: f32 = 0.2;
+
+/// One path command. Control and end points are absolute.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PathCmd {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    QuadTo(Vec2, Vec2),
+    CubicTo(Vec2, Vec2, Vec2),
+    Close,
+}
+
+/// A flattened subpath.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+}
+
+/// Path builder. Methods return `&mut Self` so calls can be chained.
+#[derive(Clone, Debug, Default)]
+pub struct Path {
+    cmds: Vec<PathCmd>,
+    start: Vec2,
+    current: Vec2,
+    open: bool,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recorded commands in order.
+    pub fn commands(&self) -> &[PathCmd] {
+        &self.cmds
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cmds.is_empty()
+    }
+
+    /// Start a new subpath at (x, y).
+    pub fn move_to(&mut self, x: f32, y: f32) -> &mut Self {
+        let p = Vec2::new(x, y);
+        self.cmds.push(PathCmd::MoveTo(p));
+        self.start = p;
+        self.current = p;
+        self.open = true;
+        self
+    }
+
+    /// Straight line to (x, y). On an empty path this acts as `move_to`; after `close` it
+    /// continues from the closed subpath's start, like the curve methods.
+    pub fn line_to(&mut self, x: f32, y: f32) -> &mut Self {
+        if self.cmds.is_empty() {
+            return self.move_to(x, y);
+        }
+        self.ensure_open();
+        let p = Vec2::new(x, y);
+        self.cmds.push(PathCmd::LineTo(p));
+        self.current = p;
+        self
+    }
+
+    /// Quadratic Bézier with control point (cx, cy) ending at (x, y).
+    pub fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) -> &mut Self {
+        self.ensure_open();
+        let p = Vec2::new(x, y);
+        self.cmds.push(PathCmd::QuadTo(Vec2::new(cx, cy), p));
+        self.current = p;
+        self
+    }
+
+    /// Cubic Bézier with control points (c1x, c1y), (c2x, c2y) ending at (x, y).
+    pub fn cubic_to(
+        &mut self,
+        c1x: f32,
+        c1y: f32,
+        c2x: f32,
+        c2y: f32,
+        x: f32,
+        y: f32,
+    ) -> &mut Self {
+        self.ensure_open();
+        let p = Vec2::new(x, y);
+        self.cmds.push(PathCmd::CubicTo(
+            Vec2::new(c1x, c1y),
+            Vec2::new(c2x, c2y),
+            p,
+        ));
+        self.current = p;
+        self
+    }
+
+    /// Tangent arc (HTML canvas `arcTo`): a line toward (x1, y1), then a circular arc of `radius`
+    /// tangent to both the current->(x1,y1) and (x1,y1)->(x2,y2) lines. Degenerate inputs
+    /// (coincident points, collinear lines, zero radius) fall back to `line_to(x1, y1)`.
+    pub fn arc_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, radius: f32) -> &mut Self {
+        self.ensure_open();
+        let p0 = self.current;
+        let p1 = Vec2::new(x1, y1);
+        let p2 = Vec2::new(x2, y2);
+        let (Some(v1), Some(v2)) = ((p0 - p1).try_normalize(), (p2 - p1).try_normalize()) else {
+            return self.line_to(x1, y1);
+        };
+        let cos = v1.dot(v2);
+        if radius <= 0.0 || cos.abs() > 0.9999 {
+            return self.line_to(x1, y1);
+        }
+        let half = cos.acos() * 0.5;
+        let d = radius / half.tan();
+        let t1 = p1 + v1 * d;
+        let t2 = p1 + v2 * d;
+        let center = p1 + (v1 + v2).normalize() * (radius / half.sin());
+        let a0 = (t1.y - center.y).atan2(t1.x - center.x);
+        let a1 = (t2.y - center.y).atan2(t2.x - center.x);
+        let mut sweep = a1 - a0;
+        if sweep > std::f32::consts::PI {
+            sweep -= TAU;
+        } else if sweep < -std::f32::consts::PI {
+            sweep += TAU;
+        }
+        self.line_to(t1.x, t1.y);
+        self.arc_segments(center, radius, a0, sweep);
+        self
+    }
+
+    /// Circular arc around (cx, cy) from angle `start` to `end` (radians, clockwise on screen;
+    /// `end < start` sweeps counter-clockwise). Starts with a line from the current point, or a
+    /// `move_to` when there is no open subpath.
+    pub fn arc(&mut self, cx: f32, cy: f32, radius: f32, start: f32, end: f32) -> &mut Self {
+        let center = Vec2::new(cx, cy);
+        let p = center + Vec2::new(start.cos(), start.sin()) * radius;
+        if self.open {
+            self.line_to(p.x, p.y);
+        } else {
+            self.move_to(p.x, p.y);
+        }
+        let sweep = (end - start).clamp(-TAU, TAU);
+        self.arc_segments(center, radius, start, sweep);
+        self
+    }
+
+    /// Close the current subpath with a line back to its start.
+    pub fn close(&mut self) -> &mut Self {
+        if self.open {
+            self.cmds.push(PathCmd::Close);
+            self.current = self.start;
+            self.open = false;
+        }
+        self
+    }
+
+    /// Flatten curves into polylines whose distance from the true curve is at most `tolerance`.
+    pub fn flatten(&self, tolerance: f32) -> Vec<Polyline> {
+        flatten::flatten(&self.cmds, tolerance)
+    }
+
+    fn ensure_open(&mut self) {
+        if !self.open {
+            let p = self.current;
+            self.move_to(p.x, p.y);
+        }
+    }
+
+    /// Append an arc as cubic segments of at most a quarter turn each.
+    fn arc_segments(&mut self, center: Vec2, r: f32, start: f32, sweep: f32) {
+        let n = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = sweep / n as f32;
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+        let mut a = start;
+        for _ in 0..n {
+            let b = a + step;
+            let (sa, ca) = a.sin_cos();
+            let (sb, cb) = b.sin_cos();
+            let p0 = center + Vec2::new(ca, sa) * r;
+            let p3 = center + Vec2::new(cb, sb) * r;
+            let c1 = p0 + Vec2::new(-sa, ca) * (r * k);
+            let c2 = p3 - Vec2::new(-sb, cb) * (r * k);
+            self.cubic_to(c1.x, c1.y, c2.x, c2.y, p3.x, p3.y);
+            a = b;
+        }
+    }
+}
+
+/// Fill a path (every subpath implicitly closed) with hard edges.
+pub fn fill_path<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    path: &Path,
+    rule: FillRule,
+    color: Color,
+) {
+    let polys = closed_polygons(path);
+    fill_polygons(surface, &polys, rule, false, &Paint::Solid(color));
+}
+
+/// Fill a path with coverage-based anti-aliasing.
+pub fn fill_path_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    path: &Path,
+    rule: FillRule,
+    color: Color,
+) {
+    let polys = closed_polygons(path);
+    fill_polygons(surface, &polys, rule, true, &Paint::Solid(color));
+}
+
+/// Stroke a path with hard edges.
+pub fn stroke_path<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    path: &Path,
+    stroke: &Stroke,
+    color: Color,
+) {
+    let polys = stroke::outline(&path.flatten(TOLERANCE), stroke);
+    fill_polygons(
+        surface,
+        &polys,
+        FillRule::NonZero,
+        false,
+        &Paint::Solid(color),
+    );
+}
+
+/// Stroke a path with coverage-based anti-aliasing.
+pub fn stroke_path_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    path: &Path,
+    stroke: &Stroke,
+    color: Color,
+) {
+    let polys = stroke::outline(&path.flatten(TOLERANCE), stroke);
+    fill_polygons(
+        surface,
+        &polys,
+        FillRule::NonZero,
+        true,
+        &Paint::Solid(color),
+    );
+}
+
+/// Stroke a path with a dash pattern measured along each subpath, hard edges. Each dash
+/// gets the stroke's caps; joins apply within a dash.
+pub fn stroke_path_dashed<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    path: &Path,
+    stroke: &Stroke,
+    dash: &Dash,
+    color: Color,
+) {
+    let lines = dash.split(&path.flatten(TOLERANCE));
+    let polys = stroke::outline(&lines, stroke);
+    fill_polygons(
+        surface,
+        &polys,
+        FillRule::NonZero,
+        false,
+        &Paint::Solid(color),
+    );
+}
+
+/// Anti-aliased `stroke_path_dashed`.
+pub fn stroke_path_dashed_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    path: &Path,
+    stroke: &Stroke,
+    dash: &Dash,
+    color: Color,
+) {
+    let lines = dash.split(&path.flatten(TOLERANCE));
+    let polys = stroke::outline(&lines, stroke);
+    fill_polygons(
+        surface,
+        &polys,
+        FillRule::NonZero,
+        true,
+        &Paint::Solid(color),
+    );
+}
+
+/// Fill a closed polygon given by its vertices.
+pub fn fill_polygon<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    points: &[(f32, f32)],
+    rule: FillRule,
+    color: Color,
+) {
+    let poly: Vec<Vec2> = points.iter().map(|&(x, y)| Vec2::new(x, y)).collect();
+    fill_polygons(surface, &[poly], rule, false, &Paint::Solid(color));
+}
+
+/// Fill a closed polygon with coverage-based anti-aliasing.
+pub fn fill_polygon_aa<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    points: &[(f32, f32)],
+    rule: FillRule,
+    color: Color,
+) {
+    let poly: Vec<Vec2> = points.iter().map(|&(x, y)| Vec2::new(x, y)).collect();
+    fill_polygons(surface, &[poly], rule, true, &Paint::Solid(color));
+}
+
+/// Fill a path with a paint source instead of a single color.
+pub fn fill_path_paint<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    path: &Path,
+    rule: FillRule,
+    paint: &Paint,
+) {
+    fill_polygons(surface, &closed_polygons(path), rule, false, paint);
+}
+
+/// Anti-aliased `fill_path_paint`.
+pub fn fill_path_aa_paint<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    path: &Path,
+    rule: FillRule,
+    paint: &Paint,
+) {
+    fill_polygons(surface, &closed_polygons(path), rule, true, paint);
+}
+
+/// Fill a closed polygon with a paint source.
+pub fn fill_polygon_paint<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    points: &[(f32, f32)],
+    rule: FillRule,
+    paint: &Paint,
+) {
+    let poly: Vec<Vec2> = points.iter().map(|&(x, y)| Vec2::new(x, y)).collect();
+    fill_polygons(surface, &[poly], rule, false, paint);
+}
+
+/// Anti-aliased `fill_polygon_paint`.
+pub fn fill_polygon_aa_paint<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    points: &[(f32, f32)],
+    rule: FillRule,
+    paint: &Paint,
+) {
+    let poly: Vec<Vec2> = points.iter().map(|&(x, y)| Vec2::new(x, y)).collect();
+    fill_polygons(surface, &[poly], rule, true, paint);
+}
+
+fn closed_polygons(path: &Path) -> Vec<Vec<Vec2>> {
+    path.flatten(TOLERANCE)
+        .into_iter()
+        .map(|p| p.points)
+        .collect()
+}
+
+fn fill_polygons<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    polys: &[Vec<Vec2>],
+    rule: FillRule,
+    aa: bool,
+    paint: &Paint,
+) {
+    let c = surface.clip_rect();
+    let bounds = (c.x, c.y, c.right(), c.bottom());
+    rasterize(polys, rule, aa, bounds, |y, x0, x1, alpha| {
+        paint::paint_span(surface, y, x0, x1, alpha, paint);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::fill_rect;
+
+    fn count(s: &Surface, c: Color) -> usize {
+        s.pixels().iter().filter(|&&p| p == c.to_u32()).count()
+    }
+
+    #[test]
+    fn builder_records_commands_and_implicit_move() {
+        let mut p = Path::new();
+        p.line_to(1.0, 2.0)
+            .line_to(3.0, 4.0)
+            .close()
+            .quad_to(0.0, 0.0, 5.0, 5.0);
+        let cmds = p.commands();
+        assert_eq!(cmds[0], PathCmd::MoveTo(Vec2::new(1.0, 2.0)));
+        assert_eq!(cmds[1], PathCmd::LineTo(Vec2::new(3.0, 4.0)));
+        assert_eq!(cmds[2], PathCmd::Close);
+        // a curve after close restarts at the subpath start
+        assert_eq!(cmds[3], PathCmd::MoveTo(Vec2::new(1.0, 2.0)));
+        assert!(matches!(cmds[4], PathCmd::QuadTo(..)));
+    }
+
+    #[test]
+    fn line_after_close_restarts_at_subpath_start() {
+        let mut p = Path::new();
+        p.move_to(1.0, 1.0)
+            .line_to(5.0, 1.0)
+            .line_to(5.0, 5.0)
+            .close()
+            .line_to(9.0, 9.0);
+        assert_eq!(
+            &p.commands()[4..],
+            &[
+                PathCmd::MoveTo(Vec2::new(1.0, 1.0)),
+                PathCmd::LineTo(Vec2::new(9.0, 9.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn axis_aligned_square_matches_fill_rect() {
+        let c = Color::rgba(200, 40, 40, 255);
+        let mut a = Surface::new(12, 12);
+        let mut b = Surface::new(12, 12);
+        let mut p = Path::new();
+        p.move_to(2.0, 3.0)
+            .line_to(9.0, 3.0)
+            .line_to(9.0, 10.0)
+            .line_to(2.0, 10.0)
+            .close();
+        fill_path(&mut a, &p, FillRule::NonZero, c);
+        fill_rect(&mut b, 2, 3, 7, 7, c);
+        assert_eq!(a.pixels(), b.pixels());
+        // AA on integer edges is identical too
+        let mut aa = Surface::new(12, 12);
+        fill_path_aa(&mut aa, &p, FillRule::EvenOdd, c);
+        assert_eq!(aa.pixels(), b.pixels());
+    }
+
+    #[test]
+    fn nonzero_vs_evenodd_on_nested_squares() {
+        let c = Color::rgba(1, 2, 3, 255);
+        let mut p = Path::new();
+        p.move_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .line_to(10.0, 10.0)
+            .line_to(0.0, 10.0)
+            .close();
+        p.move_to(3.0, 3.0)
+            .line_to(7.0, 3.0)
+            .line_to(7.0, 7.0)
+            .line_to(3.0, 7.0)
+            .close();
+        let mut nz = Surface::new(10, 10);
+        let mut eo = Surface::new(10, 10);
+        fill_path(&mut nz, &p, FillRule::NonZero, c);
+        fill_path(&mut eo, &p, FillRule::EvenOdd, c);
+        assert_eq!(count(&nz, c), 100);
+        assert_eq!(count(&eo, c), 100 - 16);
+        assert_eq!(eo.get_pixel(5, 5), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn aa_half_pixel_edge_gives_half_coverage() {
+        let c = Color::rgba(255, 255, 255, 255);
+        let mut s = Surface::new(4, 1);
+        fill_polygon_aa(
+            &mut s,
+            &[(0.0, 0.0), (2.5, 0.0), (2.5, 1.0), (0.0, 1.0)],
+            FillRule::NonZero,
+            c,
+        );
+        assert_eq!(s.get_pixel(1, 0), Some(c));
+        assert_eq!(s.get_pixel(2, 0).map(|p| p.a), Some(128));
+        assert_eq!(s.get_pixel(3, 0), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn circle_from_arcs_has_expected_area() {
+        let c = Color::rgba(9, 9, 9, 255);
+        let mut s = Surface::new(40, 40);
+        let mut p = Path::new();
+        p.arc(20.0, 20.0, 15.0, 0.0, TAU).close();
+        fill_path(&mut s, &p, FillRule::NonZero, c);
+        let area = count(&s, c) as f32;
+        let expect = std::f32::consts::PI * 15.0 * 15.0;
+        assert!((area - expect).abs() / expect < 0.02, "area {area}");
+    }
+
+    #[test]
+    fn flatten_curvature_adapts_segment_count() {
+        let mut flat = Path::new();
+        flat.move_to(0.0, 0.0).quad_to(50.0, 0.5, 100.0, 0.0);
+        let mut bent = Path::new();
+        bent.move_to(0.0, 0.0).quad_to(50.0, 80.0, 100.0, 0.0);
+        let nf = flat.flatten(TOLERANCE)[0].points.len();
+        let nb = bent.flatten(TOLERANCE)[0].points.len();
+        assert!(nf < nb, "{nf} vs {nb}");
+    }
+
+    #[test]
+    fn arc_to_rounds_corner() {
+        let c = Color::rgba(5, 5, 5, 255);
+        let mut sharp = Surface::new(20, 20);
+        let mut round = Surface::new(20, 20);
+        let mut p = Path::new();
+        p.move_to(0.0, 0.0)
+            .line_to(20.0, 0.0)
+            .line_to(20.0, 20.0)
+            .line_to(0.0, 20.0)
+            .close();
+        fill_path(&mut sharp, &p, FillRule::NonZero, c);
+        let mut q = Path::new();
+        q.move_to(0.0, 0.0)
+            .arc_to(20.0, 0.0, 20.0, 20.0, 8.0)
+            .line_to(20.0, 20.0)
+            .line_to(0.0, 20.0)
+            .close();
+        fill_path(&mut round, &q, FillRule::NonZero, c);
+        assert_eq!(sharp.get_pixel(19, 0), Some(c));
+        assert_eq!(round.get_pixel(19, 0), Some(Color::from_u32(0)));
+        assert_eq!(round.get_pixel(10, 0), Some(c));
+        assert_eq!(round.get_pixel(19, 10), Some(c));
+    }
+
+    #[test]
+    fn stroke_horizontal_line_covers_width() {
+        let c = Color::rgba(7, 7, 7, 255);
+        let mut s = Surface::new(20, 10);
+        let mut p = Path::new();
+        p.move_to(2.0, 5.0).line_to(18.0, 5.0);
+        stroke_path(&mut s, &p, &Stroke::new(2.0), c);
+        for x in 2..18 {
+            assert_eq!(s.get_pixel(x, 4), Some(c));
+            assert_eq!(s.get_pixel(x, 5), Some(c));
+        }
+        assert_eq!(s.get_pixel(10, 3), Some(Color::from_u32(0)));
+        assert_eq!(s.get_pixel(1, 4), Some(Color::from_u32(0)));
+        assert_eq!(count(&s, c), 32);
+
+        let mut sq = Surface::new(20, 10);
+        stroke_path(&mut sq, &p, &Stroke::new(2.0).with_cap(LineCap::Square), c);
+        assert_eq!(sq.get_pixel(1, 4), Some(c));
+        assert_eq!(sq.get_pixel(18, 5), Some(c));
+    }
+
+    #[test]
+    fn stroke_joins_miter_and_bevel_differ_at_corner() {
+        let c = Color::rgba(7, 7, 7, 255);
+        let mut p = Path::new();
+        p.move_to(2.0, 10.0).line_to(10.0, 10.0).line_to(10.0, 18.0);
+        let mut miter = Surface::new(20, 20);
+        let mut bevel = Surface::new(20, 20);
+        let mut round = Surface::new(20, 20);
+        stroke_path(&mut miter, &p, &Stroke::new(4.0), c);
+        stroke_path(
+            &mut bevel,
+            &p,
+            &Stroke::new(4.0).with_join(LineJoin::Bevel),
+            c,
+        );
+        stroke_path(
+            &mut round,
+            &p,
+            &Stroke::new(4.0).with_join(LineJoin::Round),
+            c,
+        );
+        // outer corner of the turn is top-right of (10,10)
+        assert_eq!(miter.get_pixel(11, 8), Some(c));
+        assert_eq!(bevel.get_pixel(11, 8), Some(Color::from_u32(0)));
+        assert!(count(&bevel, c) < count(&round, c));
+        assert!(count(&round, c) < count(&miter, c));
+    }
+
+    #[test]
+    fn closed_stroke_has_no_gap_and_aa_does_not_panic() {
+        let c = Color::rgba(7, 7, 7, 255);
+        let mut p = Path::new();
+        p.move_to(4.0, 4.0)
+            .line_to(16.0, 4.0)
+            .line_to(16.0, 16.0)
+            .line_to(4.0, 16.0)
+            .close();
+        let mut s = Surface::new(20, 20);
+        stroke_path(&mut s, &p, &Stroke::new(2.0), c);
+        for (x, y) in [(3, 3), (16, 3), (16, 16), (3, 16)] {
+            assert_eq!(s.get_pixel(x, y), Some(c), "corner ({x},{y})");
+        }
+        assert_eq!(s.get_pixel(10, 10), Some(Color::from_u32(0)));
+        stroke_path_aa(&mut s, &p, &Stroke::new(3.5), c);
+        fill_path_aa(&mut s, &p, FillRule::NonZero, c);
+    }
+
+    #[test]
+    fn fills_stay_inside_clip_rect() {
+        let mut s = Surface::new(10, 10);
+        let c = Color::rgba(200, 100, 50, 255);
+        s.push_clip(crate::Rect::new(3, 3, 4, 4));
+        fill_polygon_aa(
+            &mut s,
+            &[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)],
+            FillRule::NonZero,
+            c,
+        );
+        assert_eq!(count(&s, c), 16);
+        assert_eq!(s.get_pixel(2, 5), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn paint_fill_uses_gradient_inside_clip() {
+        use crate::paint::Gradient;
+        let (red, blue) = (Color::rgba(255, 0, 0, 255), Color::rgba(0, 0, 255, 255));
+        let paint = Paint::radial(5.0, 5.0, 5.0, Gradient::two(red, blue));
+        let mut s = Surface::new(10, 10);
+        s.push_clip(crate::Rect::new(0, 0, 10, 5));
+        let square = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        fill_polygon_paint(&mut s, &square, FillRule::NonZero, &paint);
+        assert_eq!(s.get_pixel(0, 0), Some(blue));
+        assert_eq!(s.get_pixel(4, 4), Some(paint.color_at(4.5, 4.5)));
+        assert!(s.get_pixel(4, 4).unwrap().r > 200);
+        assert_eq!(s.get_pixel(4, 5), Some(Color::from_u32(0)));
+    }
+
+    #[test]
+    fn dashed_stroke_leaves_gaps() {
+        let c = Color::rgba(255, 255, 255, 255);
+        let mut p = Path::new();
+        p.move_to(0.0, 2.0).line_to(20.0, 2.0);
+        let stroke = Stroke::new(2.0);
+        let mut s = Surface::new(20, 4);
+        stroke_path_dashed(&mut s, &p, &stroke, &Dash::new(&[4.0, 2.0]), c);
+        let row: Vec<bool> = (0..20).map(|x| s.get_pixel(x, 1) == Some(c)).collect();
+        let expect: Vec<bool> = (0..20).map(|x| x % 6 < 4).collect();
+        assert_eq!(row, expect);
+        assert_eq!(count(&s, c), 2 * expect.iter().filter(|&&on| on).count());
+
+        // A solid dash is a plain stroke.
+        let mut a = Surface::new(20, 4);
+        let mut b = Surface::new(20, 4);
+        stroke_path_dashed_aa(&mut a, &p, &stroke, &Dash::default(), c);
+        stroke_path_aa(&mut b, &p, &stroke, c);
+        assert_eq!(a.pixels(), b.pixels());
+    }
+}
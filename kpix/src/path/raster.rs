@@ -0,0 +1,297 @@
+//! Active-edge-table scanline rasterizer for closed polygons.
+//!
+//! Hard-edged fills sample each row once at the pixel center. Anti-aliased fills sample
+//! `SUBSAMPLES` sub-rows per pixel row and accumulate exact horizontal coverage of every span,
+//! so the result is a per-pixel coverage value rather than a binary mask.
+
+use kmath::Vec2;
+
+use crate::core::{Color, Surface};
+use crate::draw::aa::with_coverage;
+use crate::format::PixelFormat;
+
+/// Sub-scanlines per pixel row for anti-aliasing.
+const SUBSAMPLES: usize = 16;
+
+/// How overlapping or self-intersecting contours decide what is inside.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside where the winding number is non-zero.
+    #[default]
+    NonZero,
+    /// Inside where an odd number of edges is crossed.
+    EvenOdd,
+}
+
+impl FillRule {
+    fn inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding & 1 != 0,
+        }
+    }
+}
+
+/// Non-horizontal polygon edge with `y0 < y1`; `dir` is +1 when the original edge went down.
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    x0: f32,
+    y0: f32,
+    y1: f32,
+    dxdy: f32,
+    dir: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f32) -> f32 {
+        self.x0 + (y - self.y0) * self.dxdy
+    }
+}
+
+/// Edge list sorted by top y, walked top to bottom.
+struct EdgeTable {
+    edges: Vec<Edge>,
+    next: usize,
+    active: Vec<usize>,
+    crossings: Vec<(f32, i32)>,
+}
+
+impl EdgeTable {
+    fn new(polys: &[Vec<Vec2>]) -> Self {
+        let mut edges = Vec::new();
+        for poly in polys {
+            let n = poly.len();
+            if n < 2 {
+                continue;
+            }
+            for i in 0..n {
+                let (a, b) = (poly[i], poly[(i + 1) % n]);
+                if a.y == b.y || !(a.y.is_finite() && b.y.is_finite()) {
+                    continue;
+                }
+                let (top, bot, dir) = if a.y < b.y { (a, b, 1) } else { (b, a, -1) };
+                edges.push(Edge {
+                    x0: top.x,
+                    y0: top.y,
+                    y1: bot.y,
+                    dxdy: (bot.x - top.x) / (bot.y - top.y),
+                    dir,
+                });
+            }
+        }
+        edges.sort_by(|a, b| a.y0.total_cmp(&b.y0));
+        Self {
+            edges,
+            next: 0,
+            active: Vec::new(),
+            crossings: Vec::new(),
+        }
+    }
+
+    fn y_range(&self) -> Option<(f32, f32)> {
+        let first = self.edges.first()?;
+        let max = self.edges.iter().map(|e| e.y1).fold(f32::MIN, f32::max);
+        Some((first.y0, max))
+    }
+
+    /// Inside spans [xa, xb) along the horizontal line at `y`. Calls must use increasing `y`.
+    fn spans_at(&mut self, y: f32, rule: FillRule, mut span: impl FnMut(f32, f32)) {
+        while self.next < self.edges.len() && self.edges[self.next].y0 <= y {
+            self.active.push(self.next);
+            self.next += 1;
+        }
+        let edges = &self.edges;
+        self.active.retain(|&i| edges[i].y1 > y);
+        self.crossings.clear();
+        for &i in &self.active {
+            let e = &edges[i];
+            self.crossings.push((e.x_at(y), e.dir));
+        }
+        self.crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut winding = 0;
+        let mut start = 0.0;
+        for &(x, dir) in &self.crossings {
+            let was = rule.inside(winding);
+            winding += dir;
+            let now = rule.inside(winding);
+            if !was && now {
+                start = x;
+            } else if was && !now {
+                span(start, x);
+            }
+        }
+    }
+}
+
+/// Rasterize closed polygons within the half-open pixel bounds `(x0, y0, x1, y1)`.
+/// `emit(y, xa, xb, alpha)` receives runs of pixels [xa, xb) with equal coverage (1..=255).
+pub(crate) fn rasterize(
+    polys: &[Vec<Vec2>],
+    rule: FillRule,
+    aa: bool,
+    bounds: (i32, i32, i32, i32),
+    mut emit: impl FnMut(i32, i32, i32, u8),
+) {
+    let (bx0, by0, bx1, by1) = bounds;
+    if bx0 >= bx1 || by0 >= by1 {
+        return;
+    }
+    let mut table = EdgeTable::new(polys);
+    let Some((ymin, ymax)) = table.y_range() else {
+        return;
+    };
+    let row0 = (ymin.floor() as i32).max(by0);
+    let row1 = (ymax.ceil() as i32).min(by1);
+    if !aa {
+        for y in row0..row1 {
+            table.spans_at(y as f32 + 0.5, rule, |xa, xb| {
+                let xa = ((xa - 0.5).ceil() as i32).max(bx0);
+                let xb = ((xb - 0.5).ceil() as i32).min(bx1);
+                if xa < xb {
+                    emit(y, xa, xb, 255);
+                }
+            });
+        }
+        return;
+    }
+
+    let width = (bx1 - bx0) as usize;
+    let mut frac = vec![0.0f32; width + 1];
+    let mut delta = vec![0.0f32; width + 1];
+    let w = 1.0 / SUBSAMPLES as f32;
+    let (fx0, fx1) = (bx0 as f32, bx1 as f32);
+    for y in row0..row1 {
+        let (mut lo, mut hi) = (usize::MAX, 0usize);
+        for s in 0..SUBSAMPLES {
+            let sy = y as f32 + (s as f32 + 0.5) * w;
+            table.spans_at(sy, rule, |xa, xb| {
+                let (xa, xb) = (xa.max(fx0), xb.min(fx1));
+                if xa >= xb {
+                    return;
+                }
+                let ia = (xa.floor() as i32 - bx0) as usize;
+                let ib = (xb.floor() as i32 - bx0) as usize;
+                lo = lo.min(ia);
+                hi = hi.max(ib);
+                if ia == ib {
+                    frac[ia] += (xb - xa) * w;
+                } else {
+                    frac[ia] += ((ia + 1) as f32 + fx0 - xa) * w;
+                    delta[ia + 1] += w;
+                    delta[ib] -= w;
+                    frac[ib] += (xb - (ib as f32 + fx0)) * w;
+                }
+            });
+        }
+        if lo > hi {
+            continue;
+        }
+        let mut run = 0.0f32;
+        let mut start = 0usize;
+        let mut cur = 0u8;
+        for i in lo..=hi.min(width - 1) {
+            run += delta[i];
+            let a = ((frac[i] + run) * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+            if a != cur {
+                if cur > 0 {
+                    emit(y, bx0 + start as i32, bx0 + i as i32, cur);
+                }
+                start = i;
+                cur = a;
+            }
+        }
+        let end = hi.min(width - 1) + 1;
+        if cur > 0 {
+            emit(y, bx0 + start as i32, bx0 + end as i32, cur);
+        }
+        frac[lo..=hi].fill(0.0);
+        delta[lo..=hi].fill(0.0);
+    }
+}
+
+/// Paint a run of pixels with `color` at coverage `alpha` (255 = full).
+pub(crate) fn paint_span<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    y: i32,
+    x0: i32,
+    x1: i32,
+    alpha: u8,
+    color: Color,
+) {
+    if alpha == 255 && color.a == 255 {
+        surface.fill_span(x0, x1, y, color);
+        return;
+    }
+    let c = with_coverage(color, alpha as u32, 255);
+    for x in x0..x1 {
+        surface.blend_pixel(x, y, c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x0: f32, y0: f32, x1: f32, y1: f32) -> Vec<Vec2> {
+        vec![
+            Vec2::new(x0, y0),
+            Vec2::new(x1, y0),
+            Vec2::new(x1, y1),
+            Vec2::new(x0, y1),
+        ]
+    }
+
+    fn collect(polys: &[Vec<Vec2>], rule: FillRule, aa: bool) -> Vec<(i32, i32, i32, u8)> {
+        let mut out = Vec::new();
+        rasterize(polys, rule, aa, (0, 0, 8, 8), |y, a, b, c| {
+            out.push((y, a, b, c))
+        });
+        out
+    }
+
+    #[test]
+    fn hard_square_emits_full_rows() {
+        let spans = collect(&[square(1.0, 2.0, 4.0, 4.0)], FillRule::NonZero, false);
+        assert_eq!(spans, vec![(2, 1, 4, 255), (3, 1, 4, 255)]);
+    }
+
+    #[test]
+    fn clipped_to_bounds() {
+        let spans = collect(&[square(-5.0, -5.0, 20.0, 1.0)], FillRule::NonZero, true);
+        assert_eq!(spans, vec![(0, 0, 8, 255)]);
+    }
+
+    #[test]
+    fn aa_quarter_pixel_corner() {
+        let spans = collect(&[square(1.5, 1.5, 3.0, 3.0)], FillRule::NonZero, true);
+        // row 1: pixel 1 quarter, pixel 2 half; row 2: pixel 1 half, pixel 2 full
+        assert_eq!(
+            spans,
+            vec![
+                (1, 1, 2, 64),
+                (1, 2, 3, 128),
+                (2, 1, 2, 128),
+                (2, 2, 3, 255)
+            ]
+        );
+    }
+
+    #[test]
+    fn opposite_windings_cancel_under_nonzero() {
+        let outer = square(0.0, 0.0, 6.0, 6.0);
+        let mut inner = square(2.0, 2.0, 4.0, 4.0);
+        inner.reverse();
+        let spans = collect(&[outer, inner], FillRule::NonZero, false);
+        assert!(spans.contains(&(2, 0, 2, 255)));
+        assert!(spans.contains(&(2, 4, 6, 255)));
+    }
+
+    #[test]
+    fn paint_span_blends_partial() {
+        let mut s = Surface::new(3, 1);
+        s.clear(Color::rgba(0, 0, 0, 255));
+        paint_span(&mut s, 0, 0, 2, 128, Color::rgba(255, 255, 255, 255));
+        assert_eq!(s.get_pixel(0, 0), Some(Color::rgba(128, 128, 128, 255)));
+        assert_eq!(s.get_pixel(2, 0), Some(Color::rgba(0, 0, 0, 255)));
+    }
+}
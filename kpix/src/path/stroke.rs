@@ -0,0 +1,286 @@
+//! Stroking: turn flattened polylines into filled outline polygons.
+//!
+//! Each segment becomes a quad, and joins and caps add small polygons on top. Every polygon is
+//! emitted with the same orientation, so filling them together under `FillRule::NonZero`
+//! produces their union.
+
+use std::f32::consts::TAU;
+
+use kmath::Vec2;
+
+use super::{Polyline, TOLERANCE};
+
+/// Shape of open subpath ends.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LineCap {
+    /// End flush with the endpoint.
+    #[default]
+    Butt,
+    /// Half-disc around the endpoint.
+    Round,
+    /// Extend by half the width.
+    Square,
+}
+
+/// Shape of corners between segments.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LineJoin {
+    /// Sharp corner, falling back to bevel beyond `miter_limit`.
+    #[default]
+    Miter,
+    /// Circular corner.
+    Round,
+    /// Corner cut straight across.
+    Bevel,
+}
+
+/// Stroke style. Defaults: width 1, butt caps, miter joins with limit 4.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Stroke {
+    pub width: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// Maximum ratio of miter length to stroke width.
+    pub miter_limit: f32,
+}
+
+impl Stroke {
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 4.0,
+        }
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+/// Outline polygons for stroking `lines` with `stroke`.
+pub(super) fn outline(lines: &[Polyline], stroke: &Stroke) -> Vec<Vec<Vec2>> {
+    let mut out = Vec::new();
+    let hw = stroke.width * 0.5;
+    if hw <= 0.0 || !hw.is_finite() {
+        return out;
+    }
+    for line in lines {
+        outline_one(line, stroke, hw, &mut out);
+    }
+    out
+}
+
+fn outline_one(line: &Polyline, stroke: &Stroke, hw: f32, out: &mut Vec<Vec<Vec2>>) {
+    let mut pts: Vec<Vec2> = Vec::with_capacity(line.points.len());
+    for &p in &line.points {
+        if pts
+            .last()
+            .is_none_or(|&q: &Vec2| q.distance_squared(p) > 1e-12)
+        {
+            pts.push(p);
+        }
+    }
+    let closed = line.closed && pts.len() > 2;
+    if closed && pts[0].distance_squared(pts[pts.len() - 1]) <= 1e-12 {
+        pts.pop();
+    }
+    if pts.len() == 1 {
+        // Zero-length subpath: only round and square caps leave a mark.
+        let p = pts[0];
+        match stroke.cap {
+            LineCap::Butt => {}
+            LineCap::Round => push(out, disc(p, hw)),
+            LineCap::Square => push(out, quad(p, Vec2::new(1.0, 0.0), -hw, hw, hw)),
+        }
+        return;
+    }
+    if pts.len() < 2 {
+        return;
+    }
+
+    let n = pts.len();
+    let seg_count = if closed { n } else { n - 1 };
+    for i in 0..seg_count {
+        let (a, b) = (pts[i], pts[(i + 1) % n]);
+        let len = a.distance(b);
+        let d = (b - a) / len;
+        let square = stroke.cap == LineCap::Square && !closed;
+        let ext0 = if square && i == 0 { hw } else { 0.0 };
+        let ext1 = if square && i == seg_count - 1 {
+            hw
+        } else {
+            0.0
+        };
+        push(out, quad(a, d, -ext0, len + ext1, hw));
+    }
+
+    let joins: Vec<usize> = if closed {
+        (0..n).collect()
+    } else {
+        (1..n - 1).collect()
+    };
+    for i in joins {
+        let prev = pts[(i + n - 1) % n];
+        let v = pts[i];
+        let next = pts[(i + 1) % n];
+        join(out, prev, v, next, stroke, hw);
+    }
+
+    if !closed && stroke.cap == LineCap::Round {
+        push(out, disc(pts[0], hw));
+        push(out, disc(pts[n - 1], hw));
+    }
+}
+
+fn join(out: &mut Vec<Vec<Vec2>>, prev: Vec2, v: Vec2, next: Vec2, stroke: &Stroke, hw: f32) {
+    let d0 = (v - prev).normalize_or_zero();
+    let d1 = (next - v).normalize_or_zero();
+    let cross = d0.x * d1.y - d0.y * d1.x;
+    if cross.abs() < 1e-6 && d0.dot(d1) > 0.0 {
+        return; // straight continuation
+    }
+    if stroke.join == LineJoin::Round {
+        push(out, disc(v, hw));
+        return;
+    }
+    // Offsets on the outer side of the turn.
+    let side = if cross > 0.0 { -hw } else { hw };
+    let o0 = v + normal(d0) * side;
+    let o1 = v + normal(d1) * side;
+    let cos = d0.dot(d1);
+    let half_cos = ((1.0 + cos) * 0.5).max(0.0).sqrt();
+    if stroke.join == LineJoin::Miter && half_cos > 1e-6 && 1.0 / half_cos <= stroke.miter_limit {
+        let m = v + (normal(d0) + normal(d1)) * (side / (1.0 + cos));
+        push(out, vec![v, o0, m, o1]);
+    } else {
+        push(out, vec![v, o0, o1]);
+    }
+}
+
+fn normal(d: Vec2) -> Vec2 {
+    Vec2::new(-d.y, d.x)
+}
+
+/// Rectangle along direction `d` from `p + d*t0` to `p + d*t1`, `hw` to each side.
+fn quad(p: Vec2, d: Vec2, t0: f32, t1: f32, hw: f32) -> Vec<Vec2> {
+    let n = normal(d) * hw;
+    let a = p + d * t0;
+    let b = p + d * t1;
+    vec![a + n, b + n, b - n, a - n]
+}
+
+fn disc(c: Vec2, r: f32) -> Vec<Vec2> {
+    let step = if r > TOLERANCE {
+        2.0 * (1.0 - TOLERANCE / r).acos()
+    } else {
+        TAU / 8.0
+    };
+    let n = ((TAU / step).ceil() as usize).clamp(8, 256);
+    (0..n)
+        .map(|i| {
+            let a = i as f32 * TAU / n as f32;
+            c + Vec2::new(a.cos(), a.sin()) * r
+        })
+        .collect()
+}
+
+/// Append `poly` with a consistent (positive signed area) orientation.
+fn push(out: &mut Vec<Vec<Vec2>>, mut poly: Vec<Vec2>) {
+    let n = poly.len();
+    let area: f32 = (0..n)
+        .map(|i| {
+            let (a, b) = (poly[i], poly[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum();
+    if area < 0.0 {
+        poly.reverse();
+    }
+    if area != 0.0 {
+        out.push(poly);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(points: &[(f32, f32)], closed: bool) -> Polyline {
+        Polyline {
+            points: points.iter().map(|&(x, y)| Vec2::new(x, y)).collect(),
+            closed,
+        }
+    }
+
+    fn signed_area(poly: &[Vec2]) -> f32 {
+        let n = poly.len();
+        (0..n)
+            .map(|i| poly[i].x * poly[(i + 1) % n].y - poly[(i + 1) % n].x * poly[i].y)
+            .sum::<f32>()
+            * 0.5
+    }
+
+    #[test]
+    fn all_polygons_share_orientation() {
+        let l = line(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 3.0)], false);
+        for join in [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+            let polys = outline(std::slice::from_ref(&l), &Stroke::new(3.0).with_join(join));
+            assert!(polys.iter().all(|p| signed_area(p) > 0.0));
+        }
+    }
+
+    #[test]
+    fn segment_quad_has_stroke_area() {
+        let polys = outline(
+            &[line(&[(0.0, 0.0), (10.0, 0.0)], false)],
+            &Stroke::new(2.0),
+        );
+        assert_eq!(polys.len(), 1);
+        assert!((signed_area(&polys[0]) - 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn miter_limit_falls_back_to_bevel() {
+        // Very sharp turn: miter would be long
+        let l = line(&[(0.0, 0.0), (10.0, 0.0), (0.0, 1.0)], false);
+        let sharp = outline(std::slice::from_ref(&l), &Stroke::new(2.0));
+        let long = outline(&[l], &Stroke::new(2.0).with_miter_limit(100.0));
+        assert_eq!(sharp[2].len(), 3);
+        assert_eq!(long[2].len(), 4);
+    }
+
+    #[test]
+    fn zero_length_subpath_caps() {
+        let dot = line(&[(5.0, 5.0), (5.0, 5.0)], false);
+        assert!(outline(std::slice::from_ref(&dot), &Stroke::new(2.0)).is_empty());
+        assert_eq!(
+            outline(
+                std::slice::from_ref(&dot),
+                &Stroke::new(2.0).with_cap(LineCap::Round)
+            )
+            .len(),
+            1
+        );
+        let sq = outline(&[dot], &Stroke::new(2.0).with_cap(LineCap::Square));
+        assert!((signed_area(&sq[0]) - 4.0).abs() < 1e-4);
+    }
+}
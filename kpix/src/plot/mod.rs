@@ -0,0 +1,648 @@
+//! Charts: line, scatter, bar and histogram series on shared axes, drawn onto a `Surface`.
+//!
+//! A `Chart` collects series and labels; `draw` lays it out inside a rect with the title on
+//! top, the y-axis label above the plot area, tick labels left of and below it, the x-axis
+//! label at the bottom and a legend in the plot area's top-right corner. Axis ranges come
+//! from the data (bars and histograms always include 0), widened outward to nice tick
+//! values, unless fixed with `with_x_range`/`with_y_range`. Bar series put the x axis into
+//! category mode: one slot per index, labeled from `with_categories`. Text uses the built-in
+//! 8x8 font.
+
+mod ticks;
+
+pub use ticks::{Ticks, nice_number};
+
+use crate::core::{Color, Surface};
+use crate::draw::{self, Dash};
+use crate::format::PixelFormat;
+use crate::paint::Paint;
+use crate::path::{self, FillRule, LineJoin, Path, Stroke};
+use crate::rect::Rect;
+use crate::text;
+
+/// How a series is drawn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SeriesKind {
+    /// Points joined in order by an anti-aliased polyline.
+    Line,
+    /// A dot at each point.
+    Scatter,
+    /// Points are `(category index, value)`. Bar series share each category slot side by
+    /// side, in the order they were added.
+    Bar,
+    /// Adjacent bins: points are `(bin start, count)`, every bin `bin_width` wide.
+    Histogram { bin_width: f64 },
+}
+
+/// One named data series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub name: String,
+    pub kind: SeriesKind,
+    pub color: Color,
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Colors and sizes of the chart furniture.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ChartStyle {
+    pub background: Color,
+    pub text: Color,
+    pub axis: Color,
+    pub grid: Color,
+    pub line_width: f32,
+    pub point_radius: f32,
+}
+
+impl ChartStyle {
+    /// Dark text and axes on white.
+    pub fn light() -> Self {
+        Self {
+            background: Color::rgba(255, 255, 255, 255),
+            text: Color::rgba(30, 30, 36, 255),
+            axis: Color::rgba(60, 60, 70, 255),
+            grid: Color::rgba(200, 202, 210, 255),
+            line_width: 1.5,
+            point_radius: 2.5,
+        }
+    }
+
+    /// Light text and axes on near-black.
+    pub fn dark() -> Self {
+        Self {
+            background: Color::rgba(22, 24, 30, 255),
+            text: Color::rgba(226, 228, 236, 255),
+            axis: Color::rgba(170, 174, 188, 255),
+            grid: Color::rgba(60, 64, 78, 255),
+            ..Self::light()
+        }
+    }
+}
+
+impl Default for ChartStyle {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+/// Series plus titles, ranges and style. Series methods return `&mut Self` so calls can be
+/// chained.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chart {
+    pub title: String,
+    pub x_label: String,
+    pub y_label: String,
+    /// Fixed axis ranges; `None` fits the data.
+    pub x_range: Option<(f64, f64)>,
+    pub y_range: Option<(f64, f64)>,
+    /// Tick labels for category slots; missing ones show the index.
+    pub categories: Vec<String>,
+    pub legend: bool,
+    pub style: ChartStyle,
+    series: Vec<Series>,
+}
+
+impl Default for Chart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One axis after fitting: data range and labeled ticks inside it.
+#[derive(Clone, Debug, PartialEq)]
+struct Axis {
+    lo: f64,
+    hi: f64,
+    ticks: Vec<(f64, String)>,
+}
+
+impl Axis {
+    /// Fraction of the way from `lo` to `hi`.
+    fn frac(&self, v: f64) -> f64 {
+        (v - self.lo) / (self.hi - self.lo)
+    }
+}
+
+impl Chart {
+    /// Empty chart with the light style and a legend.
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            x_label: String::new(),
+            y_label: String::new(),
+            x_range: None,
+            y_range: None,
+            categories: Vec::new(),
+            legend: true,
+            style: ChartStyle::light(),
+            series: Vec::new(),
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn with_x_label(mut self, label: &str) -> Self {
+        self.x_label = label.to_string();
+        self
+    }
+
+    pub fn with_y_label(mut self, label: &str) -> Self {
+        self.y_label = label.to_string();
+        self
+    }
+
+    pub fn with_x_range(mut self, lo: f64, hi: f64) -> Self {
+        self.x_range = Some((lo, hi));
+        self
+    }
+
+    pub fn with_y_range(mut self, lo: f64, hi: f64) -> Self {
+        self.y_range = Some((lo, hi));
+        self
+    }
+
+    pub fn with_categories(mut self, categories: &[&str]) -> Self {
+        self.categories = categories.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub fn with_legend(mut self, legend: bool) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    pub fn with_style(mut self, style: ChartStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn series(&self) -> &[Series] {
+        &self.series
+    }
+
+    /// Add a series. An empty name keeps it out of the legend.
+    pub fn push(&mut self, series: Series) -> &mut Self {
+        self.series.push(series);
+        self
+    }
+
+    pub fn line(&mut self, name: &str, points: &[(f64, f64)], color: Color) -> &mut Self {
+        self.push(series(name, SeriesKind::Line, color, points.to_vec()))
+    }
+
+    pub fn scatter(&mut self, name: &str, points: &[(f64, f64)], color: Color) -> &mut Self {
+        self.push(series(name, SeriesKind::Scatter, color, points.to_vec()))
+    }
+
+    /// One bar per value, at category slots 0, 1, 2...
+    pub fn bars(&mut self, name: &str, values: &[f64], color: Color) -> &mut Self {
+        let points = values.iter().enumerate().map(|(i, &v)| (i as f64, v));
+        self.push(series(name, SeriesKind::Bar, color, points.collect()))
+    }
+
+    /// Count `samples` into `bins` equal bins spanning their range. Non-finite samples are
+    /// skipped; if all samples are equal, one bin 1 wide is centered on them.
+    pub fn histogram(
+        &mut self,
+        name: &str,
+        samples: &[f64],
+        bins: usize,
+        color: Color,
+    ) -> &mut Self {
+        let finite: Vec<f64> = samples.iter().copied().filter(|v| v.is_finite()).collect();
+        let lo = finite.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = finite.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let (start, bin_width, bins) = if lo < hi {
+            (lo, (hi - lo) / bins.max(1) as f64, bins.max(1))
+        } else if lo == hi {
+            (lo - 0.5, 1.0, 1)
+        } else {
+            (0.0, 1.0, 0)
+        };
+        let mut counts = vec![0usize; bins];
+        for &v in &finite {
+            let i = ((v - start) / bin_width).floor() as usize;
+            counts[i.min(bins - 1)] += 1;
+        }
+        let points = counts
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (start + i as f64 * bin_width, c as f64))
+            .collect();
+        self.push(series(
+            name,
+            SeriesKind::Histogram { bin_width },
+            color,
+            points,
+        ))
+    }
+
+    /// Draw the whole chart inside `rect`, filling it with the background first.
+    pub fn draw<P: PixelFormat>(&self, surface: &mut Surface<P>, rect: Rect) {
+        let st = self.style;
+        surface.push_clip(rect);
+        draw::fill_rect(surface, rect.x, rect.y, rect.w, rect.h, st.background);
+
+        let (pad, line) = (6, 8);
+        let mut top = rect.y + pad;
+        if !self.title.is_empty() {
+            let (w, _) = text::measure_text(&self.title);
+            text::draw_text(
+                surface,
+                rect.x + (rect.w - w) / 2,
+                top,
+                &self.title,
+                st.text,
+            );
+            top += line + pad;
+        }
+        if !self.y_label.is_empty() {
+            text::draw_text(surface, rect.x + pad, top, &self.y_label, st.text);
+            top += line + 2;
+        }
+        // Half a label of headroom for the top tick label.
+        top += line / 2;
+        let mut bottom = rect.bottom() - pad;
+        let x_label_y = bottom - line;
+        if !self.x_label.is_empty() {
+            bottom = x_label_y - 4;
+        }
+        bottom -= line + 6;
+
+        let y = self.y_axis(((bottom - top) / 40).clamp(2, 10) as usize);
+        let label_w = y.ticks.iter().map(|t| text::measure_text(&t.1).0).max();
+        let left = rect.x + pad + label_w.unwrap_or(0) + 6;
+        let right = rect.right() - pad - line;
+        let area = Rect::from_min_max(left, top, right, bottom);
+        if area.w < 2 || area.h < 2 {
+            surface.pop_clip();
+            return;
+        }
+        let x = self.x_axis((area.w / 60).clamp(2, 10) as usize);
+        let px = |v: f64| area.x as f64 + x.frac(v) * area.w as f64;
+        let py = |v: f64| area.bottom() as f64 - y.frac(v) * area.h as f64;
+
+        // Grid, axes, ticks and labels.
+        let dash = Dash::new(&[2.0, 3.0]);
+        let categorical = self.is_categorical();
+        for (v, label) in &y.ticks {
+            let ty = (py(*v).round() as i32).min(area.bottom());
+            draw::draw_line_dashed(surface, area.x, ty, area.right() - 1, ty, &dash, st.grid);
+            draw::draw_line(surface, area.x - 4, ty, area.x - 2, ty, st.axis);
+            let (w, _) = text::measure_text(label);
+            text::draw_text(surface, area.x - 6 - w, ty - line / 2, label, st.text);
+        }
+        for (v, label) in &x.ticks {
+            let tx = (px(*v).round() as i32).min(area.right() - 1);
+            if !categorical {
+                draw::draw_line_dashed(surface, tx, area.y, tx, area.bottom() - 1, &dash, st.grid);
+            }
+            draw::draw_line(
+                surface,
+                tx,
+                area.bottom() + 1,
+                tx,
+                area.bottom() + 3,
+                st.axis,
+            );
+            let (w, _) = text::measure_text(label);
+            text::draw_text(surface, tx - w / 2, area.bottom() + 6, label, st.text);
+        }
+        let (ax, ay) = (area.x - 1, area.bottom());
+        draw::draw_line(surface, ax, area.y, ax, ay, st.axis);
+        draw::draw_line(surface, ax, ay, area.right() - 1, ay, st.axis);
+        if !self.x_label.is_empty() {
+            let (w, _) = text::measure_text(&self.x_label);
+            let lx = area.x + (area.w - w) / 2;
+            text::draw_text(surface, lx, x_label_y, &self.x_label, st.text);
+        }
+
+        surface.push_clip(area);
+        let bar_count = self.bar_series().count();
+        let mut bar_index = 0;
+        for s in &self.series {
+            match s.kind {
+                SeriesKind::Line => {
+                    let mut p = Path::new();
+                    for (i, &(vx, vy)) in s.points.iter().enumerate() {
+                        let (fx, fy) = (px(vx) as f32, py(vy) as f32);
+                        if i == 0 {
+                            p.move_to(fx, fy);
+                        } else {
+                            p.line_to(fx, fy);
+                        }
+                    }
+                    let stroke = Stroke::new(st.line_width).with_join(LineJoin::Round);
+                    path::stroke_path_aa(surface, &p, &stroke, s.color);
+                }
+                SeriesKind::Scatter => {
+                    for &(vx, vy) in &s.points {
+                        dot(
+                            surface,
+                            px(vx) as f32,
+                            py(vy) as f32,
+                            st.point_radius,
+                            s.color,
+                        );
+                    }
+                }
+                SeriesKind::Bar => {
+                    // Bars of one slot fill 80% of it; adjacent bars keep a 1px gap.
+                    let bw = 0.8 / bar_count as f64;
+                    for &(i, v) in &s.points {
+                        let x0 = i - 0.4 + bar_index as f64 * bw;
+                        let gap = if bar_count > 1 { 1 } else { 0 };
+                        column(surface, px(x0), px(x0 + bw), gap, py(0.0), py(v), s.color);
+                    }
+                    bar_index += 1;
+                }
+                SeriesKind::Histogram { bin_width } => {
+                    for &(x0, count) in &s.points {
+                        let (l, r) = (px(x0), px(x0 + bin_width));
+                        column(surface, l, r, 1, py(0.0), py(count), s.color);
+                    }
+                }
+            }
+        }
+        surface.pop_clip();
+
+        if self.legend {
+            self.draw_legend(surface, area);
+        }
+        surface.pop_clip();
+    }
+
+    /// New surface of `width` x `height` with the chart drawn over all of it.
+    pub fn render(&self, width: usize, height: usize) -> Surface {
+        let mut s = Surface::new(width, height);
+        let bounds = s.bounds();
+        self.draw(&mut s, bounds);
+        s
+    }
+
+    fn draw_legend<P: PixelFormat>(&self, surface: &mut Surface<P>, area: Rect) {
+        let st = self.style;
+        let named: Vec<&Series> = self.series.iter().filter(|s| !s.name.is_empty()).collect();
+        let Some(name_w) = named.iter().map(|s| text::measure_text(&s.name).0).max() else {
+            return;
+        };
+        let (row, swatch) = (12, 12);
+        let w = 6 + swatch + 4 + name_w + 6;
+        let h = 4 + row * named.len() as i32;
+        let (bx, by) = (area.right() - w - 6, area.y + 6);
+        draw::fill_rect(surface, bx, by, w, h, st.background);
+        draw::draw_rect(surface, bx, by, w, h, st.axis);
+        for (i, s) in named.iter().enumerate() {
+            let (sx, sy) = (bx + 6, by + 4 + i as i32 * row);
+            let cy = sy + 4;
+            match s.kind {
+                SeriesKind::Line => draw::fill_rect(surface, sx, cy - 1, swatch, 2, s.color),
+                SeriesKind::Scatter => {
+                    let c = (sx as f32 + swatch as f32 / 2.0, cy as f32 + 0.5);
+                    dot(surface, c.0, c.1, st.point_radius, s.color);
+                }
+                SeriesKind::Bar | SeriesKind::Histogram { .. } => {
+                    let paint = Paint::Solid(s.color);
+                    draw::fill_rect_paint(surface, sx + 1, sy, swatch - 2, 8, &paint);
+                }
+            }
+            text::draw_text(surface, sx + swatch + 4, sy, &s.name, st.text);
+        }
+    }
+
+    fn bar_series(&self) -> impl Iterator<Item = &Series> {
+        self.series.iter().filter(|s| s.kind == SeriesKind::Bar)
+    }
+
+    fn is_categorical(&self) -> bool {
+        !self.categories.is_empty() || self.bar_series().next().is_some()
+    }
+
+    fn x_axis(&self, target: usize) -> Axis {
+        if self.is_categorical() {
+            let slots = self.bar_series().map(|s| s.points.len()).max();
+            let n = slots.unwrap_or(0).max(self.categories.len()).max(1);
+            let (lo, hi) = match self.x_range {
+                Some((a, b)) if a < b => (a, b),
+                Some((a, b)) if b < a => (b, a),
+                _ => (-0.5, n as f64 - 0.5),
+            };
+            let ticks = (0..n)
+                .filter(|&i| (lo..=hi).contains(&(i as f64)))
+                .map(|i| {
+                    let label = self.categories.get(i).cloned();
+                    (i as f64, label.unwrap_or_else(|| i.to_string()))
+                })
+                .collect();
+            return Axis { lo, hi, ticks };
+        }
+        let mut values = Vec::new();
+        for s in &self.series {
+            for &(x, _) in &s.points {
+                values.push(x);
+                if let SeriesKind::Histogram { bin_width } = s.kind {
+                    values.push(x + bin_width);
+                }
+            }
+        }
+        fit_axis(&values, self.x_range, target)
+    }
+
+    fn y_axis(&self, target: usize) -> Axis {
+        let mut values: Vec<f64> = self
+            .series
+            .iter()
+            .flat_map(|s| s.points.iter().map(|p| p.1))
+            .collect();
+        let from_zero =
+            |s: &Series| matches!(s.kind, SeriesKind::Bar | SeriesKind::Histogram { .. });
+        if self.series.iter().any(from_zero) {
+            values.push(0.0);
+        }
+        fit_axis(&values, self.y_range, target)
+    }
+}
+
+fn series(name: &str, kind: SeriesKind, color: Color, points: Vec<(f64, f64)>) -> Series {
+    Series {
+        name: name.to_string(),
+        kind,
+        color,
+        points,
+    }
+}
+
+/// Nice ticks for the finite `values`, or for `fixed` without widening it.
+fn fit_axis(values: &[f64], fixed: Option<(f64, f64)>, target: usize) -> Axis {
+    let finite = values.iter().copied().filter(|v| v.is_finite());
+    let (lo, hi) = match fixed {
+        Some((a, b)) if a < b => (a, b),
+        Some((a, b)) if b < a => (b, a),
+        _ => finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        }),
+    };
+    let (lo, hi) = if lo <= hi { (lo, hi) } else { (0.0, 1.0) };
+    let t = Ticks::nice(lo, hi, target);
+    let (lo, hi) = if fixed.is_some() && lo < hi {
+        (lo, hi)
+    } else {
+        (t.start, t.end())
+    };
+    // Allow for rounding at the ends.
+    let eps = t.step * 1e-9;
+    let ticks = t
+        .values()
+        .into_iter()
+        .filter(|&v| v >= lo - eps && v <= hi + eps)
+        .map(|v| (v, t.label(v)))
+        .collect();
+    Axis { lo, hi, ticks }
+}
+
+fn dot<P: PixelFormat>(surface: &mut Surface<P>, x: f32, y: f32, r: f32, color: Color) {
+    let mut p = Path::new();
+    p.arc(x, y, r, 0.0, std::f32::consts::TAU).close();
+    path::fill_path_aa(surface, &p, FillRule::NonZero, color);
+}
+
+/// Filled column between pixel x edges `l..r` (shrunk by `gap` on the right) and between
+/// the y positions `y0` and `y1`, either way up. Translucent colors blend, so overlapping
+/// histograms stay visible. Edges far outside the clip are pulled in to just beyond it;
+/// columns with a non-finite edge are skipped.
+fn column<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    l: f64,
+    r: f64,
+    gap: i32,
+    y0: f64,
+    y1: f64,
+    color: Color,
+) {
+    if ![l, r, y0, y1].iter().all(|v| v.is_finite()) {
+        return;
+    }
+    let clip = surface.clip_rect();
+    let fit = |v: f64, lo: i32, hi: i32| v.round().clamp(lo as f64 - 1.0, hi as f64 + 1.0) as i32;
+    let (l, r) = (
+        fit(l, clip.x, clip.right()),
+        fit(r, clip.x, clip.right()) - gap,
+    );
+    let (t, b) = (
+        fit(y0.min(y1), clip.y, clip.bottom()),
+        fit(y0.max(y1), clip.y, clip.bottom()),
+    );
+    let paint = Paint::Solid(color);
+    draw::fill_rect_paint(surface, l, t, (r - l).max(1), b - t, &paint);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color::rgba(220, 40, 40, 255);
+    const BLUE: Color = Color::rgba(40, 80, 220, 255);
+
+    #[test]
+    fn axes_fit_the_data_on_nice_ticks() {
+        let mut c = Chart::new();
+        c.line("a", &[(0.0, 0.13), (7.0, 0.92)], RED);
+        let y = c.y_axis(5);
+        assert_eq!((y.lo, y.hi), (0.0, 1.0));
+        assert_eq!(y.ticks.first().map(|t| t.1.as_str()), Some("0.0"));
+        let x = c.x_axis(4);
+        assert_eq!((x.lo, x.hi), (0.0, 8.0));
+        assert_eq!(x.ticks.len(), 5);
+
+        let c = c.with_y_range(0.25, 0.75);
+        let y = c.y_axis(5);
+        assert_eq!((y.lo, y.hi), (0.25, 0.75));
+        assert!(y.ticks.iter().all(|t| t.0 >= 0.25 && t.0 <= 0.75));
+    }
+
+    #[test]
+    fn bars_switch_to_category_slots_and_include_zero() {
+        let mut c = Chart::new().with_categories(&["Q1", "Q2"]);
+        c.bars("a", &[3.0, -2.0, 5.0], RED).bars("b", &[4.0], BLUE);
+        let x = c.x_axis(5);
+        assert_eq!((x.lo, x.hi), (-0.5, 2.5));
+        let labels: Vec<&str> = x.ticks.iter().map(|t| t.1.as_str()).collect();
+        assert_eq!(labels, ["Q1", "Q2", "2"]);
+        let y = c.y_axis(5);
+        assert!(y.lo <= -2.0 && y.hi >= 5.0);
+
+        let mut c = Chart::new();
+        c.bars("", &[3.0, 4.0], RED);
+        assert!(c.y_axis(5).lo == 0.0);
+    }
+
+    #[test]
+    fn histogram_counts_every_finite_sample() {
+        let mut c = Chart::new();
+        let samples = [0.0, 0.1, 0.5, 0.9, 1.0, 1.0, f64::NAN];
+        c.histogram("h", &samples, 2, RED);
+        let s = &c.series()[0];
+        assert_eq!(s.kind, SeriesKind::Histogram { bin_width: 0.5 });
+        assert_eq!(s.points, vec![(0.0, 2.0), (0.5, 4.0)]);
+
+        c.histogram("same", &[2.0, 2.0], 8, BLUE);
+        assert_eq!(c.series()[1].points, vec![(1.5, 2.0)]);
+    }
+
+    #[test]
+    fn extreme_bars_are_clamped_to_the_plot() {
+        let mut c = Chart::new().with_y_range(0.0, 1.0);
+        c.bars("x", &[0.5, 1e12], RED);
+        let s = c.render(120, 80);
+        let column: Vec<_> = (0..80).filter_map(|y| s.get_pixel(90, y)).collect();
+        assert!(column.contains(&RED));
+
+        let mut c = Chart::new();
+        c.bars("x", &[f64::INFINITY, 1.0], RED);
+        c.render(120, 80);
+        let mut c = Chart::new();
+        c.histogram("h", &[-1e308, 1e308], 4, RED);
+        c.render(120, 80);
+    }
+
+    #[test]
+    fn degenerate_category_range_falls_back_to_the_slots() {
+        let mut c = Chart::new().with_x_range(1.0, 1.0);
+        c.bars("x", &[1.0, 2.0], RED);
+        let x = c.x_axis(5);
+        assert_eq!((x.lo, x.hi), (-0.5, 1.5));
+        let c = c.with_x_range(1.5, -0.5);
+        assert_eq!(c.x_axis(5).lo, -0.5);
+        c.render(60, 40);
+    }
+
+    #[test]
+    fn draw_stays_inside_its_rect() {
+        let mut c = Chart::new()
+            .with_title("T")
+            .with_x_label("x")
+            .with_y_label("y");
+        c.line("up", &[(0.0, 0.0), (10.0, 10.0)], RED)
+            .scatter("pts", &[(5.0, 2.0)], BLUE);
+        let mut s = Surface::new(200, 150);
+        let rect = Rect::new(20, 10, 160, 120);
+        c.draw(&mut s, rect);
+        let mut red = 0;
+        for y in 0..150 {
+            for x in 0..200 {
+                let p = s.get_pixel(x, y).unwrap();
+                if !rect.contains(x, y) {
+                    assert_eq!(p, Color::rgba(0, 0, 0, 0));
+                } else if p.r > 150 && p.g < 120 {
+                    red += 1;
+                }
+            }
+        }
+        assert!(red > 50);
+        assert_eq!(s.get_pixel(20, 10), Some(ChartStyle::light().background));
+        assert_eq!(c.render(40, 20).width(), 40);
+    }
+}
@@ -0,0 +1,131 @@
+//! Axis ticks on "nice" numbers: steps of 1, 2 or 5 times a power of ten.
+
+/// `x` rounded to 1, 2, 5 or 10 times a power of ten. With `round` the closest such number,
+/// otherwise the smallest one not below `x`. Non-positive or non-finite `x` gives 1.
+pub fn nice_number(x: f64, round: bool) -> f64 {
+    if !(x > 0.0 && x.is_finite()) {
+        return 1.0;
+    }
+    let exp = x.log10().floor();
+    let pow = 10f64.powf(exp);
+    let f = x / pow;
+    let nice = if round {
+        match f {
+            f if f < 1.5 => 1.0,
+            f if f < 3.0 => 2.0,
+            f if f < 7.0 => 5.0,
+            _ => 10.0,
+        }
+    } else {
+        match f {
+            f if f <= 1.0 => 1.0,
+            f if f <= 2.0 => 2.0,
+            f if f <= 5.0 => 5.0,
+            _ => 10.0,
+        }
+    };
+    nice * pow
+}
+
+/// Evenly spaced ticks `start, start + step, ...`, `count` of them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ticks {
+    pub start: f64,
+    pub step: f64,
+    pub count: usize,
+}
+
+impl Ticks {
+    /// About `target` intervals on nice numbers, widened outward so the first and last tick
+    /// enclose `[lo, hi]`. An empty range is widened around its value first.
+    pub fn nice(lo: f64, hi: f64, target: usize) -> Self {
+        let (mut lo, mut hi) = if lo <= hi { (lo, hi) } else { (hi, lo) };
+        if !(lo.is_finite() && hi.is_finite()) {
+            (lo, hi) = (0.0, 1.0);
+        }
+        if hi - lo < f64::EPSILON * hi.abs().max(1.0) {
+            let pad = if lo == 0.0 { 1.0 } else { lo.abs() * 0.1 };
+            (lo, hi) = (lo - pad, hi + pad);
+        }
+        let target = target.max(1);
+        // Halved so a range spanning most of f64 does not overflow; the cap keeps the rounded
+        // step finite.
+        let span = hi / 2.0 - lo / 2.0;
+        let step = nice_number((span / target as f64 * 2.0).min(f64::MAX / 10.0), true);
+        let (first, last) = ((lo / step).floor(), (hi / step).ceil());
+        Self {
+            start: first * step,
+            step,
+            count: ((last - first) as usize + 1).min(target * 4),
+        }
+    }
+
+    /// Last tick value.
+    pub fn end(&self) -> f64 {
+        self.value(self.count.saturating_sub(1))
+    }
+
+    /// Tick `i`, computed by multiplication so rounding errors do not accumulate.
+    pub fn value(&self, i: usize) -> f64 {
+        self.start + self.step * i as f64
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        (0..self.count).map(|i| self.value(i)).collect()
+    }
+
+    /// Digits after the decimal point needed to tell neighboring ticks apart.
+    pub fn decimals(&self) -> usize {
+        (-self.step.log10().floor()).max(0.0) as usize
+    }
+
+    /// `v` formatted with `decimals()` digits; never "-0".
+    pub fn label(&self, v: f64) -> String {
+        let s = format!("{:.*}", self.decimals(), v);
+        if s.starts_with('-') && s[1..].chars().all(|c| c == '0' || c == '.') {
+            s[1..].to_string()
+        } else {
+            s
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nice_numbers_round_to_1_2_5() {
+        assert_eq!(nice_number(0.37, true), 0.5);
+        assert_eq!(nice_number(0.37, false), 0.5);
+        assert_eq!(nice_number(2.6, true), 2.0);
+        assert_eq!(nice_number(2.6, false), 5.0);
+        assert_eq!(nice_number(7200.0, true), 10000.0);
+        assert_eq!(nice_number(-3.0, true), 1.0);
+    }
+
+    #[test]
+    fn ticks_enclose_the_range_with_readable_labels() {
+        let t = Ticks::nice(0.13, 0.92, 5);
+        assert_eq!((t.start, t.step, t.count), (0.0, 0.2, 6));
+        assert!((t.end() - 1.0).abs() < 1e-12);
+        let labels: Vec<String> = t.values().iter().map(|&v| t.label(v)).collect();
+        assert_eq!(labels, ["0.0", "0.2", "0.4", "0.6", "0.8", "1.0"]);
+
+        let t = Ticks::nice(-37.0, 480.0, 5);
+        assert_eq!((t.start, t.step, t.count), (-100.0, 100.0, 7));
+        assert_eq!(t.label(-100.0), "-100");
+
+        let t = Ticks::nice(3.0, 3.0, 4);
+        assert!(t.start <= 3.0 && t.end() >= 3.0 && t.count > 1);
+        assert_eq!(Ticks::nice(-0.5, 0.5, 2).label(-0.0001), "0.0");
+    }
+
+    #[test]
+    fn ticks_stay_bounded_across_all_of_f64() {
+        let t = Ticks::nice(-1e308, 1e308, 5);
+        assert!(t.step.is_finite() && t.count <= 20);
+        assert_eq!(t.values().len(), t.count);
+        assert!(t.start <= -1e308 && t.end() >= 1e308);
+    }
+}
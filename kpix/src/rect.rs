@@ -0,0 +1,207 @@
+//! Integer pixel rectangle.
+
+/// Axis-aligned pixel rectangle covering the half-open area [x, x+w) x [y, y+h).
+/// Constructors normalize negative sizes the same way `draw::fill_rect` does.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+impl Rect {
+    /// `(x, y)` is a corner; `w`, `h` may be negative.
+    pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        let (x0, x1) = if w >= 0 { (x, x + w) } else { (x + w, x) };
+        let (y0, y1) = if h >= 0 { (y, y + h) } else { (y + h, y) };
+        Self::from_min_max(x0, y0, x1, y1)
+    }
+
+    /// From two corners in any order; `(x1, y1)` is exclusive when it is the larger one.
+    pub fn from_min_max(x0: i32, y0: i32, x1: i32, y1: i32) -> Self {
+        let (x0, x1) = (x0.min(x1), x0.max(x1));
+        let (y0, y1) = (y0.min(y1), y0.max(y1));
+        Self {
+            x: x0,
+            y: y0,
+            w: x1 - x0,
+            h: y1 - y0,
+        }
+    }
+
+    /// Exclusive right edge.
+    #[inline]
+    pub fn right(&self) -> i32 {
+        self.x + self.w
+    }
+
+    /// Exclusive bottom edge.
+    #[inline]
+    pub fn bottom(&self) -> i32 {
+        self.y + self.h
+    }
+
+    /// True when the rect covers no pixels.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.w <= 0 || self.h <= 0
+    }
+
+    /// Number of covered pixels (0 when empty).
+    pub fn area(&self) -> i64 {
+        if self.is_empty() {
+            0
+        } else {
+            self.w as i64 * self.h as i64
+        }
+    }
+
+    /// Half-open containment test.
+    #[inline]
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// Overlapping area; empty (zero size) when the rects do not overlap.
+    pub fn intersect(&self, other: Rect) -> Rect {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = self.right().min(other.right());
+        let y1 = self.bottom().min(other.bottom());
+        if x0 >= x1 || y0 >= y1 {
+            return Rect {
+                x: x0,
+                y: y0,
+                w: 0,
+                h: 0,
+            };
+        }
+        Rect::from_min_max(x0, y0, x1, y1)
+    }
+
+    /// Smallest rect containing both; empty rects are ignored.
+    pub fn union(&self, other: Rect) -> Rect {
+        if self.is_empty() {
+            return other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        Rect::from_min_max(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.right().max(other.right()),
+            self.bottom().max(other.bottom()),
+        )
+    }
+}
+
+/// Clip state shared by `Surface` and `HdrSurface`: the effective clip (always inside the
+/// surface bounds) plus the clips saved by `push`, so both surfaces clip identically.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ClipStack {
+    current: Rect,
+    saved: Vec<Rect>,
+}
+
+impl ClipStack {
+    /// No clip pushed: everything inside `bounds` is writable.
+    pub(crate) fn new(bounds: Rect) -> Self {
+        Self {
+            current: bounds,
+            saved: Vec::new(),
+        }
+    }
+
+    /// The innermost pushed clip, or the bounds.
+    #[inline]
+    pub(crate) fn current(&self) -> Rect {
+        self.current
+    }
+
+    /// Restrict to `rect` intersected with the current clip.
+    pub(crate) fn push(&mut self, rect: Rect) {
+        self.saved.push(self.current);
+        self.current = self.current.intersect(rect);
+    }
+
+    /// Restore the clip active before the matching `push` and return the removed one,
+    /// or `None` when nothing is pushed.
+    pub(crate) fn pop(&mut self) -> Option<Rect> {
+        let parent = self.saved.pop()?;
+        Some(std::mem::replace(&mut self.current, parent))
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, x: i32, y: i32) -> bool {
+        self.current.contains(x, y)
+    }
+
+    /// The part of the half-open span [x0, x1) on row `y` inside the clip, if any.
+    #[inline]
+    pub(crate) fn span(&self, x0: i32, x1: i32, y: i32) -> Option<(i32, i32)> {
+        let c = self.current;
+        if y < c.y || y >= c.bottom() {
+            return None;
+        }
+        let (x0, x1) = (x0.max(c.x), x1.min(c.right()));
+        (x0 < x1).then_some((x0, x1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_normalizes_negative_size() {
+        assert_eq!(Rect::new(4, 3, -3, -2), Rect::new(1, 1, 3, 2));
+        assert_eq!(Rect::from_min_max(5, 6, 1, 2), Rect::new(1, 2, 4, 4));
+    }
+
+    #[test]
+    fn edges_contains_and_area() {
+        let r = Rect::new(1, 2, 3, 4);
+        assert_eq!((r.right(), r.bottom()), (4, 6));
+        assert!(r.contains(1, 2));
+        assert!(!r.contains(4, 2));
+        assert!(!r.contains(1, 6));
+        assert_eq!(r.area(), 12);
+        assert_eq!(Rect::new(0, 0, 0, 5).area(), 0);
+    }
+
+    #[test]
+    fn intersect_overlap_and_disjoint() {
+        let a = Rect::new(0, 0, 4, 4);
+        let b = Rect::new(2, 1, 5, 2);
+        assert_eq!(a.intersect(b), Rect::new(2, 1, 2, 2));
+        // touching edges do not overlap
+        assert!(a.intersect(Rect::new(4, 0, 2, 2)).is_empty());
+    }
+
+    #[test]
+    fn union_ignores_empty() {
+        let a = Rect::new(0, 0, 2, 2);
+        let b = Rect::new(3, 1, 1, 3);
+        assert_eq!(a.union(b), Rect::new(0, 0, 4, 4));
+        assert_eq!(a.union(Rect::default()), a);
+        assert_eq!(Rect::default().union(b), b);
+    }
+
+    #[test]
+    fn clip_stack_nests_and_clips_spans() {
+        let mut c = ClipStack::new(Rect::new(0, 0, 10, 10));
+        c.push(Rect::new(2, 2, 6, 6));
+        c.push(Rect::new(-5, 4, 9, 20));
+        assert_eq!(c.current(), Rect::new(2, 4, 2, 4));
+        assert_eq!(c.span(-3, 30, 5), Some((2, 4)));
+        assert_eq!(c.span(0, 3, 3), None);
+        assert_eq!(c.span(4, 9, 5), None);
+        assert!(c.contains(3, 7) && !c.contains(3, 8));
+        assert_eq!(c.pop(), Some(Rect::new(2, 4, 2, 4)));
+        assert_eq!(c.pop(), Some(Rect::new(2, 2, 6, 6)));
+        assert_eq!(c.current(), Rect::new(0, 0, 10, 10));
+        assert_eq!(c.pop(), None);
+    }
+}
@@ -0,0 +1,185 @@
+//! Frame animation over sprite sheet indices.
+
+use std::time::Duration;
+
+/// What happens after the last frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PlayMode {
+    /// Start over from the first frame.
+    #[default]
+    Loop,
+    /// Play backwards to the first frame, then forwards again. The end frames are shown once
+    /// per turn, not twice.
+    PingPong,
+    /// Stop on the last frame.
+    Once,
+}
+
+/// One step of an animation: a sprite sheet frame index shown for `duration`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AnimFrame {
+    pub frame: usize,
+    pub duration: Duration,
+}
+
+impl AnimFrame {
+    pub fn new(frame: usize, duration: Duration) -> Self {
+        Self { frame, duration }
+    }
+}
+
+/// A sequence of frames plus playback time. Advance it with `update` and draw
+/// `frame()` from the sheet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Animation {
+    frames: Vec<AnimFrame>,
+    pub mode: PlayMode,
+    elapsed: Duration,
+}
+
+impl Animation {
+    /// Default mode: `PlayMode::Loop`.
+    pub fn new(frames: impl IntoIterator<Item = AnimFrame>) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Every frame shown for the same `duration`.
+    pub fn uniform(frames: impl IntoIterator<Item = usize>, duration: Duration) -> Self {
+        Self::new(frames.into_iter().map(|f| AnimFrame::new(f, duration)))
+    }
+
+    pub fn with_mode(mut self, mode: PlayMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn frames(&self) -> &[AnimFrame] {
+        &self.frames
+    }
+
+    /// Time since the start (or the last `reset`); wraps around for repeating modes.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Length of one pass: all frames for `Loop` and `Once`, there and back for `PingPong`.
+    pub fn cycle_duration(&self) -> Duration {
+        self.sequence().map(|f| f.duration).sum()
+    }
+
+    /// Advance playback by `dt`.
+    pub fn update(&mut self, dt: Duration) {
+        let cycle = self.cycle_duration();
+        if cycle.is_zero() {
+            return;
+        }
+        self.elapsed = self.elapsed.saturating_add(dt);
+        match self.mode {
+            PlayMode::Once => self.elapsed = self.elapsed.min(cycle),
+            PlayMode::Loop | PlayMode::PingPong => {
+                let nanos = self.elapsed.as_nanos() % cycle.as_nanos();
+                self.elapsed = Duration::from_nanos(nanos as u64);
+            }
+        }
+    }
+
+    /// Jump back to the first frame.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// True once a `Once` animation has reached its end. Repeating modes never finish.
+    pub fn is_finished(&self) -> bool {
+        self.mode == PlayMode::Once && self.elapsed >= self.cycle_duration()
+    }
+
+    /// Sprite sheet index to draw now; 0 without frames.
+    pub fn frame(&self) -> usize {
+        self.frame_at(self.elapsed)
+    }
+
+    /// Sprite sheet index shown at time `t` from the start.
+    pub fn frame_at(&self, t: Duration) -> usize {
+        let Some(last) = self.frames.last() else {
+            return 0;
+        };
+        let cycle = self.cycle_duration();
+        if cycle.is_zero() {
+            return self.frames[0].frame;
+        }
+        let mut t = match self.mode {
+            PlayMode::Once if t >= cycle => return last.frame,
+            PlayMode::Once => t,
+            PlayMode::Loop | PlayMode::PingPong => {
+                Duration::from_nanos((t.as_nanos() % cycle.as_nanos()) as u64)
+            }
+        };
+        for f in self.sequence() {
+            if t < f.duration {
+                return f.frame;
+            }
+            t -= f.duration;
+        }
+        last.frame
+    }
+
+    /// Frames in playback order for one cycle.
+    fn sequence(&self) -> impl Iterator<Item = &AnimFrame> {
+        let n = self.frames.len();
+        let back = match self.mode {
+            PlayMode::PingPong if n > 2 => &self.frames[1..n - 1],
+            _ => &[],
+        };
+        self.frames.iter().chain(back.iter().rev())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    fn order(anim: &mut Animation, steps: usize, dt: Duration) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                let f = anim.frame();
+                anim.update(dt);
+                f
+            })
+            .collect()
+    }
+
+    #[test]
+    fn modes_step_through_frames() {
+        let base = Animation::uniform([10, 11, 12, 13], 100 * MS);
+        let mut a = base.clone();
+        assert_eq!(order(&mut a, 6, 100 * MS), vec![10, 11, 12, 13, 10, 11]);
+        let mut a = base.clone().with_mode(PlayMode::PingPong);
+        assert_eq!(a.cycle_duration(), 600 * MS);
+        assert_eq!(
+            order(&mut a, 8, 100 * MS),
+            vec![10, 11, 12, 13, 12, 11, 10, 11]
+        );
+        let mut a = base.with_mode(PlayMode::Once);
+        assert_eq!(order(&mut a, 6, 100 * MS), vec![10, 11, 12, 13, 13, 13]);
+        assert!(a.is_finished());
+        a.reset();
+        assert_eq!((a.frame(), a.is_finished()), (10, false));
+    }
+
+    #[test]
+    fn per_frame_durations_and_large_steps() {
+        let mut a = Animation::new([AnimFrame::new(0, 50 * MS), AnimFrame::new(1, 150 * MS)]);
+        assert_eq!(a.frame_at(49 * MS), 0);
+        assert_eq!(a.frame_at(50 * MS), 1);
+        assert_eq!(a.frame_at(250 * MS), 1);
+        // A long hitch wraps around instead of growing without bound.
+        a.update(Duration::from_secs(1000) + 20 * MS);
+        assert_eq!((a.frame(), a.elapsed()), (0, 20 * MS));
+        assert_eq!(Animation::default().frame(), 0);
+    }
+}
@@ -0,0 +1,251 @@
+//! Packing loose images into one sprite sheet.
+//!
+//! `pack` is a skyline packer: the top edge of everything placed so far is kept as a list of
+//! horizontal segments, and each rect (tallest first) goes wherever it rests closest to the
+//! top, leftmost on ties. Output width is fixed; height is whatever the rects need.
+
+use std::io;
+
+use super::SpriteSheet;
+use crate::blit;
+use crate::core::Surface;
+use crate::format::PixelFormat;
+use crate::rect::Rect;
+
+/// Place rects of the given `(w, h)` sizes inside a strip `width` wide, keeping `padding`
+/// pixels between neighbours. Returns positions in input order, or `None` when a rect is
+/// wider than the strip. Non-positive sizes get an empty rect at the origin.
+pub fn pack(sizes: &[(i32, i32)], width: i32, padding: i32) -> Option<Vec<Rect>> {
+    let padding = padding.max(0);
+    // Padding goes right of and below each rect; widening the strip by the same amount lets
+    // rects touch the right edge.
+    let strip = width + padding;
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| (std::cmp::Reverse(sizes[i].1), std::cmp::Reverse(sizes[i].0)));
+
+    let mut out = vec![Rect::default(); sizes.len()];
+    let mut sky = vec![Segment {
+        x: 0,
+        y: 0,
+        w: strip,
+    }];
+    for i in order {
+        let (w, h) = sizes[i];
+        if w <= 0 || h <= 0 {
+            continue;
+        }
+        let (pw, ph) = (w + padding, h + padding);
+        let (x, y) = place(&sky, pw, strip)?;
+        raise(&mut sky, x, pw, y + ph);
+        out[i] = Rect::new(x, y, w, h);
+    }
+    Some(out)
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Segment {
+    x: i32,
+    y: i32,
+    w: i32,
+}
+
+/// Lowest, then leftmost, position for a rect `w` wide on the skyline.
+fn place(sky: &[Segment], w: i32, strip: i32) -> Option<(i32, i32)> {
+    let mut best: Option<(i32, i32)> = None;
+    for (i, seg) in sky.iter().enumerate() {
+        if seg.x + w > strip {
+            break;
+        }
+        // Resting height: the tallest segment under the span.
+        let mut y = 0;
+        for s in &sky[i..] {
+            if s.x >= seg.x + w {
+                break;
+            }
+            y = y.max(s.y);
+        }
+        if best.is_none_or(|(_, by)| y < by) {
+            best = Some((seg.x, y));
+        }
+    }
+    best
+}
+
+/// Lift the skyline over `[x, x + w)` to `top`, merging equal neighbours.
+fn raise(sky: &mut Vec<Segment>, x: i32, w: i32, top: i32) {
+    let end = x + w;
+    let lifted = Segment { x, y: top, w };
+    let mut next = Vec::with_capacity(sky.len() + 2);
+    let mut inserted = false;
+    for &s in sky.iter() {
+        let s_end = s.x + s.w;
+        if s_end <= x {
+            next.push(s);
+            continue;
+        }
+        if s.x < x {
+            next.push(Segment { w: x - s.x, ..s });
+        }
+        if !inserted {
+            next.push(lifted);
+            inserted = true;
+        }
+        if s_end > end {
+            let start = s.x.max(end);
+            next.push(Segment {
+                x: start,
+                y: s.y,
+                w: s_end - start,
+            });
+        }
+    }
+    next.dedup_by(|b, a| {
+        if a.y == b.y && a.x + a.w == b.x {
+            a.w += b.w;
+            true
+        } else {
+            false
+        }
+    });
+    *sky = next;
+}
+
+/// Collects named images and packs them into one RGBA8 `SpriteSheet`.
+#[derive(Clone, Debug)]
+pub struct AtlasBuilder {
+    width: i32,
+    padding: i32,
+    images: Vec<(String, Surface)>,
+}
+
+impl AtlasBuilder {
+    /// Atlas `width` pixels wide, no padding.
+    pub fn new(width: i32) -> Self {
+        Self {
+            width,
+            padding: 0,
+            images: Vec::new(),
+        }
+    }
+
+    /// Transparent pixels kept between packed images, e.g. to stop bilinear sampling from
+    /// bleeding into neighbours.
+    pub fn with_padding(mut self, padding: i32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Queue `image` under `name` (converted to RGBA8).
+    pub fn add<P: PixelFormat>(&mut self, name: &str, image: &Surface<P>) -> &mut Self {
+        self.images.push((name.to_string(), image.to_rgba8()));
+        self
+    }
+
+    /// Pack everything into a sheet whose frames keep the order of `add` and whose height
+    /// fits the packed images. Fails with `InvalidInput` on an empty, duplicate or
+    /// whitespace-containing name (names must survive `to_manifest`) or an image wider than
+    /// the atlas.
+    pub fn build(&self) -> io::Result<SpriteSheet> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidInput, msg);
+        for (i, (name, _)) in self.images.iter().enumerate() {
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(invalid(format!("invalid sprite name {name:?}")));
+            }
+            if self.images[..i].iter().any(|(n, _)| n == name) {
+                return Err(invalid(format!("duplicate sprite name {name:?}")));
+            }
+        }
+        let sizes: Vec<(i32, i32)> = self
+            .images
+            .iter()
+            .map(|(_, s)| (s.width() as i32, s.height() as i32))
+            .collect();
+        let rects = pack(&sizes, self.width, self.padding)
+            .ok_or_else(|| invalid(format!("image wider than the {}px atlas", self.width)))?;
+        let height = rects.iter().map(|r| r.bottom()).max().unwrap_or(0);
+
+        let mut sheet = SpriteSheet::new(Surface::new(self.width.max(0) as usize, height as usize));
+        for ((name, image), rect) in self.images.iter().zip(rects) {
+            blit::blit(image, image.bounds(), &mut sheet.surface, rect.x, rect.y);
+            sheet.push(Some(name), rect);
+        }
+        Ok(sheet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Color;
+
+    fn overlaps(rects: &[Rect]) -> bool {
+        rects
+            .iter()
+            .enumerate()
+            .any(|(i, a)| rects[i + 1..].iter().any(|b| !a.intersect(*b).is_empty()))
+    }
+
+    #[test]
+    fn packs_without_overlap_inside_width() {
+        let sizes: Vec<(i32, i32)> = (0..40)
+            .map(|i| (3 + (i * 7) % 13, 2 + (i * 5) % 11))
+            .collect();
+        let rects = pack(&sizes, 48, 1).unwrap();
+        for (r, &(w, h)) in rects.iter().zip(&sizes) {
+            assert_eq!((r.w, r.h), (w, h));
+            assert!(r.x >= 0 && r.y >= 0 && r.right() <= 48);
+        }
+        assert!(!overlaps(&rects));
+        // Padding keeps a gap: grown rects still don't overlap.
+        let grown: Vec<Rect> = rects
+            .iter()
+            .map(|r| Rect::new(r.x, r.y, r.w + 1, r.h + 1))
+            .collect();
+        assert!(!overlaps(&grown));
+        // At least two thirds of the used strip is covered.
+        let height = rects.iter().map(|r| r.bottom()).max().unwrap();
+        let area: i32 = sizes.iter().map(|&(w, h)| (w + 1) * (h + 1)).sum();
+        assert!(height * 49 < area * 3 / 2, "height {height}");
+        assert_eq!(pack(&[(49, 1)], 48, 0), None);
+    }
+
+    #[test]
+    fn equal_squares_fill_rows() {
+        let rects = pack(&[(4, 4); 6], 12, 0).unwrap();
+        assert_eq!(rects.iter().map(|r| r.bottom()).max(), Some(8));
+        assert_eq!(rects[3], Rect::new(0, 4, 4, 4));
+    }
+
+    #[test]
+    fn builder_copies_images_and_names_frames() {
+        let mut a = Surface::new(3, 2);
+        a.clear(Color::rgba(255, 0, 0, 255));
+        let mut b = Surface::new(2, 4);
+        b.clear(Color::rgba(0, 255, 0, 255));
+        let mut builder = AtlasBuilder::new(8).with_padding(1);
+        builder.add("a", &a).add("b", &b);
+        let sheet = builder.build().unwrap();
+        let (ra, rb) = (sheet.get("a").unwrap(), sheet.get("b").unwrap());
+        assert_eq!((ra.w, ra.h, rb.w, rb.h), (3, 2, 2, 4));
+        assert_eq!(sheet.index_of("b"), Some(1));
+        let s = sheet.surface();
+        assert_eq!(s.get_pixel(ra.x, ra.y), Some(Color::rgba(255, 0, 0, 255)));
+        assert_eq!(
+            s.get_pixel(rb.right() - 1, rb.bottom() - 1),
+            Some(Color::rgba(0, 255, 0, 255))
+        );
+        assert_eq!(s.height(), 4);
+
+        let parsed = SpriteSheet::from_manifest(s.clone(), &sheet.to_manifest()).unwrap();
+        assert_eq!(parsed.get("a"), Some(ra));
+
+        builder.add("a", &a);
+        assert_eq!(
+            builder.build().unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        let mut wide = AtlasBuilder::new(2);
+        wide.add("x y", &a);
+        assert!(wide.build().is_err());
+    }
+}
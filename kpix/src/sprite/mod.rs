@@ -0,0 +1,226 @@
+//! Sprite sheets, frame animation and atlas packing.
+//!
+//! A `SpriteSheet` is one surface plus a list of frame rects, optionally named. Frames come
+//! from a uniform grid, from a text manifest, or from `AtlasBuilder`, which packs loose
+//! images into a single sheet. Drawing goes through `blit`, so flips, rotation and blend
+//! modes work as they do there.
+//!
+//! Manifest format: one frame per line as `name x y w h`; blank lines and lines starting
+//! with `#` are ignored. Names may not contain whitespace.
+
+mod anim;
+mod atlas;
+
+pub use anim::{AnimFrame, Animation, PlayMode};
+pub use atlas::{AtlasBuilder, pack};
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+use crate::blit::{self, BlendMode, BlitOptions};
+use crate::core::Surface;
+use crate::format::{PixelFormat, Rgba8};
+use crate::rect::Rect;
+
+/// A surface sliced into frames.
+#[derive(Clone, Debug)]
+pub struct SpriteSheet<P: PixelFormat = Rgba8> {
+    surface: Surface<P>,
+    frames: Vec<Rect>,
+    names: HashMap<String, usize>,
+}
+
+impl<P: PixelFormat> SpriteSheet<P> {
+    /// Sheet without frames; add them with `push`.
+    pub fn new(surface: Surface<P>) -> Self {
+        Self {
+            surface,
+            frames: Vec::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Slice into `frame_w` x `frame_h` cells, row by row. Partial cells at the right and
+    /// bottom edges are dropped.
+    pub fn from_grid(surface: Surface<P>, frame_w: i32, frame_h: i32) -> Self {
+        let mut sheet = Self::new(surface);
+        if frame_w <= 0 || frame_h <= 0 {
+            return sheet;
+        }
+        let (cols, rows) = (
+            sheet.surface.width() as i32 / frame_w,
+            sheet.surface.height() as i32 / frame_h,
+        );
+        for row in 0..rows {
+            for col in 0..cols {
+                sheet
+                    .frames
+                    .push(Rect::new(col * frame_w, row * frame_h, frame_w, frame_h));
+            }
+        }
+        sheet
+    }
+
+    /// Frames listed in `manifest`, in order of appearance. Fails with `InvalidData` on a
+    /// malformed line, a duplicate name, or a rect outside the surface.
+    pub fn from_manifest(surface: Surface<P>, manifest: &str) -> io::Result<Self> {
+        let mut sheet = Self::new(surface);
+        for (n, line) in manifest.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |msg: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("manifest line {}: {msg}", n + 1),
+                )
+            };
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, x, y, w, h] = fields[..] else {
+                return Err(err("expected `name x y w h`"));
+            };
+            let num = |s: &str| s.parse::<i32>().map_err(|_| err("invalid number"));
+            let rect = Rect::new(num(x)?, num(y)?, num(w)?, num(h)?);
+            if rect.is_empty() || rect.intersect(sheet.surface.bounds()) != rect {
+                return Err(err("rect is empty or outside the surface"));
+            }
+            if sheet.index_of(name).is_some() {
+                return Err(err("duplicate name"));
+            }
+            sheet.push(Some(name), rect);
+        }
+        Ok(sheet)
+    }
+
+    /// `from_manifest` with the manifest read from `path`.
+    pub fn load_manifest(surface: Surface<P>, path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_manifest(surface, &text)
+    }
+
+    /// Manifest text for the named frames, in frame order.
+    pub fn to_manifest(&self) -> String {
+        let mut named: Vec<(&String, &usize)> = self.names.iter().collect();
+        named.sort_by_key(|&(_, &i)| i);
+        let mut out = String::new();
+        for (name, &i) in named {
+            let r = self.frames[i];
+            let _ = writeln!(out, "{name} {} {} {} {}", r.x, r.y, r.w, r.h);
+        }
+        out
+    }
+
+    /// Append a frame and return its index. A name already in use moves to the new frame.
+    pub fn push(&mut self, name: Option<&str>, rect: Rect) -> usize {
+        let i = self.frames.len();
+        self.frames.push(rect);
+        if let Some(name) = name {
+            self.names.insert(name.to_string(), i);
+        }
+        i
+    }
+
+    pub fn surface(&self) -> &Surface<P> {
+        &self.surface
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn frame(&self, index: usize) -> Option<Rect> {
+        self.frames.get(index).copied()
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// Rect of the frame called `name`.
+    pub fn get(&self, name: &str) -> Option<Rect> {
+        self.index_of(name).and_then(|i| self.frame(i))
+    }
+
+    /// Alpha-blend frame `index` with its top-left corner at `(x, y)`. Unknown frames draw
+    /// nothing.
+    pub fn draw<D: PixelFormat>(&self, dst: &mut Surface<D>, index: usize, x: i32, y: i32) {
+        let opts = BlitOptions::new().with_mode(BlendMode::Alpha);
+        self.draw_ex(dst, index, x, y, &opts);
+    }
+
+    /// Draw frame `index` with explicit blend mode, flips and rotation.
+    pub fn draw_ex<D: PixelFormat>(
+        &self,
+        dst: &mut Surface<D>,
+        index: usize,
+        x: i32,
+        y: i32,
+        opts: &BlitOptions,
+    ) {
+        if let Some(rect) = self.frame(index) {
+            blit::blit_ex(&self.surface, rect, dst, x, y, opts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Color;
+
+    const RED: Color = Color::rgba(255, 0, 0, 255);
+
+    #[test]
+    fn grid_slices_row_major_and_drops_partial_cells() {
+        let sheet = SpriteSheet::from_grid(Surface::new(50, 20), 16, 8);
+        assert_eq!(sheet.len(), 3 * 2);
+        assert_eq!(sheet.frame(4), Some(Rect::new(16, 8, 16, 8)));
+        assert_eq!(sheet.frame(6), None);
+        assert!(SpriteSheet::from_grid(Surface::new(4, 4), 0, 2).is_empty());
+    }
+
+    #[test]
+    fn manifest_round_trips_and_reports_bad_lines() {
+        let text = "# hero\nidle 0 0 8 8\n\nrun  8 0 8 8\n";
+        let sheet = SpriteSheet::from_manifest(Surface::new(16, 8), text).unwrap();
+        assert_eq!(sheet.get("run"), Some(Rect::new(8, 0, 8, 8)));
+        assert_eq!(sheet.index_of("idle"), Some(0));
+        assert_eq!(sheet.to_manifest(), "idle 0 0 8 8\nrun 8 0 8 8\n");
+
+        for bad in ["a 0 0 8", "a 0 0 x 8", "a 10 0 8 8", "a 0 0 4 4\na 4 0 4 4"] {
+            let err = SpriteSheet::from_manifest(Surface::new(16, 8), bad).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+        let err = SpriteSheet::from_manifest(Surface::new(16, 8), "\n\nb 0").unwrap_err();
+        assert!(err.to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn draw_blends_and_flips_frames() {
+        // Frame 0: left pixel red, right pixel half-transparent.
+        let mut src = Surface::new(4, 1);
+        src.set_pixel(0, 0, RED);
+        src.set_pixel(1, 0, Color::rgba(0, 0, 255, 128));
+        let sheet = SpriteSheet::from_grid(src, 2, 1);
+
+        let bg = Color::rgba(0, 0, 0, 255);
+        let mut dst = Surface::new(2, 2);
+        dst.clear(bg);
+        sheet.draw(&mut dst, 0, 0, 0);
+        assert_eq!(dst.get_pixel(0, 0), Some(RED));
+        assert_eq!(dst.get_pixel(1, 0), Some(Color::rgba(0, 0, 128, 255)));
+
+        let flipped = BlitOptions::new().with_flip(true, false);
+        sheet.draw_ex(&mut dst, 0, 0, 1, &flipped);
+        assert_eq!(dst.get_pixel(1, 1), Some(RED));
+        assert_eq!(dst.get_pixel(0, 1), Some(Color::rgba(0, 0, 255, 128)));
+        sheet.draw(&mut dst, 9, 0, 0);
+    }
+}
@@ -0,0 +1,456 @@
+//! Terminal preview using ANSI escape sequences.
+//!
+//! Each text cell shows two pixels with the upper half block `▀`: the foreground color paints
+//! the top pixel and the background color the bottom one. Colors are sent as 24-bit SGR
+//! codes or quantized to the xterm 256-color or 16-color palettes. Alpha is composited over
+//! a background color, since the terminal's own background is unknown.
+//!
+//! `to_ansi` produces a still image for printing; `TermRenderer` redraws live frames in
+//! place, re-emitting only the cells that changed since the previous frame.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::core::{Color, Surface};
+use crate::format::{Indexed, PixelFormat};
+use crate::resample::ResizeKernel;
+
+/// How colors are encoded.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// 24-bit `38;2;r;g;b` codes.
+    #[default]
+    TrueColor,
+    /// xterm 256-color palette: the 6x6x6 cube plus the 24-step gray ramp.
+    Ansi256,
+    /// The 16 basic colors, assuming xterm's default palette.
+    Ansi16,
+}
+
+impl ColorMode {
+    /// Guess from the environment: `COLORTERM=truecolor|24bit` gives `TrueColor`, a `TERM`
+    /// containing `256color` gives `Ansi256`, anything else `Ansi16`.
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorMode::TrueColor;
+        }
+        let term = std::env::var("TERM").unwrap_or_default();
+        if term.contains("256color") {
+            ColorMode::Ansi256
+        } else {
+            ColorMode::Ansi16
+        }
+    }
+}
+
+/// Terminal output options. Defaults: true color, no size limit, box downscaling, black
+/// background.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TermOptions {
+    pub mode: ColorMode,
+    /// Largest output in text cells `(columns, rows)`. Larger surfaces are shrunk to fit,
+    /// keeping their aspect ratio; smaller ones are never enlarged.
+    pub fit: Option<(usize, usize)>,
+    /// Resampling kernel used when shrinking.
+    pub kernel: ResizeKernel,
+    /// Color that translucent pixels are composited over.
+    pub background: Color,
+}
+
+impl Default for TermOptions {
+    fn default() -> Self {
+        Self {
+            mode: ColorMode::TrueColor,
+            fit: None,
+            kernel: ResizeKernel::Box,
+            background: Color::rgba(0, 0, 0, 255),
+        }
+    }
+}
+
+impl TermOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mode(mut self, mode: ColorMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_fit(mut self, columns: usize, rows: usize) -> Self {
+        self.fit = Some((columns, rows));
+        self
+    }
+
+    pub fn with_kernel(mut self, kernel: ResizeKernel) -> Self {
+        self.kernel = kernel;
+        self
+    }
+
+    pub fn with_background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+}
+
+/// Terminal size in `(columns, rows)` from the `COLUMNS` and `LINES` environment variables.
+/// `None` when either is missing or not a number; many shells do not export them, so callers
+/// usually fall back to a fixed size or query the terminal themselves (see the
+/// `term_preview` example).
+pub fn terminal_size() -> Option<(usize, usize)> {
+    size_from_env(|k| std::env::var(k).ok())
+}
+
+fn size_from_env(var: impl Fn(&str) -> Option<String>) -> Option<(usize, usize)> {
+    let num = |k: &str| var(k).and_then(|v| v.trim().parse::<usize>().ok());
+    Some((num("COLUMNS")?, num("LINES")?))
+}
+
+/// Render a still image as lines of half blocks, each ending in a reset and a newline.
+pub fn to_ansi<P: PixelFormat>(surface: &Surface<P>, opts: &TermOptions) -> String {
+    let grid = Grid::new(surface, opts);
+    let mut out = String::new();
+    for row in 0..grid.rows {
+        let mut last = None;
+        for col in 0..grid.cols {
+            let cell = grid.cells[row * grid.cols + col];
+            cell.write_sgr(last, &mut out);
+            out.push('▀');
+            last = Some(cell);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// Live preview that redraws in place from the top-left corner of the terminal.
+#[derive(Clone, Debug, Default)]
+pub struct TermRenderer {
+    opts: TermOptions,
+    prev: Option<Grid>,
+}
+
+impl TermRenderer {
+    pub fn new(opts: TermOptions) -> Self {
+        Self { opts, prev: None }
+    }
+
+    pub fn options(&self) -> &TermOptions {
+        &self.opts
+    }
+
+    /// Draw a frame. The first frame (or one whose cell grid changed size) hides the cursor,
+    /// clears the screen and draws every cell; later frames only move to and repaint cells
+    /// whose colors changed. Returns the number of cells written.
+    pub fn draw<P: PixelFormat>(
+        &mut self,
+        surface: &Surface<P>,
+        out: &mut impl Write,
+    ) -> io::Result<usize> {
+        let grid = Grid::new(surface, &self.opts);
+        let prev = self
+            .prev
+            .as_ref()
+            .filter(|p| p.cols == grid.cols && p.rows == grid.rows);
+        let mut buf = String::new();
+        if prev.is_none() {
+            buf.push_str("\x1b[?25l\x1b[2J");
+        }
+        let mut written = 0;
+        let mut cursor = None;
+        let mut last = None;
+        for row in 0..grid.rows {
+            for col in 0..grid.cols {
+                let i = row * grid.cols + col;
+                let cell = grid.cells[i];
+                if prev.is_some_and(|p| p.cells[i] == cell) {
+                    continue;
+                }
+                if cursor != Some((row, col)) {
+                    let _ = write!(buf, "\x1b[{};{}H", row + 1, col + 1);
+                }
+                cell.write_sgr(last, &mut buf);
+                buf.push('▀');
+                cursor = Some((row, col + 1));
+                last = Some(cell);
+                written += 1;
+            }
+        }
+        if written > 0 {
+            buf.push_str("\x1b[0m");
+        }
+        out.write_all(buf.as_bytes())?;
+        out.flush()?;
+        self.prev = Some(grid);
+        Ok(written)
+    }
+
+    /// Force the next `draw` to repaint everything, e.g. after other output scrolled the
+    /// screen.
+    pub fn invalidate(&mut self) {
+        self.prev = None;
+    }
+
+    /// Reset colors, move the cursor below the last frame and show it again.
+    pub fn finish(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let rows = self.prev.take().map_or(0, |g| g.rows);
+        write!(out, "\x1b[0m\x1b[{};1H\x1b[?25h", rows + 1)?;
+        out.flush()
+    }
+}
+
+/// One encoded color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Ink {
+    Rgb(u8, u8, u8),
+    Ansi256(u8),
+    Ansi16(u8),
+}
+
+impl Ink {
+    fn write_sgr(self, background: bool, out: &mut String) {
+        let layer = if background { 48 } else { 38 };
+        let _ = match self {
+            Ink::Rgb(r, g, b) => write!(out, "\x1b[{layer};2;{r};{g};{b}m"),
+            Ink::Ansi256(n) => write!(out, "\x1b[{layer};5;{n}m"),
+            Ink::Ansi16(n) => {
+                let base = if n < 8 { 30 + n } else { 90 + n - 8 };
+                write!(out, "\x1b[{}m", base + if background { 10 } else { 0 })
+            }
+        };
+    }
+}
+
+/// Top pixel as foreground, bottom pixel as background.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Cell {
+    fg: Ink,
+    bg: Ink,
+}
+
+impl Cell {
+    /// SGR codes for whichever of the colors differ from the previous cell's.
+    fn write_sgr(self, last: Option<Cell>, out: &mut String) {
+        if last.is_none_or(|l| l.fg != self.fg) {
+            self.fg.write_sgr(false, out);
+        }
+        if last.is_none_or(|l| l.bg != self.bg) {
+            self.bg.write_sgr(true, out);
+        }
+    }
+}
+
+/// A surface encoded as half-block cells.
+#[derive(Clone, Debug)]
+struct Grid {
+    cols: usize,
+    rows: usize,
+    cells: Vec<Cell>,
+}
+
+impl Grid {
+    fn new<P: PixelFormat>(surface: &Surface<P>, opts: &TermOptions) -> Self {
+        let (w, h) = fitted_size(surface.width(), surface.height(), opts.fit);
+        let scaled;
+        let src = if (w, h) == (surface.width(), surface.height()) {
+            surface
+        } else {
+            scaled = surface.resize(w, h, opts.kernel);
+            &scaled
+        };
+        let xterm16 = Indexed::new(XTERM16);
+        let pixel = |x: usize, y: usize| {
+            let c = src.get_pixel(x as i32, y as i32).unwrap_or(opts.background);
+            let c = flatten(c, opts.background);
+            match opts.mode {
+                ColorMode::TrueColor => Ink::Rgb(c.r, c.g, c.b),
+                ColorMode::Ansi256 => Ink::Ansi256(ansi256(c)),
+                ColorMode::Ansi16 => Ink::Ansi16(xterm16.nearest(c)),
+            }
+        };
+        let rows = h.div_ceil(2);
+        let mut cells = Vec::with_capacity(w * rows);
+        for row in 0..rows {
+            for x in 0..w {
+                cells.push(Cell {
+                    fg: pixel(x, row * 2),
+                    bg: pixel(x, row * 2 + 1),
+                });
+            }
+        }
+        Self {
+            cols: w,
+            rows,
+            cells,
+        }
+    }
+}
+
+/// Pixel size that fits `fit` cells (two pixel rows per cell), never enlarging.
+fn fitted_size(w: usize, h: usize, fit: Option<(usize, usize)>) -> (usize, usize) {
+    let Some((cols, rows)) = fit else {
+        return (w, h);
+    };
+    let (max_w, max_h) = (cols.max(1), (rows * 2).max(1));
+    if w <= max_w && h <= max_h {
+        return (w, h);
+    }
+    let scale = (max_w as f64 / w as f64).min(max_h as f64 / h as f64);
+    let fit = |v: usize, max: usize| ((v as f64 * scale).round() as usize).clamp(1, max);
+    (fit(w, max_w), fit(h, max_h))
+}
+
+/// Composite straight-alpha `c` over opaque `bg`.
+fn flatten(c: Color, bg: Color) -> Color {
+    let a = c.a as u32;
+    let mix = |s: u8, d: u8| ((s as u32 * a + d as u32 * (255 - a) + 127) / 255) as u8;
+    Color::rgba(mix(c.r, bg.r), mix(c.g, bg.g), mix(c.b, bg.b), 255)
+}
+
+/// Nearest xterm 256-color index: best of the closest cube color and closest gray step.
+fn ansi256(c: Color) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let level = |v: u8| (0..6).min_by_key(|&i| LEVELS[i].abs_diff(v)).unwrap_or(0);
+    let (r, g, b) = (level(c.r), level(c.g), level(c.b));
+    let cube = Color::rgba(LEVELS[r], LEVELS[g], LEVELS[b], 255);
+    let avg = (c.r as u32 + c.g as u32 + c.b as u32) / 3;
+    let step = (avg.saturating_sub(3) / 10).min(23) as u8;
+    let gv = 8 + step * 10;
+    let gray = Color::rgba(gv, gv, gv, 255);
+    let dist = |a: Color| {
+        let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2);
+        d(a.r, c.r) + d(a.g, c.g) + d(a.b, c.b)
+    };
+    if dist(gray) < dist(cube) {
+        232 + step
+    } else {
+        16 + 36 * r as u8 + 6 * g as u8 + b as u8
+    }
+}
+
+/// xterm's default values for the 16 basic colors.
+const XTERM16: [Color; 16] = [
+    Color::rgba(0, 0, 0, 255),
+    Color::rgba(205, 0, 0, 255),
+    Color::rgba(0, 205, 0, 255),
+    Color::rgba(205, 205, 0, 255),
+    Color::rgba(0, 0, 238, 255),
+    Color::rgba(205, 0, 205, 255),
+    Color::rgba(0, 205, 205, 255),
+    Color::rgba(229, 229, 229, 255),
+    Color::rgba(127, 127, 127, 255),
+    Color::rgba(255, 0, 0, 255),
+    Color::rgba(0, 255, 0, 255),
+    Color::rgba(255, 255, 0, 255),
+    Color::rgba(92, 92, 255, 255),
+    Color::rgba(255, 0, 255, 255),
+    Color::rgba(0, 255, 255, 255),
+    Color::rgba(255, 255, 255, 255),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color::rgba(255, 0, 0, 255);
+    const BLUE: Color = Color::rgba(0, 0, 255, 255);
+
+    #[test]
+    fn size_needs_both_variables() {
+        let env = |c: Option<&str>, l: Option<&str>| {
+            size_from_env(move |k| match k {
+                "COLUMNS" => c.map(String::from),
+                "LINES" => l.map(String::from),
+                _ => None,
+            })
+        };
+        assert_eq!(env(Some("120"), Some(" 40\n")), Some((120, 40)));
+        assert_eq!(env(Some("120"), None), None);
+        assert_eq!(env(Some("wide"), Some("40")), None);
+    }
+
+    #[test]
+    fn half_blocks_pair_rows_and_pad_odd_heights() {
+        let mut s = Surface::new(2, 3);
+        s.clear(RED);
+        s.fill_span(0, 2, 1, BLUE);
+        s.set_pixel(1, 2, Color::rgba(0, 0, 0, 0));
+        let text = to_ansi(&s, &TermOptions::new());
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        // Colors are only re-sent when they change within a line.
+        assert_eq!(lines[0], "\x1b[38;2;255;0;0m\x1b[48;2;0;0;255m▀▀\x1b[0m");
+        // Missing bottom row and the transparent pixel both show the background.
+        let bg = TermOptions::new().with_background(Color::rgba(9, 9, 9, 255));
+        let text = to_ansi(&s, &bg);
+        assert!(
+            text.lines()
+                .nth(1)
+                .unwrap()
+                .ends_with("\x1b[38;2;255;0;0m\x1b[48;2;9;9;9m▀\x1b[38;2;9;9;9m▀\x1b[0m")
+        );
+    }
+
+    #[test]
+    fn palette_fallbacks() {
+        assert_eq!(ansi256(Color::rgba(255, 0, 0, 255)), 196);
+        assert_eq!(ansi256(Color::rgba(0, 0, 0, 255)), 16);
+        assert_eq!(ansi256(Color::rgba(128, 128, 128, 255)), 244);
+        assert_eq!(ansi256(Color::rgba(95, 135, 175, 255)), 67);
+        // Bright red over dark blue in 16 colors; mid gray on the 256-color ramp.
+        let mut s = Surface::new(1, 2);
+        s.set_pixel(0, 0, RED);
+        s.set_pixel(0, 1, Color::rgba(0, 0, 150, 255));
+        let ansi16 = to_ansi(&s, &TermOptions::new().with_mode(ColorMode::Ansi16));
+        assert_eq!(ansi16, "\x1b[91m\x1b[44m▀\x1b[0m\n");
+        s.clear(Color::rgba(120, 120, 120, 255));
+        let ansi256 = to_ansi(&s, &TermOptions::new().with_mode(ColorMode::Ansi256));
+        assert_eq!(ansi256, "\x1b[38;5;243m\x1b[48;5;243m▀\x1b[0m\n");
+    }
+
+    #[test]
+    fn fit_shrinks_keeping_aspect_but_never_enlarges() {
+        assert_eq!(fitted_size(256, 128, Some((80, 24))), (80, 40));
+        assert_eq!(fitted_size(100, 400, Some((80, 50))), (25, 100));
+        assert_eq!(fitted_size(10, 10, Some((80, 24))), (10, 10));
+        assert_eq!(fitted_size(10, 10, None), (10, 10));
+        let s = Surface::new(40, 40);
+        let text = to_ansi(&s, &TermOptions::new().with_fit(10, 100));
+        assert_eq!(text.lines().count(), 5);
+    }
+
+    #[test]
+    fn renderer_only_repaints_changed_cells() {
+        let mut s = Surface::new(4, 4);
+        s.clear(RED);
+        let mut r = TermRenderer::new(TermOptions::new());
+        let mut out = Vec::new();
+        assert_eq!(r.draw(&s, &mut out).unwrap(), 8);
+        assert!(out.starts_with(b"\x1b[?25l\x1b[2J\x1b[1;1H"));
+
+        out.clear();
+        assert_eq!(r.draw(&s, &mut out).unwrap(), 0);
+        assert!(out.is_empty());
+
+        s.set_pixel(2, 3, BLUE);
+        s.set_pixel(3, 2, BLUE);
+        let text = {
+            out.clear();
+            assert_eq!(r.draw(&s, &mut out).unwrap(), 2);
+            String::from_utf8(out.clone()).unwrap()
+        };
+        // Row 2, column 3; the next cell follows without another cursor move.
+        assert_eq!(
+            text,
+            "\x1b[2;3H\x1b[38;2;255;0;0m\x1b[48;2;0;0;255m▀\x1b[38;2;0;0;255m\x1b[48;2;255;0;0m▀\x1b[0m"
+        );
+
+        out.clear();
+        r.finish(&mut out).unwrap();
+        assert_eq!(out, b"\x1b[0m\x1b[3;1H\x1b[?25h");
+        out.clear();
+        assert_eq!(r.draw(&s, &mut out).unwrap(), 8);
+    }
+}
@@ -0,0 +1,188 @@
+//! BDF (Glyph Bitmap Distribution Format 2.1) parser.
+//!
+//! Only what is needed for drawing is read: the font bounding box, `FONT_ASCENT` /
+//! `FONT_DESCENT` when present, and each glyph's `ENCODING`, `DWIDTH`, `BBX` and `BITMAP`.
+//! BDF measures glyph offsets up from the baseline; they are converted to offsets down from
+//! the top of the line.
+
+use std::io;
+
+use super::{BitmapFont, Glyph};
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("BDF: {msg}"))
+}
+
+fn ints<const N: usize>(args: &[&str]) -> io::Result<[i32; N]> {
+    let mut out = [0; N];
+    if args.len() < N {
+        return Err(invalid("missing numbers"));
+    }
+    for (o, a) in out.iter_mut().zip(args) {
+        *o = a.parse().map_err(|_| invalid("bad number"))?;
+    }
+    Ok(out)
+}
+
+/// Glyph fields collected between `STARTCHAR` and `ENDCHAR`.
+#[derive(Default)]
+struct Pending {
+    encoding: Option<i32>,
+    advance: Option<i32>,
+    bbx: Option<[i32; 4]>,
+    bits: Vec<u8>,
+}
+
+pub(super) fn parse(src: &str) -> io::Result<BitmapFont> {
+    if !src.trim_start().starts_with("STARTFONT") {
+        return Err(invalid("missing STARTFONT"));
+    }
+    let mut font_bbx: Option<[i32; 4]> = None;
+    let (mut ascent, mut descent) = (None, None);
+    let mut glyphs: Vec<Pending> = Vec::new();
+    let mut cur: Option<Pending> = None;
+    let mut in_bitmap = false;
+
+    for line in src.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(key) = parts.next() else {
+            continue;
+        };
+        let args: Vec<&str> = parts.collect();
+        if in_bitmap {
+            let g = cur
+                .as_mut()
+                .ok_or_else(|| invalid("BITMAP outside glyph"))?;
+            if key == "ENDCHAR" {
+                in_bitmap = false;
+                glyphs.push(cur.take().unwrap_or_default());
+                continue;
+            }
+            if key.len() % 2 != 0 {
+                return Err(invalid("odd-length bitmap row"));
+            }
+            for i in (0..key.len()).step_by(2) {
+                let byte = u8::from_str_radix(&key[i..i + 2], 16)
+                    .map_err(|_| invalid("bad bitmap hex"))?;
+                g.bits.push(byte);
+            }
+            continue;
+        }
+        match key {
+            "FONTBOUNDINGBOX" => font_bbx = Some(ints::<4>(&args)?),
+            "FONT_ASCENT" => ascent = Some(ints::<1>(&args)?[0]),
+            "FONT_DESCENT" => descent = Some(ints::<1>(&args)?[0]),
+            "STARTCHAR" => cur = Some(Pending::default()),
+            "ENCODING" => {
+                if let Some(g) = cur.as_mut() {
+                    g.encoding = Some(ints::<1>(&args)?[0]);
+                }
+            }
+            "DWIDTH" => {
+                if let Some(g) = cur.as_mut() {
+                    g.advance = Some(ints::<1>(&args)?[0]);
+                }
+            }
+            "BBX" => {
+                if let Some(g) = cur.as_mut() {
+                    g.bbx = Some(ints::<4>(&args)?);
+                }
+            }
+            "BITMAP" => {
+                if cur.is_none() {
+                    return Err(invalid("BITMAP outside glyph"));
+                }
+                in_bitmap = true;
+            }
+            "ENDCHAR" => {
+                if let Some(g) = cur.take() {
+                    glyphs.push(g);
+                }
+            }
+            _ => {}
+        }
+    }
+    if in_bitmap || cur.is_some() {
+        return Err(invalid("unterminated glyph"));
+    }
+
+    let [_, fh, _, fy] = font_bbx.ok_or_else(|| invalid("missing FONTBOUNDINGBOX"))?;
+    let ascent = ascent.unwrap_or(fh + fy);
+    let descent = descent.unwrap_or(-fy);
+    let mut font = BitmapFont::new(ascent + descent);
+    for g in glyphs {
+        let Some(c) = g
+            .encoding
+            .filter(|&e| e >= 0)
+            .and_then(|e| char::from_u32(e as u32))
+        else {
+            continue;
+        };
+        let [w, h, x, y] = g.bbx.or(font_bbx).unwrap_or_default();
+        let stride = (w.max(0) as usize).div_ceil(8);
+        if g.bits.len() != stride * h.max(0) as usize {
+            return Err(invalid("bitmap size does not match BBX"));
+        }
+        let advance = g.advance.unwrap_or(w);
+        font.insert(c, Glyph::new(w, h, x, ascent - (y + h), advance, g.bits));
+    }
+    Ok(font)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "STARTFONT 2.1
+FONT -test-tiny
+SIZE 4 75 75
+FONTBOUNDINGBOX 4 6 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 5
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR period
+ENCODING 46
+SWIDTH 500 0
+DWIDTH 2 0
+BBX 1 1 0 0
+BITMAP
+80
+ENDCHAR
+STARTCHAR T
+ENCODING 84
+DWIDTH 4 0
+BBX 3 5 0 0
+BITMAP
+E0
+40
+40
+40
+40
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn parses_metrics_and_bitmaps() {
+        let f = parse(SAMPLE).unwrap();
+        assert_eq!(f.line_height(), 6);
+        assert_eq!(f.len(), 2);
+        let dot = f.glyph('.').unwrap();
+        // Sitting on the baseline: 5 rows of ascent, 1-pixel glyph -> top at row 4
+        assert_eq!((dot.x, dot.y, dot.advance), (0, 4, 2));
+        let t = f.glyph('T').unwrap();
+        assert_eq!((t.width, t.height, t.y), (3, 5, 0));
+        assert!(t.pixel(0, 0) && t.pixel(2, 0) && t.pixel(1, 4) && !t.pixel(0, 1));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(parse("hello").is_err());
+        let truncated = SAMPLE.replace("40\nENDCHAR", "ENDCHAR");
+        assert!(parse(&truncated).is_err());
+        let open = SAMPLE.replace("ENDCHAR\nENDFONT", "ENDFONT");
+        assert!(parse(&open).is_err());
+    }
+}
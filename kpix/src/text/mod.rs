@@ -0,0 +1,510 @@
+//! Bitmap font text rendering.
+//!
+//! Text is laid out on a pen that starts at the top-left of each line and advances by every
+//! glyph's `advance`. `\n` starts a new line. Glyphs are drawn as solid runs of `scale` x
+//! `scale` blocks, so scaled text stays crisp and every write goes through `fill_rect`.
+
+mod bdf;
+mod font8x8;
+mod psf;
+pub mod ttf;
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::core::{Color, Surface};
+use crate::draw::fill_rect;
+use crate::format::PixelFormat;
+use crate::rect::Rect;
+
+pub use ttf::{GlyphCache, TtfFont, draw_text_ttf, measure_text_ttf};
+
+/// One glyph bitmap, 1 bit per pixel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Glyph {
+    pub width: i32,
+    pub height: i32,
+    /// Offset of the bitmap's top-left corner from the pen (top of the line).
+    pub x: i32,
+    pub y: i32,
+    /// Horizontal pen advance.
+    pub advance: i32,
+    /// Row-major bits, most significant bit leftmost, each row padded to whole bytes.
+    pub bits: Vec<u8>,
+}
+
+impl Glyph {
+    pub fn new(width: i32, height: i32, x: i32, y: i32, advance: i32, bits: Vec<u8>) -> Self {
+        Self {
+            width,
+            height,
+            x,
+            y,
+            advance,
+            bits,
+        }
+    }
+
+    /// Bytes per bitmap row.
+    #[inline]
+    pub fn stride(&self) -> usize {
+        (self.width.max(0) as usize).div_ceil(8)
+    }
+
+    /// Whether bitmap pixel `(x, y)` is set. Out-of-range pixels are unset.
+    pub fn pixel(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return false;
+        }
+        let byte = self.bits.get(y as usize * self.stride() + x as usize / 8);
+        byte.is_some_and(|&b| b & (0x80 >> (x % 8)) != 0)
+    }
+}
+
+/// A set of glyphs sharing one line height.
+#[derive(Clone, Debug, Default)]
+pub struct BitmapFont {
+    line_height: i32,
+    glyphs: HashMap<char, Glyph>,
+}
+
+impl BitmapFont {
+    /// Empty font; add glyphs with `insert`.
+    pub fn new(line_height: i32) -> Self {
+        Self {
+            line_height,
+            glyphs: HashMap::new(),
+        }
+    }
+
+    /// Built-in 8x8 font covering printable ASCII.
+    pub fn builtin_8x8() -> &'static BitmapFont {
+        font8x8::font_8x8()
+    }
+
+    /// Built-in 8x16 font: the 8x8 glyphs with every row doubled.
+    pub fn builtin_8x16() -> &'static BitmapFont {
+        font8x8::font_8x16()
+    }
+
+    /// Parse a BDF (Glyph Bitmap Distribution Format) text font.
+    pub fn from_bdf(src: &str) -> io::Result<Self> {
+        bdf::parse(src)
+    }
+
+    /// Parse a PC Screen Font, version 1 or 2.
+    pub fn from_psf(data: &[u8]) -> io::Result<Self> {
+        psf::parse(data)
+    }
+
+    /// Load a PSF or BDF file, detected by its leading bytes.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let data = std::fs::read(path)?;
+        if psf::is_psf(&data) {
+            return Self::from_psf(&data);
+        }
+        let src = std::str::from_utf8(&data).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidData, "font is neither PSF nor BDF")
+        })?;
+        Self::from_bdf(src)
+    }
+
+    #[inline]
+    pub fn line_height(&self) -> i32 {
+        self.line_height
+    }
+
+    pub fn insert(&mut self, c: char, glyph: Glyph) {
+        self.glyphs.insert(c, glyph);
+    }
+
+    /// Glyph for `c`, falling back to `'?'` when the font lacks it.
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    /// Unscaled width of a single line (no `\n` handling).
+    fn line_width(&self, line: &str) -> i32 {
+        line.chars()
+            .map(|c| self.glyph(c).map_or(0, |g| g.advance))
+            .sum()
+    }
+}
+
+/// Horizontal alignment of each line relative to the anchor or box.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Text layout options. Defaults: scale 1, left aligned, no extra line gap.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TextStyle {
+    /// Integer magnification (values below 1 are treated as 1).
+    pub scale: i32,
+    pub align: Align,
+    /// Extra pixels between lines, after scaling.
+    pub line_gap: i32,
+}
+
+impl TextStyle {
+    pub fn new() -> Self {
+        Self {
+            scale: 1,
+            align: Align::Left,
+            line_gap: 0,
+        }
+    }
+
+    pub fn with_scale(mut self, scale: i32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_line_gap(mut self, line_gap: i32) -> Self {
+        self.line_gap = line_gap;
+        self
+    }
+
+    fn scale(&self) -> i32 {
+        self.scale.max(1)
+    }
+
+    fn line_advance(&self, font: &BitmapFont) -> i32 {
+        font.line_height * self.scale() + self.line_gap
+    }
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draw `text` with the built-in 8x8 font, top-left at `(x, y)`.
+pub fn draw_text<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    x: i32,
+    y: i32,
+    text: &str,
+    color: Color,
+) {
+    draw_text_styled(
+        surface,
+        BitmapFont::builtin_8x8(),
+        x,
+        y,
+        text,
+        color,
+        &TextStyle::new(),
+    );
+}
+
+/// Size of `text` in the built-in 8x8 font.
+pub fn measure_text(text: &str) -> (i32, i32) {
+    measure_text_styled(BitmapFont::builtin_8x8(), text, &TextStyle::new())
+}
+
+/// Draw `text` with `font`. `x` is the left edge, center or right edge of every line
+/// depending on `style.align`; `y` is the top of the first line.
+pub fn draw_text_styled<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    font: &BitmapFont,
+    x: i32,
+    y: i32,
+    text: &str,
+    color: Color,
+    style: &TextStyle,
+) {
+    let step = style.line_advance(font);
+    for (i, line) in text.split('\n').enumerate() {
+        let w = font.line_width(line) * style.scale();
+        let x0 = match style.align {
+            Align::Left => x,
+            Align::Center => x - w / 2,
+            Align::Right => x - w,
+        };
+        draw_line(
+            surface,
+            font,
+            x0,
+            y + i as i32 * step,
+            line,
+            color,
+            style.scale(),
+        );
+    }
+}
+
+/// Width and height of the block `draw_text_styled` would cover.
+pub fn measure_text_styled(font: &BitmapFont, text: &str, style: &TextStyle) -> (i32, i32) {
+    let mut w = 0;
+    let mut lines = 0;
+    for line in text.split('\n') {
+        w = w.max(font.line_width(line) * style.scale());
+        lines += 1;
+    }
+    let h = lines * style.line_advance(font) - style.line_gap;
+    (w, h)
+}
+
+/// Break `text` into lines no wider than `max_width` pixels. Lines break at spaces; words that
+/// are too long on their own are split between characters. Explicit `\n` is kept.
+pub fn wrap_text(font: &BitmapFont, text: &str, max_width: i32, style: &TextStyle) -> Vec<String> {
+    let limit = max_width / style.scale();
+    let mut out = Vec::new();
+    for para in text.split('\n') {
+        let mut line = String::new();
+        let mut line_w = 0;
+        for word in para.split(' ').filter(|w| !w.is_empty()) {
+            let word_w = font.line_width(word);
+            let space_w = if line.is_empty() {
+                0
+            } else {
+                font.line_width(" ")
+            };
+            if line_w + space_w + word_w <= limit {
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                line_w += space_w + word_w;
+                continue;
+            }
+            if !line.is_empty() {
+                out.push(std::mem::take(&mut line));
+                line_w = 0;
+            }
+            for c in word.chars() {
+                let cw = font.glyph(c).map_or(0, |g| g.advance);
+                if line_w + cw > limit && !line.is_empty() {
+                    out.push(std::mem::take(&mut line));
+                    line_w = 0;
+                }
+                line.push(c);
+                line_w += cw;
+            }
+        }
+        out.push(line);
+    }
+    out
+}
+
+/// Wrap `text` to the width of `rect`, align each line inside it and clip to it.
+/// Returns the height of the wrapped text, which may exceed `rect.h`.
+pub fn draw_text_box<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    font: &BitmapFont,
+    rect: Rect,
+    text: &str,
+    color: Color,
+    style: &TextStyle,
+) -> i32 {
+    let lines = wrap_text(font, text, rect.w, style);
+    let x = match style.align {
+        Align::Left => rect.x,
+        Align::Center => rect.x + rect.w / 2,
+        Align::Right => rect.right(),
+    };
+    let joined = lines.join("\n");
+    surface.push_clip(rect);
+    draw_text_styled(surface, font, x, rect.y, &joined, color, style);
+    surface.pop_clip();
+    measure_text_styled(font, &joined, style).1
+}
+
+/// Draw one line of glyphs at pen `(x, y)`.
+fn draw_line<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    font: &BitmapFont,
+    mut x: i32,
+    y: i32,
+    line: &str,
+    color: Color,
+    scale: i32,
+) {
+    // Skip lines entirely above or below the clip.
+    let clip = surface.clip_rect();
+    if y >= clip.bottom() || y + font.line_height * scale <= clip.y {
+        return;
+    }
+    for c in line.chars() {
+        let Some(g) = font.glyph(c) else {
+            continue;
+        };
+        draw_glyph(surface, g, x + g.x * scale, y + g.y * scale, color, scale);
+        x += g.advance * scale;
+        if x >= clip.right() {
+            break;
+        }
+    }
+}
+
+/// Draw the set bits of `g` as horizontal runs of `scale`-sized blocks.
+fn draw_glyph<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    g: &Glyph,
+    x: i32,
+    y: i32,
+    color: Color,
+    scale: i32,
+) {
+    for gy in 0..g.height {
+        let mut gx = 0;
+        while gx < g.width {
+            if !g.pixel(gx, gy) {
+                gx += 1;
+                continue;
+            }
+            let start = gx;
+            while gx < g.width && g.pixel(gx, gy) {
+                gx += 1;
+            }
+            fill_rect(
+                surface,
+                x + start * scale,
+                y + gy * scale,
+                (gx - start) * scale,
+                scale,
+                color,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(s: &Surface) -> usize {
+        s.pixels().iter().filter(|&&p| p != 0).count()
+    }
+
+    #[test]
+    fn builtin_fonts_cover_printable_ascii() {
+        let f8 = BitmapFont::builtin_8x8();
+        let f16 = BitmapFont::builtin_8x16();
+        assert_eq!(f8.len(), 95);
+        assert_eq!((f8.line_height(), f16.line_height()), (8, 16));
+        // 'A' top row is 0x0C with bit 0 leftmost: pixels 2 and 3 are set
+        let a = f8.glyph('A').unwrap();
+        assert!(a.pixel(2, 0) && a.pixel(3, 0) && !a.pixel(1, 0) && !a.pixel(4, 0));
+        let a16 = f16.glyph('A').unwrap();
+        for y in 0..8 {
+            for x in 0..8 {
+                assert_eq!(a16.pixel(x, 2 * y), a.pixel(x, y));
+                assert_eq!(a16.pixel(x, 2 * y + 1), a.pixel(x, y));
+            }
+        }
+        // Missing glyphs fall back to '?'
+        assert_eq!(f8.glyph('\u{3042}'), f8.glyph('?'));
+    }
+
+    #[test]
+    fn measure_counts_lines_and_scale() {
+        assert_eq!(measure_text("FPS 60"), (48, 8));
+        assert_eq!(measure_text("ab\nlonger"), (48, 16));
+        let f = BitmapFont::builtin_8x8();
+        let style = TextStyle::new().with_scale(3).with_line_gap(2);
+        assert_eq!(measure_text_styled(f, "ab\ncd", &style), (48, 50));
+    }
+
+    #[test]
+    fn draw_scales_pixels() {
+        let mut a = Surface::new(8, 8);
+        draw_text(&mut a, 0, 0, "#", Color::rgba(255, 255, 255, 255));
+        let mut b = Surface::new(16, 16);
+        let style = TextStyle::new().with_scale(2);
+        let f = BitmapFont::builtin_8x8();
+        draw_text_styled(
+            &mut b,
+            f,
+            0,
+            0,
+            "#",
+            Color::rgba(255, 255, 255, 255),
+            &style,
+        );
+        assert!(lit(&a) > 0);
+        assert_eq!(lit(&b), lit(&a) * 4);
+        for y in 0..16 {
+            for x in 0..16 {
+                assert_eq!(b.get_pixel(x, y), a.get_pixel(x / 2, y / 2));
+            }
+        }
+    }
+
+    #[test]
+    fn alignment_moves_lines_relative_to_anchor() {
+        let f = BitmapFont::builtin_8x8();
+        let white = Color::rgba(255, 255, 255, 255);
+        let mut left = Surface::new(32, 8);
+        draw_text_styled(&mut left, f, 8, 0, "||", white, &TextStyle::new());
+        let mut right = Surface::new(32, 8);
+        let style = TextStyle::new().with_align(Align::Right);
+        draw_text_styled(&mut right, f, 24, 0, "||", white, &style);
+        assert_eq!(left.pixels(), right.pixels());
+        let mut center = Surface::new(32, 8);
+        let style = TextStyle::new().with_align(Align::Center);
+        draw_text_styled(&mut center, f, 16, 0, "||", white, &style);
+        assert_eq!(left.pixels(), center.pixels());
+    }
+
+    #[test]
+    fn wrap_breaks_at_spaces_and_splits_long_words() {
+        let f = BitmapFont::builtin_8x8();
+        let style = TextStyle::new();
+        assert_eq!(
+            wrap_text(f, "the quick brown fox", 80, &style),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(wrap_text(f, "abcdefg", 24, &style), vec!["abc", "def", "g"]);
+        assert_eq!(wrap_text(f, "a\n\nb", 80, &style), vec!["a", "", "b"]);
+        // Scale shrinks the usable width
+        assert_eq!(
+            wrap_text(f, "ab cd", 32, &style.with_scale(2)),
+            vec!["ab", "cd"]
+        );
+    }
+
+    #[test]
+    fn text_box_clips_to_rect() {
+        let f = BitmapFont::builtin_8x8();
+        let mut s = Surface::new(40, 40);
+        let rect = Rect::new(4, 4, 24, 12);
+        let h = draw_text_box(
+            &mut s,
+            f,
+            rect,
+            "MMM MMM MMM",
+            Color::rgba(255, 255, 255, 255),
+            &TextStyle::new(),
+        );
+        assert_eq!(h, 24);
+        for y in 0..40 {
+            for x in 0..40 {
+                if !rect.contains(x, y) {
+                    assert_eq!(s.get_pixel(x, y), Some(Color::from_u32(0)));
+                }
+            }
+        }
+        assert_eq!(s.clip_rect(), s.bounds());
+    }
+}
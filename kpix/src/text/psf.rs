@@ -0,0 +1,241 @@
+//! PC Screen Font (PSF1 and PSF2) parser, the console font format used by Linux.
+//!
+//! Glyphs are fixed-size cells. With a Unicode table each glyph maps to the code points listed
+//! for it; without one, glyph `i` is assumed to be code point `i`.
+
+use std::io;
+
+use super::{BitmapFont, Glyph};
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE512: u8 = 0x01;
+const PSF1_MODEHASTAB: u8 = 0x02;
+const PSF1_MODESEQ: u8 = 0x04;
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("PSF: {msg}"))
+}
+
+pub(super) fn is_psf(data: &[u8]) -> bool {
+    data.starts_with(&PSF1_MAGIC) || data.starts_with(&PSF2_MAGIC)
+}
+
+pub(super) fn parse(data: &[u8]) -> io::Result<BitmapFont> {
+    if data.starts_with(&PSF2_MAGIC) {
+        parse_psf2(data)
+    } else if data.starts_with(&PSF1_MAGIC) {
+        parse_psf1(data)
+    } else {
+        Err(invalid("bad magic"))
+    }
+}
+
+/// Per-glyph code points, one list per glyph.
+type UnicodeTable = Vec<Vec<char>>;
+
+fn parse_psf1(data: &[u8]) -> io::Result<BitmapFont> {
+    let [_, _, mode, charsize] = *data
+        .first_chunk::<4>()
+        .ok_or_else(|| invalid("truncated header"))?;
+    let count = if mode & PSF1_MODE512 != 0 { 512 } else { 256 };
+    let height = charsize as usize;
+    let glyph_end = 4 + count * height;
+    let glyphs = data
+        .get(4..glyph_end)
+        .ok_or_else(|| invalid("truncated glyphs"))?;
+    let table = if mode & (PSF1_MODEHASTAB | PSF1_MODESEQ) != 0 {
+        let raw = &data[glyph_end..];
+        let units: Vec<u16> = raw
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        Some(psf1_table(&units, count)?)
+    } else {
+        None
+    };
+    Ok(build(glyphs, count, 8, height, table))
+}
+
+/// PSF1 table: UCS-2 values per glyph, `0xFFFE` starts sequences (ignored), `0xFFFF` ends.
+fn psf1_table(units: &[u16], count: usize) -> io::Result<UnicodeTable> {
+    let mut table = vec![Vec::new(); count];
+    let mut it = units.iter();
+    for entry in table.iter_mut() {
+        let mut in_seq = false;
+        loop {
+            let &u = it
+                .next()
+                .ok_or_else(|| invalid("truncated unicode table"))?;
+            match u {
+                0xFFFF => break,
+                0xFFFE => in_seq = true,
+                _ if !in_seq => entry.extend(char::from_u32(u as u32)),
+                _ => {}
+            }
+        }
+    }
+    Ok(table)
+}
+
+fn parse_psf2(data: &[u8]) -> io::Result<BitmapFont> {
+    let word = |i: usize| -> io::Result<u32> {
+        let b = data
+            .get(i * 4..i * 4 + 4)
+            .ok_or_else(|| invalid("truncated header"))?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let header_size = word(2)? as usize;
+    let flags = word(3)?;
+    let count = word(4)? as usize;
+    let charsize = word(5)? as usize;
+    let height = word(6)? as usize;
+    let width = word(7)? as usize;
+    if width == 0 || height == 0 {
+        return Err(invalid("empty glyph size"));
+    }
+    if charsize != width.div_ceil(8) * height {
+        return Err(invalid("charsize does not match width and height"));
+    }
+    if count > data.len() / charsize {
+        return Err(invalid("glyph count exceeds data"));
+    }
+    let glyph_end = count
+        .checked_mul(charsize)
+        .and_then(|n| n.checked_add(header_size))
+        .ok_or_else(|| invalid("glyph data overflow"))?;
+    let glyphs = data
+        .get(header_size..glyph_end)
+        .ok_or_else(|| invalid("truncated glyphs"))?;
+    let table = if flags & PSF2_HAS_UNICODE_TABLE != 0 {
+        Some(psf2_table(&data[glyph_end..], count)?)
+    } else {
+        None
+    };
+    Ok(build(glyphs, count, width, height, table))
+}
+
+/// PSF2 table: UTF-8 strings per glyph, `0xFE` starts sequences (ignored), `0xFF` ends.
+fn psf2_table(raw: &[u8], count: usize) -> io::Result<UnicodeTable> {
+    let mut table = vec![Vec::new(); count];
+    let mut rest = raw;
+    for entry in table.iter_mut() {
+        let end = rest
+            .iter()
+            .position(|&b| b == 0xFF)
+            .ok_or_else(|| invalid("truncated unicode table"))?;
+        let singles = &rest[..end];
+        let singles = &singles[..singles.iter().position(|&b| b == 0xFE).unwrap_or(end)];
+        let s = std::str::from_utf8(singles).map_err(|_| invalid("bad UTF-8 in unicode table"))?;
+        entry.extend(s.chars());
+        rest = &rest[end + 1..];
+    }
+    Ok(table)
+}
+
+fn build(
+    glyphs: &[u8],
+    count: usize,
+    width: usize,
+    height: usize,
+    table: Option<UnicodeTable>,
+) -> BitmapFont {
+    let charsize = width.div_ceil(8) * height;
+    let mut font = BitmapFont::new(height as i32);
+    for i in 0..count {
+        let bits = glyphs[i * charsize..(i + 1) * charsize].to_vec();
+        let glyph = Glyph::new(width as i32, height as i32, 0, 0, width as i32, bits);
+        match &table {
+            Some(t) => {
+                for &c in &t[i] {
+                    font.insert(c, glyph.clone());
+                }
+            }
+            None => {
+                if let Some(c) = char::from_u32(i as u32) {
+                    font.insert(c, glyph);
+                }
+            }
+        }
+    }
+    font
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn psf2(width: u32, height: u32, glyphs: &[&[u8]], table: Option<&[u8]>) -> Vec<u8> {
+        let charsize = width.div_ceil(8) * height;
+        let mut out = PSF2_MAGIC.to_vec();
+        let flags = table.is_some() as u32;
+        for v in [0, 32, flags, glyphs.len() as u32, charsize, height, width] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        for g in glyphs {
+            out.extend_from_slice(g);
+        }
+        out.extend_from_slice(table.unwrap_or_default());
+        out
+    }
+
+    #[test]
+    fn psf1_without_table_maps_index_to_code_point() {
+        let mut data = vec![0x36, 0x04, 0x00, 2];
+        data.resize(4 + 256 * 2, 0);
+        data[4 + 65 * 2] = 0x81; // 'A' row 0: leftmost and rightmost pixels
+        let f = parse(&data).unwrap();
+        assert_eq!(f.line_height(), 2);
+        let a = f.glyph('A').unwrap();
+        assert!(a.pixel(0, 0) && a.pixel(7, 0) && !a.pixel(1, 0) && !a.pixel(0, 1));
+    }
+
+    #[test]
+    fn psf1_table_maps_several_code_points() {
+        let mut data = vec![0x36, 0x04, PSF1_MODEHASTAB, 1];
+        data.resize(4 + 256, 0);
+        data[4] = 0xFF;
+        // glyph 0 -> 'x' and U+00D7, with a sequence that is ignored; others empty
+        for u in [0x78u16, 0xD7, 0xFFFE, 0x41, 0x301, 0xFFFF] {
+            data.extend_from_slice(&u.to_le_bytes());
+        }
+        for _ in 1..256 {
+            data.extend_from_slice(&0xFFFFu16.to_le_bytes());
+        }
+        let f = parse(&data).unwrap();
+        assert_eq!(f.len(), 2);
+        assert!(f.glyph('\u{D7}').unwrap().pixel(3, 0));
+    }
+
+    #[test]
+    fn psf2_wide_glyphs_and_utf8_table() {
+        // 10x2 glyph: 2 bytes per row
+        let g: &[u8] = &[0x80, 0x40, 0x00, 0x00];
+        let mut table = "é".as_bytes().to_vec();
+        table.push(0xFF);
+        let data = psf2(10, 2, &[g], Some(&table));
+        assert!(is_psf(&data));
+        let f = parse(&data).unwrap();
+        let e = f.glyph('é').unwrap();
+        assert_eq!((e.width, e.advance), (10, 10));
+        assert!(e.pixel(0, 0) && e.pixel(9, 0) && !e.pixel(8, 0));
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let data = psf2(8, 8, &[&[0; 8]], None);
+        assert!(parse(&data[..data.len() - 1]).is_err());
+        assert!(parse(&[0x36, 0x04, 0x00]).is_err());
+        assert!(parse(b"nope").is_err());
+    }
+
+    #[test]
+    fn rejects_zero_size_and_huge_count() {
+        assert!(parse(&psf2(0, 8, &[], None)).is_err());
+        assert!(parse(&psf2(8, 0, &[], None)).is_err());
+        let mut data = psf2(8, 8, &[&[0; 8]], None);
+        data[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse(&data).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+//! Glyph rasterization, caching and text drawing for TrueType fonts.
+
+use std::collections::HashMap;
+
+use kmath::Vec2;
+
+use super::TtfFont;
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+use crate::path::{FillRule, TOLERANCE, paint_span, rasterize};
+
+/// Anti-aliased coverage mask of one glyph at one size.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GlyphBitmap {
+    pub width: i32,
+    pub height: i32,
+    /// Offset of the bitmap's top-left corner from the pen on the baseline (y down).
+    pub left: i32,
+    pub top: i32,
+    /// Row-major coverage, 0..=255.
+    pub coverage: Vec<u8>,
+}
+
+/// Largest glyph bitmap, in pixels, that [`rasterize_glyph`] will allocate.
+const MAX_GLYPH_PIXELS: usize = 1 << 24;
+
+/// Rasterize glyph `gid` at size `px` (em height in pixels).
+///
+/// Glyphs whose bitmap would exceed 2^24 pixels come back empty.
+pub fn rasterize_glyph(font: &TtfFont, gid: u16, px: f32) -> GlyphBitmap {
+    let polys: Vec<Vec<Vec2>> = font
+        .glyph_path(gid, px, 0.0, 0.0)
+        .flatten(TOLERANCE)
+        .into_iter()
+        .map(|p| p.points)
+        .collect();
+    let mut min = Vec2::new(f32::INFINITY, f32::INFINITY);
+    let mut max = Vec2::new(f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for q in polys.iter().flatten() {
+        min = Vec2::new(min.x.min(q.x), min.y.min(q.y));
+        max = Vec2::new(max.x.max(q.x), max.y.max(q.y));
+    }
+    if !(min.x < max.x && min.y < max.y) {
+        return GlyphBitmap::default();
+    }
+    let (x0, y0) = (min.x.floor() as i32, min.y.floor() as i32);
+    let (x1, y1) = (max.x.ceil() as i32, max.y.ceil() as i32);
+    let (Some(w), Some(h)) = (x1.checked_sub(x0), y1.checked_sub(y0)) else {
+        return GlyphBitmap::default();
+    };
+    let size = (w as usize).checked_mul(h as usize);
+    let Some(size) = size.filter(|&n| n <= MAX_GLYPH_PIXELS) else {
+        return GlyphBitmap::default();
+    };
+    let mut coverage = vec![0u8; size];
+    rasterize(
+        &polys,
+        FillRule::NonZero,
+        true,
+        (x0, y0, x1, y1),
+        |y, xa, xb, a| {
+            let row = ((y - y0) * w) as usize;
+            coverage[row + (xa - x0) as usize..row + (xb - x0) as usize].fill(a);
+        },
+    );
+    GlyphBitmap {
+        width: w,
+        height: h,
+        left: x0,
+        top: y0,
+        coverage,
+    }
+}
+
+/// A font plus its rasterized glyphs, keyed by glyph index and pixel size.
+#[derive(Clone, Debug)]
+pub struct GlyphCache {
+    font: TtfFont,
+    glyphs: HashMap<(u16, u32), GlyphBitmap>,
+}
+
+impl GlyphCache {
+    pub fn new(font: TtfFont) -> Self {
+        Self {
+            font,
+            glyphs: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    pub fn font(&self) -> &TtfFont {
+        &self.font
+    }
+
+    /// Bitmap for `gid` at `px`, rasterized on first use.
+    pub fn glyph(&mut self, gid: u16, px: f32) -> &GlyphBitmap {
+        let font = &self.font;
+        self.glyphs
+            .entry((gid, px.to_bits()))
+            .or_insert_with(|| rasterize_glyph(font, gid, px))
+    }
+
+    /// Number of cached bitmaps.
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.glyphs.clear();
+    }
+}
+
+/// Draw `text` at size `px` with the top of the first line at `y`. Pen positions include
+/// kerning and are rounded to whole pixels per glyph; `\n` starts a new line.
+pub fn draw_text_ttf<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    cache: &mut GlyphCache,
+    x: i32,
+    y: i32,
+    text: &str,
+    px: f32,
+    color: Color,
+) {
+    let font = cache.font();
+    let scale = font.scale(px);
+    let ascent = font.ascent() as f32 * scale;
+    let line_h = font.line_height(px);
+    for (i, line) in text.split('\n').enumerate() {
+        let baseline = (y as f32 + ascent + i as f32 * line_h).round() as i32;
+        let mut pen = x as f32;
+        let mut prev = None;
+        for c in line.chars() {
+            let font = cache.font();
+            let gid = font.glyph_index(c);
+            if let Some(p) = prev {
+                pen += font.kerning(p, gid) as f32 * scale;
+            }
+            let advance = font.advance_width(gid) as f32 * scale;
+            let origin = pen.round() as i32;
+            let bm = cache.glyph(gid, px);
+            draw_bitmap(surface, bm, origin + bm.left, baseline + bm.top, color);
+            pen += advance;
+            prev = Some(gid);
+        }
+    }
+}
+
+/// Width (widest line) and height (lines times line height) of `text` at size `px`.
+pub fn measure_text_ttf(font: &TtfFont, text: &str, px: f32) -> (i32, i32) {
+    let scale = font.scale(px);
+    let mut width = 0.0f32;
+    let mut lines = 0;
+    for line in text.split('\n') {
+        let mut pen = 0.0;
+        let mut prev = None;
+        for c in line.chars() {
+            let gid = font.glyph_index(c);
+            if let Some(p) = prev {
+                pen += font.kerning(p, gid) as f32 * scale;
+            }
+            pen += font.advance_width(gid) as f32 * scale;
+            prev = Some(gid);
+        }
+        width = width.max(pen);
+        lines += 1;
+    }
+    (
+        width.ceil() as i32,
+        (lines as f32 * font.line_height(px)).ceil() as i32,
+    )
+}
+
+fn draw_bitmap<P: PixelFormat>(
+    surface: &mut Surface<P>,
+    bm: &GlyphBitmap,
+    x: i32,
+    y: i32,
+    color: Color,
+) {
+    let clip = surface.clip_rect();
+    let rows = (clip.y - y).max(0)..(clip.bottom() - y).min(bm.height);
+    for row in rows {
+        let line = &bm.coverage[(row * bm.width) as usize..((row + 1) * bm.width) as usize];
+        let mut col = 0;
+        while col < line.len() {
+            let a = line[col];
+            let start = col;
+            while col < line.len() && line[col] == a {
+                col += 1;
+            }
+            if a > 0 {
+                paint_span(surface, y + row, x + start as i32, x + col as i32, a, color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::ttf::tests::test_font;
+
+    fn cache() -> GlyphCache {
+        GlyphCache::new(TtfFont::from_bytes(test_font()).unwrap())
+    }
+
+    #[test]
+    fn square_glyph_is_fully_covered() {
+        let mut c = cache();
+        let bm = c.glyph(1, 10.0).clone();
+        assert_eq!((bm.width, bm.height, bm.left, bm.top), (5, 5, 0, -5));
+        assert!(bm.coverage.iter().all(|&a| a == 255));
+        // Blank glyph rasterizes to an empty bitmap
+        assert_eq!(c.glyph(0, 10.0).width, 0);
+        // So does a glyph too large to allocate
+        assert_eq!(c.glyph(1, 1.0e6).coverage.len(), 0);
+    }
+
+    #[test]
+    fn composite_glyph_has_gap_between_components() {
+        let mut c = cache();
+        let bm = c.glyph(3, 10.0);
+        assert_eq!((bm.width, bm.height), (11, 5));
+        let row = &bm.coverage[..11];
+        assert_eq!(row[5], 0);
+        assert!(row[..5].iter().chain(&row[6..]).all(|&a| a == 255));
+    }
+
+    #[test]
+    fn curved_glyph_has_partial_coverage() {
+        let mut c = cache();
+        let bm = c.glyph(2, 40.0);
+        assert!(bm.coverage.iter().any(|&a| a > 0 && a < 255));
+        // Between the square through the on-curve midpoints (10 px wide at this size) and the
+        // curve's bounding box (62.5..437.5 units = 15 px).
+        let area: u32 = bm.coverage.iter().map(|&a| a as u32).sum();
+        let filled = area as f32 / 255.0;
+        assert!(filled > 10.0 * 10.0 && filled < 15.0 * 15.0);
+        assert_eq!((bm.left, bm.width, bm.height), (2, 16, 16)); // 2.5..17.5 px, pixel aligned
+    }
+
+    #[test]
+    fn cache_reuses_bitmaps_per_size() {
+        let mut c = cache();
+        c.glyph(1, 10.0);
+        c.glyph(1, 10.0);
+        c.glyph(1, 12.0);
+        assert_eq!(c.len(), 2);
+        c.clear();
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn draw_places_glyphs_on_baseline_with_kerning() {
+        let mut c = cache();
+        let mut s = Surface::new(16, 12);
+        let white = Color::rgba(255, 255, 255, 255);
+        draw_text_ttf(&mut s, &mut c, 0, 0, "AA", 10.0, white);
+        // ascent 8 px: squares cover rows 3..8; second A starts at 6 - 1 (kerning) = 5
+        for y in 0..12 {
+            for x in 0..16 {
+                let inside = (3..8).contains(&y) && (0..10).contains(&x);
+                let expected = if inside { white } else { Color::from_u32(0) };
+                assert_eq!(s.get_pixel(x, y), Some(expected), "({x}, {y})");
+            }
+        }
+        assert_eq!(measure_text_ttf(c.font(), "AA", 10.0), (11, 10));
+        assert_eq!(measure_text_ttf(c.font(), "A\nAO", 10.0), (12, 20));
+    }
+}
@@ -0,0 +1,187 @@
+//! Character to glyph mapping (`cmap` formats 0, 4, 6 and 12).
+
+use super::{u16_at, u32_at};
+
+/// A supported `cmap` subtable: absolute offset and format.
+#[derive(Copy, Clone, Debug)]
+pub(super) struct Subtable {
+    offset: usize,
+    format: u16,
+}
+
+impl Subtable {
+    /// Pick the best Unicode subtable of the `cmap` table at `table`: full-repertoire
+    /// (format 12) first, then BMP, then the Mac Roman fallback.
+    pub(super) fn pick(data: &[u8], table: usize) -> Option<Self> {
+        let count = u16_at(data, table + 2)? as usize;
+        let mut best: Option<(u8, Self)> = None;
+        for i in 0..count {
+            let rec = table + 4 + i * 8;
+            let platform = u16_at(data, rec)?;
+            let encoding = u16_at(data, rec + 2)?;
+            let offset = table + u32_at(data, rec + 4)? as usize;
+            let Some(format) = u16_at(data, offset) else {
+                continue;
+            };
+            if !matches!(format, 0 | 4 | 6 | 12) {
+                continue;
+            }
+            let rank = match (platform, encoding) {
+                (3, 10) | (0, 4) | (0, 6) => 4,
+                (3, 1) | (0, 3) => 3,
+                (0, _) => 2,
+                (1, 0) => 1,
+                _ => continue,
+            };
+            if best.is_none_or(|(r, _)| rank > r) {
+                best = Some((rank, Self { offset, format }));
+            }
+        }
+        best.map(|(_, t)| t)
+    }
+
+    pub(super) fn lookup(&self, data: &[u8], c: u32) -> Option<u16> {
+        let t = self.offset;
+        match self.format {
+            0 => {
+                if c < 256 {
+                    data.get(t + 6 + c as usize).map(|&g| g as u16)
+                } else {
+                    None
+                }
+            }
+            4 => format4(data, t, c),
+            6 => {
+                let first = u16_at(data, t + 6)? as u32;
+                let count = u16_at(data, t + 8)? as u32;
+                let i = c.checked_sub(first).filter(|&i| i < count)?;
+                u16_at(data, t + 10 + i as usize * 2)
+            }
+            12 => format12(data, t, c),
+            _ => None,
+        }
+    }
+}
+
+/// Segment mapping to delta values (BMP only).
+fn format4(data: &[u8], t: usize, c: u32) -> Option<u16> {
+    if c > 0xFFFF {
+        return None;
+    }
+    let c = c as u16;
+    let seg_x2 = u16_at(data, t + 6)? as usize;
+    let ends = t + 14;
+    let starts = ends + seg_x2 + 2;
+    let deltas = starts + seg_x2;
+    let range_offsets = deltas + seg_x2;
+    // First segment whose end code is >= c.
+    let (mut lo, mut hi) = (0, seg_x2 / 2);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if u16_at(data, ends + mid * 2)? < c {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    let i = lo;
+    if i >= seg_x2 / 2 || u16_at(data, starts + i * 2)? > c {
+        return None;
+    }
+    let start = u16_at(data, starts + i * 2)?;
+    let delta = u16_at(data, deltas + i * 2)?;
+    let ro_at = range_offsets + i * 2;
+    let ro = u16_at(data, ro_at)? as usize;
+    if ro == 0 {
+        return Some(c.wrapping_add(delta));
+    }
+    let g = u16_at(data, ro_at + ro + (c - start) as usize * 2)?;
+    (g != 0).then(|| g.wrapping_add(delta))
+}
+
+/// Segmented coverage (full Unicode range).
+fn format12(data: &[u8], t: usize, c: u32) -> Option<u16> {
+    let groups = u32_at(data, t + 12)? as usize;
+    let (mut lo, mut hi) = (0, groups);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let g = t + 16 + mid * 12;
+        let (start, end) = (u32_at(data, g)?, u32_at(data, g + 4)?);
+        if c < start {
+            hi = mid;
+        } else if c > end {
+            lo = mid + 1;
+        } else {
+            let gid = u32_at(data, g + 8)?.checked_add(c - start)?;
+            return u16::try_from(gid).ok();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format12_groups_and_format6_range() {
+        // format 12 with groups [0x41..=0x43 -> 10], [0x1F600..=0x1F600 -> 20]
+        let mut d = Vec::new();
+        d.extend_from_slice(&12u16.to_be_bytes());
+        d.extend_from_slice(&[0; 10]);
+        d.extend_from_slice(&2u32.to_be_bytes());
+        for (s, e, g) in [(0x41u32, 0x43u32, 10u32), (0x1F600, 0x1F600, 20)] {
+            for v in [s, e, g] {
+                d.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        let t = Subtable {
+            offset: 0,
+            format: 12,
+        };
+        assert_eq!(t.lookup(&d, 0x42), Some(11));
+        assert_eq!(t.lookup(&d, 0x1F600), Some(20));
+        assert_eq!(t.lookup(&d, 0x44), None);
+
+        // A hostile start glyph id that overflows u32 maps to nothing instead of panicking.
+        let mut bad = d.clone();
+        bad[24..28].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(t.lookup(&bad, 0x41), None);
+        assert_eq!(t.lookup(&bad, 0x43), None);
+
+        // format 6: first code 0x30, two entries
+        let mut d = Vec::new();
+        for v in [6u16, 14, 0, 0x30, 2, 7, 9] {
+            d.extend_from_slice(&v.to_be_bytes());
+        }
+        let t = Subtable {
+            offset: 0,
+            format: 6,
+        };
+        assert_eq!(t.lookup(&d, 0x31), Some(9));
+        assert_eq!(t.lookup(&d, 0x32), None);
+        assert_eq!(t.lookup(&d, 0x2F), None);
+    }
+
+    #[test]
+    fn format4_range_offset_indexes_glyph_array() {
+        // One real segment 'a'..='c' through glyphIdArray, plus the terminator.
+        let mut d = Vec::new();
+        let words: [u16; 7] = [4, 0, 0, 4, 0, 0, 0];
+        for v in words {
+            d.extend_from_slice(&v.to_be_bytes());
+        }
+        for v in [0x63u16, 0xFFFF, 0, 0x61, 0xFFFF, 0, 1, 4, 0, 5, 6, 0] {
+            // ends, pad, starts, deltas, rangeOffsets (4 -> glyphIdArray), glyphIdArray
+            d.extend_from_slice(&v.to_be_bytes());
+        }
+        let t = Subtable {
+            offset: 0,
+            format: 4,
+        };
+        assert_eq!(t.lookup(&d, 'a' as u32), Some(5));
+        assert_eq!(t.lookup(&d, 'b' as u32), Some(6));
+        assert_eq!(t.lookup(&d, 'c' as u32), None); // glyph 0 entry
+        assert_eq!(t.lookup(&d, 'z' as u32), None);
+    }
+}
@@ -0,0 +1,303 @@
+//! `glyf` outline decoding: simple glyphs and (nested) composite glyphs.
+
+use std::io;
+
+use super::{TtfFont, i16_at, invalid, u16_at};
+
+/// Outline point in font units, y up.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(super) struct Point {
+    pub x: f32,
+    pub y: f32,
+    pub on: bool,
+}
+
+pub(super) type Contour = Vec<Point>;
+
+/// Composite nesting limit, guarding against reference cycles.
+const MAX_DEPTH: u32 = 8;
+
+/// Points plus components one outline may decode, so composites that reference the same child
+/// many times at every level cannot fan out exponentially.
+const MAX_OUTLINE_COST: usize = 1 << 16;
+
+const ON_CURVE: u8 = 0x01;
+const X_SHORT: u8 = 0x02;
+const Y_SHORT: u8 = 0x04;
+const REPEAT: u8 = 0x08;
+const X_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_SAME_OR_POSITIVE: u8 = 0x20;
+
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const SCALED_COMPONENT_OFFSET: u16 = 0x0800;
+
+/// Contours of glyph `gid`; empty for blank glyphs.
+pub(super) fn outline(font: &TtfFont, gid: u16) -> io::Result<Vec<Contour>> {
+    let mut out = Vec::new();
+    let mut budget = MAX_OUTLINE_COST;
+    match decode(font, gid, 0, &mut out, &mut budget) {
+        Some(()) => Ok(out),
+        None if budget == 0 => Err(invalid("glyph outline too complex")),
+        None => Err(invalid("malformed glyph")),
+    }
+}
+
+/// Take `cost` from `budget`, emptying it when there is not enough left.
+fn spend(budget: &mut usize, cost: usize) -> Option<()> {
+    match budget.checked_sub(cost) {
+        Some(left) => {
+            *budget = left;
+            Some(())
+        }
+        None => {
+            *budget = 0;
+            None
+        }
+    }
+}
+
+fn decode(
+    font: &TtfFont,
+    gid: u16,
+    depth: u32,
+    out: &mut Vec<Contour>,
+    budget: &mut usize,
+) -> Option<()> {
+    let data = font.glyph_data(gid)?;
+    if data.is_empty() {
+        return Some(());
+    }
+    let contours = i16_at(data, 0)?;
+    if contours >= 0 {
+        simple(data, contours as usize, out, budget)
+    } else if depth < MAX_DEPTH {
+        composite(font, data, depth, out, budget)
+    } else {
+        None
+    }
+}
+
+fn simple(data: &[u8], contours: usize, out: &mut Vec<Contour>, budget: &mut usize) -> Option<()> {
+    let mut ends = Vec::with_capacity(contours);
+    for i in 0..contours {
+        let e = u16_at(data, 10 + i * 2)? as usize;
+        if ends.last().is_some_and(|&l| e <= l) {
+            return None;
+        }
+        ends.push(e);
+    }
+    let n = ends.last().map_or(0, |&e| e + 1);
+    spend(budget, n)?;
+    let insn_len = u16_at(data, 10 + contours * 2)? as usize;
+    let mut p = 12 + contours * 2 + insn_len;
+
+    let mut flags = Vec::with_capacity(n);
+    while flags.len() < n {
+        let f = *data.get(p)?;
+        p += 1;
+        flags.push(f);
+        if f & REPEAT != 0 {
+            let r = *data.get(p)?;
+            p += 1;
+            flags.extend(std::iter::repeat_n(f, r as usize));
+        }
+    }
+    flags.truncate(n);
+
+    let mut coords = |short: u8, same_or_pos: u8| -> Option<Vec<i32>> {
+        let mut v = 0i32;
+        let mut out = Vec::with_capacity(n);
+        for &f in &flags {
+            if f & short != 0 {
+                let d = *data.get(p)? as i32;
+                p += 1;
+                v += if f & same_or_pos != 0 { d } else { -d };
+            } else if f & same_or_pos == 0 {
+                v += i16_at(data, p)? as i32;
+                p += 2;
+            }
+            out.push(v);
+        }
+        Some(out)
+    };
+    let xs = coords(X_SHORT, X_SAME_OR_POSITIVE)?;
+    let ys = coords(Y_SHORT, Y_SAME_OR_POSITIVE)?;
+
+    let mut start = 0;
+    for e in ends {
+        out.push(
+            (start..=e)
+                .map(|i| Point {
+                    x: xs[i] as f32,
+                    y: ys[i] as f32,
+                    on: flags[i] & ON_CURVE != 0,
+                })
+                .collect(),
+        );
+        start = e + 1;
+    }
+    Some(())
+}
+
+fn composite(
+    font: &TtfFont,
+    data: &[u8],
+    depth: u32,
+    out: &mut Vec<Contour>,
+    budget: &mut usize,
+) -> Option<()> {
+    let mut p = 10;
+    loop {
+        spend(budget, 1)?;
+        let flags = u16_at(data, p)?;
+        let gid = u16_at(data, p + 2)?;
+        p += 4;
+        let (arg1, arg2) = if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            let a = (u16_at(data, p)?, u16_at(data, p + 2)?);
+            p += 4;
+            if flags & ARGS_ARE_XY_VALUES != 0 {
+                (a.0 as i16 as i32, a.1 as i16 as i32)
+            } else {
+                (a.0 as i32, a.1 as i32)
+            }
+        } else {
+            let a = (*data.get(p)?, *data.get(p + 1)?);
+            p += 2;
+            if flags & ARGS_ARE_XY_VALUES != 0 {
+                (a.0 as i8 as i32, a.1 as i8 as i32)
+            } else {
+                (a.0 as i32, a.1 as i32)
+            }
+        };
+        let f2dot14 = |at: usize| i16_at(data, at).map(|v| v as f32 / 16384.0);
+        // Matrix [a c; b d] applied as x' = a*x + c*y, y' = b*x + d*y.
+        let (a, b, c, d) = if flags & WE_HAVE_A_SCALE != 0 {
+            let s = f2dot14(p)?;
+            p += 2;
+            (s, 0.0, 0.0, s)
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            let m = (f2dot14(p)?, f2dot14(p + 2)?);
+            p += 4;
+            (m.0, 0.0, 0.0, m.1)
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            let m = (
+                f2dot14(p)?,
+                f2dot14(p + 2)?,
+                f2dot14(p + 4)?,
+                f2dot14(p + 6)?,
+            );
+            p += 8;
+            m
+        } else {
+            (1.0, 0.0, 0.0, 1.0)
+        };
+
+        let mut child = Vec::new();
+        decode(font, gid, depth + 1, &mut child, budget)?;
+        for q in child.iter_mut().flatten() {
+            let (x, y) = (q.x, q.y);
+            q.x = a * x + c * y;
+            q.y = b * x + d * y;
+        }
+        let (dx, dy) = if flags & ARGS_ARE_XY_VALUES != 0 {
+            let (ox, oy) = (arg1 as f32, arg2 as f32);
+            if flags & SCALED_COMPONENT_OFFSET != 0 {
+                (a * ox + c * oy, b * ox + d * oy)
+            } else {
+                (ox, oy)
+            }
+        } else {
+            // Align child point `arg2` with the already placed point `arg1`.
+            let parent = out.iter().flatten().nth(arg1 as usize)?;
+            let own = child.iter().flatten().nth(arg2 as usize)?;
+            (parent.x - own.x, parent.y - own.y)
+        };
+        for q in child.iter_mut().flatten() {
+            q.x += dx;
+            q.y += dy;
+        }
+        out.extend(child);
+
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::ttf::tests::test_font;
+
+    #[test]
+    fn simple_square_points() {
+        let f = TtfFont::from_bytes(test_font()).unwrap();
+        let o = outline(&f, 1).unwrap();
+        assert_eq!(o.len(), 1);
+        let pts: Vec<(f32, f32, bool)> = o[0].iter().map(|p| (p.x, p.y, p.on)).collect();
+        assert_eq!(
+            pts,
+            vec![
+                (0.0, 0.0, true),
+                (0.0, 500.0, true),
+                (500.0, 500.0, true),
+                (500.0, 0.0, true)
+            ]
+        );
+        assert!(outline(&f, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn composite_offsets_components() {
+        let f = TtfFont::from_bytes(test_font()).unwrap();
+        let o = outline(&f, 3).unwrap();
+        assert_eq!(o.len(), 2);
+        assert_eq!(o[0], outline(&f, 1).unwrap()[0]);
+        assert!(
+            o[1].iter()
+                .zip(&o[0])
+                .all(|(b, a)| b.x == a.x + 600.0 && b.y == a.y)
+        );
+    }
+
+    #[test]
+    fn short_and_repeated_flags() {
+        // One contour of three points: flags use REPEAT and short/same encodings.
+        let mut g = vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0];
+        // flag 0: on, x short +, y same (0); repeated twice more
+        g.extend_from_slice(&[
+            ON_CURVE | X_SHORT | X_SAME_OR_POSITIVE | Y_SAME_OR_POSITIVE | REPEAT,
+            2,
+        ]);
+        g.extend_from_slice(&[10, 20, 5]); // x deltas
+        let (mut out, mut budget) = (Vec::new(), MAX_OUTLINE_COST);
+        simple(&g, 1, &mut out, &mut budget).unwrap();
+        let xs: Vec<f32> = out[0].iter().map(|p| p.x).collect();
+        assert_eq!(xs, vec![10.0, 30.0, 35.0]);
+        assert!(out[0].iter().all(|p| p.y == 0.0 && p.on));
+    }
+
+    #[test]
+    fn composite_fan_out_exhausts_budget() {
+        // 100 references to the 4-point square cost 500; a budget of 400 runs out.
+        let f = TtfFont::from_bytes(test_font()).unwrap();
+        let mut g = vec![0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0];
+        for i in 0..100 {
+            let more = if i < 99 { MORE_COMPONENTS } else { 0 };
+            g.extend_from_slice(&(more | ARGS_ARE_XY_VALUES).to_be_bytes());
+            g.extend_from_slice(&[0, 1, 0, 0]); // glyph 1 at offset (0, 0)
+        }
+        let mut out = Vec::new();
+        let mut budget = 500;
+        composite(&f, &g, 0, &mut out, &mut budget).unwrap();
+        assert_eq!((out.len(), budget), (100, 0));
+        let mut budget = 400;
+        assert!(composite(&f, &g, 0, &mut Vec::new(), &mut budget).is_none());
+        assert_eq!(budget, 0);
+    }
+}
@@ -0,0 +1,517 @@
+//! TrueType font support: table parsing, glyph outlines and cached glyph rasterization.
+//!
+//! Only TrueType (`glyf`) outlines are read; CFF-flavoured OpenType fonts are rejected. Outlines
+//! become `Path`s of quadratic curves in pixel space and are rasterized with the anti-aliased
+//! scanline filler from `path`. Kerning comes from the classic `kern` table (format 0).
+
+mod cache;
+mod cmap;
+mod glyf;
+
+pub use cache::{GlyphBitmap, GlyphCache, draw_text_ttf, measure_text_ttf, rasterize_glyph};
+
+use std::io;
+use std::ops::Range;
+use std::path::Path as FsPath;
+
+use crate::path::Path;
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("TTF: {msg}"))
+}
+
+fn u16_at(d: &[u8], o: usize) -> Option<u16> {
+    d.get(o..o + 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn i16_at(d: &[u8], o: usize) -> Option<i16> {
+    u16_at(d, o).map(|v| v as i16)
+}
+
+fn u32_at(d: &[u8], o: usize) -> Option<u32> {
+    d.get(o..o + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// A parsed TrueType font. Metrics are in font units unless a pixel size is passed.
+#[derive(Clone, Debug)]
+pub struct TtfFont {
+    data: Vec<u8>,
+    units_per_em: u16,
+    num_glyphs: u16,
+    long_loca: bool,
+    ascent: i16,
+    descent: i16,
+    line_gap: i16,
+    num_hmetrics: u16,
+    loca: Range<usize>,
+    glyf: Range<usize>,
+    hmtx: Range<usize>,
+    cmap: Option<cmap::Subtable>,
+    /// Pair list of the first horizontal format-0 `kern` subtable: (start, pair count).
+    kern: Option<(usize, usize)>,
+}
+
+impl TtfFont {
+    /// Parse a `.ttf` file, or the first font of a `.ttc` collection.
+    pub fn from_bytes(data: Vec<u8>) -> io::Result<Self> {
+        let truncated = || invalid("truncated table directory");
+        let mut base = 0usize;
+        let tag = u32_at(&data, 0).ok_or_else(truncated)?;
+        if &tag.to_be_bytes() == b"ttcf" {
+            base = u32_at(&data, 12).ok_or_else(truncated)? as usize;
+        }
+        match &u32_at(&data, base).ok_or_else(truncated)?.to_be_bytes() {
+            [0, 1, 0, 0] | b"true" => {}
+            b"OTTO" => return Err(invalid("CFF outlines are not supported")),
+            _ => return Err(invalid("not a TrueType font")),
+        }
+        let num_tables = u16_at(&data, base + 4).ok_or_else(truncated)? as usize;
+        let mut tables = Vec::with_capacity(num_tables);
+        for i in 0..num_tables {
+            let rec = base + 12 + i * 16;
+            let tag = data.get(rec..rec + 4).ok_or_else(truncated)?;
+            let off = u32_at(&data, rec + 8).ok_or_else(truncated)? as usize;
+            let len = u32_at(&data, rec + 12).ok_or_else(truncated)? as usize;
+            if off.checked_add(len).is_none_or(|end| end > data.len()) {
+                return Err(invalid("table extends past end of file"));
+            }
+            tables.push(([tag[0], tag[1], tag[2], tag[3]], off..off + len));
+        }
+        let find = |tag: &[u8; 4]| {
+            tables
+                .iter()
+                .find(|(t, _)| t == tag)
+                .map(|(_, r)| r.clone())
+        };
+        let require = |tag: &[u8; 4]| {
+            find(tag)
+                .ok_or_else(|| invalid(&format!("missing table {}", String::from_utf8_lossy(tag))))
+        };
+
+        let head = require(b"head")?.start;
+        let hhea = require(b"hhea")?.start;
+        let maxp = require(b"maxp")?.start;
+        let field = |v: Option<u16>| v.ok_or_else(|| invalid("truncated header table"));
+        let units_per_em = field(u16_at(&data, head + 18))?;
+        if units_per_em == 0 {
+            return Err(invalid("unitsPerEm is zero"));
+        }
+        let long_loca = field(u16_at(&data, head + 50))? != 0;
+        let num_glyphs = field(u16_at(&data, maxp + 4))?;
+        let ascent = field(u16_at(&data, hhea + 4))? as i16;
+        let descent = field(u16_at(&data, hhea + 6))? as i16;
+        let line_gap = field(u16_at(&data, hhea + 8))? as i16;
+        let num_hmetrics = field(u16_at(&data, hhea + 34))?;
+        let hmtx = require(b"hmtx")?;
+        if num_hmetrics == 0 || hmtx.len() < num_hmetrics as usize * 4 {
+            return Err(invalid("hmtx too short"));
+        }
+        let loca = require(b"loca")?;
+        let entry = if long_loca { 4 } else { 2 };
+        if loca.len() < (num_glyphs as usize + 1) * entry {
+            return Err(invalid("loca too short"));
+        }
+        let glyf = require(b"glyf")?;
+        let cmap = find(b"cmap").and_then(|r| cmap::Subtable::pick(&data, r.start));
+        let kern = find(b"kern").and_then(|r| kern_pairs(&data, r));
+        Ok(Self {
+            data,
+            units_per_em,
+            num_glyphs,
+            long_loca,
+            ascent,
+            descent,
+            line_gap,
+            num_hmetrics,
+            loca,
+            glyf,
+            hmtx,
+            cmap,
+            kern,
+        })
+    }
+
+    pub fn load(path: impl AsRef<FsPath>) -> io::Result<Self> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    #[inline]
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    #[inline]
+    pub fn num_glyphs(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    /// Distance from baseline to the top of the line (font units, positive).
+    #[inline]
+    pub fn ascent(&self) -> i16 {
+        self.ascent
+    }
+
+    /// Distance from baseline to the bottom of the line (font units, usually negative).
+    #[inline]
+    pub fn descent(&self) -> i16 {
+        self.descent
+    }
+
+    #[inline]
+    pub fn line_gap(&self) -> i16 {
+        self.line_gap
+    }
+
+    /// Pixels per font unit when the em square is `px` pixels tall.
+    #[inline]
+    pub fn scale(&self, px: f32) -> f32 {
+        px / self.units_per_em as f32
+    }
+
+    /// Baseline-to-baseline distance in pixels at size `px`.
+    pub fn line_height(&self, px: f32) -> f32 {
+        (self.ascent as f32 - self.descent as f32 + self.line_gap as f32) * self.scale(px)
+    }
+
+    /// Glyph index for `c`; 0 (`.notdef`) when unmapped.
+    pub fn glyph_index(&self, c: char) -> u16 {
+        self.cmap
+            .as_ref()
+            .and_then(|t| t.lookup(&self.data, c as u32))
+            .filter(|&g| g < self.num_glyphs)
+            .unwrap_or(0)
+    }
+
+    /// Horizontal advance of glyph `gid` in font units.
+    pub fn advance_width(&self, gid: u16) -> u16 {
+        let i = gid.min(self.num_hmetrics - 1) as usize;
+        u16_at(&self.data, self.hmtx.start + i * 4).unwrap_or(0)
+    }
+
+    /// Kerning adjustment between two glyphs in font units (0 when none).
+    pub fn kerning(&self, left: u16, right: u16) -> i16 {
+        let Some((start, count)) = self.kern else {
+            return 0;
+        };
+        let key = (left as u32) << 16 | right as u32;
+        let (mut lo, mut hi) = (0, count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let at = start + mid * 6;
+            let k = u32_at(&self.data, at).unwrap_or(u32::MAX);
+            match k.cmp(&key) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return i16_at(&self.data, at + 4).unwrap_or(0),
+            }
+        }
+        0
+    }
+
+    /// Outline of glyph `gid` at size `px` with its baseline origin at `(x, y)`, y down.
+    /// Empty for blank or malformed glyphs.
+    pub fn glyph_path(&self, gid: u16, px: f32, x: f32, y: f32) -> Path {
+        let s = self.scale(px);
+        let map = |p: glyf::Point| (x + p.x * s, y - p.y * s);
+        let mut path = Path::new();
+        for contour in glyf::outline(self, gid).unwrap_or_default() {
+            let n = contour.len();
+            if n == 0 {
+                continue;
+            }
+            let mid = |a: glyf::Point, b: glyf::Point| glyf::Point {
+                x: (a.x + b.x) * 0.5,
+                y: (a.y + b.y) * 0.5,
+                on: true,
+            };
+            // Start on an on-curve point, synthesizing one when every point is off-curve.
+            let (start, seq) = if contour[0].on {
+                (contour[0], &contour[1..])
+            } else if contour[n - 1].on {
+                (contour[n - 1], &contour[..n - 1])
+            } else {
+                (mid(contour[0], contour[n - 1]), &contour[..])
+            };
+            let (sx, sy) = map(start);
+            path.move_to(sx, sy);
+            let mut ctrl: Option<glyf::Point> = None;
+            for &q in seq {
+                match (q.on, ctrl) {
+                    (true, None) => {
+                        let (qx, qy) = map(q);
+                        path.line_to(qx, qy);
+                    }
+                    (true, Some(c)) => {
+                        let ((cx, cy), (qx, qy)) = (map(c), map(q));
+                        path.quad_to(cx, cy, qx, qy);
+                        ctrl = None;
+                    }
+                    (false, None) => ctrl = Some(q),
+                    (false, Some(c)) => {
+                        let ((cx, cy), (mx, my)) = (map(c), map(mid(c, q)));
+                        path.quad_to(cx, cy, mx, my);
+                        ctrl = Some(q);
+                    }
+                }
+            }
+            if let Some(c) = ctrl {
+                let ((cx, cy), (qx, qy)) = (map(c), map(start));
+                path.quad_to(cx, cy, qx, qy);
+            }
+            path.close();
+        }
+        path
+    }
+
+    /// Raw `glyf` bytes of glyph `gid` (empty for blank glyphs).
+    fn glyph_data(&self, gid: u16) -> Option<&[u8]> {
+        if gid >= self.num_glyphs {
+            return None;
+        }
+        let i = gid as usize;
+        let (a, b) = if self.long_loca {
+            let at = self.loca.start + i * 4;
+            (
+                u32_at(&self.data, at)? as usize,
+                u32_at(&self.data, at + 4)? as usize,
+            )
+        } else {
+            let at = self.loca.start + i * 2;
+            (
+                u16_at(&self.data, at)? as usize * 2,
+                u16_at(&self.data, at + 2)? as usize * 2,
+            )
+        };
+        if a > b || b > self.glyf.len() {
+            return None;
+        }
+        self.data.get(self.glyf.start + a..self.glyf.start + b)
+    }
+}
+
+/// Locate the pairs of the first usable `kern` subtable (horizontal, format 0, not minimum
+/// values, not cross-stream). Apple's version-1 `kern` header is not supported.
+fn kern_pairs(data: &[u8], table: Range<usize>) -> Option<(usize, usize)> {
+    let t = table.start;
+    if u16_at(data, t)? != 0 {
+        return None;
+    }
+    let n_tables = u16_at(data, t + 2)?;
+    let mut sub = t + 4;
+    for _ in 0..n_tables {
+        let len = u16_at(data, sub + 2)? as usize;
+        let coverage = u16_at(data, sub + 4)?;
+        let format = coverage >> 8;
+        if format == 0 && coverage & 0x0007 == 0x0001 {
+            let pairs = u16_at(data, sub + 6)? as usize;
+            let start = sub + 14;
+            if start + pairs * 6 > table.end {
+                return None;
+            }
+            return Some((start, pairs));
+        }
+        sub += len;
+    }
+    None
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Tiny test font: units_per_em 1000, ascent 800, descent -200, advances 600.
+    /// Glyphs: 0 empty `.notdef`, 1 `A` = 500x500 square, 2 `O` = quadratic diamond-circle,
+    /// 3 `B` = composite of two `A` squares 600 units apart. Kern pair (A, A) = -100.
+    pub(crate) fn test_font() -> Vec<u8> {
+        fn be16(v: &mut Vec<u8>, x: u16) {
+            v.extend_from_slice(&x.to_be_bytes());
+        }
+        fn be32(v: &mut Vec<u8>, x: u32) {
+            v.extend_from_slice(&x.to_be_bytes());
+        }
+        // Simple glyph from one contour of (x, y, on) points.
+        fn simple(points: &[(i16, i16, bool)]) -> Vec<u8> {
+            let mut g = Vec::new();
+            be16(&mut g, 1);
+            for _ in 0..4 {
+                be16(&mut g, 0); // bbox, unused by the parser
+            }
+            be16(&mut g, points.len() as u16 - 1);
+            be16(&mut g, 0); // no instructions
+            for &(_, _, on) in points {
+                g.push(on as u8);
+            }
+            let (mut px, mut py) = (0i16, 0i16);
+            for &(x, _, _) in points {
+                be16(&mut g, (x - px) as u16);
+                px = x;
+            }
+            for &(_, y, _) in points {
+                be16(&mut g, (y - py) as u16);
+                py = y;
+            }
+            g
+        }
+        let square = simple(&[
+            (0, 0, true),
+            (0, 500, true),
+            (500, 500, true),
+            (500, 0, true),
+        ]);
+        let circle = simple(&[
+            (250, 0, false),
+            (0, 250, false),
+            (250, 500, false),
+            (500, 250, false),
+        ]);
+        let mut composite = Vec::new();
+        be16(&mut composite, -1i16 as u16);
+        for _ in 0..4 {
+            be16(&mut composite, 0);
+        }
+        for (flags, dx) in [(0x0023u16, 0i16), (0x0003, 600)] {
+            // ARG_1_AND_2_ARE_WORDS | ARGS_ARE_XY_VALUES (| MORE_COMPONENTS)
+            be16(&mut composite, flags);
+            be16(&mut composite, 1);
+            be16(&mut composite, dx as u16);
+            be16(&mut composite, 0);
+        }
+        let glyphs = [Vec::new(), square, circle, composite];
+
+        let mut glyf = Vec::new();
+        let mut loca = Vec::new();
+        for g in &glyphs {
+            be32(&mut loca, glyf.len() as u32);
+            glyf.extend_from_slice(g);
+            if glyf.len() % 2 == 1 {
+                glyf.push(0);
+            }
+        }
+        be32(&mut loca, glyf.len() as u32);
+
+        let mut head = vec![0u8; 54];
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        head[50..52].copy_from_slice(&1u16.to_be_bytes()); // long loca
+        let mut hhea = vec![0u8; 36];
+        hhea[4..6].copy_from_slice(&800i16.to_be_bytes());
+        hhea[6..8].copy_from_slice(&(-200i16).to_be_bytes());
+        hhea[34..36].copy_from_slice(&(glyphs.len() as u16).to_be_bytes());
+        let mut maxp = vec![0u8; 6];
+        maxp[4..6].copy_from_slice(&(glyphs.len() as u16).to_be_bytes());
+        let mut hmtx = Vec::new();
+        for _ in &glyphs {
+            be16(&mut hmtx, 600);
+            be16(&mut hmtx, 0);
+        }
+
+        // cmap format 4: one segment per mapped char plus the 0xFFFF terminator
+        let map = [('A', 1u16), ('B', 3), ('O', 2)];
+        let seg = map.len() + 1;
+        let mut sub = Vec::new();
+        be16(&mut sub, 4);
+        be16(&mut sub, (16 + seg * 8) as u16);
+        be16(&mut sub, 0);
+        be16(&mut sub, (seg * 2) as u16);
+        for _ in 0..3 {
+            be16(&mut sub, 0); // search hints, unused
+        }
+        let codes: Vec<u16> = map.iter().map(|&(c, _)| c as u16).chain([0xFFFF]).collect();
+        for &c in &codes {
+            be16(&mut sub, c); // endCode
+        }
+        be16(&mut sub, 0);
+        for &c in &codes {
+            be16(&mut sub, c); // startCode
+        }
+        for (i, &c) in codes.iter().enumerate() {
+            // idDelta; the terminator maps 0xFFFF to glyph 0
+            be16(&mut sub, map.get(i).map_or(1, |m| m.1.wrapping_sub(c)));
+        }
+        for _ in &codes {
+            be16(&mut sub, 0); // idRangeOffset
+        }
+        let mut cmap = Vec::new();
+        be16(&mut cmap, 0);
+        be16(&mut cmap, 1);
+        be16(&mut cmap, 3);
+        be16(&mut cmap, 1);
+        be32(&mut cmap, 12);
+        cmap.extend_from_slice(&sub);
+
+        let mut kern = Vec::new();
+        for v in [0u16, 1, 0, 20, 0x0001, 1, 6, 0, 0, 1, 1] {
+            be16(&mut kern, v);
+        }
+        be16(&mut kern, -100i16 as u16);
+
+        let tables: [(&[u8; 4], Vec<u8>); 8] = [
+            (b"cmap", cmap),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"kern", kern),
+            (b"loca", loca),
+            (b"maxp", maxp),
+        ];
+        let mut out = Vec::new();
+        be32(&mut out, 0x0001_0000);
+        be16(&mut out, tables.len() as u16);
+        for _ in 0..3 {
+            be16(&mut out, 0);
+        }
+        let mut offset = 12 + tables.len() * 16;
+        for (tag, body) in &tables {
+            out.extend_from_slice(*tag);
+            be32(&mut out, 0);
+            be32(&mut out, offset as u32);
+            be32(&mut out, body.len() as u32);
+            offset += body.len().div_ceil(4) * 4;
+        }
+        for (_, body) in &tables {
+            out.extend_from_slice(body);
+            out.resize(out.len().div_ceil(4) * 4, 0);
+        }
+        out
+    }
+
+    #[test]
+    fn parses_metrics_cmap_and_kerning() {
+        let f = TtfFont::from_bytes(test_font()).unwrap();
+        assert_eq!((f.units_per_em(), f.num_glyphs()), (1000, 4));
+        assert_eq!((f.ascent(), f.descent(), f.line_gap()), (800, -200, 0));
+        assert_eq!(f.line_height(20.0), 20.0);
+        assert_eq!(['A', 'B', 'O', 'Z'].map(|c| f.glyph_index(c)), [1, 3, 2, 0]);
+        assert_eq!(f.advance_width(2), 600);
+        assert_eq!(f.kerning(1, 1), -100);
+        assert_eq!(f.kerning(1, 2), 0);
+    }
+
+    #[test]
+    fn off_curve_only_contour_becomes_quads() {
+        let f = TtfFont::from_bytes(test_font()).unwrap();
+        let p = f.glyph_path(2, 1000.0, 0.0, 0.0);
+        let quads = p
+            .commands()
+            .iter()
+            .filter(|c| matches!(c, crate::path::PathCmd::QuadTo(..)))
+            .count();
+        assert_eq!(quads, 4);
+        // Curve stays inside the control diamond's bounding box, y flipped
+        for line in p.flatten(0.5) {
+            for q in line.points {
+                assert!((0.0..=500.0).contains(&q.x) && (-500.0..=0.0).contains(&q.y));
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(TtfFont::from_bytes(b"OTTO\0\0\0\0\0\0\0\0".to_vec()).is_err());
+        assert!(TtfFont::from_bytes(vec![0, 1, 0]).is_err());
+        let mut data = test_font();
+        data.truncate(200);
+        assert!(TtfFont::from_bytes(data).is_err());
+    }
+}
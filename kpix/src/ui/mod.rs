@@ -0,0 +1,570 @@
+//! Immediate-mode UI: panels, labels, buttons, checkboxes and sliders.
+//!
+//! Each frame, build an `Input` from the window's events and create a `Ui` over the surface.
+//! Widget calls draw immediately and return a `Response` describing the interaction. State
+//! that must survive between frames (which widget is held or focused, the previous mouse
+//! button) lives in a `UiState` owned by the caller; the `Ui` writes it back when dropped.
+//!
+//! Widgets are identified by call order, so a UI that calls the same widgets in the same
+//! order every frame keeps its focus and drag state. Placement comes from a stack of
+//! layouts: the root one stacks widgets vertically from the top-left corner, and
+//! `vertical`, `horizontal` and `panel` open nested ones.
+//!
+//! Keyboard: `Tab` moves focus to the next widget, `Enter`/`Space` activate the focused
+//! button or checkbox, and `Left`/`Right` step the focused slider.
+
+mod nine;
+mod widgets;
+
+pub use nine::NineSlice;
+
+use crate::blit::BlendMode;
+use crate::core::{Color, Surface};
+use crate::draw::{self, CornerRadii};
+use crate::format::{PixelFormat, Rgba8};
+use crate::rect::Rect;
+
+/// Keys the widgets react to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Key {
+    Tab,
+    Enter,
+    Space,
+    Left,
+    Right,
+}
+
+/// Input for one frame: mouse position, button state and keys pressed since the last frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Input {
+    pub mouse_x: i32,
+    pub mouse_y: i32,
+    pub mouse_down: bool,
+    pub keys: Vec<Key>,
+}
+
+impl Input {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mouse(mut self, x: i32, y: i32) -> Self {
+        self.mouse_x = x;
+        self.mouse_y = y;
+        self
+    }
+
+    pub fn with_mouse_down(mut self, down: bool) -> Self {
+        self.mouse_down = down;
+        self
+    }
+
+    pub fn with_key(mut self, key: Key) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    /// True when `key` was pressed this frame.
+    pub fn pressed(&self, key: Key) -> bool {
+        self.keys.contains(&key)
+    }
+}
+
+/// Colors and metrics used by every widget.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub text: Color,
+    pub panel: Color,
+    pub border: Color,
+    pub widget: Color,
+    pub hovered: Color,
+    pub held: Color,
+    pub accent: Color,
+    pub focus: Color,
+    /// Space between a widget's edge and its contents, and inside panels.
+    pub padding: i32,
+    /// Gap between consecutive widgets in a layout.
+    pub spacing: i32,
+    pub radius: i32,
+    /// Track length of `slider`.
+    pub slider_width: i32,
+}
+
+impl Theme {
+    /// Dark gray widgets with a blue accent.
+    pub fn dark() -> Self {
+        Self {
+            text: Color::rgba(230, 232, 238, 255),
+            panel: Color::rgba(36, 38, 46, 255),
+            border: Color::rgba(80, 84, 98, 255),
+            widget: Color::rgba(60, 64, 78, 255),
+            hovered: Color::rgba(76, 82, 100, 255),
+            held: Color::rgba(44, 48, 60, 255),
+            accent: Color::rgba(80, 150, 250, 255),
+            focus: Color::rgba(250, 200, 80, 255),
+            padding: 4,
+            spacing: 4,
+            radius: 3,
+            slider_width: 100,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// What happened to a widget this frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Response {
+    /// Area the widget occupies.
+    pub rect: Rect,
+    /// The mouse is over the widget (and no other widget is being held).
+    pub hovered: bool,
+    /// The mouse button went down on the widget and has not been released yet.
+    pub held: bool,
+    /// Released over the widget after pressing on it, or activated from the keyboard.
+    pub clicked: bool,
+    /// The widget's value changed.
+    pub changed: bool,
+    /// The widget has keyboard focus.
+    pub focused: bool,
+}
+
+/// UI state kept between frames.
+#[derive(Clone, Debug, Default)]
+pub struct UiState {
+    pub theme: Theme,
+    held: Option<usize>,
+    focus: Option<usize>,
+    mouse_was_down: bool,
+    widget_count: usize,
+}
+
+impl UiState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Id of the focused widget (its index in call order), if any.
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+}
+
+/// Direction in which a layout places consecutive widgets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Vertical,
+    Horizontal,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Layout {
+    dir: Direction,
+    x: i32,
+    y: i32,
+    used: Rect,
+}
+
+impl Layout {
+    fn new(dir: Direction, x: i32, y: i32) -> Self {
+        Self {
+            dir,
+            x,
+            y,
+            used: Rect::default(),
+        }
+    }
+}
+
+/// One frame of UI over a surface.
+pub struct Ui<'a, P: PixelFormat = Rgba8> {
+    surface: &'a mut Surface<P>,
+    state: &'a mut UiState,
+    input: &'a Input,
+    theme: Theme,
+    layouts: Vec<Layout>,
+    next_id: usize,
+}
+
+impl<'a, P: PixelFormat> Ui<'a, P> {
+    /// Start a frame. The root layout is vertical, inset by the theme padding.
+    pub fn new(state: &'a mut UiState, surface: &'a mut Surface<P>, input: &'a Input) -> Self {
+        if input.pressed(Key::Tab) && state.widget_count > 0 {
+            let next = state.focus.map_or(0, |f| f + 1);
+            state.focus = Some(next % state.widget_count);
+        }
+        let theme = state.theme;
+        let root = Layout::new(Direction::Vertical, theme.padding, theme.padding);
+        Self {
+            surface,
+            state,
+            input,
+            theme,
+            layouts: vec![root],
+            next_id: 0,
+        }
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn input(&self) -> &Input {
+        self.input
+    }
+
+    /// The surface being drawn on, for custom drawing between widgets.
+    pub fn surface(&mut self) -> &mut Surface<P> {
+        self.surface
+    }
+
+    /// Run `f` with a nested vertical layout at the current position.
+    pub fn vertical<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.nested(Direction::Vertical, f)
+    }
+
+    /// Run `f` with a nested horizontal layout at the current position.
+    pub fn horizontal<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.nested(Direction::Horizontal, f)
+    }
+
+    /// Draw a panel filling `rect` and lay `f`'s widgets out vertically inside it, clipped to
+    /// the panel. The panel does not take space in the enclosing layout.
+    pub fn panel<R>(&mut self, rect: Rect, f: impl FnOnce(&mut Self) -> R) -> R {
+        let t = self.theme;
+        let radii = CornerRadii::all(t.radius);
+        let (x, y, w, h) = (rect.x, rect.y, rect.w, rect.h);
+        draw::fill_round_rect(self.surface, x, y, w, h, radii, t.panel);
+        draw::draw_round_rect(self.surface, x, y, w, h, radii, t.border);
+        self.inside(rect, f)
+    }
+
+    /// `panel` with the background drawn from a nine-slice image.
+    pub fn panel_nine<S: PixelFormat, R>(
+        &mut self,
+        src: &Surface<S>,
+        nine: &NineSlice,
+        rect: Rect,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        nine.draw(src, self.surface, rect, BlendMode::Alpha);
+        self.inside(rect, f)
+    }
+
+    /// Leave an empty gap of `size` pixels along the current layout direction.
+    pub fn space(&mut self, size: i32) {
+        let layout = self.layouts.last_mut().expect("root layout");
+        match layout.dir {
+            Direction::Vertical => layout.y += size,
+            Direction::Horizontal => layout.x += size,
+        }
+    }
+
+    /// Reserve a `w` x `h` rect at the current position and advance the layout.
+    pub fn allocate(&mut self, w: i32, h: i32) -> Rect {
+        let spacing = self.theme.spacing;
+        let layout = self.layouts.last_mut().expect("root layout");
+        let rect = Rect::new(layout.x, layout.y, w, h);
+        match layout.dir {
+            Direction::Vertical => layout.y += h + spacing,
+            Direction::Horizontal => layout.x += w + spacing,
+        }
+        layout.used = layout.used.union(rect);
+        rect
+    }
+
+    fn nested<R>(&mut self, dir: Direction, f: impl FnOnce(&mut Self) -> R) -> R {
+        let parent = *self.layouts.last().expect("root layout");
+        self.layouts.push(Layout::new(dir, parent.x, parent.y));
+        let r = f(self);
+        let used = self.layouts.pop().expect("nested layout").used;
+        if !used.is_empty() {
+            self.allocate(used.right() - parent.x, used.bottom() - parent.y);
+        }
+        r
+    }
+
+    fn inside<R>(&mut self, rect: Rect, f: impl FnOnce(&mut Self) -> R) -> R {
+        let pad = self.theme.padding;
+        self.surface.push_clip(rect);
+        self.layouts
+            .push(Layout::new(Direction::Vertical, rect.x + pad, rect.y + pad));
+        let r = f(self);
+        self.layouts.pop();
+        self.surface.pop_clip();
+        r
+    }
+
+    /// Mouse and keyboard handling shared by all interactive widgets.
+    fn interact(&mut self, rect: Rect) -> Response {
+        let id = self.next_id;
+        self.next_id += 1;
+        let input = self.input;
+        let over = rect.contains(input.mouse_x, input.mouse_y)
+            && self
+                .surface
+                .clip_rect()
+                .contains(input.mouse_x, input.mouse_y);
+        let free = self.state.held.is_none_or(|h| h == id);
+        let hovered = over && free;
+        let pressed = input.mouse_down && !self.state.mouse_was_down;
+        let released = !input.mouse_down && self.state.mouse_was_down;
+        if pressed && hovered {
+            self.state.held = Some(id);
+            self.state.focus = Some(id);
+        }
+        let was_held = self.state.held == Some(id);
+        let focused = self.state.focus == Some(id);
+        let keyed = focused && (input.pressed(Key::Enter) || input.pressed(Key::Space));
+        Response {
+            rect,
+            hovered,
+            held: was_held && input.mouse_down,
+            clicked: (released && was_held && over) || keyed,
+            changed: false,
+            focused,
+        }
+    }
+
+    /// Fill color for a widget body in the given interaction state.
+    fn body_color(&self, r: &Response) -> Color {
+        if r.held {
+            self.theme.held
+        } else if r.hovered {
+            self.theme.hovered
+        } else {
+            self.theme.widget
+        }
+    }
+
+    /// Focus ring just outside `rect`.
+    fn draw_focus(&mut self, r: &Response) {
+        if r.focused {
+            let g = r.rect;
+            let radii = CornerRadii::all(self.theme.radius + 1);
+            let c = self.theme.focus;
+            draw::draw_round_rect(self.surface, g.x - 1, g.y - 1, g.w + 2, g.h + 2, radii, c);
+        }
+    }
+}
+
+impl<P: PixelFormat> Drop for Ui<'_, P> {
+    fn drop(&mut self) {
+        let s = &mut *self.state;
+        s.mouse_was_down = self.input.mouse_down;
+        if !self.input.mouse_down {
+            s.held = None;
+        }
+        s.widget_count = self.next_id;
+        if s.focus.is_some_and(|f| f >= self.next_id) {
+            s.focus = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run one headless frame.
+    fn frame<R>(
+        state: &mut UiState,
+        surface: &mut Surface,
+        input: &Input,
+        f: impl FnOnce(&mut Ui) -> R,
+    ) -> R {
+        let mut ui = Ui::new(state, surface, input);
+        f(&mut ui)
+    }
+
+    #[test]
+    fn nine_slice_keeps_corners_and_stretches_middle() {
+        let mut src = Surface::new(3, 3);
+        src.clear(Color::rgba(0, 0, 255, 255));
+        src.set_pixel(0, 0, Color::rgba(255, 0, 0, 255));
+        src.set_pixel(1, 1, Color::rgba(0, 255, 0, 255));
+        let nine = NineSlice::new(src.bounds(), 1);
+        let mut dst = Surface::new(8, 6);
+        nine.draw(&src, &mut dst, Rect::new(1, 1, 6, 4), BlendMode::Replace);
+        assert_eq!(dst.get_pixel(0, 0), Some(Color::TRANSPARENT));
+        assert_eq!(dst.get_pixel(1, 1), Some(Color::rgba(255, 0, 0, 255)));
+        assert_eq!(dst.get_pixel(2, 1), Some(Color::rgba(0, 0, 255, 255)));
+        for (x, y) in [(2, 2), (5, 2), (2, 3), (5, 3)] {
+            assert_eq!(dst.get_pixel(x, y), Some(Color::rgba(0, 255, 0, 255)));
+        }
+        assert_eq!(dst.get_pixel(6, 4), Some(Color::rgba(0, 0, 255, 255)));
+        assert_eq!(dst.get_pixel(7, 5), Some(Color::TRANSPARENT));
+
+        // Too small for the corners: they shrink, nothing spills outside.
+        let mut tiny = Surface::new(3, 3);
+        nine.draw(&src, &mut tiny, Rect::new(1, 1, 1, 1), BlendMode::Replace);
+        assert_eq!(tiny.get_pixel(1, 1), Some(Color::rgba(0, 0, 255, 255)));
+        assert_eq!(tiny.get_pixel(2, 2), Some(Color::TRANSPARENT));
+
+        // Insets summing past i32 still split the rect.
+        let m = i32::MAX;
+        let wide = NineSlice::with_insets(src.bounds(), m, m, m, m);
+        let mut out = Surface::new(2, 2);
+        wide.draw(&src, &mut out, Rect::new(0, 0, 2, 2), BlendMode::Replace);
+        assert!(out.pixels().iter().all(|&p| p != 0));
+    }
+
+    #[test]
+    fn layouts_stack_and_nest() {
+        let mut state = UiState::new();
+        let mut s = Surface::new(200, 100);
+        let (a, b, c, d) = frame(&mut state, &mut s, &Input::new(), |ui| {
+            let a = ui.allocate(10, 5);
+            let (b, c) = ui.horizontal(|ui| (ui.allocate(20, 8), ui.allocate(6, 12)));
+            let d = ui.allocate(3, 3);
+            (a, b, c, d)
+        });
+        assert_eq!(a, Rect::new(4, 4, 10, 5));
+        assert_eq!(b, Rect::new(4, 13, 20, 8));
+        assert_eq!(c, Rect::new(28, 13, 6, 12));
+        assert_eq!(d, Rect::new(4, 29, 3, 3));
+    }
+
+    #[test]
+    fn button_clicks_on_release_over_it() {
+        let mut state = UiState::new();
+        let mut s = Surface::new(100, 40);
+        let up = Input::new().with_mouse(10, 10);
+        let down = up.clone().with_mouse_down(true);
+
+        let r = frame(&mut state, &mut s, &up, |ui| ui.button("GO"));
+        assert!(r.hovered && !r.held && !r.clicked);
+        let r = frame(&mut state, &mut s, &down, |ui| ui.button("GO"));
+        assert!(r.held && !r.clicked && r.focused);
+        let r = frame(&mut state, &mut s, &up, |ui| ui.button("GO"));
+        assert!(r.clicked && !r.held);
+
+        // Pressing elsewhere and releasing over the button does nothing.
+        let away = Input::new().with_mouse(90, 35).with_mouse_down(true);
+        frame(&mut state, &mut s, &away, |ui| ui.button("GO"));
+        let r = frame(&mut state, &mut s, &up, |ui| ui.button("GO"));
+        assert!(!r.clicked);
+
+        // Dragging off a held button cancels the click.
+        frame(&mut state, &mut s, &down, |ui| ui.button("GO"));
+        let r = frame(&mut state, &mut s, &Input::new().with_mouse(90, 35), |ui| {
+            ui.button("GO")
+        });
+        assert!(!r.clicked);
+    }
+
+    #[test]
+    fn checkbox_toggles_from_mouse_and_keyboard() {
+        let mut state = UiState::new();
+        let mut s = Surface::new(100, 60);
+        let mut on = false;
+        let ui_frame = |state: &mut UiState, s: &mut Surface, input: &Input, on: &mut bool| {
+            frame(state, s, input, |ui| {
+                ui.button("A");
+                ui.checkbox("B", on)
+            })
+        };
+        // Checkbox is the second widget: (4, 24) .. (4 + 12 + 4 + 8, 36).
+        let at = Input::new().with_mouse(8, 28);
+        ui_frame(
+            &mut state,
+            &mut s,
+            &at.clone().with_mouse_down(true),
+            &mut on,
+        );
+        let r = ui_frame(&mut state, &mut s, &at, &mut on);
+        assert!(r.clicked && r.changed && on);
+
+        let away = Input::new().with_mouse(90, 55);
+        ui_frame(&mut state, &mut s, &away, &mut on);
+        let tab = away.clone().with_key(Key::Tab);
+        let r = ui_frame(&mut state, &mut s, &tab, &mut on);
+        assert!(
+            !r.focused,
+            "Tab moves focus from the checkbox back to the button"
+        );
+        let r = ui_frame(
+            &mut state,
+            &mut s,
+            &tab.clone().with_key(Key::Space),
+            &mut on,
+        );
+        assert!(r.focused && r.changed && !on);
+    }
+
+    #[test]
+    fn slider_follows_drag_and_arrow_keys() {
+        let mut state = UiState::new();
+        let mut s = Surface::new(200, 40);
+        let mut v = 0.0f32;
+        // Track spans x = 4..104 on the first row.
+        let drag = Input::new().with_mouse(54, 10).with_mouse_down(true);
+        let r = frame(&mut state, &mut s, &drag, |ui| {
+            ui.slider("V", &mut v, 0.0..=10.0)
+        });
+        assert!(r.changed && r.held);
+        assert!((v - 5.0).abs() < 0.1, "{v}");
+
+        // Keeps following past the end while held.
+        let far = drag.clone().with_mouse(500, 90);
+        frame(&mut state, &mut s, &far, |ui| {
+            ui.slider("V", &mut v, 0.0..=10.0)
+        });
+        assert_eq!(v, 10.0);
+
+        let left = Input::new().with_key(Key::Left);
+        let r = frame(&mut state, &mut s, &left, |ui| {
+            ui.slider("V", &mut v, 0.0..=10.0)
+        });
+        assert!(r.changed && r.focused);
+        assert!((v - 9.5).abs() < 1e-4, "{v}");
+    }
+
+    #[test]
+    fn slider_accepts_reversed_and_nan_ranges() {
+        let mut state = UiState::new();
+        let mut s = Surface::new(200, 40);
+        let mut v = 20.0f32;
+        frame(&mut state, &mut s, &Input::new(), |ui| {
+            ui.slider("V", &mut v, 10.0..=0.0)
+        });
+        assert_eq!(v, 10.0);
+        frame(&mut state, &mut s, &Input::new(), |ui| {
+            ui.slider("V", &mut v, f32::NAN..=5.0)
+        });
+        assert_eq!(v, 5.0);
+        frame(&mut state, &mut s, &Input::new(), |ui| {
+            ui.slider("V", &mut v, f32::NAN..=f32::NAN)
+        });
+        assert_eq!(v, 0.0);
+    }
+
+    #[test]
+    fn widgets_draw_inside_panels() {
+        let mut state = UiState::new();
+        let mut s = Surface::new(120, 60);
+        let panel = Rect::new(10, 10, 100, 40);
+        let r = frame(&mut state, &mut s, &Input::new(), |ui| {
+            ui.panel(panel, |ui| {
+                ui.label("HI");
+                ui.button("OK")
+            })
+        });
+        assert_eq!(r.rect.x, 14);
+        assert!(r.rect.y > 14);
+        let t = Theme::dark();
+        assert_eq!(s.get_pixel(0, 0), Some(Color::TRANSPARENT));
+        assert_eq!(s.get_pixel(60, 45), Some(t.panel));
+        let mid = r.rect.y + r.rect.h / 2;
+        assert_eq!(s.get_pixel(r.rect.x + 2, mid), Some(t.widget));
+    }
+}
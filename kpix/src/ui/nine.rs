@@ -0,0 +1,75 @@
+//! Nine-slice scaling: corners stay fixed, edges stretch along one axis, the center both.
+
+use crate::blit::{self, BlendMode, BlitOptions};
+use crate::core::Surface;
+use crate::format::PixelFormat;
+use crate::rect::Rect;
+
+/// A source rect split into a 3x3 grid by four insets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NineSlice {
+    pub src: Rect,
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl NineSlice {
+    /// Same inset on all four sides.
+    pub fn new(src: Rect, border: i32) -> Self {
+        Self::with_insets(src, border, border, border, border)
+    }
+
+    pub fn with_insets(src: Rect, left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self {
+            src,
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// Stretch the slices of `src` over `rect` of `dst` with nearest filtering. When `rect`
+    /// is smaller than the corners, they shrink proportionally and the middle disappears.
+    pub fn draw<S: PixelFormat, D: PixelFormat>(
+        &self,
+        src: &Surface<S>,
+        dst: &mut Surface<D>,
+        rect: Rect,
+        mode: BlendMode,
+    ) {
+        let r = rect;
+        let s = self.src;
+        let (sl, sr) = fit(self.left, self.right, s.w);
+        let (st, sb) = fit(self.top, self.bottom, s.h);
+        let (dl, dr) = fit(sl, sr, r.w);
+        let (dt, db) = fit(st, sb, r.h);
+        let sx = [s.x, s.x + sl, s.right() - sr, s.right()];
+        let sy = [s.y, s.y + st, s.bottom() - sb, s.bottom()];
+        let dx = [r.x, r.x + dl, r.right() - dr, r.right()];
+        let dy = [r.y, r.y + dt, r.bottom() - db, r.bottom()];
+        let opts = BlitOptions::new().with_mode(mode);
+        for j in 0..3 {
+            for i in 0..3 {
+                let from = Rect::from_min_max(sx[i], sy[j], sx[i + 1], sy[j + 1]);
+                let to = Rect::from_min_max(dx[i], dy[j], dx[i + 1], dy[j + 1]);
+                if !from.is_empty() && !to.is_empty() {
+                    blit::blit_scaled(src, from, dst, to, &opts);
+                }
+            }
+        }
+    }
+}
+
+/// Insets `(a, b)` clamped to be non-negative and scaled down to fit in `size`.
+fn fit(a: i32, b: i32, size: i32) -> (i32, i32) {
+    let (a, b, size) = (a.max(0) as i64, b.max(0) as i64, size.max(0) as i64);
+    if a + b <= size {
+        (a as i32, b as i32)
+    } else {
+        let a2 = a * size / (a + b);
+        (a2 as i32, (size - a2) as i32)
+    }
+}
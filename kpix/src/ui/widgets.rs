@@ -0,0 +1,129 @@
+//! Widgets. Every widget is one row tall: the 8px font plus padding above and below.
+
+use std::ops::RangeInclusive;
+
+use super::{Key, Response, Ui};
+use crate::draw::{self, CornerRadii};
+use crate::format::PixelFormat;
+use crate::rect::Rect;
+use crate::text;
+
+impl<P: PixelFormat> Ui<'_, P> {
+    fn row_height(&self) -> i32 {
+        8 + 2 * self.theme.padding
+    }
+
+    /// Text on its own; never interactive.
+    pub fn label(&mut self, label: &str) -> Response {
+        let (w, _) = text::measure_text(label);
+        let rect = self.allocate(w, self.row_height());
+        let pad = self.theme.padding;
+        text::draw_text(self.surface, rect.x, rect.y + pad, label, self.theme.text);
+        Response {
+            rect,
+            ..Response::default()
+        }
+    }
+
+    /// Push button sized to its label. Check `clicked`.
+    pub fn button(&mut self, label: &str) -> Response {
+        let pad = self.theme.padding;
+        let (w, _) = text::measure_text(label);
+        let rect = self.allocate(w + 2 * pad, self.row_height());
+        let r = self.interact(rect);
+        let body = self.body_color(&r);
+        let radii = CornerRadii::all(self.theme.radius);
+        let s = &mut *self.surface;
+        draw::fill_round_rect(s, rect.x, rect.y, rect.w, rect.h, radii, body);
+        draw::draw_round_rect(s, rect.x, rect.y, rect.w, rect.h, radii, self.theme.border);
+        text::draw_text(s, rect.x + pad, rect.y + pad, label, self.theme.text);
+        self.draw_focus(&r);
+        r
+    }
+
+    /// Box followed by a label; clicking either toggles `value`.
+    pub fn checkbox(&mut self, label: &str, value: &mut bool) -> Response {
+        let pad = self.theme.padding;
+        let size = self.row_height() - 4;
+        let (w, _) = text::measure_text(label);
+        let rect = self.allocate(size + pad + w, self.row_height());
+        let mut r = self.interact(rect);
+        if r.clicked {
+            *value = !*value;
+            r.changed = true;
+        }
+        let body = self.body_color(&r);
+        let t = self.theme;
+        let b = Rect::new(rect.x, rect.y + 2, size, size);
+        let s = &mut *self.surface;
+        let radii = CornerRadii::all(t.radius.min(size / 4));
+        draw::fill_round_rect(s, b.x, b.y, b.w, b.h, radii, body);
+        draw::draw_round_rect(s, b.x, b.y, b.w, b.h, radii, t.border);
+        if *value {
+            draw::fill_rect(s, b.x + 3, b.y + 3, b.w - 6, b.h - 6, t.accent);
+        }
+        text::draw_text(s, b.right() + pad, rect.y + pad, label, t.text);
+        self.draw_focus(&Response { rect: b, ..r });
+        r
+    }
+
+    /// Horizontal slider for `value` within `range`, followed by the label and current value.
+    /// Dragging maps the mouse x across the track; `Left`/`Right` step by 1/20 of the range.
+    /// A reversed range is swapped and a NaN bound is ignored.
+    pub fn slider(&mut self, label: &str, value: &mut f32, range: RangeInclusive<f32>) -> Response {
+        let t = self.theme;
+        let (a, b) = (*range.start(), *range.end());
+        // `min`/`max` return the other operand when one is NaN.
+        let (lo, hi) = (a.min(b), a.max(b));
+        let (lo, hi) = if lo.is_nan() { (0.0, 0.0) } else { (lo, hi) };
+        let caption = format!("{label} {:.2}", value.clamp(lo, hi));
+        let (w, _) = text::measure_text(&caption);
+        let rect = self.allocate(t.slider_width + t.padding + w, self.row_height());
+        let track = Rect::new(rect.x, rect.y, t.slider_width, rect.h);
+        let mut r = self.interact(track);
+        r.rect = rect;
+
+        let old = *value;
+        if r.held {
+            let f = (self.input.mouse_x - track.x) as f32 / track.w.max(1) as f32;
+            *value = lo + f.clamp(0.0, 1.0) * (hi - lo);
+        }
+        if r.focused {
+            let step = (hi - lo) / 20.0;
+            for key in &self.input.keys {
+                match key {
+                    Key::Left => *value -= step,
+                    Key::Right => *value += step,
+                    _ => {}
+                }
+            }
+        }
+        *value = value.clamp(lo, hi);
+        r.changed = *value != old;
+
+        let f = if hi > lo {
+            (*value - lo) / (hi - lo)
+        } else {
+            0.0
+        };
+        let knob_x = track.x + (f * (track.w - 6) as f32).round() as i32;
+        let cy = track.y + track.h / 2;
+        let body = self.body_color(&r);
+        let s = &mut *self.surface;
+        let radii = CornerRadii::all(2);
+        draw::fill_round_rect(s, track.x, cy - 2, track.w, 4, radii, t.widget);
+        draw::fill_round_rect(s, track.x, cy - 2, knob_x - track.x + 3, 4, radii, t.accent);
+        draw::fill_round_rect(s, knob_x, track.y + 2, 6, track.h - 4, radii, body);
+        draw::draw_round_rect(s, knob_x, track.y + 2, 6, track.h - 4, radii, t.accent);
+        let caption = format!("{label} {:.2}", *value);
+        text::draw_text(
+            s,
+            track.right() + t.padding,
+            rect.y + t.padding,
+            &caption,
+            t.text,
+        );
+        self.draw_focus(&Response { rect: track, ..r });
+        r
+    }
+}
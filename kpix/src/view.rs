@@ -0,0 +1,295 @@
+//! Rectangular views into a surface and parallel row processing.
+//!
+//! Views borrow a surface and address a sub-rect in local coordinates: `(0, 0)` is the
+//! view's top-left pixel and rows are the view-wide slices of the surface rows. Writes
+//! through a view are bounded by its rect, not by the surface's clip stack.
+//!
+//! `par_rows_mut` hands contiguous bands of rows to scoped threads, one band per available
+//! core, so a per-pixel closure runs in parallel without any shared mutable state.
+
+use std::thread;
+
+use crate::core::{Color, Surface};
+use crate::format::PixelFormat;
+use crate::rect::Rect;
+
+impl<P: PixelFormat> Surface<P> {
+    /// Read-only view of `rect` clipped to the surface bounds.
+    pub fn view(&self, rect: Rect) -> SurfaceView<'_, P> {
+        SurfaceView {
+            rect: visible(rect, self.bounds()),
+            surface: self,
+        }
+    }
+
+    /// Mutable view of `rect` clipped to the surface bounds. With dirty tracking on, the
+    /// whole view counts as changed.
+    pub fn view_mut(&mut self, rect: Rect) -> SurfaceViewMut<'_, P> {
+        let rect = visible(rect, self.bounds());
+        self.mark_dirty(rect);
+        SurfaceViewMut {
+            rect,
+            surface: self,
+        }
+    }
+
+    /// Call `f(y, row)` for every row, in parallel bands. Writes bypass the clip rect.
+    pub fn par_rows_mut<F>(&mut self, f: F)
+    where
+        F: Fn(usize, &mut [P::Pixel]) + Sync,
+    {
+        par_rows(self.rows_mut().enumerate().collect(), &f);
+    }
+}
+
+/// Read-only sub-rect of a surface.
+#[derive(Debug)]
+pub struct SurfaceView<'a, P: PixelFormat> {
+    surface: &'a Surface<P>,
+    rect: Rect,
+}
+
+// Manual impls: deriving would require `P: Copy`.
+impl<P: PixelFormat> Clone for SurfaceView<'_, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: PixelFormat> Copy for SurfaceView<'_, P> {}
+
+impl<'a, P: PixelFormat> SurfaceView<'a, P> {
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.rect.w as usize
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.rect.h as usize
+    }
+
+    /// Area covered, in surface coordinates.
+    #[inline]
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Pixel at local `(x, y)`, or `None` outside the view.
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<Color> {
+        self.get_raw(x, y).map(|v| self.surface.format().decode(v))
+    }
+
+    /// Stored value at local `(x, y)`, or `None` outside the view.
+    pub fn get_raw(&self, x: i32, y: i32) -> Option<P::Pixel> {
+        let (w, h) = (self.rect.w, self.rect.h);
+        if !(0..w).contains(&x) || !(0..h).contains(&y) {
+            return None;
+        }
+        self.surface.get_raw(self.rect.x + x, self.rect.y + y)
+    }
+
+    /// Local row `y`, or `None` past the bottom.
+    pub fn row(&self, y: usize) -> Option<&'a [P::Pixel]> {
+        if y >= self.height() {
+            return None;
+        }
+        let row = self.surface.row(self.rect.y as usize + y)?;
+        Some(&row[self.rect.x as usize..self.rect.right() as usize])
+    }
+
+    /// Rows from top to bottom.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &'a [P::Pixel]> + use<'a, P> {
+        let view = *self;
+        (0..self.height()).map(move |y| view.row(y).unwrap_or_default())
+    }
+
+    /// Copy the view into a new surface of the same format.
+    pub fn to_surface(&self) -> Surface<P> {
+        let pixels = self.rows().flatten().copied().collect();
+        Surface::from_pixels_with_format(
+            self.width(),
+            self.height(),
+            pixels,
+            self.surface.format().clone(),
+        )
+        .expect("rows cover the view")
+    }
+}
+
+/// Mutable sub-rect of a surface.
+#[derive(Debug)]
+pub struct SurfaceViewMut<'a, P: PixelFormat> {
+    surface: &'a mut Surface<P>,
+    rect: Rect,
+}
+
+impl<P: PixelFormat> SurfaceViewMut<'_, P> {
+    /// Read-only view of the same area.
+    pub fn as_view(&self) -> SurfaceView<'_, P> {
+        SurfaceView {
+            surface: self.surface,
+            rect: self.rect,
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.rect.w as usize
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.rect.h as usize
+    }
+
+    #[inline]
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn get_pixel(&self, x: i32, y: i32) -> Option<Color> {
+        self.as_view().get_pixel(x, y)
+    }
+
+    /// Set local `(x, y)`; ignored outside the view.
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
+        let v = self.surface.format().encode(color);
+        self.set_raw(x, y, v);
+    }
+
+    /// Store a raw value at local `(x, y)`; ignored outside the view.
+    pub fn set_raw(&mut self, x: i32, y: i32, value: P::Pixel) {
+        let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) else {
+            return;
+        };
+        if let Some(p) = self.row_mut(y).and_then(|row| row.get_mut(x)) {
+            *p = value;
+        }
+    }
+
+    /// Fill the whole view.
+    pub fn fill(&mut self, color: Color) {
+        let v = self.surface.format().encode(color);
+        self.rows_mut().for_each(|row| row.fill(v));
+    }
+
+    /// Mutable local row `y`, or `None` past the bottom.
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [P::Pixel]> {
+        if y >= self.height() {
+            return None;
+        }
+        let (x0, x1) = (self.rect.x as usize, self.rect.right() as usize);
+        let row = self.surface.raw_row_mut(self.rect.y as usize + y)?;
+        Some(&mut row[x0..x1])
+    }
+
+    /// Mutable rows from top to bottom.
+    pub fn rows_mut(&mut self) -> impl ExactSizeIterator<Item = &mut [P::Pixel]> {
+        let (x0, x1) = (self.rect.x as usize, self.rect.right() as usize);
+        let (y0, y1) = (self.rect.y as usize, self.rect.bottom() as usize);
+        self.surface
+            .raw_rows_mut()
+            .skip(y0)
+            .take(y1 - y0)
+            .map(move |row| &mut row[x0..x1])
+    }
+
+    /// Call `f(y, row)` for every local row, in parallel bands.
+    pub fn par_rows_mut<F>(&mut self, f: F)
+    where
+        F: Fn(usize, &mut [P::Pixel]) + Sync,
+    {
+        par_rows(self.rows_mut().enumerate().collect(), &f);
+    }
+}
+
+/// `rect` inside `bounds`, normalized to an empty rect at the origin when nothing is left.
+fn visible(rect: Rect, bounds: Rect) -> Rect {
+    let r = rect.intersect(bounds);
+    if r.is_empty() { Rect::default() } else { r }
+}
+
+/// Split `rows` into one contiguous band per core and process the bands on scoped threads.
+fn par_rows<T: Send>(mut rows: Vec<(usize, &mut [T])>, f: &(impl Fn(usize, &mut [T]) + Sync)) {
+    let threads = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(rows.len());
+    if threads <= 1 {
+        rows.into_iter().for_each(|(y, row)| f(y, row));
+        return;
+    }
+    let band = rows.len().div_ceil(threads);
+    thread::scope(|s| {
+        while !rows.is_empty() {
+            let rest = rows.split_off(band.min(rows.len()));
+            let chunk = std::mem::replace(&mut rows, rest);
+            s.spawn(move || chunk.into_iter().for_each(|(y, row)| f(y, row)));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Gray8;
+
+    fn numbered(w: usize, h: usize) -> Surface<Gray8> {
+        let px = (0..w * h).map(|i| i as u8).collect();
+        Surface::from_pixels_with_format(w, h, px, Gray8).unwrap()
+    }
+
+    #[test]
+    fn views_use_local_coordinates_and_clip_to_bounds() {
+        let s = numbered(5, 4);
+        let v = s.view(Rect::new(3, 1, 10, 2));
+        assert_eq!(
+            (v.width(), v.height(), v.rect()),
+            (2, 2, Rect::new(3, 1, 2, 2))
+        );
+        assert_eq!(v.get_raw(0, 0), Some(8));
+        assert_eq!(v.get_raw(2, 0), None);
+        assert_eq!(
+            v.rows().collect::<Vec<_>>(),
+            vec![&[8, 9][..], &[13, 14][..]]
+        );
+        assert_eq!(v.to_surface().pixels(), &[8, 9, 13, 14]);
+        let empty = s.view(Rect::new(7, 7, 3, 3));
+        assert_eq!((empty.width(), empty.rows().count()), (0, 0));
+    }
+
+    #[test]
+    fn mutable_views_write_inside_their_rect_only() {
+        let mut s = numbered(4, 3);
+        s.push_clip(Rect::new(0, 0, 1, 1)); // views ignore the clip stack
+        let mut v = s.view_mut(Rect::new(1, 1, 2, 2));
+        v.fill(Color::rgba(255, 255, 255, 255));
+        v.set_raw(0, 1, 7);
+        v.set_raw(2, 0, 99);
+        v.set_raw(-1, 0, 99);
+        v.row_mut(0).unwrap()[1] = 6;
+        assert_eq!(v.get_pixel(1, 0), Some(Color::rgba(6, 6, 6, 255)));
+        assert_eq!(s.pixels(), &[0, 1, 2, 3, 4, 255, 6, 7, 8, 7, 255, 11]);
+    }
+
+    #[test]
+    fn par_rows_visits_each_row_once_with_its_index() {
+        let mut s = Surface::new(7, 123);
+        s.par_rows_mut(|y, row| {
+            for (x, p) in row.iter_mut().enumerate() {
+                *p += Color::rgba(x as u8, y as u8, 0, 255).to_u32();
+            }
+        });
+        for y in 0..123 {
+            for x in 0..7 {
+                let c = s.get_pixel(x, y).unwrap();
+                assert_eq!((c.r, c.g), (x as u8, y as u8));
+            }
+        }
+
+        let mut v = s.view_mut(Rect::new(2, 100, 3, 50));
+        v.par_rows_mut(|y, row| row.fill(y as u32));
+        assert_eq!(s.row(122).unwrap()[2..5], [22, 22, 22]);
+        assert_eq!(s.get_pixel(1, 122).unwrap().g, 122);
+    }
+}
@@ -0,0 +1,94 @@
+use kdev::out;
+use kmath::{Vec2, Vec3};
+use kpix::Color;
+use kraster2d::core::texture::Texture;
+use kraster2d::raster::{
+    Vertex, draw_triangle_solid, draw_triangle_textured, draw_triangle_vertex_color,
+};
+use kraster2d::{Frame, Sampler, io};
+
+fn v(x: f32, y: f32, z: f32, color: [f32; 3]) -> Vertex {
+    Vertex {
+        pos: Vec3::new(x, y, z),
+        uv: Vec2::new(x / 256.0, y / 256.0),
+        color,
+        w: 1.0,
+    }
+}
+
+fn make_stripes(w: usize, h: usize) -> Texture {
+    let mut px = Vec::with_capacity(w * h);
+    for y in 0..h {
+        for x in 0..w {
+            let c = if ((x + y) / 4) % 2 == 0 {
+                Color::rgba(230, 210, 120, 255)
+            } else {
+                Color::rgba(120, 90, 40, 255)
+            };
+            px.push(c.to_u32());
+        }
+    }
+    Texture::from_rgba_le(px, w, h)
+}
+
+fn main() {
+    let mut frame = Frame::with_depth(256, 256);
+    frame.clear(Color::rgba(20, 30, 50, 255));
+
+    // Three triangles that pierce one another: each one's z runs from near (0.1) to far
+    // (0.9) in a different direction, so no draw order could sort them.
+    let red = [0.9, 0.25, 0.25];
+    let green = [0.25, 0.85, 0.35];
+    let blue = [0.3, 0.45, 0.95];
+    draw_triangle_vertex_color(
+        &mut frame,
+        v(20.0, 40.0, 0.1, red),
+        v(230.0, 60.0, 0.9, red),
+        v(60.0, 220.0, 0.1, red),
+    );
+    draw_triangle_vertex_color(
+        &mut frame,
+        v(236.0, 30.0, 0.1, green),
+        v(200.0, 230.0, 0.1, green),
+        v(30.0, 120.0, 0.9, green),
+    );
+    draw_triangle_vertex_color(
+        &mut frame,
+        v(128.0, 10.0, 0.9, blue),
+        v(240.0, 200.0, 0.2, blue),
+        v(16.0, 200.0, 0.2, blue),
+    );
+
+    // A textured strip across the middle at constant depth, then a solid triangle behind
+    // everything: it only shows where nothing nearer was drawn.
+    let tex = make_stripes(64, 64);
+    let sampler = Sampler::new();
+    let white = [1.0; 3];
+    let (a, b) = (v(0.0, 118.0, 0.5, white), v(256.0, 118.0, 0.5, white));
+    let (c, d) = (v(256.0, 138.0, 0.5, white), v(0.0, 138.0, 0.5, white));
+    draw_triangle_textured(&mut frame, a, b, c, &tex, &sampler);
+    draw_triangle_textured(&mut frame, a, c, d, &tex, &sampler);
+    draw_triangle_solid(
+        &mut frame,
+        v(0.0, 256.0, 0.95, white),
+        v(128.0, 150.0, 0.95, white),
+        v(256.0, 256.0, 0.95, white),
+        Color::rgba(70, 70, 90, 255),
+    );
+
+    // Visualize the depth buffer: near is bright, the cleared far plane is black.
+    let mut depth = Frame::new(256, 256);
+    let zs = frame.depth().expect("frame has a depth buffer");
+    for (i, &z) in zs.iter().enumerate() {
+        let g = ((1.0 - z).clamp(0.0, 1.0) * 255.0).round() as u8;
+        depth.set_pixel(
+            (i % 256) as i32,
+            (i / 256) as i32,
+            Color::rgba(g, g, g, 255),
+        );
+    }
+
+    let out_dir = out::example_output_dir("depth").expect("failed to create output directory");
+    io::write::write_ppm(&frame, out_dir.join("depth.ppm")).expect("failed to write PPM");
+    io::write::write_ppm(&depth, out_dir.join("depth_buffer.ppm")).expect("failed to write PPM");
+}
@@ -0,0 +1,75 @@
+use kdev::out;
+use kmath::{Vec2, Vec3};
+use kpix::Color;
+use kraster2d::core::texture::Texture;
+use kraster2d::raster::{Vertex, draw_triangle_textured};
+use kraster2d::{Filter, Frame, MipFilter, Sampler, Wrap, io};
+
+const SIZE: usize = 256;
+const NEAR: f32 = 1.0;
+const FAR: f32 = 40.0;
+
+fn make_checker_tex(size: usize, cell: usize) -> Texture {
+    let mut px = Vec::with_capacity(size * size);
+    for y in 0..size {
+        for x in 0..size {
+            let c = if ((x / cell) ^ (y / cell)).is_multiple_of(2) {
+                Color::rgba(235, 235, 235, 255)
+            } else {
+                Color::rgba(30, 60, 120, 255)
+            };
+            px.push(c.to_u32());
+        }
+    }
+    Texture::from_rgba_le(px, size, size)
+}
+
+/// Same pinhole projection as the `perspective` example: `w` is the view-space z.
+fn project(p: Vec3, uv: Vec2) -> Vertex {
+    let f = SIZE as f32 * 0.8;
+    let c = SIZE as f32 / 2.0;
+    let depth = (1.0 / NEAR - 1.0 / p.z) / (1.0 / NEAR - 1.0 / FAR);
+    Vertex {
+        pos: Vec3::new(c + f * p.x / p.z, c * 0.6 + f * p.y / p.z, depth),
+        uv,
+        color: [1.0; 3],
+        w: p.z,
+    }
+}
+
+/// A long floor tiled many times, so texels shrink far below a pixel toward the horizon.
+fn draw_floor(frame: &mut Frame, tex: &Texture, sampler: &Sampler) {
+    let corners = [
+        (Vec3::new(-6.0, 1.0, 36.0), Vec2::new(0.0, 0.0)),
+        (Vec3::new(6.0, 1.0, 36.0), Vec2::new(6.0, 0.0)),
+        (Vec3::new(6.0, 1.0, 1.2), Vec2::new(6.0, 18.0)),
+        (Vec3::new(-6.0, 1.0, 1.2), Vec2::new(0.0, 18.0)),
+    ];
+    let v = corners.map(|(p, uv)| project(p, uv));
+    draw_triangle_textured(frame, v[0], v[1], v[2], tex, sampler);
+    draw_triangle_textured(frame, v[0], v[2], v[3], tex, sampler);
+}
+
+fn main() {
+    let tex = make_checker_tex(128, 8).with_mipmaps();
+    let base = Sampler::new()
+        .with_filter(Filter::Bilinear)
+        .with_wrap(Wrap::Repeat);
+    let out_dir = out::example_output_dir("mipmap").expect("failed to create output directory");
+    for (name, sampler) in [
+        ("none", base),
+        ("nearest_mip", base.with_mip_filter(MipFilter::Nearest)),
+        ("trilinear", base.with_mip_filter(MipFilter::Linear)),
+        (
+            "anisotropic",
+            base.with_mip_filter(MipFilter::Linear)
+                .with_max_anisotropy(8),
+        ),
+    ] {
+        let mut frame = Frame::with_depth(SIZE, SIZE);
+        frame.clear(Color::rgba(20, 30, 50, 255));
+        draw_floor(&mut frame, &tex, &sampler);
+        let path = out_dir.join(format!("{name}.ppm"));
+        io::write::write_ppm(&frame, path).expect("failed to write PPM");
+    }
+}
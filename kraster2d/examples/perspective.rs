@@ -0,0 +1,71 @@
+use kdev::out;
+use kmath::{Vec2, Vec3};
+use kpix::Color;
+use kraster2d::core::texture::Texture;
+use kraster2d::raster::{Vertex, draw_triangle_textured};
+use kraster2d::{Frame, Interpolation, Sampler, io};
+
+const SIZE: usize = 256;
+const NEAR: f32 = 1.0;
+const FAR: f32 = 12.0;
+
+fn make_checker_tex(w: usize, h: usize, cell: usize) -> Texture {
+    let mut px = Vec::with_capacity(w * h);
+    for y in 0..h {
+        for x in 0..w {
+            let c = if ((x / cell) ^ (y / cell)).is_multiple_of(2) {
+                Color::rgba(230, 230, 230, 255)
+            } else {
+                Color::rgba(200, 60, 60, 255)
+            };
+            px.push(c.to_u32());
+        }
+    }
+    Texture::from_rgba_le(px, w, h)
+}
+
+/// Pinhole projection of a view-space point (camera at the origin looking down +z, y down).
+/// Depth goes from 0 at NEAR to 1 at FAR, linear in 1/z so it interpolates in screen space;
+/// `w` is the view-space z.
+fn project(p: Vec3, uv: Vec2) -> Vertex {
+    let f = SIZE as f32 * 0.8;
+    let c = SIZE as f32 / 2.0;
+    let depth = (1.0 / NEAR - 1.0 / p.z) / (1.0 / NEAR - 1.0 / FAR);
+    Vertex {
+        pos: Vec3::new(c + f * p.x / p.z, c + f * p.y / p.z, depth),
+        uv,
+        color: [1.0; 3],
+        w: p.z,
+    }
+}
+
+/// A floor quad 1 unit below the eye, split into two triangles.
+fn draw_floor(frame: &mut Frame, tex: &Texture) {
+    let corners = [
+        (Vec3::new(-2.0, 1.0, 10.0), Vec2::new(0.0, 0.0)),
+        (Vec3::new(2.0, 1.0, 10.0), Vec2::new(1.0, 0.0)),
+        (Vec3::new(2.0, 1.0, 1.5), Vec2::new(1.0, 1.0)),
+        (Vec3::new(-2.0, 1.0, 1.5), Vec2::new(0.0, 1.0)),
+    ];
+    let v = corners.map(|(p, uv)| project(p, uv));
+    let sampler = Sampler::new();
+    draw_triangle_textured(frame, v[0], v[1], v[2], tex, &sampler);
+    draw_triangle_textured(frame, v[0], v[2], v[3], tex, &sampler);
+}
+
+fn main() {
+    let tex = make_checker_tex(64, 64, 8);
+    let out_dir =
+        out::example_output_dir("perspective").expect("failed to create output directory");
+    for (name, mode) in [
+        ("perspective", Interpolation::Perspective),
+        ("affine", Interpolation::Affine),
+    ] {
+        let mut frame = Frame::with_depth(SIZE, SIZE);
+        frame.clear(Color::rgba(20, 30, 50, 255));
+        frame.set_interpolation(mode);
+        draw_floor(&mut frame, &tex);
+        let path = out_dir.join(format!("{name}.ppm"));
+        io::write::write_ppm(&frame, path).expect("failed to write PPM");
+    }
+}
@@ -3,7 +3,7 @@ use kmath::{Transform2D, Vec2, Vec3};
 use kpix::Color;
 use kraster2d::core::texture::Texture;
 use kraster2d::raster::{Vertex, draw_triangle_textured};
-use kraster2d::{Frame, io};
+use kraster2d::{Filter, Frame, MipFilter, Sampler, io};
 
 fn make_checker_tex(w: usize, h: usize, cell: usize) -> Texture {
     let mut px = Vec::with_capacity(w * h);
@@ -24,7 +24,12 @@ fn make_checker_tex(w: usize, h: usize, cell: usize) -> Texture {
 
 fn main() {
     let mut frame = Frame::new(256, 256);
-    let tex = make_checker_tex(64, 64, 8);
+    // A fine checker that is minified most of the time: trilinear mipmapping keeps it from
+    // shimmering as the quad turns and shrinks.
+    let tex = make_checker_tex(256, 256, 4).with_mipmaps();
+    let sampler = Sampler::new()
+        .with_filter(Filter::Bilinear)
+        .with_mip_filter(MipFilter::Linear);
     let center = Vec2::new(128.0, 128.0);
 
     // Resolve output directory via kdev helper
@@ -51,7 +56,8 @@ fn main() {
         frame.clear(Color::rgba(20, 30, 50, 255));
         let t = i as f32 / frames as f32;
         let angle = t * std::f32::consts::TAU; // 0..2pi
-        let tr = Transform2D::new(center, angle, Vec2::ONE);
+        let scale = 0.65 + 0.35 * angle.cos(); // pulses between 0.3x and 1x
+        let tr = Transform2D::new(center, angle, Vec2::new(scale, scale));
 
         // Transform quad vertices
         let mut pts = [Vec2::ZERO; 4];
@@ -64,14 +70,15 @@ fn main() {
             pos: Vec3::new(p.x, p.y, 0.0),
             uv,
             color: [1.0; 3],
+            w: 1.0,
         };
         let v0 = make_v(pts[0], uvs[0]);
         let v1 = make_v(pts[1], uvs[1]);
         let v2 = make_v(pts[2], uvs[2]);
         let v3 = make_v(pts[3], uvs[3]);
 
-        draw_triangle_textured(&mut frame, v0, v1, v2, &tex);
-        draw_triangle_textured(&mut frame, v0, v2, v3, &tex);
+        draw_triangle_textured(&mut frame, v0, v1, v2, &tex, &sampler);
+        draw_triangle_textured(&mut frame, v0, v2, v3, &tex, &sampler);
 
         let path = out_dir.join(format!("frame{:04}.ppm", i));
         io::write::write_ppm(&frame, path).expect("failed to write PPM");
@@ -0,0 +1,73 @@
+use kdev::out;
+use kmath::{Vec2, Vec3};
+use kpix::Color;
+use kraster2d::core::texture::Texture;
+use kraster2d::raster::{Vertex, draw_triangle_textured};
+use kraster2d::{Filter, Frame, Sampler, Wrap, io};
+
+const CELL: f32 = 128.0;
+
+/// 4x4 texture with a distinct color per corner so wrapping and mirroring are visible.
+fn make_tile() -> Texture {
+    let mut px = Vec::with_capacity(16);
+    for y in 0..4 {
+        for x in 0..4 {
+            let c = match (x < 2, y < 2) {
+                (true, true) => Color::rgba(230, 70, 60, 255),
+                (false, true) => Color::rgba(250, 210, 70, 255),
+                (true, false) => Color::rgba(60, 120, 230, 255),
+                (false, false) => Color::rgba(240, 240, 240, 255),
+            };
+            let c = if x == 0 && y == 0 {
+                Color::rgba(20, 20, 20, 255)
+            } else {
+                c
+            };
+            px.push(c.to_u32());
+        }
+    }
+    Texture::from_rgba_le(px, 4, 4)
+}
+
+/// A square cell showing uv from -1 to 2 on both axes, so the texture appears once in the
+/// middle with wrapped copies around it.
+fn draw_cell(frame: &mut Frame, col: usize, row: usize, tex: &Texture, sampler: &Sampler) {
+    let (x0, y0) = (col as f32 * CELL + 4.0, row as f32 * CELL + 4.0);
+    let s = CELL - 8.0;
+    let v = |dx: f32, dy: f32| {
+        let mut v = Vertex::new(Vec3::new(x0 + dx * s, y0 + dy * s, 0.0));
+        v.uv = Vec2::new(-1.0 + 3.0 * dx, -1.0 + 3.0 * dy);
+        v
+    };
+    let (a, b, c, d) = (v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0), v(0.0, 1.0));
+    draw_triangle_textured(frame, a, b, c, tex, sampler);
+    draw_triangle_textured(frame, a, c, d, tex, sampler);
+}
+
+fn main() {
+    let tex = make_tile();
+    let wraps = [
+        Wrap::Repeat,
+        Wrap::MirroredRepeat,
+        Wrap::ClampToEdge,
+        Wrap::ClampToBorder(Color::rgba(40, 160, 90, 255)),
+    ];
+    let mut frame = Frame::new(5 * CELL as usize, 2 * CELL as usize);
+    frame.clear(Color::rgba(20, 30, 50, 255));
+    // Columns: the four wrap modes, then repeat across u with clamp-to-edge down v.
+    // Rows: nearest, bilinear.
+    for (row, filter) in [Filter::Nearest, Filter::Bilinear].into_iter().enumerate() {
+        for (col, &wrap) in wraps.iter().enumerate() {
+            let sampler = Sampler::new().with_filter(filter).with_wrap(wrap);
+            draw_cell(&mut frame, col, row, &tex, &sampler);
+        }
+        let mixed = Sampler::new()
+            .with_filter(filter)
+            .with_wrap_u(Wrap::Repeat)
+            .with_wrap_v(Wrap::ClampToEdge);
+        draw_cell(&mut frame, 4, row, &tex, &mixed);
+    }
+
+    let out_dir = out::example_output_dir("sampler").expect("failed to create output directory");
+    io::write::write_ppm(&frame, out_dir.join("sampler.ppm")).expect("failed to write PPM");
+}
@@ -0,0 +1,47 @@
+/// Depth comparison: a fragment passes when `func.passes(new_z, stored_z)` is true.
+/// With the default `Less` and a buffer cleared to 1.0, smaller z is nearer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DepthFunc {
+    #[default]
+    Less,
+    LessEqual,
+    Greater,
+    /// Pass every fragment (the depth buffer still records z if writes are enabled).
+    Always,
+    /// Reject every fragment.
+    Never,
+}
+
+impl DepthFunc {
+    #[inline]
+    pub fn passes(self, z: f32, stored: f32) -> bool {
+        match self {
+            DepthFunc::Less => z < stored,
+            DepthFunc::LessEqual => z <= stored,
+            DepthFunc::Greater => z > stored,
+            DepthFunc::Always => true,
+            DepthFunc::Never => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_modes() {
+        use DepthFunc::*;
+        let cases = [
+            (Less, [true, false, false]),
+            (LessEqual, [true, true, false]),
+            (Greater, [false, false, true]),
+            (Always, [true, true, true]),
+            (Never, [false, false, false]),
+        ];
+        for (f, expect) in cases {
+            let got = [f.passes(0.2, 0.5), f.passes(0.5, 0.5), f.passes(0.8, 0.5)];
+            assert_eq!(got, expect, "{f:?}");
+        }
+    }
+}
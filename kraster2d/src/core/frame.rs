@@ -1,23 +1,139 @@
 use kpix::{Color, Surface};
 
+use super::depth::DepthFunc;
+
+/// How vertex attributes (uv, color) are interpolated across a triangle.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Weight each vertex by `1/w` so attributes stay correct under projection.
+    #[default]
+    Perspective,
+    /// Linear in screen space, ignoring `w`. Enough for 2D sprites.
+    Affine,
+}
+
 /// Framebuffer wrapper for kraster2d.
-/// Phase A: color buffer only (depth is reserved for later phases).
+/// Color buffer plus an optional depth buffer (Phase F) and the state the triangle functions
+/// read: depth compare mode, depth writes and attribute interpolation. Without a depth
+/// buffer every fragment is drawn in submission order.
 #[derive(Clone, Debug)]
 pub struct Frame {
     surface: Surface,
-    #[allow(dead_code)]
-    depth: Option<Vec<f32>>, // reserved for Z-buffer (Phase F)
+    depth: Option<Vec<f32>>,
+    depth_func: DepthFunc,
+    depth_write: bool,
+    interpolation: Interpolation,
 }
 
 impl Frame {
-    /// Create a new frame with the given dimensions.
+    /// Create a new frame with the given dimensions (color only).
     pub fn new(width: usize, height: usize) -> Self {
         Self {
             surface: Surface::new(width, height),
             depth: None,
+            depth_func: DepthFunc::Less,
+            depth_write: true,
+            interpolation: Interpolation::Perspective,
         }
     }
 
+    /// Create a frame with a depth buffer cleared to 1.0.
+    pub fn with_depth(width: usize, height: usize) -> Self {
+        let mut f = Self::new(width, height);
+        f.enable_depth();
+        f
+    }
+
+    /// Allocate the depth buffer (cleared to 1.0) if it does not exist yet.
+    pub fn enable_depth(&mut self) {
+        if self.depth.is_none() {
+            self.depth = Some(vec![1.0; self.width() * self.height()]);
+        }
+    }
+
+    /// Drop the depth buffer; later draws skip the depth test.
+    pub fn disable_depth(&mut self) {
+        self.depth = None;
+    }
+
+    #[inline]
+    pub fn has_depth(&self) -> bool {
+        self.depth.is_some()
+    }
+
+    /// Fill the depth buffer with `z` (no-op without one).
+    pub fn clear_depth(&mut self, z: f32) {
+        if let Some(d) = &mut self.depth {
+            d.fill(z);
+        }
+    }
+
+    /// Depth values, row-major.
+    #[inline]
+    pub fn depth(&self) -> Option<&[f32]> {
+        self.depth.as_deref()
+    }
+
+    /// Stored depth at (x, y), or None without a buffer or outside the frame.
+    pub fn depth_at(&self, x: i32, y: i32) -> Option<f32> {
+        let i = self.index(x, y)?;
+        self.depth.as_ref().map(|d| d[i])
+    }
+
+    #[inline]
+    pub fn depth_func(&self) -> DepthFunc {
+        self.depth_func
+    }
+
+    /// Comparison used by the depth test (default `Less`).
+    pub fn set_depth_func(&mut self, func: DepthFunc) {
+        self.depth_func = func;
+    }
+
+    #[inline]
+    pub fn depth_write(&self) -> bool {
+        self.depth_write
+    }
+
+    /// Whether passing fragments store their z (default true).
+    pub fn set_depth_write(&mut self, enabled: bool) {
+        self.depth_write = enabled;
+    }
+
+    #[inline]
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Attribute interpolation used by the triangle functions (default `Perspective`).
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+    }
+
+    /// Run the depth test for a fragment at (x, y) with depth `z`, storing `z` when it
+    /// passes and writes are enabled. Always passes without a depth buffer.
+    pub fn depth_test(&mut self, x: i32, y: i32, z: f32) -> bool {
+        let Some(i) = self.index(x, y) else {
+            return false;
+        };
+        let Some(d) = &mut self.depth else {
+            return true;
+        };
+        if !self.depth_func.passes(z, d[i]) {
+            return false;
+        }
+        if self.depth_write {
+            d[i] = z;
+        }
+        true
+    }
+
+    #[inline]
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let (w, h) = (self.width() as i32, self.height() as i32);
+        (x >= 0 && y >= 0 && x < w && y < h).then(|| y as usize * w as usize + x as usize)
+    }
+
     /// Frame width in pixels.
     #[inline]
     pub fn width(&self) -> usize {
@@ -77,4 +193,27 @@ mod tests {
         let idx = f.width() + 1;
         assert_eq!(f.pixels()[idx], c.to_u32());
     }
+
+    #[test]
+    fn depth_buffer_test_and_write() {
+        let mut f = Frame::new(2, 2);
+        assert!(f.depth_test(0, 0, 5.0));
+        assert_eq!(f.depth_at(0, 0), None);
+
+        f.enable_depth();
+        assert_eq!(f.depth_at(1, 1), Some(1.0));
+        assert!(f.depth_test(1, 1, 0.5));
+        assert!(!f.depth_test(1, 1, 0.7));
+        assert_eq!(f.depth_at(1, 1), Some(0.5));
+        assert!(!f.depth_test(2, 0, 0.0));
+
+        f.set_depth_write(false);
+        assert!(f.depth_test(0, 1, 0.25));
+        assert_eq!(f.depth_at(0, 1), Some(1.0));
+
+        f.set_depth_func(DepthFunc::Greater);
+        f.clear_depth(0.0);
+        assert!(f.depth_test(1, 1, 0.1));
+        assert_eq!(f.depth(), Some(&[0.0; 4][..]));
+    }
 }
@@ -1,2 +1,4 @@
+pub mod depth;
 pub mod frame;
+pub mod sampler;
 pub mod texture;
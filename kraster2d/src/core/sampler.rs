@@ -0,0 +1,151 @@
+use kpix::Color;
+
+/// Texel filter.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Filter {
+    /// The texel containing the sample point.
+    #[default]
+    Nearest,
+    /// Weighted average of the four nearest texel centers (premultiplied alpha).
+    Bilinear,
+}
+
+/// How `Texture::sample_grad` picks between mip levels. Has no effect on a texture without
+/// a mip chain.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MipFilter {
+    /// Always sample level 0.
+    #[default]
+    None,
+    /// The level closest to the pixel footprint.
+    Nearest,
+    /// Blend the two levels around the footprint (trilinear with `Filter::Bilinear`).
+    Linear,
+}
+
+/// What a texel coordinate outside `[0, size)` maps to.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Wrap {
+    /// Tile the texture.
+    Repeat,
+    /// Tile with every other copy mirrored, so edges meet seamlessly.
+    MirroredRepeat,
+    /// Use the edge texel.
+    #[default]
+    ClampToEdge,
+    /// Use a constant color.
+    ClampToBorder(Color),
+}
+
+impl Wrap {
+    /// Texel index for `i` in a texture axis of `n` texels, or None for the border color.
+    #[inline]
+    pub fn apply(self, i: i64, n: usize) -> Option<usize> {
+        let n = n as i64;
+        let i = match self {
+            Wrap::Repeat => i.rem_euclid(n),
+            Wrap::MirroredRepeat => {
+                let m = i.rem_euclid(2 * n);
+                if m < n { m } else { 2 * n - 1 - m }
+            }
+            Wrap::ClampToEdge => i.clamp(0, n - 1),
+            Wrap::ClampToBorder(_) => {
+                if i < 0 || i >= n {
+                    return None;
+                }
+                i
+            }
+        };
+        Some(i as usize)
+    }
+
+    /// Texel-space coordinate `x` on an axis of `n` texels moved into one period, or to just
+    /// past the edges for the clamp modes, so that it and the texel after it fit an `i64`.
+    /// `apply` maps the floor of the result (and the next texel) to the same texels as `x`.
+    #[inline]
+    pub(crate) fn reduce(self, x: f64, n: usize) -> f64 {
+        let n = n as f64;
+        match self {
+            Wrap::Repeat => x.rem_euclid(n),
+            Wrap::MirroredRepeat => x.rem_euclid(2.0 * n),
+            Wrap::ClampToEdge | Wrap::ClampToBorder(_) => x.clamp(-1.0, n),
+        }
+    }
+}
+
+/// Filtering and addressing for `Texture::sample`, set independently per axis.
+/// Default: nearest, clamp to edge on both axes, no mipmapping or anisotropy.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Sampler {
+    pub filter: Filter,
+    pub mip_filter: MipFilter,
+    pub wrap_u: Wrap,
+    pub wrap_v: Wrap,
+    /// Upper bound on the taps `Texture::sample_grad` spreads along a stretched footprint;
+    /// values below 2 disable anisotropic filtering.
+    pub max_anisotropy: u32,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_mip_filter(mut self, mip_filter: MipFilter) -> Self {
+        self.mip_filter = mip_filter;
+        self
+    }
+
+    pub fn with_max_anisotropy(mut self, max_anisotropy: u32) -> Self {
+        self.max_anisotropy = max_anisotropy;
+        self
+    }
+
+    /// Same wrap mode on both axes.
+    pub fn with_wrap(self, wrap: Wrap) -> Self {
+        self.with_wrap_u(wrap).with_wrap_v(wrap)
+    }
+
+    pub fn with_wrap_u(mut self, wrap: Wrap) -> Self {
+        self.wrap_u = wrap;
+        self
+    }
+
+    pub fn with_wrap_v(mut self, wrap: Wrap) -> Self {
+        self.wrap_v = wrap;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_modes_map_indices() {
+        let map = |w: Wrap| (-5..9).map(|i| w.apply(i, 4)).collect::<Vec<_>>();
+        let some = |v: &[usize]| v.iter().map(|&i| Some(i)).collect::<Vec<_>>();
+        assert_eq!(
+            map(Wrap::Repeat),
+            some(&[3, 0, 1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3, 0])
+        );
+        assert_eq!(
+            map(Wrap::MirroredRepeat),
+            some(&[3, 3, 2, 1, 0, 0, 1, 2, 3, 3, 2, 1, 0, 0])
+        );
+        assert_eq!(
+            map(Wrap::ClampToEdge),
+            some(&[0, 0, 0, 0, 0, 0, 1, 2, 3, 3, 3, 3, 3, 3])
+        );
+        let border = map(Wrap::ClampToBorder(Color::rgba(0, 0, 0, 0)));
+        assert_eq!(
+            border[4..10],
+            [None, Some(0), Some(1), Some(2), Some(3), None]
+        );
+    }
+}
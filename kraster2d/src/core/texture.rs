@@ -1,11 +1,15 @@
 use kmath::Vec2;
 use kpix::Color;
+use kpix::color::{linear_to_srgb, srgb_to_linear};
+
+use super::sampler::{Filter, MipFilter, Sampler, Wrap};
 
 #[derive(Clone, Debug)]
 pub struct Texture {
     width: usize,
     height: usize,
-    pixels: Vec<u32>, // packed RGBA little-endian
+    pixels: Vec<u32>,   // packed RGBA little-endian
+    mips: Vec<Texture>, // levels 1.. (each half the size of the previous), empty until generated
 }
 
 impl Texture {
@@ -16,9 +20,78 @@ impl Texture {
             width,
             height,
             pixels,
+            mips: Vec::new(),
         }
     }
 
+    /// `self` with a full mip chain; see `generate_mipmaps`.
+    pub fn with_mipmaps(mut self) -> Self {
+        self.generate_mipmaps();
+        self
+    }
+
+    /// Build levels 1.. down to 1x1, each half the previous size (rounded down, at least 1).
+    /// Every texel is a box average of the 2x2 texels it covers (3 along an odd axis, so no
+    /// row or column is dropped), taken in linear light with premultiplied alpha: a black and
+    /// white checker minifies to sRGB 188 rather than 128, and transparent texels do not
+    /// darken their neighbours. Replaces any previous chain.
+    pub fn generate_mipmaps(&mut self) {
+        self.mips.clear();
+        let mut prev: &Texture = self;
+        let mut chain = Vec::new();
+        while prev.width > 1 || prev.height > 1 {
+            chain.push(prev.downsample());
+            prev = chain.last().expect("just pushed");
+        }
+        self.mips = chain;
+    }
+
+    /// Number of mip levels including the base texture (1 until mipmaps are generated).
+    #[inline]
+    pub fn mip_levels(&self) -> usize {
+        1 + self.mips.len()
+    }
+
+    /// Mip level `level` (0 is the base texture).
+    pub fn mip(&self, level: usize) -> Option<&Texture> {
+        match level {
+            0 => Some(self),
+            _ => self.mips.get(level - 1),
+        }
+    }
+
+    fn downsample(&self) -> Texture {
+        let (sw, sh) = (self.width, self.height);
+        let (dw, dh) = ((sw / 2).max(1), (sh / 2).max(1));
+        // Source range covered by destination index `i` of `d` along an axis of `s`.
+        let span = |i: usize, s: usize, d: usize| (i * s / d)..((i + 1) * s).div_ceil(d);
+        let mut pixels = Vec::with_capacity(dw * dh);
+        for y in 0..dh {
+            for x in 0..dw {
+                let mut acc = [0.0f32; 4];
+                let mut n = 0.0;
+                for sy in span(y, sh, dh) {
+                    for sx in span(x, sw, dw) {
+                        let c = Color::from_u32(self.pixels[sy * sw + sx]);
+                        let a = c.a as f32 / 255.0;
+                        acc[0] += srgb_to_linear(c.r as f32 / 255.0) * a;
+                        acc[1] += srgb_to_linear(c.g as f32 / 255.0) * a;
+                        acc[2] += srgb_to_linear(c.b as f32 / 255.0) * a;
+                        acc[3] += a;
+                        n += 1.0;
+                    }
+                }
+                let enc = |v: f32| {
+                    let v = if acc[3] > 0.0 { v / acc[3] } else { 0.0 };
+                    (linear_to_srgb(v.clamp(0.0, 1.0)) * 255.0).round() as u8
+                };
+                let a = (acc[3] / n * 255.0).round() as u8;
+                pixels.push(Color::rgba(enc(acc[0]), enc(acc[1]), enc(acc[2]), a).to_u32());
+            }
+        }
+        Texture::from_rgba_le(pixels, dw, dh)
+    }
+
     #[inline]
     pub fn width(&self) -> usize {
         self.width
@@ -30,6 +103,8 @@ impl Texture {
     }
 
     /// Nearest sampling with clamp. uv expected in [0,1]. Origin at top-left (v grows downward).
+    /// uv 0 and 1 land on the centers of the first and last texels, unlike `sample`, where
+    /// each texel covers an equal share of [0,1].
     pub fn sample_nearest(&self, uv: Vec2) -> Color {
         let u = uv.x.clamp(0.0, 1.0);
         let v = uv.y.clamp(0.0, 1.0);
@@ -42,6 +117,140 @@ impl Texture {
         let idx = ty * self.width + tx;
         Color::from_u32(self.pixels[idx])
     }
+
+    /// Sample at `uv` with the sampler's filter and per-axis wrap modes. Texel (i, j) covers
+    /// uv `[i/w, (i+1)/w) x [j/h, (j+1)/h)`; bilinear weights are measured between texel
+    /// centers. Non-finite uv reads as 0.
+    pub fn sample(&self, uv: Vec2, sampler: &Sampler) -> Color {
+        let finite = |v: f32| if v.is_finite() { v as f64 } else { 0.0 };
+        let x = finite(uv.x) * self.width as f64;
+        let y = finite(uv.y) * self.height as f64;
+        let reduce = |x: f64, y: f64| {
+            let x = sampler.wrap_u.reduce(x, self.width);
+            (x, sampler.wrap_v.reduce(y, self.height))
+        };
+        match sampler.filter {
+            Filter::Nearest => {
+                let (x, y) = reduce(x, y);
+                self.texel(x.floor() as i64, y.floor() as i64, sampler)
+            }
+            Filter::Bilinear => {
+                let (x, y) = reduce(x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = ((x - x0) as f32, (y - y0) as f32);
+                let (ix, iy) = (x0 as i64, y0 as i64);
+                let taps = [
+                    (self.texel(ix, iy, sampler), (1.0 - fx) * (1.0 - fy)),
+                    (self.texel(ix + 1, iy, sampler), fx * (1.0 - fy)),
+                    (self.texel(ix, iy + 1, sampler), (1.0 - fx) * fy),
+                    (self.texel(ix + 1, iy + 1, sampler), fx * fy),
+                ];
+                blend_premultiplied(taps)
+            }
+        }
+    }
+
+    /// Sample at `uv` given how far uv moves per pixel along screen x (`duv_dx`) and y
+    /// (`duv_dy`). The footprint's longer axis, measured in level-0 texels, picks the level of
+    /// detail (log2 of its length) for the sampler's `MipFilter`; below 1 texel the base
+    /// level is magnified with `filter`. With `max_anisotropy >= 2` a stretched footprint is
+    /// covered by up to that many taps along its long axis instead, each at the finer level
+    /// that fits the short axis. Without a mip chain or mip filter only anisotropy applies.
+    pub fn sample_grad(&self, uv: Vec2, duv_dx: Vec2, duv_dy: Vec2, sampler: &Sampler) -> Color {
+        let texels = |d: Vec2| Vec2::new(d.x * self.width as f32, d.y * self.height as f32);
+        let (lx, ly) = (texels(duv_dx).length(), texels(duv_dy).length());
+        let (major, minor, axis) = if lx >= ly {
+            (lx, ly, duv_dx)
+        } else {
+            (ly, lx, duv_dy)
+        };
+        let taps = if sampler.max_anisotropy >= 2 && major > 1.0 {
+            let ratio = (major / minor.max(1e-6)).ceil();
+            if ratio.is_finite() {
+                ratio.min(sampler.max_anisotropy as f32) as usize
+            } else {
+                sampler.max_anisotropy as usize
+            }
+        } else {
+            1
+        };
+        let lod = (major / taps as f32).log2();
+        if taps == 1 {
+            return self.sample_lod(uv, lod, sampler);
+        }
+        let w = 1.0 / taps as f32;
+        blend_premultiplied((0..taps).map(|i| {
+            let t = (i as f32 + 0.5) * w - 0.5;
+            (self.sample_lod(uv + axis * t, lod, sampler), w)
+        }))
+    }
+
+    /// Sample at level of detail `lod` (0 = base, 1 = half size, ...), clamped to the chain.
+    fn sample_lod(&self, uv: Vec2, lod: f32, sampler: &Sampler) -> Color {
+        let last = self.mips.len();
+        let level = |i: usize| self.mip(i.min(last)).expect("clamped to the chain");
+        if self.mips.is_empty() || lod.is_nan() || lod <= 0.0 {
+            return self.sample(uv, sampler);
+        }
+        match sampler.mip_filter {
+            MipFilter::None => self.sample(uv, sampler),
+            MipFilter::Nearest => level(lod.round() as usize).sample(uv, sampler),
+            MipFilter::Linear => {
+                let base = lod.floor();
+                let (i, f) = (base as usize, lod - base);
+                if i >= last || f <= 0.0 {
+                    return level(i).sample(uv, sampler);
+                }
+                blend_premultiplied([
+                    (level(i).sample(uv, sampler), 1.0 - f),
+                    (level(i + 1).sample(uv, sampler), f),
+                ])
+            }
+        }
+    }
+
+    /// Texel at integer coordinates after wrapping; the border color when either axis is
+    /// outside a `ClampToBorder` range.
+    fn texel(&self, x: i64, y: i64, sampler: &Sampler) -> Color {
+        let tx = sampler.wrap_u.apply(x, self.width);
+        let ty = sampler.wrap_v.apply(y, self.height);
+        match (tx, ty) {
+            (Some(tx), Some(ty)) => Color::from_u32(self.pixels[ty * self.width + tx]),
+            _ => border(sampler, tx.is_none()),
+        }
+    }
+}
+
+/// Border color of the axis that left its range (u first).
+fn border(sampler: &Sampler, u_out: bool) -> Color {
+    let wrap = if u_out {
+        sampler.wrap_u
+    } else {
+        sampler.wrap_v
+    };
+    match wrap {
+        Wrap::ClampToBorder(c) => c,
+        _ => Color::rgba(0, 0, 0, 0),
+    }
+}
+
+/// Weighted sum of straight-alpha colors, accumulated premultiplied so transparent texels
+/// do not bleed their color.
+fn blend_premultiplied(taps: impl IntoIterator<Item = (Color, f32)>) -> Color {
+    let mut acc = [0.0f32; 4];
+    for (c, w) in taps {
+        let a = c.a as f32 * w;
+        acc[0] += c.r as f32 * a;
+        acc[1] += c.g as f32 * a;
+        acc[2] += c.b as f32 * a;
+        acc[3] += a;
+    }
+    if acc[3] <= 0.0 {
+        return Color::rgba(0, 0, 0, 0);
+    }
+    let ch = |v: f32| (v / acc[3]).round().clamp(0.0, 255.0) as u8;
+    let a = acc[3].round().clamp(0.0, 255.0) as u8;
+    Color::rgba(ch(acc[0]), ch(acc[1]), ch(acc[2]), a)
 }
 
 #[cfg(test)]
@@ -72,6 +281,19 @@ mod tests {
         assert_eq!(tl2, Color::rgba(255, 0, 0, 255));
     }
 
+    #[test]
+    fn nearest_sampling_maps_uv_across_texel_centers() {
+        // 4x1 with texel i = i * 10 in red: u = 0.2 rounds 0.6 to texel 1, where `sample`
+        // reads texel 0.
+        let px = (0..4)
+            .map(|i| Color::rgba(i * 10, 0, 0, 255).to_u32())
+            .collect();
+        let tex = Texture::from_rgba_le(px, 4, 1);
+        let at = |u: f32| tex.sample_nearest(Vec2::new(u, 0.5)).r;
+        assert_eq!([0.0, 0.2, 0.5, 0.8, 1.0].map(at), [0, 10, 20, 20, 30]);
+        assert_eq!(tex.sample(Vec2::new(0.2, 0.5), &Sampler::new()).r, 0);
+    }
+
     #[test]
     #[should_panic]
     fn zero_width_is_disallowed() {
@@ -83,4 +305,179 @@ mod tests {
     fn zero_height_is_disallowed() {
         let _ = Texture::from_rgba_le(Vec::new(), 1, 0);
     }
+
+    fn tex_2x2() -> Texture {
+        let px = vec![
+            Color::rgba(255, 0, 0, 255).to_u32(),
+            Color::rgba(0, 255, 0, 255).to_u32(),
+            Color::rgba(0, 0, 255, 255).to_u32(),
+            Color::rgba(255, 255, 255, 255).to_u32(),
+        ];
+        Texture::from_rgba_le(px, 2, 2)
+    }
+
+    #[test]
+    fn wrap_modes_apply_per_axis() {
+        let tex = tex_2x2();
+        let red = Color::rgba(255, 0, 0, 255);
+        let green = Color::rgba(0, 255, 0, 255);
+        let blue = Color::rgba(0, 0, 255, 255);
+        let repeat = Sampler::new().with_wrap(Wrap::Repeat);
+        assert_eq!(tex.sample(Vec2::new(1.25, 2.25), &repeat), red);
+        assert_eq!(tex.sample(Vec2::new(-0.25, 0.25), &repeat), green);
+        let mirror = Sampler::new().with_wrap(Wrap::MirroredRepeat);
+        assert_eq!(tex.sample(Vec2::new(1.25, 0.25), &mirror), green);
+        assert_eq!(tex.sample(Vec2::new(2.25, 0.25), &mirror), red);
+
+        let pink = Color::rgba(255, 0, 255, 255);
+        let mixed = Sampler::new()
+            .with_wrap_u(Wrap::Repeat)
+            .with_wrap_v(Wrap::ClampToBorder(pink));
+        assert_eq!(
+            tex.sample(Vec2::new(1.75, 0.75), &mixed),
+            Color::rgba(255, 255, 255, 255)
+        );
+        assert_eq!(tex.sample(Vec2::new(0.25, 1.25), &mixed), pink);
+        assert_eq!(tex.sample(Vec2::new(0.25, 5.0), &Sampler::new()), blue);
+    }
+
+    #[test]
+    fn bilinear_blends_between_texel_centers() {
+        let tex = tex_2x2();
+        let lin = Sampler::new().with_filter(Filter::Bilinear);
+        // Texel centers return the texel; the middle averages all four.
+        assert_eq!(
+            tex.sample(Vec2::new(0.25, 0.25), &lin),
+            Color::rgba(255, 0, 0, 255)
+        );
+        assert_eq!(
+            tex.sample(Vec2::new(0.5, 0.5), &lin),
+            Color::rgba(128, 128, 128, 255)
+        );
+        // Halfway between red and green along the top row.
+        assert_eq!(
+            tex.sample(Vec2::new(0.5, 0.25), &lin),
+            Color::rgba(128, 128, 0, 255)
+        );
+        // Clamped edges do not blend past the edge texel.
+        assert_eq!(
+            tex.sample(Vec2::new(0.0, 0.25), &lin),
+            Color::rgba(255, 0, 0, 255)
+        );
+        // Repeat blends the right edge with the left one.
+        let rep = lin.with_wrap(Wrap::Repeat);
+        assert_eq!(
+            tex.sample(Vec2::new(1.0, 0.25), &rep),
+            Color::rgba(128, 128, 0, 255)
+        );
+
+        // A transparent border fades alpha without darkening the color.
+        let fade = lin.with_wrap(Wrap::ClampToBorder(Color::rgba(0, 0, 0, 0)));
+        let c = tex.sample(Vec2::new(0.0, 0.25), &fade);
+        assert_eq!((c.r, c.g, c.b, c.a), (255, 0, 0, 128));
+    }
+
+    #[test]
+    fn far_and_non_finite_uv_stay_in_range() {
+        let tex = tex_2x2();
+        let red = Color::rgba(255, 0, 0, 255);
+        let white = Color::rgba(255, 255, 255, 255);
+        for filter in [Filter::Nearest, Filter::Bilinear] {
+            for wrap in [
+                Wrap::Repeat,
+                Wrap::MirroredRepeat,
+                Wrap::ClampToEdge,
+                Wrap::ClampToBorder(red),
+            ] {
+                let s = Sampler::new().with_filter(filter).with_wrap(wrap);
+                for uv in [
+                    Vec2::new(1e19, 0.5),
+                    Vec2::new(-1e19, 0.5),
+                    Vec2::new(f32::MAX, f32::MIN),
+                    Vec2::new(f32::NAN, f32::INFINITY),
+                ] {
+                    tex.sample(uv, &s);
+                }
+            }
+        }
+        // Whole periods away, repeat reads the same texel.
+        let repeat = Sampler::new()
+            .with_filter(Filter::Bilinear)
+            .with_wrap(Wrap::Repeat);
+        assert_eq!(tex.sample(Vec2::new(4096.75, 0.75), &repeat), white);
+        let clamp = Sampler::new().with_filter(Filter::Bilinear);
+        assert_eq!(tex.sample(Vec2::new(1e19, 1e19), &clamp), white);
+        assert_eq!(tex.sample(Vec2::new(-1e19, -1e19), &clamp), red);
+        assert_eq!(tex.sample(Vec2::new(f32::NAN, 0.25), &clamp), red);
+    }
+
+    #[test]
+    fn mipmaps_average_in_linear_light() {
+        let (black, white) = (Color::rgba(0, 0, 0, 255), Color::rgba(255, 255, 255, 255));
+        let px = (0..16)
+            .map(|i| {
+                if (i % 4 + i / 4) % 2 == 0 {
+                    black
+                } else {
+                    white
+                }
+                .to_u32()
+            })
+            .collect();
+        let tex = Texture::from_rgba_le(px, 4, 4).with_mipmaps();
+        assert_eq!(tex.mip_levels(), 3);
+        let l1 = tex.mip(1).unwrap();
+        assert_eq!((l1.width(), l1.height()), (2, 2));
+        assert_eq!(
+            Color::from_u32(l1.pixels[0]),
+            Color::rgba(188, 188, 188, 255)
+        );
+        assert!(tex.mip(3).is_none());
+
+        // Odd sizes keep every texel; a transparent texel only lowers alpha.
+        let clear = Color::rgba(0, 0, 0, 0).to_u32();
+        let red = Color::rgba(255, 0, 0, 255).to_u32();
+        let tex =
+            Texture::from_rgba_le(vec![red, clear, red, red, clear, red], 3, 2).with_mipmaps();
+        assert_eq!(tex.mip_levels(), 2);
+        let top = tex.mip(1).unwrap();
+        assert_eq!((top.width(), top.height()), (1, 1));
+        assert_eq!(Color::from_u32(top.pixels[0]), Color::rgba(255, 0, 0, 170));
+    }
+
+    #[test]
+    fn sample_grad_picks_levels_from_the_footprint() {
+        // 4x4: level 0 red, averages to a single purple texel two levels down.
+        let red = Color::rgba(255, 0, 0, 255);
+        let blue = Color::rgba(0, 0, 255, 255);
+        let px = (0..16)
+            .map(|i| if (i / 4) % 2 == 0 { red } else { blue }.to_u32())
+            .collect();
+        let tex = Texture::from_rgba_le(px, 4, 4).with_mipmaps();
+        let purple = Color::from_u32(tex.mip(2).unwrap().pixels[0]);
+        let uv = Vec2::new(0.125, 0.125);
+        let (one, four) = (Vec2::new(0.25, 0.0), Vec2::new(1.0, 0.0));
+        let dy = |d: Vec2| Vec2::new(d.y, d.x);
+
+        let nearest = Sampler::new().with_mip_filter(MipFilter::Nearest);
+        assert_eq!(tex.sample_grad(uv, one, dy(one), &nearest), red);
+        assert_eq!(tex.sample_grad(uv, four, dy(four), &nearest), purple);
+        // No mip filter ignores the footprint.
+        assert_eq!(tex.sample_grad(uv, four, dy(four), &Sampler::new()), red);
+
+        // Linear blends red level 0 and purple level 1 halfway at a 1.41-texel footprint.
+        let linear = Sampler::new().with_mip_filter(MipFilter::Linear);
+        let mid = Vec2::new(2.0f32.sqrt() / 4.0, 0.0);
+        let c = tex.sample_grad(uv, mid, dy(mid), &linear);
+        let half = |a: u8, b: u8| (a as i32 + b as i32) / 2;
+        assert!((c.r as i32 - half(red.r, purple.r)).abs() <= 1, "{c:?}");
+        assert!((c.b as i32 - half(red.b, purple.b)).abs() <= 1, "{c:?}");
+
+        // A footprint 4 texels along u but 1 along v stays within the red row when
+        // anisotropic taps are allowed.
+        let thin = Vec2::new(0.0, 0.25);
+        assert_eq!(tex.sample_grad(uv, four, thin, &nearest), purple);
+        let aniso = nearest.with_max_anisotropy(4);
+        assert_eq!(tex.sample_grad(uv, four, thin, &aniso), red);
+    }
 }
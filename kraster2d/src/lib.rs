@@ -2,4 +2,6 @@ pub mod core;
 pub mod io;
 pub mod raster;
 
-pub use core::frame::Frame;
+pub use core::depth::DepthFunc;
+pub use core::frame::{Frame, Interpolation};
+pub use core::sampler::{Filter, MipFilter, Sampler, Wrap};
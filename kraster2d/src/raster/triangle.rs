@@ -2,14 +2,18 @@ use kmath::{Vec2, Vec3, num::EPS};
 use kpix::Color;
 
 use super::triangle_setup::{bbox_clamped, edge_function, is_top_left, signed_area};
-use crate::core::frame::Frame;
+use crate::core::frame::{Frame, Interpolation};
+use crate::core::sampler::Sampler;
 use crate::core::texture::Texture;
 
 #[derive(Copy, Clone, Debug)]
 pub struct Vertex {
-    pub pos: Vec3,       // screen space (x,y in pixels), z reserved
+    pub pos: Vec3,       // screen space (x,y in pixels), z depth (see `Frame::depth_test`)
     pub uv: Vec2,        // for later phases
     pub color: [f32; 3], // for later phases
+    /// Clip-space w (view-space depth for a pinhole projection); 1.0 for 2D. Must be positive
+    /// for perspective-correct interpolation.
+    pub w: f32,
 }
 
 impl Vertex {
@@ -18,60 +22,109 @@ impl Vertex {
             pos,
             uv: Vec2::ZERO,
             color: [1.0, 1.0, 1.0],
+            w: 1.0,
         }
     }
+
+    pub fn with_w(mut self, w: f32) -> Self {
+        self.w = w;
+        self
+    }
 }
 
 /// Draw a filled triangle with a solid color using barycentric edge functions and Top-Left rule.
 pub fn draw_triangle_solid(frame: &mut Frame, v0: Vertex, v1: Vertex, v2: Vertex, color: Color) {
-    let (w, h) = (frame.width(), frame.height());
-    raster_core(w, h, v0, v1, v2, |x, y, _a, _b, _c| {
-        frame.set_pixel(x, y, color);
-    });
+    raster_core(frame, v0, v1, v2, |_frag| color);
 }
 
 /// Draw a filled triangle with vertex color interpolation.
 pub fn draw_triangle_vertex_color(frame: &mut Frame, v0: Vertex, v1: Vertex, v2: Vertex) {
-    let (w, h) = (frame.width(), frame.height());
-    raster_core(w, h, v0, v1, v2, |x, y, a, b, c| {
+    raster_core(frame, v0, v1, v2, |frag| {
+        let [a, b, c] = frag.weights();
         let r = (a * v0.color[0] + b * v1.color[0] + c * v2.color[0]).clamp(0.0, 1.0);
         let g = (a * v0.color[1] + b * v1.color[1] + c * v2.color[1]).clamp(0.0, 1.0);
         let bch = (a * v0.color[2] + b * v1.color[2] + c * v2.color[2]).clamp(0.0, 1.0);
         let to_u8 = |v: f32| -> u8 { (v * 255.0 + 0.5).floor().clamp(0.0, 255.0) as u8 };
-        let col = Color::rgba(to_u8(r), to_u8(g), to_u8(bch), 255);
-        frame.set_pixel(x, y, col);
+        Color::rgba(to_u8(r), to_u8(g), to_u8(bch), 255)
     });
 }
 
-/// Draw a textured triangle with nearest sampling (uv interpolation, clamp).
+/// Draw a textured triangle, sampling `tex` at the interpolated uv with `sampler`. The uv
+/// derivatives for mip selection are forward differences to the next pixel in x and y, as
+/// a 2x2 quad would give, evaluated exactly from the (perspective-correct) interpolation.
 pub fn draw_triangle_textured(
     frame: &mut Frame,
     v0: Vertex,
     v1: Vertex,
     v2: Vertex,
     tex: &Texture,
+    sampler: &Sampler,
 ) {
-    let (w, h) = (frame.width(), frame.height());
-    raster_core(w, h, v0, v1, v2, |x, y, a, b, c| {
-        let uv = Vec2::new(
+    let uv_at = |[a, b, c]: [f32; 3]| {
+        Vec2::new(
             a * v0.uv.x + b * v1.uv.x + c * v2.uv.x,
             a * v0.uv.y + b * v1.uv.y + c * v2.uv.y,
-        );
-        let col = tex.sample_nearest(uv);
-        frame.set_pixel(x, y, col);
+        )
+    };
+    raster_core(frame, v0, v1, v2, |frag| {
+        let uv = uv_at(frag.weights());
+        let duv_dx = uv_at(frag.weights_at(1.0, 0.0)) - uv;
+        let duv_dy = uv_at(frag.weights_at(0.0, 1.0)) - uv;
+        tex.sample_grad(uv, duv_dx, duv_dy, sampler)
     });
 }
 
-/// Shared rasterization core: handles orientation, bbox, Top-Left rule, and barycentric weights.
-/// The callback receives integer pixel coords and normalized weights (a,b,c) summing to ~1.
+/// Interpolation state of one covered pixel, handed to the shading callback of `raster_core`.
+struct Fragment {
+    screen: [f32; 3], // screen-space barycentric weights at the pixel center
+    step_x: [f32; 3], // change of `screen` per pixel along x
+    step_y: [f32; 3], // ... and along y
+    inv_w: [f32; 3],  // 1/w per vertex (all 1.0 for affine interpolation)
+}
+
+impl Fragment {
+    /// Normalized attribute weights at the pixel center.
+    #[inline]
+    fn weights(&self) -> [f32; 3] {
+        self.weights_at(0.0, 0.0)
+    }
+
+    /// Normalized attribute weights `(dx, dy)` pixels away from the center; the point may
+    /// lie outside the triangle, which is what screen-space derivatives need at its edges.
+    fn weights_at(&self, dx: f32, dy: f32) -> [f32; 3] {
+        let p: [f32; 3] = std::array::from_fn(|i| {
+            (self.screen[i] + dx * self.step_x[i] + dy * self.step_y[i]) * self.inv_w[i]
+        });
+        let s = p[0] + p[1] + p[2];
+        p.map(|v| v / s)
+    }
+}
+
+/// Shared rasterization core: handles orientation, bbox, Top-Left rule, barycentric weights
+/// and the depth test. z is interpolated linearly in screen space; fragments that fail the
+/// frame's depth test are skipped before shading. The callback receives a `Fragment` whose
+/// normalized weights (a,b,c) sum to ~1 and returns the pixel color. With
+/// `Interpolation::Perspective` the weights are divided by each vertex's w and renormalized;
+/// triangles with a non-positive or non-finite w are skipped (there is no near-plane
+/// clipping).
 fn raster_core(
-    width: usize,
-    height: usize,
+    frame: &mut Frame,
     v0: Vertex,
     v1: Vertex,
     v2: Vertex,
-    mut shade: impl FnMut(i32, i32, f32, f32, f32),
+    mut shade: impl FnMut(&Fragment) -> Color,
 ) {
+    let (width, height) = (frame.width(), frame.height());
+    let inv_w = match frame.interpolation() {
+        Interpolation::Perspective => {
+            let ws = [v0.w, v1.w, v2.w];
+            if ws.iter().any(|w| !(w.is_finite() && *w > EPS)) {
+                return;
+            }
+            ws.map(|w| 1.0 / w)
+        }
+        Interpolation::Affine => [1.0; 3],
+    };
     let p0 = Vec2::new(v0.pos.x, v0.pos.y);
     let p1 = Vec2::new(v1.pos.x, v1.pos.y);
     let p2 = Vec2::new(v2.pos.x, v2.pos.y);
@@ -82,6 +135,10 @@ fn raster_core(
     }
     let sign = if area < 0.0 { -1.0 } else { 1.0 };
     let inv_area = 1.0 / area.abs();
+    // Edge functions are affine in p, so each weight changes by a constant per pixel.
+    let k = sign * inv_area;
+    let step_x = [(p1.y - p2.y) * k, (p2.y - p0.y) * k, (p0.y - p1.y) * k];
+    let step_y = [(p2.x - p1.x) * k, (p0.x - p2.x) * k, (p1.x - p0.x) * k];
 
     let (min_x, min_y, max_x, max_y) = bbox_clamped(p0, p1, p2, width, height);
 
@@ -106,7 +163,16 @@ fn raster_core(
             let a = w0 * inv_area;
             let b = w1 * inv_area;
             let c = w2 * inv_area;
-            shade(x, y, a, b, c);
+            let z = a * v0.pos.z + b * v1.pos.z + c * v2.pos.z;
+            if frame.depth_test(x, y, z) {
+                let frag = Fragment {
+                    screen: [a, b, c],
+                    step_x,
+                    step_y,
+                    inv_w,
+                };
+                frame.set_pixel(x, y, shade(&frag));
+            }
         }
     }
 }
@@ -114,7 +180,7 @@ fn raster_core(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Frame;
+    use crate::{DepthFunc, Frame, MipFilter};
 
     #[test]
     fn solid_triangle_renders_some_pixels() {
@@ -170,16 +236,19 @@ mod tests {
             pos: Vec3::new(4.0, 4.0, 0.0),
             uv: Vec2::ZERO,
             color: [1.0, 1.0, 1.0],
+            w: 1.0,
         };
         let v1 = Vertex {
             pos: Vec3::new(28.0, 5.0, 0.0),
             uv: Vec2::ZERO,
             color: [1.0, 1.0, 1.0],
+            w: 1.0,
         };
         let v2 = Vertex {
             pos: Vec3::new(10.0, 26.0, 0.0),
             uv: Vec2::ZERO,
             color: [1.0, 1.0, 1.0],
+            w: 1.0,
         };
         let c = Color::rgba(255, 255, 255, 255);
         draw_triangle_solid(&mut f_solid, v0, v1, v2, c);
@@ -205,20 +274,177 @@ mod tests {
             pos: Vec3::new(2.0, 2.0, 0.0),
             uv: Vec2::new(0.0, 0.0),
             color: [0.0; 3],
+            w: 1.0,
         };
         let v1 = Vertex {
             pos: Vec3::new(12.0, 2.0, 0.0),
             uv: Vec2::new(0.0, 0.0),
             color: [0.0; 3],
+            w: 1.0,
         };
         let v2 = Vertex {
             pos: Vec3::new(2.0, 12.0, 0.0),
             uv: Vec2::new(0.0, 0.0),
             color: [0.0; 3],
+            w: 1.0,
         };
-        draw_triangle_textured(&mut f, v0, v1, v2, &tex);
+        draw_triangle_textured(&mut f, v0, v1, v2, &tex, &Sampler::new());
         // Find at least one filled pixel and assert it's red
         let red = Color::rgba(255, 0, 0, 255).to_u32();
         assert!(f.pixels().contains(&red));
     }
+
+    fn flat(x0: f32, y0: f32, x1: f32, y1: f32, z: f32) -> [Vertex; 3] {
+        [
+            Vertex::new(Vec3::new(x0, y0, z)),
+            Vertex::new(Vec3::new(x1, y0, z)),
+            Vertex::new(Vec3::new(x0, y1, z)),
+        ]
+    }
+
+    #[test]
+    fn depth_test_sorts_overlaps_in_any_order() {
+        let red = Color::rgba(255, 0, 0, 255);
+        let blue = Color::rgba(0, 0, 255, 255);
+        let [a0, a1, a2] = flat(0.0, 0.0, 16.0, 16.0, 0.2);
+        let [b0, b1, b2] = flat(0.0, 0.0, 16.0, 16.0, 0.8);
+        let mut near_first = Frame::with_depth(16, 16);
+        draw_triangle_solid(&mut near_first, a0, a1, a2, red);
+        draw_triangle_solid(&mut near_first, b0, b1, b2, blue);
+        let mut far_first = Frame::with_depth(16, 16);
+        draw_triangle_solid(&mut far_first, b0, b1, b2, blue);
+        draw_triangle_solid(&mut far_first, a0, a1, a2, red);
+        assert_eq!(near_first.pixels(), far_first.pixels());
+        assert_eq!(near_first.pixels()[0], red.to_u32());
+        let z = near_first.depth_at(0, 0).unwrap();
+        assert!((z - 0.2).abs() < 1e-6);
+
+        // Without a depth buffer the last draw wins.
+        let mut plain = Frame::new(16, 16);
+        draw_triangle_solid(&mut plain, a0, a1, a2, red);
+        draw_triangle_solid(&mut plain, b0, b1, b2, blue);
+        assert_eq!(plain.pixels()[0], blue.to_u32());
+
+        let mut greater = Frame::with_depth(16, 16);
+        greater.set_depth_func(DepthFunc::Greater);
+        greater.clear_depth(0.0);
+        draw_triangle_solid(&mut greater, b0, b1, b2, blue);
+        draw_triangle_solid(&mut greater, a0, a1, a2, red);
+        assert_eq!(greater.pixels()[0], blue.to_u32());
+
+        let mut never = Frame::with_depth(16, 16);
+        never.set_depth_func(DepthFunc::Never);
+        draw_triangle_solid(&mut never, a0, a1, a2, red);
+        assert!(never.pixels().iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn interpolated_depth_splits_intersecting_triangles() {
+        // Two triangles crossing at x = 8: one tilts toward the viewer left to right, the
+        // other away.
+        let white = Color::rgba(255, 255, 255, 255);
+        let tri = |zl: f32, zr: f32, rgb: [f32; 3]| {
+            let v = |x: f32, y: f32, z: f32| Vertex {
+                pos: Vec3::new(x, y, z),
+                uv: Vec2::ZERO,
+                color: rgb,
+                w: 1.0,
+            };
+            [v(0.0, 0.0, zl), v(32.0, 0.0, zr), v(0.0, 32.0, zl)]
+        };
+        let [a0, a1, a2] = tri(0.0, 1.0, [1.0, 0.0, 0.0]);
+        let [b0, b1, b2] = tri(0.5, 0.25, [0.0, 0.0, 1.0]);
+        let mut f = Frame::with_depth(32, 32);
+        draw_triangle_vertex_color(&mut f, a0, a1, a2);
+        draw_triangle_vertex_color(&mut f, b0, b1, b2);
+        let at = |f: &Frame, x: usize| Color::from_u32(f.pixels()[2 * 32 + x]);
+        assert_eq!(at(&f, 4), Color::rgba(255, 0, 0, 255));
+        assert_eq!(at(&f, 24), Color::rgba(0, 0, 255, 255));
+
+        // Textured draws are depth tested too: redrawing the first triangle at equal depth
+        // only replaces its visible part. Writes can be masked off.
+        let tex = Texture::from_rgba_le(vec![white.to_u32()], 1, 1);
+        f.set_depth_func(DepthFunc::LessEqual);
+        f.set_depth_write(false);
+        let before = f.depth().unwrap().to_vec();
+        draw_triangle_textured(&mut f, a0, a1, a2, &tex, &Sampler::new());
+        assert_eq!(at(&f, 4), white);
+        assert_eq!(at(&f, 24), Color::rgba(0, 0, 255, 255));
+        assert_eq!(f.depth().unwrap(), &before[..]);
+    }
+
+    #[test]
+    fn perspective_weights_follow_w() {
+        // Red runs from 0 at v0 to 1 at v1, which is four times farther away.
+        let mut v0 = Vertex::new(Vec3::new(0.0, 0.0, 0.0));
+        v0.color = [0.0; 3];
+        let mut v1 = Vertex::new(Vec3::new(100.0, 0.0, 0.0)).with_w(4.0);
+        v1.color = [1.0, 0.0, 0.0];
+        let mut v2 = Vertex::new(Vec3::new(0.0, 100.0, 0.0));
+        v2.color = [0.0; 3];
+        let red_at = |f: &Frame| Color::from_u32(f.pixels()[49]).r;
+
+        // Halfway across the screen is only a fifth of the way along the far edge.
+        let mut f = Frame::new(100, 100);
+        draw_triangle_vertex_color(&mut f, v0, v1, v2);
+        assert!((48..=53).contains(&red_at(&f)), "{}", red_at(&f));
+
+        let mut affine = Frame::new(100, 100);
+        affine.set_interpolation(Interpolation::Affine);
+        draw_triangle_vertex_color(&mut affine, v0, v1, v2);
+        assert!((124..=128).contains(&red_at(&affine)));
+
+        // Equal w gives the affine result; a vertex behind the eye drops the triangle.
+        let (a, b, c) = (v0.with_w(2.0), v1.with_w(2.0), v2.with_w(2.0));
+        let mut same = Frame::new(100, 100);
+        draw_triangle_vertex_color(&mut same, a, b, c);
+        assert_eq!(red_at(&same), red_at(&affine));
+        let mut behind = Frame::new(100, 100);
+        draw_triangle_vertex_color(&mut behind, v0.with_w(-1.0), v1, v2);
+        assert!(behind.pixels().iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn textured_derivatives_select_mip_level() {
+        // 1-pixel checker: level 0 is black/white, every smaller level is uniform grey.
+        let (black, white) = (Color::rgba(0, 0, 0, 255), Color::rgba(255, 255, 255, 255));
+        let px = (0..64 * 64)
+            .map(|i| {
+                if (i % 64 + i / 64) % 2 == 0 {
+                    black
+                } else {
+                    white
+                }
+                .to_u32()
+            })
+            .collect();
+        let tex = Texture::from_rgba_le(px, 64, 64).with_mipmaps();
+        let grey = tex.mip(2).unwrap().sample_nearest(Vec2::ZERO).to_u32();
+        let sampler = Sampler::new().with_mip_filter(MipFilter::Nearest);
+        let draw = |size: f32| {
+            let mut f = Frame::new(64, 64);
+            let v = |x: f32, y: f32| {
+                let mut v = Vertex::new(Vec3::new(x * size, y * size, 0.0));
+                v.uv = Vec2::new(x, y);
+                v
+            };
+            let (a, b, c, d) = (v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0), v(0.0, 1.0));
+            draw_triangle_textured(&mut f, a, b, c, &tex, &sampler);
+            draw_triangle_textured(&mut f, a, c, d, &tex, &sampler);
+            f
+        };
+
+        // One texel per pixel reproduces the checker; 4x minified reads the grey level.
+        let full = draw(64.0);
+        assert_eq!(full.pixels()[0], black.to_u32());
+        assert_eq!(full.pixels()[1], white.to_u32());
+        let small = draw(16.0);
+        for y in 0..16 {
+            assert!(
+                small.pixels()[y * 64..y * 64 + 16]
+                    .iter()
+                    .all(|&p| p == grey)
+            );
+        }
+    }
 }